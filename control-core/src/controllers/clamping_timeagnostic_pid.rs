@@ -1,35 +1,14 @@
 use std::time::Instant;
 
+use super::pid::{Limits, PidConfig, PidController};
+
+/// PID whose output is a change per cycle (terms are multiplied by the cycle time),
+/// with optional clamping of every term.
+///
+/// A special case of [`PidController`] with `time_agnostic` enabled.
 #[derive(Debug)]
 pub struct ClampingTimeagnosticPidController {
-    // Params
-    /// Proportional gain
-    kp: f64,
-    /// Integral gain
-    ki: f64,
-    /// Derivative gain
-    kd: f64,
-    // State
-    /// Proportional error
-    ep: f64,
-    /// Integral error
-    ei: f64,
-    /// Derivative error
-    ed: f64,
-
-    min_ep: Option<f64>,
-    max_ep: Option<f64>,
-
-    min_ei: Option<f64>,
-    max_ei: Option<f64>,
-
-    min_ed: Option<f64>,
-    max_ed: Option<f64>,
-
-    min_signal: Option<f64>,
-    max_signal: Option<f64>,
-
-    last: Option<Instant>,
+    pid: PidController,
 }
 
 impl ClampingTimeagnosticPidController {
@@ -46,129 +25,47 @@ impl ClampingTimeagnosticPidController {
         min_signal: Option<f64>,
         max_signal: Option<f64>,
     ) -> Self {
+        let mut config = PidConfig::new(kp, ki, kd);
+        config.error_limits = Limits::new(min_ep, max_ep);
+        config.integral_limits = Limits::new(min_ei, max_ei);
+        config.derivative_limits = Limits::new(min_ed, max_ed);
+        config.output_limits = Limits::new(min_signal, max_signal);
+        config.time_agnostic = true;
         Self {
-            kp,
-            ki,
-            kd,
-            ep: 0.0,
-            ei: 0.0,
-            ed: 0.0,
-            last: None,
-            min_ep,
-            max_ep,
-            min_ei,
-            max_ei,
-            min_ed,
-            max_ed,
-            min_signal,
-            max_signal,
+            pid: PidController::with_config(config),
         }
     }
 
     pub const fn simple_new(kp: f64, ki: f64, kd: f64) -> Self {
-        Self {
-            kp,
-            ki,
-            kd,
-            ep: 0.0,
-            ei: 0.0,
-            ed: 0.0,
-            min_ep: None,
-            max_ep: None,
-            min_ei: None,
-            max_ei: None,
-            min_ed: None,
-            max_ed: None,
-            last: None,
-            min_signal: None,
-            max_signal: None,
-        }
+        Self::new(kp, ki, kd, None, None, None, None, None, None, None, None)
     }
 
     pub const fn get_kp(&self) -> f64 {
-        self.kp
+        self.pid.get_kp()
     }
 
     pub const fn get_ki(&self) -> f64 {
-        self.ki
+        self.pid.get_ki()
     }
 
     pub const fn get_kd(&self) -> f64 {
-        self.kd
+        self.pid.get_kd()
     }
 
     pub const fn configure(&mut self, ki: f64, kp: f64, kd: f64) {
-        self.reset();
-        self.kp = kp;
-        self.ki = ki;
-        self.kd = kd;
+        self.pid.configure(ki, kp, kd);
     }
 
     pub const fn optional_clamp(value: f64, min: Option<f64>, max: Option<f64>) -> f64 {
-        match (min, max) {
-            (Some(min), Some(max)) => value.clamp(min, max),
-            (Some(min), None) => value.max(min),
-            (None, Some(max)) => value.min(max),
-            (None, None) => value,
-        }
+        Limits::new(min, max).clamp(value)
     }
 
     pub fn update(&mut self, error: f64, t: Instant) -> f64 {
-        match self.last {
-            // First update
-            None => {
-                // Calculate error
-                let ep = error;
-
-                // Calculate signal
-                let signal = self.kp * ep;
-                let clamped_signal = Self::optional_clamp(signal, self.min_signal, self.max_signal);
-
-                // Set values
-                self.ep = Self::optional_clamp(ep, self.min_ep, self.max_ep);
-                self.ei = 0.0;
-                self.ed = 0.0;
-                self.last = Some(t);
-
-                clamped_signal
-            }
-            // Subsequent updates
-            Some(last) => {
-                // Calculate the time delta in seconds
-                let dt = t.duration_since(last).as_secs_f64();
-
-                // Calculate errors
-                let ep = Self::optional_clamp(error, self.min_ep, self.max_ep);
-
-                let ei = Self::optional_clamp(ep.mul_add(dt, self.ei), self.min_ei, self.max_ei);
-
-                let ed = Self::optional_clamp((ep - self.ep) / dt, self.min_ed, self.max_ed);
-
-                // Make factors timeagnostic
-                let kp = self.kp * dt;
-                let ki = self.ki * dt;
-                let kd = self.kd * dt;
-
-                // Calculate signal
-                let signal = kd.mul_add(ed, kp.mul_add(ep, ki * ei));
-                let clamped_signal = Self::optional_clamp(signal, self.min_signal, self.max_signal);
-
-                // Set values
-                self.ep = ep;
-                self.ei = ei;
-                self.ed = ed;
-                self.last = Some(t);
-
-                clamped_signal
-            }
-        }
+        self.pid.update(error, t)
     }
 
     pub const fn reset(&mut self) {
-        self.ep = 0.0;
-        self.ei = 0.0;
-        self.ed = 0.0;
-        self.last = None;
+        self.pid.reset();
     }
 }
 
@@ -215,4 +112,29 @@ mod tests {
         let clamped = ClampingTimeagnosticPidController::optional_clamp(val, None, None);
         assert_eq!(clamped, 42.0);
     }
+
+    #[test]
+    fn test_update_is_timeagnostic_and_clamped() {
+        use std::time::{Duration, Instant};
+
+        let mut pid = ClampingTimeagnosticPidController::new(
+            1.0,
+            1.0,
+            0.0,
+            None,
+            None,
+            None,
+            Some(0.15),
+            None,
+            None,
+            None,
+            Some(0.5),
+        );
+        let start = Instant::now();
+        assert_eq!(pid.update(1.0, start), 0.5);
+        // (kp * 1 + ki * 0.1) * 0.1
+        assert!((pid.update(1.0, start + Duration::from_millis(100)) - 0.11).abs() < 1e-9);
+        // integral clamped to 0.15
+        assert!((pid.update(1.0, start + Duration::from_millis(200)) - 0.115).abs() < 1e-9);
+    }
 }
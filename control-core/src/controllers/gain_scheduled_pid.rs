@@ -0,0 +1,215 @@
+use std::time::Instant;
+
+use anyhow::Result;
+
+use super::pid::{PidConfig, PidController};
+use crate::helpers::interpolation::{normalize, scale};
+
+/// PID gains at one point of the scheduling variable
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GainSchedulePoint {
+    /// Value of the scheduling variable
+    pub at: f64,
+    pub kp: f64,
+    pub ki: f64,
+    pub kd: f64,
+}
+
+/// Which value the gains are scheduled over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SchedulingVariable {
+    #[default]
+    Setpoint,
+    Measurement,
+}
+
+/// Table of gain sets, linearly interpolated between its points
+#[derive(Debug, Clone, PartialEq)]
+pub struct GainSchedule {
+    /// Sorted by `at`, at least one point
+    points: Vec<GainSchedulePoint>,
+}
+
+impl GainSchedule {
+    pub fn new(mut points: Vec<GainSchedulePoint>) -> Result<Self> {
+        if points.is_empty() {
+            return Err(anyhow::anyhow!(
+                "[{}::GainSchedule::new] Schedule needs at least one point",
+                module_path!()
+            ));
+        }
+        if points.iter().any(|p| {
+            !(p.at.is_finite() && p.kp.is_finite() && p.ki.is_finite() && p.kd.is_finite())
+        }) {
+            return Err(anyhow::anyhow!(
+                "[{}::GainSchedule::new] Schedule contains non-finite values",
+                module_path!()
+            ));
+        }
+
+        points.sort_by(|a, b| a.at.total_cmp(&b.at));
+        if points.windows(2).any(|pair| pair[0].at == pair[1].at) {
+            return Err(anyhow::anyhow!(
+                "[{}::GainSchedule::new] Schedule contains duplicate points",
+                module_path!()
+            ));
+        }
+        Ok(Self { points })
+    }
+
+    pub fn points(&self) -> &[GainSchedulePoint] {
+        &self.points
+    }
+
+    /// Gains at `x`, held constant outside of the table
+    pub fn gains_at(&self, x: f64) -> (f64, f64, f64) {
+        let upper = self.points.partition_point(|p| p.at < x);
+        let point = match upper {
+            0 => self.points[0],
+            i if i == self.points.len() => self.points[i - 1],
+            i => {
+                let (a, b) = (self.points[i - 1], self.points[i]);
+                let t = normalize(x, a.at, b.at);
+                GainSchedulePoint {
+                    at: x,
+                    kp: scale(t, a.kp, b.kp),
+                    ki: scale(t, a.ki, b.ki),
+                    kd: scale(t, a.kd, b.kd),
+                }
+            }
+        };
+        (point.kp, point.ki, point.kd)
+    }
+}
+
+/// PID whose gains follow a [`GainSchedule`] over the setpoint or the measurement.
+///
+/// Gains are switched bumpless every update. Without a schedule it is a plain [`PidController`].
+#[derive(Debug)]
+pub struct GainScheduledPidController {
+    pid: PidController,
+    schedule: Option<GainSchedule>,
+    variable: SchedulingVariable,
+}
+
+impl GainScheduledPidController {
+    pub const fn new(config: PidConfig, variable: SchedulingVariable) -> Self {
+        Self {
+            pid: PidController::with_config(config),
+            schedule: None,
+            variable,
+        }
+    }
+
+    pub fn set_schedule(&mut self, schedule: Option<GainSchedule>) {
+        self.schedule = schedule;
+    }
+
+    pub const fn get_schedule(&self) -> Option<&GainSchedule> {
+        self.schedule.as_ref()
+    }
+
+    /// Fixed gains, removes the schedule
+    pub fn set_gains(&mut self, kp: f64, ki: f64, kd: f64) {
+        self.schedule = None;
+        self.pid.set_gains(kp, ki, kd);
+    }
+
+    /// Set new fixed gains and reset the state, removes the schedule
+    pub fn configure(&mut self, ki: f64, kp: f64, kd: f64) {
+        self.schedule = None;
+        self.pid.configure(ki, kp, kd);
+    }
+
+    /// Currently active gains
+    pub const fn get_kp(&self) -> f64 {
+        self.pid.get_kp()
+    }
+
+    pub const fn get_ki(&self) -> f64 {
+        self.pid.get_ki()
+    }
+
+    pub const fn get_kd(&self) -> f64 {
+        self.pid.get_kd()
+    }
+
+    pub fn update_with(
+        &mut self,
+        setpoint: f64,
+        measurement: f64,
+        feed_forward: f64,
+        t: Instant,
+    ) -> f64 {
+        if let Some(schedule) = &self.schedule {
+            let x = match self.variable {
+                SchedulingVariable::Setpoint => setpoint,
+                SchedulingVariable::Measurement => measurement,
+            };
+            let (kp, ki, kd) = schedule.gains_at(x);
+            self.pid.set_gains(kp, ki, kd);
+        }
+        self.pid.update_with(setpoint, measurement, feed_forward, t)
+    }
+
+    pub const fn reset(&mut self) {
+        self.pid.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(at: f64, kp: f64, ki: f64, kd: f64) -> GainSchedulePoint {
+        GainSchedulePoint { at, kp, ki, kd }
+    }
+
+    #[test]
+    fn test_schedule_interpolates_and_holds() {
+        let schedule = GainSchedule::new(vec![
+            point(280.0, 2.0, 0.2, 20.0),
+            point(150.0, 1.0, 0.1, 10.0),
+        ])
+        .unwrap();
+
+        assert_eq!(schedule.gains_at(100.0), (1.0, 0.1, 10.0));
+        assert_eq!(schedule.gains_at(150.0), (1.0, 0.1, 10.0));
+        let (kp, ki, kd) = schedule.gains_at(215.0);
+        assert!((kp - 1.5).abs() < 1e-9);
+        assert!((ki - 0.15).abs() < 1e-9);
+        assert!((kd - 15.0).abs() < 1e-9);
+        assert_eq!(schedule.gains_at(300.0), (2.0, 0.2, 20.0));
+    }
+
+    #[test]
+    fn test_schedule_rejects_invalid_tables() {
+        assert!(GainSchedule::new(vec![]).is_err());
+        assert!(
+            GainSchedule::new(vec![point(1.0, 1.0, 0.0, 0.0), point(1.0, 2.0, 0.0, 0.0)]).is_err()
+        );
+        assert!(GainSchedule::new(vec![point(f64::NAN, 1.0, 0.0, 0.0)]).is_err());
+    }
+
+    #[test]
+    fn test_controller_follows_schedule() {
+        let mut pid = GainScheduledPidController::new(
+            PidConfig::new(1.0, 0.0, 0.0),
+            SchedulingVariable::Setpoint,
+        );
+        pid.set_schedule(Some(
+            GainSchedule::new(vec![
+                point(100.0, 1.0, 0.0, 0.0),
+                point(200.0, 3.0, 0.0, 0.0),
+            ])
+            .unwrap(),
+        ));
+
+        assert_eq!(pid.update_with(150.0, 149.0, 0.0, Instant::now()), 2.0);
+        assert_eq!(pid.get_kp(), 2.0);
+
+        pid.set_gains(5.0, 0.0, 0.0);
+        assert!(pid.get_schedule().is_none());
+        assert_eq!(pid.get_kp(), 5.0);
+    }
+}
@@ -1,5 +1,8 @@
 pub mod clamping_timeagnostic_pid;
 pub mod first_degree_motion;
+pub mod gain_scheduled_pid;
 pub mod pid;
 pub mod pid_autotuner;
 pub mod second_degree_motion;
+pub mod smith_predictor;
+pub mod step_response_identifier;
@@ -1,104 +1,466 @@
 use std::time::Instant;
 
-#[derive(Debug)]
-pub struct PidController {
-    // Params
+/// Optional lower and upper bound
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Limits {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl Limits {
+    pub const NONE: Self = Self {
+        min: None,
+        max: None,
+    };
+
+    pub const fn new(min: Option<f64>, max: Option<f64>) -> Self {
+        Self { min, max }
+    }
+
+    pub const fn clamp(&self, value: f64) -> f64 {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) => value.clamp(min, max),
+            (Some(min), None) => value.max(min),
+            (None, Some(max)) => value.min(max),
+            (None, None) => value,
+        }
+    }
+}
+
+/// Keeps the integrator from winding up while the output is limited
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum AntiWindup {
+    /// Integrate regardless of saturation
+    #[default]
+    None,
+    /// Hold the integrator while the output is saturated and the error drives it further into the limit
+    ConditionalIntegration,
+    /// Feed the part of the output cut off by the limits back into the integrator.
+    /// `tracking_gain` is in 1/s, a common choice is `ki / kp`.
+    BackCalculation { tracking_gain: f64 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PidConfig {
     /// Proportional gain
-    kp: f64,
+    pub kp: f64,
     /// Integral gain
-    ki: f64,
+    pub ki: f64,
     /// Derivative gain
-    kd: f64,
+    pub kd: f64,
+    /// Weight of the setpoint in the proportional term, 1.0 is the plain error
+    pub setpoint_weight_p: f64,
+    /// Weight of the setpoint in the derivative term.
+    /// 0.0 differentiates the measurement only, so setpoint changes cause no derivative kick.
+    pub setpoint_weight_d: f64,
+    /// Time constant of the first order derivative filter in seconds, 0.0 disables the filter
+    pub derivative_filter_time: f64,
+    /// Applied to the error and the weighted errors of the P and D terms
+    pub error_limits: Limits,
+    /// Applied to the accumulated error integral (error × s)
+    pub integral_limits: Limits,
+    /// Applied to the derivative before filtering
+    pub derivative_limits: Limits,
+    pub output_limits: Limits,
+    pub anti_windup: AntiWindup,
+    /// Multiply the PID terms by the cycle time, the output then is a change per cycle
+    pub time_agnostic: bool,
+}
+
+impl PidConfig {
+    /// Plain PID without limits, filtering or weighting
+    pub const fn new(kp: f64, ki: f64, kd: f64) -> Self {
+        Self {
+            kp,
+            ki,
+            kd,
+            setpoint_weight_p: 1.0,
+            setpoint_weight_d: 1.0,
+            derivative_filter_time: 0.0,
+            error_limits: Limits::NONE,
+            integral_limits: Limits::NONE,
+            derivative_limits: Limits::NONE,
+            output_limits: Limits::NONE,
+            anti_windup: AntiWindup::None,
+            time_agnostic: false,
+        }
+    }
+}
+
+impl Default for PidConfig {
+    fn default() -> Self {
+        Self::new(0.0, 0.0, 0.0)
+    }
+}
+
+#[derive(Debug)]
+pub struct PidController {
+    // Params
+    config: PidConfig,
     // State
-    /// Proportional error
+    /// Proportional error, weighted
     ep: f64,
     /// Integral error
     ei: f64,
-    /// Derivative error
+    /// Derivative error, filtered
     ed: f64,
+    /// Weighted error the derivative is taken of
+    last_derivative_input: f64,
+    last_output: f64,
 
     last: Option<Instant>,
 }
 
 impl PidController {
     pub const fn new(kp: f64, ki: f64, kd: f64) -> Self {
+        Self::with_config(PidConfig::new(kp, ki, kd))
+    }
+
+    pub const fn with_config(config: PidConfig) -> Self {
         Self {
-            kp,
-            ki,
-            kd,
+            config,
             ep: 0.0,
             ei: 0.0,
             ed: 0.0,
+            last_derivative_input: 0.0,
+            last_output: 0.0,
             last: None,
         }
     }
 
+    /// Set new gains and reset the state
     pub const fn configure(&mut self, ki: f64, kp: f64, kd: f64) {
         self.reset();
-        self.kp = kp;
-        self.ki = ki;
-        self.kd = kd;
+        self.config.kp = kp;
+        self.config.ki = ki;
+        self.config.kd = kd;
+    }
+
+    /// Set new gains without a jump in the output.
+    ///
+    /// The integral absorbs the change of the P and D terms. This is not possible
+    /// if the new `ki` is zero or the integral limits are reached, then the output jumps.
+    pub fn set_gains(&mut self, kp: f64, ki: f64, kd: f64) {
+        if self.last.is_some() && ki != 0.0 {
+            let old = self.config;
+            let difference = (old.kp - kp).mul_add(self.ep, (old.kd - kd) * self.ed);
+            self.ei = old
+                .integral_limits
+                .clamp(old.ki.mul_add(self.ei, difference) / ki);
+        }
+        self.config.kp = kp;
+        self.config.ki = ki;
+        self.config.kd = kd;
+    }
+
+    /// Replace the configuration, gains are changed bumpless
+    pub fn set_config(&mut self, config: PidConfig) {
+        self.set_gains(config.kp, config.ki, config.kd);
+        self.config = config;
+        self.ei = config.integral_limits.clamp(self.ei);
+    }
+
+    pub const fn get_config(&self) -> &PidConfig {
+        &self.config
     }
 
     pub const fn get_kp(&self) -> f64 {
-        self.kp
+        self.config.kp
     }
 
     pub const fn get_ki(&self) -> f64 {
-        self.ki
+        self.config.ki
     }
 
     pub const fn get_kd(&self) -> f64 {
-        self.kd
+        self.config.kd
     }
 
+    /// Update with the error only, the derivative is always taken of the error
     pub fn update(&mut self, error: f64, t: Instant) -> f64 {
-        match self.last {
-            // First update
-            None => {
-                // Calculate error
-                let ep = error;
+        self.update_with(0.0, -error, 0.0, t)
+    }
+
+    /// Update with separate setpoint and measurement, required for setpoint weighting.
+    /// `feed_forward` is added to the output before the output limits.
+    pub fn update_with(
+        &mut self,
+        setpoint: f64,
+        measurement: f64,
+        feed_forward: f64,
+        t: Instant,
+    ) -> f64 {
+        let c = self.config;
 
-                // Calculate signal
-                let signal = self.kp * ep;
+        // Calculate errors
+        let error = c.error_limits.clamp(setpoint - measurement);
+        let ep = c
+            .error_limits
+            .clamp(c.setpoint_weight_p.mul_add(setpoint, -measurement));
+        let derivative_input = c
+            .error_limits
+            .clamp(c.setpoint_weight_d.mul_add(setpoint, -measurement));
+
+        let last = match self.last {
+            Some(last) => last,
+            // First update, without a time delta only P and feed forward
+            None => {
+                let output = c.output_limits.clamp(c.kp.mul_add(ep, feed_forward));
 
-                // Set values
                 self.ep = ep;
                 self.ei = 0.0;
-
                 self.ed = 0.0;
+                self.last_derivative_input = derivative_input;
+                self.last_output = output;
                 self.last = Some(t);
 
-                signal
+                return output;
             }
-            // Subsequent updates
-            Some(last) => {
-                // Calculate the time delta in seconds
-                let dt = t.duration_since(last).as_secs_f64();
+        };
 
-                // Calculate errors
-                let ep = error;
-                let ei = ep.mul_add(dt, self.ei);
-                let ed = (ep - self.ep) / dt;
+        // Calculate the time delta in seconds
+        let dt = t.duration_since(last).as_secs_f64();
+        if dt <= 0.0 {
+            return self.last_output;
+        }
 
-                // Calculate signal
-                let signal = self.kd.mul_add(ed, self.kp.mul_add(ep, self.ki * ei));
+        let ed_raw = c
+            .derivative_limits
+            .clamp((derivative_input - self.last_derivative_input) / dt);
+        let ed = match c.derivative_filter_time > 0.0 {
+            true => {
+                c.derivative_filter_time.mul_add(self.ed, dt * ed_raw)
+                    / (c.derivative_filter_time + dt)
+            }
+            false => ed_raw,
+        };
 
-                // Set values
-                self.ep = ep;
-                self.ei = ei;
-                self.ed = ed;
-                self.last = Some(t);
+        let scale = match c.time_agnostic {
+            true => dt,
+            false => 1.0,
+        };
+        let signal =
+            |ei: f64| scale.mul_add(c.kd.mul_add(ed, c.kp.mul_add(ep, c.ki * ei)), feed_forward);
 
-                signal
+        let ei_integrated = c.integral_limits.clamp(error.mul_add(dt, self.ei));
+        let ei = match c.anti_windup {
+            AntiWindup::None => ei_integrated,
+            AntiWindup::ConditionalIntegration => {
+                let unlimited = signal(ei_integrated);
+                let excess = unlimited - c.output_limits.clamp(unlimited);
+                // Only hold if integrating pushes further into the limit
+                match excess != 0.0 && excess.signum() == (c.ki * error).signum() {
+                    true => self.ei,
+                    false => ei_integrated,
+                }
             }
-        }
+            AntiWindup::BackCalculation { tracking_gain } if c.ki != 0.0 => {
+                let unlimited = signal(self.ei);
+                let excess = c.output_limits.clamp(unlimited) - unlimited;
+                let tracking = tracking_gain * excess / (c.ki * scale);
+                c.integral_limits
+                    .clamp((error + tracking).mul_add(dt, self.ei))
+            }
+            AntiWindup::BackCalculation { .. } => ei_integrated,
+        };
+
+        let output = c.output_limits.clamp(signal(ei));
+
+        // Set values
+        self.ep = ep;
+        self.ei = ei;
+        self.ed = ed;
+        self.last_derivative_input = derivative_input;
+        self.last_output = output;
+        self.last = Some(t);
+
+        output
     }
 
     pub const fn reset(&mut self) {
         self.ep = 0.0;
         self.ei = 0.0;
         self.ed = 0.0;
+        self.last_derivative_input = 0.0;
+        self.last_output = 0.0;
         self.last = None;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const DT: Duration = Duration::from_millis(100);
+
+    /// Runs `update` with constant errors, one per 100 ms
+    fn run(pid: &mut PidController, errors: &[f64]) -> Vec<f64> {
+        let start = Instant::now();
+        errors
+            .iter()
+            .enumerate()
+            .map(|(i, error)| pid.update(*error, start + DT * i as u32))
+            .collect()
+    }
+
+    #[test]
+    fn test_plain_pid() {
+        let mut pid = PidController::new(2.0, 1.0, 0.5);
+        let out = run(&mut pid, &[1.0, 1.0, 2.0]);
+
+        assert_eq!(out[0], 2.0);
+        // p = 2, i = 0.1, d = 0
+        assert!((out[1] - 2.1).abs() < 1e-9);
+        // p = 4, i = 0.3, d = 0.5 * 10
+        assert!((out[2] - 9.3).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_output_limits() {
+        let mut config = PidConfig::new(10.0, 0.0, 0.0);
+        config.output_limits = Limits::new(Some(-1.0), Some(1.0));
+        let mut pid = PidController::with_config(config);
+
+        assert_eq!(run(&mut pid, &[5.0, -5.0]), vec![1.0, -1.0]);
+    }
+
+    #[test]
+    fn test_conditional_integration_holds_integral() {
+        let mut config = PidConfig::new(1.0, 1.0, 0.0);
+        config.output_limits = Limits::new(Some(0.0), Some(1.0));
+        config.anti_windup = AntiWindup::ConditionalIntegration;
+        let mut pid = PidController::with_config(config);
+
+        // Saturated for a long time, the integral must not grow
+        run(&mut pid, &[5.0; 50]);
+        assert_eq!(pid.ei, 0.0);
+
+        // Leaving saturation integrates again
+        let start = Instant::now();
+        pid.reset();
+        pid.update(0.5, start);
+        pid.update(0.5, start + DT);
+        assert!((pid.ei - 0.05).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_without_anti_windup_integral_grows() {
+        let mut config = PidConfig::new(1.0, 1.0, 0.0);
+        config.output_limits = Limits::new(Some(0.0), Some(1.0));
+        let mut pid = PidController::with_config(config);
+
+        run(&mut pid, &[5.0; 50]);
+        assert!(pid.ei > 20.0);
+    }
+
+    #[test]
+    fn test_back_calculation_limits_integral() {
+        let mut config = PidConfig::new(1.0, 1.0, 0.0);
+        config.output_limits = Limits::new(Some(0.0), Some(1.0));
+        config.anti_windup = AntiWindup::BackCalculation { tracking_gain: 5.0 };
+        let mut pid = PidController::with_config(config);
+
+        run(&mut pid, &[5.0; 200]);
+        // Settles where the tracking cancels the error: e + kt / ki * (1 - (e + ei)) = 0
+        let expected = 1.0 - 5.0 + 5.0 / 5.0;
+        assert!((pid.ei - expected).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_derivative_on_measurement_has_no_setpoint_kick() {
+        let mut config = PidConfig::new(0.0, 0.0, 1.0);
+        config.setpoint_weight_d = 0.0;
+        let mut pid = PidController::with_config(config);
+
+        let start = Instant::now();
+        pid.update_with(10.0, 5.0, 0.0, start);
+        // Setpoint step, measurement constant
+        assert_eq!(pid.update_with(50.0, 5.0, 0.0, start + DT), 0.0);
+        // Measurement rises by 1 in 100 ms
+        assert!((pid.update_with(50.0, 6.0, 0.0, start + DT * 2) + 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_setpoint_weight_p() {
+        let mut config = PidConfig::new(1.0, 0.0, 0.0);
+        config.setpoint_weight_p = 0.5;
+        let mut pid = PidController::with_config(config);
+
+        assert_eq!(pid.update_with(10.0, 2.0, 0.0, Instant::now()), 3.0);
+    }
+
+    #[test]
+    fn test_derivative_filter() {
+        let mut config = PidConfig::new(0.0, 0.0, 1.0);
+        config.derivative_filter_time = 0.1;
+        let mut pid = PidController::with_config(config);
+
+        let out = run(&mut pid, &[0.0, 1.0, 1.0]);
+        // Raw derivative 10, filtered with dt = tf to half
+        assert!((out[1] - 5.0).abs() < 1e-9);
+        assert!((out[2] - 2.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_feed_forward() {
+        let mut config = PidConfig::new(1.0, 0.0, 0.0);
+        config.output_limits = Limits::new(None, Some(10.0));
+        let mut pid = PidController::with_config(config);
+
+        let start = Instant::now();
+        assert_eq!(pid.update_with(2.0, 1.0, 3.0, start), 4.0);
+        assert_eq!(pid.update_with(2.0, 1.0, 30.0, start + DT), 10.0);
+    }
+
+    #[test]
+    fn test_set_gains_is_bumpless() {
+        let mut pid = PidController::new(1.0, 1.0, 0.0);
+        let start = Instant::now();
+        pid.update(1.0, start);
+        let before = pid.update(1.0, start + DT);
+
+        pid.set_gains(3.0, 2.0, 0.0);
+        // Same time, error unchanged -> same output
+        let after = pid.update(1.0, start + DT * 2) - 2.0 * 0.1;
+        assert!((before - after).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_set_gains_respects_integral_limits() {
+        let mut config = PidConfig::new(1.0, 1.0, 0.0);
+        config.integral_limits = Limits::new(Some(-0.5), Some(0.5));
+        let mut pid = PidController::with_config(config);
+        run(&mut pid, &[1.0, 1.0]);
+
+        // Absorbing the P change would need an integral of 1.1
+        pid.set_gains(0.0, 1.0, 0.0);
+        assert_eq!(pid.ei, 0.5);
+
+        config.kp = 0.0;
+        config.integral_limits = Limits::new(Some(-0.2), Some(0.2));
+        pid.set_config(config);
+        assert_eq!(pid.ei, 0.2);
+    }
+
+    #[test]
+    fn test_configure_resets() {
+        let mut pid = PidController::new(1.0, 1.0, 0.0);
+        run(&mut pid, &[1.0, 1.0]);
+        pid.configure(0.0, 2.0, 0.0);
+
+        assert_eq!(pid.ei, 0.0);
+        assert_eq!(pid.update(1.0, Instant::now()), 2.0);
+    }
+
+    #[test]
+    fn test_time_agnostic_scales_with_dt() {
+        let mut config = PidConfig::new(1.0, 0.0, 0.0);
+        config.time_agnostic = true;
+        let mut pid = PidController::with_config(config);
+
+        let out = run(&mut pid, &[1.0, 1.0]);
+        assert_eq!(out[0], 1.0);
+        assert!((out[1] - 0.1).abs() < 1e-9);
+    }
+}
@@ -11,11 +11,18 @@
 //! 3. Calculating the ultimate gain (Ku) and period (Tu) from peak data
 //! 4. Applying Ziegler-Nichols rules to derive Kp, Ki, Kd values
 //!
+//! The ultimate gain and period are kept in the result, so the gains can be
+//! recomputed with a different [`TuningRule`] afterwards.
+//!
 //! This tuner is generic and can be used for any physical quantity (pressure in
 //! bar, temperature in °C, etc.).  The caller is responsible for mapping the
 //! returned duty-cycle (0.0 … max_power) to the actual actuator output.
 
-use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use std::{
+    f64::consts::PI,
+    time::{Duration, Instant},
+};
 
 const REQUIRED_PEAK_COUNT: usize = 20;
 const MIN_PEAKS_FOR_PID: usize = 4;
@@ -55,6 +62,48 @@ pub struct AutoTuneResult {
     pub tu: f64,
 }
 
+impl AutoTuneResult {
+    /// Same Ku/Tu, gains recomputed with `rule`
+    pub fn with_rule(&self, rule: TuningRule) -> Self {
+        let (kp, ti, td) = rule.parameters(self.ku, self.tu);
+        Self {
+            kp,
+            ki: if ti > 0.0 { kp / ti } else { 0.0 },
+            kd: kp * td,
+            ku: self.ku,
+            tu: self.tu,
+        }
+    }
+}
+
+/// Tuning rules from the ultimate gain (Ku) and period (Tu)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TuningRule {
+    /// Classic Ziegler–Nichols, fast with about 25 % overshoot
+    ZieglerNichols,
+    /// Tyreus–Luyben, less aggressive and more robust than Ziegler–Nichols
+    TyreusLuyben,
+    /// SIMC PI with τc = θ, treating the loop as integrating with dead time
+    /// θ = Tu / 4, which fits slow heating zones
+    Simc,
+    /// Ziegler–Nichols "no overshoot" variant
+    NoOvershoot,
+}
+
+impl TuningRule {
+    /// Returns `(kp, ti, td)`
+    fn parameters(self, ku: f64, tu: f64) -> (f64, f64, f64) {
+        match self {
+            Self::ZieglerNichols => (0.6 * ku, 0.5 * tu, 0.125 * tu),
+            Self::TyreusLuyben => (ku / 2.2, 2.2 * tu, tu / 6.3),
+            // integrating process k'·e^(-θs): Ku = π / (2 k' θ), Tu = 4 θ
+            // SIMC: Kc = 1 / (k' (τc + θ)), Ti = 4 (τc + θ)
+            Self::Simc => (ku / PI, 2.0 * tu, 0.0),
+            Self::NoOvershoot => (0.2 * ku, 0.5 * tu, tu / 3.0),
+        }
+    }
+}
+
 /// State of the auto-tuning process
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum AutoTuneState {
@@ -280,7 +329,7 @@ impl PidAutoTuner {
         if amplitude == 0.0 || time_diff == 0.0 {
             return None;
         }
-        let ku = 4.0 * self.config.max_power / (std::f64::consts::PI * amplitude);
+        let ku = 4.0 * self.config.max_power / (PI * amplitude);
         let tu = time_diff;
 
         // Ziegler-Nichols rules
@@ -554,4 +603,31 @@ mod tests {
         assert!(result.ki > 0.0);
         assert!(result.kd > 0.0);
     }
+
+    #[test]
+    fn test_tuning_rules() {
+        let result = AutoTuneResult {
+            kp: 0.0,
+            ki: 0.0,
+            kd: 0.0,
+            ku: 10.0,
+            tu: 20.0,
+        };
+
+        let zn = result.with_rule(TuningRule::ZieglerNichols);
+        assert!((zn.kp - 6.0).abs() < 1e-9);
+        assert!((zn.ki - 0.6).abs() < 1e-9);
+        assert!((zn.kd - 15.0).abs() < 1e-9);
+        assert_eq!((zn.ku, zn.tu), (10.0, 20.0));
+
+        let tl = result.with_rule(TuningRule::TyreusLuyben);
+        let no_overshoot = result.with_rule(TuningRule::NoOvershoot);
+        assert!(tl.kp < zn.kp && tl.ki < zn.ki);
+        assert!(no_overshoot.kp < tl.kp);
+
+        let simc = result.with_rule(TuningRule::Simc);
+        assert!((simc.kp - 10.0 / PI).abs() < 1e-9);
+        assert!((simc.ki - simc.kp / 40.0).abs() < 1e-9);
+        assert_eq!(simc.kd, 0.0);
+    }
 }
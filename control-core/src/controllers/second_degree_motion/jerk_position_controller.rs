@@ -0,0 +1,306 @@
+use super::acceleration_position_controller::MotionControllerError;
+use super::jerk_speed_controller::JerkSpeedController;
+
+/// Jerk-limited (S-curve) position trajectory planner.
+///
+/// The speed is shaped by a [`JerkSpeedController`], so acceleration changes at most
+/// with the jerk limit. Each update the planner predicts the distance needed to stop
+/// from the current speed and acceleration. While it is shorter than the remaining
+/// distance the planner targets the maximum speed, otherwise zero, and the S-curve
+/// deceleration ends at the target.
+///
+/// Discrete updates leave a small error at the end of a move, once stopped within
+/// `position_tolerance` the position snaps to the target.
+///
+/// # Example
+/// ```ignore
+/// let mut planner = JerkPositionController::new(100.0, 500.0, 5000.0, 0.01)?;
+/// loop {
+///     let position = planner.update(target, 0.001);
+///     let speed = planner.get_speed();
+/// }
+/// ```
+#[derive(Debug)]
+pub struct JerkPositionController {
+    speed_controller: JerkSpeedController,
+    position: f64,
+    target_position: f64,
+    max_speed: f64,
+    max_acceleration: f64,
+    max_jerk: f64,
+    position_tolerance: f64,
+    /// Braking towards the target, kept until standstill
+    braking: bool,
+}
+
+impl JerkPositionController {
+    /// Create a planner at rest at position 0
+    ///
+    /// # Errors
+    /// Returns [`MotionControllerError::InvalidSpeedLimits`] if `max_speed` is negative and
+    /// [`MotionControllerError::InvalidAccelerationLimits`] if `max_acceleration` or
+    /// `max_jerk` are not positive.
+    pub fn new(
+        max_speed: f64,
+        max_acceleration: f64,
+        max_jerk: f64,
+        position_tolerance: f64,
+    ) -> Result<Self, MotionControllerError> {
+        if max_speed < 0.0 {
+            return Err(MotionControllerError::InvalidSpeedLimits);
+        }
+        if max_acceleration <= 0.0 || max_jerk <= 0.0 {
+            return Err(MotionControllerError::InvalidAccelerationLimits);
+        }
+
+        Ok(Self {
+            speed_controller: JerkSpeedController::new_simple(None, max_acceleration, max_jerk),
+            position: 0.0,
+            target_position: 0.0,
+            max_speed,
+            max_acceleration,
+            max_jerk,
+            position_tolerance: position_tolerance.abs(),
+            braking: false,
+        })
+    }
+
+    /// Advance the trajectory towards `target_position` by `dt` seconds
+    ///
+    /// # Returns
+    /// The planned position after this time step
+    pub fn update(&mut self, target_position: f64, dt: f64) -> f64 {
+        if target_position != self.target_position {
+            self.braking = false;
+        }
+        self.target_position = target_position;
+
+        let remaining = target_position - self.position;
+        let speed = self.speed_controller.get_speed();
+        let acceleration = self.speed_controller.get_acceleration();
+
+        if self.is_stopped() {
+            self.braking = false;
+            if remaining.abs() <= self.position_tolerance {
+                self.position = target_position;
+                return self.position;
+            }
+        }
+
+        let direction = remaining.signum();
+        let speed_along = speed * direction;
+        let stopping_distance = Self::stopping_distance(
+            speed_along,
+            acceleration * direction,
+            self.max_acceleration,
+            self.max_jerk,
+        );
+
+        // Look one cycle ahead, braking a cycle late would overshoot the target
+        if speed_along >= 0.0 && speed_along.mul_add(dt, stopping_distance) >= remaining.abs() {
+            self.braking = true;
+        }
+
+        // Moving away from the target turns around right away
+        let target_speed = if self.braking {
+            0.0
+        } else {
+            direction * self.max_speed
+        };
+
+        let new_speed = self.speed_controller.update(target_speed, dt);
+        self.position += (speed + new_speed) / 2.0 * dt;
+        self.position
+    }
+
+    /// Distance covered until standstill when braking from `speed` (≥ 0) with
+    /// `acceleration` along the direction of motion, at full jerk and deceleration.
+    ///
+    /// The acceleration first ramps to the peak deceleration, holds it and ramps back to zero.
+    pub fn stopping_distance(
+        speed: f64,
+        acceleration: f64,
+        max_deceleration: f64,
+        max_jerk: f64,
+    ) -> f64 {
+        if speed <= 0.0 {
+            return 0.0;
+        }
+        let jerk = max_jerk;
+
+        // Speed change of the ramps, solved for the peak deceleration of a triangular profile
+        let speed_budget = speed + acceleration.powi(2) / (2.0 * jerk);
+        if speed_budget <= 0.0 {
+            // Already decelerating harder than needed
+            return 0.0;
+        }
+        let (peak, hold_time) = {
+            let peak = (speed_budget * jerk).sqrt();
+            if peak <= max_deceleration {
+                (peak, 0.0)
+            } else {
+                let hold = (speed_budget - max_deceleration.powi(2) / jerk) / max_deceleration;
+                (max_deceleration, hold)
+            }
+        };
+
+        // Ramp from the current acceleration down to -peak
+        let t1 = ((acceleration + peak) / jerk).max(0.0);
+        let d1 = t1 * t1.mul_add((jerk * t1).mul_add(-1.0 / 6.0, acceleration / 2.0), speed);
+        let v1 = t1.mul_add((jerk * t1).mul_add(-0.5, acceleration), speed);
+
+        // Hold the peak deceleration
+        let d2 = v1 * hold_time - peak * hold_time.powi(2) / 2.0;
+        let v2 = peak.mul_add(-hold_time, v1);
+
+        // Ramp back to zero
+        let t3 = peak / jerk;
+        let d3 = v2 * t3 - peak * t3.powi(2) / 2.0 + jerk * t3.powi(3) / 6.0;
+
+        (d1 + d2 + d3).max(0.0)
+    }
+
+    /// Reset to standstill at `position`
+    pub fn reset(&mut self, position: f64) {
+        self.position = position;
+        self.target_position = position;
+        self.braking = false;
+        // Zero speed is always within the unlimited speed range
+        let _ = self.speed_controller.reset(0.0);
+    }
+
+    pub const fn get_position(&self) -> f64 {
+        self.position
+    }
+
+    pub const fn get_target_position(&self) -> f64 {
+        self.target_position
+    }
+
+    pub const fn get_speed(&self) -> f64 {
+        self.speed_controller.get_speed()
+    }
+
+    pub const fn get_acceleration(&self) -> f64 {
+        self.speed_controller.get_acceleration()
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.get_speed().abs() < 1e-9 && self.get_acceleration().abs() < 1e-9
+    }
+
+    /// Standing at the target position
+    pub fn is_at_target(&self) -> bool {
+        self.position == self.target_position && self.is_stopped()
+    }
+
+    pub const fn get_max_speed(&self) -> f64 {
+        self.max_speed
+    }
+
+    /// Takes effect immediately, a higher current speed is braked with the jerk limit
+    pub const fn set_max_speed(&mut self, max_speed: f64) {
+        self.max_speed = max_speed.abs();
+    }
+
+    pub const fn get_max_acceleration(&self) -> f64 {
+        self.max_acceleration
+    }
+
+    /// # Errors
+    /// Returns [`MotionControllerError::InvalidAccelerationLimits`] if not positive
+    pub fn set_max_acceleration(
+        &mut self,
+        max_acceleration: f64,
+    ) -> Result<(), MotionControllerError> {
+        if max_acceleration <= 0.0 {
+            return Err(MotionControllerError::InvalidAccelerationLimits);
+        }
+        self.speed_controller
+            .set_max_acceleration(max_acceleration)?;
+        self.speed_controller
+            .set_min_acceleration(-max_acceleration)?;
+        self.max_acceleration = max_acceleration;
+        Ok(())
+    }
+
+    pub const fn get_max_jerk(&self) -> f64 {
+        self.max_jerk
+    }
+
+    /// # Errors
+    /// Returns [`MotionControllerError::InvalidAccelerationLimits`] if not positive
+    pub fn set_max_jerk(&mut self, max_jerk: f64) -> Result<(), MotionControllerError> {
+        if max_jerk <= 0.0 {
+            return Err(MotionControllerError::InvalidAccelerationLimits);
+        }
+        self.speed_controller.set_max_jerk(max_jerk)?;
+        self.speed_controller.set_min_jerk(-max_jerk)?;
+        self.max_jerk = max_jerk;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f64 = 0.001;
+
+    fn run_to_target(planner: &mut JerkPositionController, target: f64) -> (f64, f64, usize) {
+        let mut max_position: f64 = planner.get_position();
+        let mut max_acceleration: f64 = 0.0;
+        let mut steps = 0;
+        while steps < 100_000 {
+            planner.update(target, DT);
+            max_position = max_position.max(planner.get_position());
+            max_acceleration = max_acceleration.max(planner.get_acceleration().abs());
+            steps += 1;
+            if planner.is_at_target() {
+                break;
+            }
+        }
+        (max_position, max_acceleration, steps)
+    }
+
+    #[test]
+    fn test_reaches_target_without_overshoot() {
+        let mut planner = JerkPositionController::new(100.0, 500.0, 5000.0, 0.01).unwrap();
+        let (max_position, max_acceleration, steps) = run_to_target(&mut planner, 50.0);
+
+        assert!(planner.is_at_target());
+        assert_eq!(planner.get_position(), 50.0);
+        assert!(steps < 100_000);
+        assert!(max_position < 50.0 + 0.05);
+        assert!(max_acceleration <= 500.0 + 1e-6);
+    }
+
+    #[test]
+    fn test_short_move() {
+        let mut planner = JerkPositionController::new(100.0, 500.0, 5000.0, 0.01).unwrap();
+        planner.reset(10.0);
+        let (max_position, _, _) = run_to_target(&mut planner, 10.5);
+
+        assert!(planner.is_at_target());
+        assert!(max_position < 10.5 + 0.05);
+
+        run_to_target(&mut planner, 2.0);
+        assert_eq!(planner.get_position(), 2.0);
+    }
+
+    #[test]
+    fn test_stopping_distance() {
+        // Constant deceleration phase: v = 100, a = 500, j = 5000
+        // ramps take 0.1 s each and lose 25 each, the hold loses 50 in 0.1 s
+        let distance = JerkPositionController::stopping_distance(100.0, 0.0, 500.0, 5000.0);
+        // 10 - 5 / 6 while ramping in, 7.5 - 2.5 while holding, 2.5 - 2.5 + 5 / 6 ramping out
+        assert!((distance - 15.0).abs() < 1e-9);
+
+        assert_eq!(
+            JerkPositionController::stopping_distance(0.0, 0.0, 500.0, 5000.0),
+            0.0
+        );
+        // Still accelerating needs more room
+        assert!(JerkPositionController::stopping_distance(100.0, 200.0, 500.0, 5000.0) > distance);
+    }
+}
@@ -77,10 +77,10 @@ impl JerkSpeedController {
     ) -> Self {
         // Create the base controller with renamed parameters
         let base_controller = AccelerationPositionController::new(
-            min_jerk,         // min_speed in the base controller
-            max_jerk,         // max_speed in the base controller
-            min_acceleration, // min_acceleration in the base controller
-            max_acceleration, // max_acceleration in the base controller
+            min_acceleration, // min_speed in the base controller
+            max_acceleration, // max_speed in the base controller
+            min_jerk,         // min_acceleration in the base controller
+            max_jerk,         // max_acceleration in the base controller
             min_speed,        // min_position in the base controller
             max_speed,        // max_position in the base controller
             1e-6,             // position_tolerance
@@ -376,3 +376,28 @@ impl JerkSpeedController {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acceleration_and_jerk_stay_within_limits() {
+        let dt = 0.001;
+        let mut controller = JerkSpeedController::new_simple(None, 2.0, 50.0);
+
+        let mut last_acceleration = 0.0;
+        let mut max_acceleration: f64 = 0.0;
+        for _ in 0..10_000 {
+            controller.update(10.0, dt);
+            let acceleration = controller.get_acceleration();
+            max_acceleration = max_acceleration.max(acceleration.abs());
+            assert!(((acceleration - last_acceleration) / dt).abs() <= 50.0 + 1e-6);
+            last_acceleration = acceleration;
+        }
+
+        // The acceleration limit is reached but never exceeded
+        assert!((max_acceleration - 2.0).abs() < 1e-9);
+        assert!((controller.get_speed() - 10.0).abs() < 1e-6);
+    }
+}
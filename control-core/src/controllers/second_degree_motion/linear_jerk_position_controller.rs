@@ -0,0 +1,147 @@
+use std::time::Instant;
+
+use qitech_lib::units::{
+    acceleration::meter_per_second_squared,
+    f64::{Acceleration, Jerk, Length, Velocity},
+    jerk::meter_per_second_cubed,
+    length::meter,
+    velocity::meter_per_second,
+};
+
+use super::acceleration_position_controller::MotionControllerError;
+use super::jerk_position_controller::JerkPositionController;
+
+/// Linear Jerk Position Controller with proper physical units
+///
+/// This controller plans jerk-limited (S-curve) moves to a target position with proper SI units.
+/// It wraps the core JerkPositionController and provides unit-typed interfaces for position (Length),
+/// velocity (Velocity), acceleration (Acceleration), and jerk (Jerk).
+///
+/// The planned speed can be used as the velocity command of a drive in velocity mode,
+/// for example a stepper that should stop softly at the end of every move.
+///
+/// # Example
+/// ```ignore
+/// let mut controller = LinearJerkPositionController::new(
+///     Velocity::new::<millimeter_per_second>(50.0),
+///     Acceleration::new::<millimeter_per_second_squared>(200.0),
+///     Jerk::new::<millimeter_per_second_cubed>(4000.0),
+///     Length::new::<millimeter>(0.01),
+/// )?;
+///
+/// // Control loop
+/// loop {
+///     controller.update(target_position, Instant::now());
+///     let velocity_command = controller.get_speed();
+/// }
+/// ```
+#[derive(Debug)]
+pub struct LinearJerkPositionController {
+    controller: JerkPositionController,
+    last_update: Option<Instant>,
+}
+
+impl LinearJerkPositionController {
+    /// Create a new linear position controller at rest at position 0
+    ///
+    /// # Parameters
+    /// * `max_speed` - Cruise speed of a move
+    /// * `max_acceleration` - Maximum acceleration and deceleration magnitude
+    /// * `max_jerk` - Maximum jerk magnitude
+    /// * `position_tolerance` - A stopped move within this distance snaps to the target
+    ///
+    /// # Errors
+    /// Returns MotionControllerError if the limits are invalid
+    pub fn new(
+        max_speed: Velocity,
+        max_acceleration: Acceleration,
+        max_jerk: Jerk,
+        position_tolerance: Length,
+    ) -> Result<Self, MotionControllerError> {
+        Ok(Self {
+            controller: JerkPositionController::new(
+                max_speed.get::<meter_per_second>(),
+                max_acceleration.get::<meter_per_second_squared>(),
+                max_jerk.get::<meter_per_second_cubed>(),
+                position_tolerance.get::<meter>(),
+            )?,
+            last_update: None,
+        })
+    }
+
+    /// Advance the trajectory towards `target_position`
+    ///
+    /// # Returns
+    /// The planned position at time `t`
+    pub fn update(&mut self, target_position: Length, t: Instant) -> Length {
+        // Calculate dt from the last update
+        let dt = if let Some(last_t) = self.last_update {
+            t.duration_since(last_t).as_secs_f64()
+        } else {
+            0.0 // First update, no time has passed
+        };
+        self.last_update = Some(t);
+
+        let position_raw = self.controller.update(target_position.get::<meter>(), dt);
+        Length::new::<meter>(position_raw)
+    }
+
+    /// Get the planned position
+    pub fn get_position(&self) -> Length {
+        Length::new::<meter>(self.controller.get_position())
+    }
+
+    /// Get the target position of the current move
+    pub fn get_target_position(&self) -> Length {
+        Length::new::<meter>(self.controller.get_target_position())
+    }
+
+    /// Get the planned speed
+    pub fn get_speed(&self) -> Velocity {
+        Velocity::new::<meter_per_second>(self.controller.get_speed())
+    }
+
+    /// Get the planned acceleration
+    pub fn get_acceleration(&self) -> Acceleration {
+        Acceleration::new::<meter_per_second_squared>(self.controller.get_acceleration())
+    }
+
+    /// Standing at the target position
+    pub fn is_at_target(&self) -> bool {
+        self.controller.is_at_target()
+    }
+
+    /// Get the cruise speed
+    pub fn get_max_speed(&self) -> Velocity {
+        Velocity::new::<meter_per_second>(self.controller.get_max_speed())
+    }
+
+    /// Set the cruise speed, takes effect during a move
+    pub fn set_max_speed(&mut self, max_speed: Velocity) {
+        self.controller
+            .set_max_speed(max_speed.get::<meter_per_second>());
+    }
+
+    /// Set the maximum acceleration magnitude
+    pub fn set_max_acceleration(
+        &mut self,
+        max_acceleration: Acceleration,
+    ) -> Result<(), MotionControllerError> {
+        self.controller
+            .set_max_acceleration(max_acceleration.get::<meter_per_second_squared>())
+    }
+
+    /// Set the maximum jerk magnitude
+    pub fn set_max_jerk(&mut self, max_jerk: Jerk) -> Result<(), MotionControllerError> {
+        self.controller
+            .set_max_jerk(max_jerk.get::<meter_per_second_cubed>())
+    }
+
+    /// Reset the controller to standstill at `position`
+    ///
+    /// The next update starts a new time base.
+    pub fn reset(&mut self, position: Length) {
+        self.controller.reset(position.get::<meter>());
+        self.last_update = None;
+    }
+}
@@ -1,6 +1,8 @@
 pub mod acceleration_position_controller;
 pub mod angular_acceleration_position_controller;
 pub mod angular_jerk_speed_controller;
+pub mod jerk_position_controller;
 pub mod jerk_speed_controller;
 pub mod linear_acceleration_position_controller;
+pub mod linear_jerk_position_controller;
 pub mod linear_jerk_speed_controller;
@@ -0,0 +1,145 @@
+//! Smith predictor for processes with a long dead time
+//!
+//! The predictor runs a [`FopdtModel`] of the process twice, with and without
+//! its dead time. The difference is added to the measurement:
+//!
+//! ```text
+//! y_compensated = y + y_model(t) - y_model(t - θ)
+//! ```
+//!
+//! so the controller sees the effect of its output right away instead of
+//! after the dead time. Model errors still show up in the measurement, so
+//! disturbances are corrected as usual.
+//!
+//! The predictor does not own a controller, feed the compensated measurement
+//! into any of them and report the output that was actually applied.
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use super::step_response_identifier::FopdtModel;
+
+#[derive(Debug)]
+pub struct SmithPredictor {
+    model: FopdtModel,
+    /// Output applied to the process since the last update
+    output: f64,
+    /// Model response without dead time
+    model_value: f64,
+    /// Model response over the last dead time, oldest first
+    history: VecDeque<(Instant, f64)>,
+    last: Option<Instant>,
+}
+
+impl SmithPredictor {
+    pub const fn new(model: FopdtModel) -> Self {
+        Self {
+            model,
+            output: 0.0,
+            model_value: 0.0,
+            history: VecDeque::new(),
+            last: None,
+        }
+    }
+
+    pub const fn get_model(&self) -> &FopdtModel {
+        &self.model
+    }
+
+    /// Report the output that was applied to the process, it drives the model until
+    /// the next call
+    pub const fn set_output(&mut self, output: f64) {
+        self.output = output;
+    }
+
+    /// Advance the model and return the measurement with the dead time compensated
+    pub fn update(&mut self, measurement: f64, t: Instant) -> f64 {
+        let dt = match self.last {
+            Some(last) => t.duration_since(last).as_secs_f64(),
+            None => 0.0,
+        };
+        self.last = Some(t);
+
+        // Exact discretization of the first order lag for a constant output over dt
+        let time_constant = self.model.time_constant.max(f64::EPSILON);
+        let alpha = 1.0 - (-dt / time_constant).exp();
+        self.model_value += (self.model.gain * self.output - self.model_value) * alpha;
+        self.history.push_back((t, self.model_value));
+
+        measurement + self.model_value - self.delayed_model_value(t)
+    }
+
+    /// Model response one dead time ago, drops history that is no longer needed
+    fn delayed_model_value(&mut self, t: Instant) -> f64 {
+        let dead_time = Duration::from_secs_f64(self.model.dead_time.max(0.0));
+        let Some(cutoff) = t.checked_sub(dead_time) else {
+            return self.history.front().map_or(0.0, |(_, value)| *value);
+        };
+
+        // Keep the newest sample at or before the cutoff
+        while self.history.len() > 1 && self.history[1].0 <= cutoff {
+            self.history.pop_front();
+        }
+        self.history.front().map_or(0.0, |(_, value)| *value)
+    }
+
+    /// Clear the model state, for example after the process was disturbed
+    pub fn reset(&mut self) {
+        self.output = 0.0;
+        self.model_value = 0.0;
+        self.history.clear();
+        self.last = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODEL: FopdtModel = FopdtModel {
+        gain: 2.0,
+        time_constant: 20.0,
+        dead_time: 5.0,
+    };
+
+    #[test]
+    fn test_without_output_passes_measurement() {
+        let mut predictor = SmithPredictor::new(MODEL);
+        let start = Instant::now();
+        for i in 0..20 {
+            let now = start + Duration::from_secs(i);
+            assert_eq!(predictor.update(25.0, now), 25.0);
+        }
+    }
+
+    #[test]
+    fn test_compensates_dead_time_of_exact_model() {
+        let mut predictor = SmithPredictor::new(MODEL);
+        let mut plant = SmithPredictor::new(MODEL);
+        let start = Instant::now();
+        let dt = Duration::from_millis(100);
+
+        // Step at t = 0, the plant only responds after the dead time
+        predictor.set_output(1.0);
+        plant.set_output(1.0);
+        for i in 0..600 {
+            let now = start + dt * i;
+            plant.update(0.0, now);
+            let delayed = plant.delayed_model_value(now);
+            let measurement = 20.0 + delayed;
+
+            let compensated = predictor.update(measurement, now);
+            let undelayed = 20.0 + predictor.model_value;
+            assert!((compensated - undelayed).abs() < 1e-9);
+        }
+
+        // Responds immediately, the measurement lags behind
+        let mut predictor = SmithPredictor::new(MODEL);
+        predictor.update(20.0, start);
+        predictor.set_output(1.0);
+        let compensated = predictor.update(20.0, start + Duration::from_secs(2));
+        assert!(compensated > 20.1);
+    }
+}
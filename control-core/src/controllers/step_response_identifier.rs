@@ -0,0 +1,479 @@
+//! Open-loop step-response identification with IMC (lambda) tuning
+//!
+//! Unlike the relay method in [`super::pid_autotuner`] the process is not
+//! oscillated. The output is held until a baseline is recorded, then raised
+//! by a single step and held again until the measurement settles.
+//!
+//! A first-order-plus-dead-time model
+//!
+//! ```text
+//! G(s) = K · e^(-θs) / (τs + 1)
+//! ```
+//!
+//! is fitted with the two-point method (28.3 % and 63.2 % of the response),
+//! and PID gains are derived from it with IMC/lambda tuning, where `lambda`
+//! is the desired closed-loop time constant.
+
+use std::time::{Duration, Instant};
+
+use super::pid_autotuner::AutoTuneError;
+
+/// Response fraction reached after θ + τ/3
+const FIT_POINT_LOW: f64 = 0.283;
+/// Response fraction reached after θ + τ
+const FIT_POINT_HIGH: f64 = 0.632;
+
+/// First-order-plus-dead-time process model
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FopdtModel {
+    /// Process gain in measurement units per output unit
+    pub gain: f64,
+    /// Time constant τ in s
+    pub time_constant: f64,
+    /// Dead time θ in s
+    pub dead_time: f64,
+}
+
+impl FopdtModel {
+    /// Fit the model to a step response.
+    ///
+    /// # Arguments
+    /// * `samples`   – `(time in s, measurement)` sorted by time
+    /// * `step_time` – time of the step in s, samples before it form the baseline
+    /// * `step`      – change of the output at `step_time`
+    ///
+    /// The last tenth of the samples is taken as the settled value, so the log
+    /// has to cover the whole response.
+    pub fn fit(samples: &[(f64, f64)], step_time: f64, step: f64) -> Option<Self> {
+        if step == 0.0 || !step.is_finite() {
+            return None;
+        }
+
+        let split = samples.partition_point(|(t, _)| *t < step_time);
+        let (baseline, response) = samples.split_at(split);
+        if baseline.is_empty() || response.len() < 10 {
+            return None;
+        }
+
+        let initial = mean(baseline.iter().map(|(_, y)| *y));
+        let settled = mean(response[response.len() * 9 / 10..].iter().map(|(_, y)| *y));
+        let delta = settled - initial;
+        if delta == 0.0 || !delta.is_finite() {
+            return None;
+        }
+
+        let t_low = crossing_time(response, initial, delta, FIT_POINT_LOW)? - step_time;
+        let t_high = crossing_time(response, initial, delta, FIT_POINT_HIGH)? - step_time;
+
+        let time_constant = 1.5 * (t_high - t_low);
+        let dead_time = (t_high - time_constant).max(0.0);
+        if time_constant <= 0.0 {
+            return None;
+        }
+
+        Some(Self {
+            gain: delta / step,
+            time_constant,
+            dead_time,
+        })
+    }
+
+    /// Finite, non-zero gain, positive time constant and non-negative dead time
+    pub fn is_valid(&self) -> bool {
+        self.gain.is_normal()
+            && self.time_constant.is_finite()
+            && self.time_constant > 0.0
+            && self.dead_time.is_finite()
+            && self.dead_time >= 0.0
+    }
+
+    /// IMC PI gains `(kp, ki, kd)` for the closed-loop time constant `lambda` in s
+    pub fn imc_pi(&self, lambda: f64) -> (f64, f64, f64) {
+        let kp = self.time_constant / (self.gain * (lambda + self.dead_time));
+        (kp, kp / self.time_constant, 0.0)
+    }
+
+    /// IMC PID gains `(kp, ki, kd)` for the closed-loop time constant `lambda` in s,
+    /// using a first order Padé approximation of the dead time
+    pub fn imc_pid(&self, lambda: f64) -> (f64, f64, f64) {
+        let half_dead_time = 0.5 * self.dead_time;
+        let ti = self.time_constant + half_dead_time;
+        let td = self.time_constant * half_dead_time / ti;
+        let kp = ti / (self.gain * (lambda + half_dead_time));
+        (kp, kp / ti, kp * td)
+    }
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), y| (sum + y, count + 1));
+    sum / count as f64
+}
+
+/// First time the response passes `fraction` of `delta`, linearly interpolated
+fn crossing_time(response: &[(f64, f64)], initial: f64, delta: f64, fraction: f64) -> Option<f64> {
+    let progress = |y: f64| (y - initial) / delta;
+    response.windows(2).find_map(|pair| {
+        let ((t0, y0), (t1, y1)) = (pair[0], pair[1]);
+        let (p0, p1) = (progress(y0), progress(y1));
+        if p0 < fraction && p1 >= fraction {
+            Some(t0 + (t1 - t0) * (fraction - p0) / (p1 - p0))
+        } else {
+            None
+        }
+    })
+}
+
+/// Configuration for a step test
+#[derive(Debug, Clone)]
+pub struct StepTestConfig {
+    /// Output change applied after the baseline (e.g. `0.1` for +10 % duty)
+    pub step: f64,
+    /// Time the output is held before the step to record the baseline
+    pub baseline_duration: Duration,
+    /// Samples are recorded at most this often
+    pub sample_interval: Duration,
+    /// The response counts as settled when it stays within this band
+    /// (measurement units) for `settle_duration`
+    pub settle_tolerance: f64,
+    pub settle_duration: Duration,
+    /// Maximum duration of the test before it is aborted
+    pub max_duration: Duration,
+}
+
+impl Default for StepTestConfig {
+    fn default() -> Self {
+        Self {
+            step: 0.1,
+            baseline_duration: Duration::from_secs(30),
+            sample_interval: Duration::from_secs(1),
+            settle_tolerance: 0.2,
+            settle_duration: Duration::from_secs(120),
+            max_duration: Duration::from_secs(3600),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StepTestState {
+    NotStarted,
+    Baseline,
+    Stepped,
+    Completed,
+    Failed,
+}
+
+/// Runs a step test in the control loop and fits a [`FopdtModel`] to it
+///
+/// # Usage
+/// ```rust,ignore
+/// let mut identifier = StepResponseIdentifier::new(StepTestConfig::default());
+/// identifier.start(now, current_duty);
+///
+/// // In the control loop:
+/// let duty = identifier.update(measured_value, now);
+/// if let Ok(model) = identifier.result() {
+///     let (kp, ki, kd) = model.imc_pid(lambda);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct StepResponseIdentifier {
+    config: StepTestConfig,
+    state: StepTestState,
+    initial_output: f64,
+    start_time: Option<Instant>,
+    last_sample: Option<Instant>,
+    /// `(time since start in s, measurement)`
+    samples: Vec<(f64, f64)>,
+    result: Option<FopdtModel>,
+}
+
+impl StepResponseIdentifier {
+    pub const fn new(config: StepTestConfig) -> Self {
+        Self {
+            config,
+            state: StepTestState::NotStarted,
+            initial_output: 0.0,
+            start_time: None,
+            last_sample: None,
+            samples: Vec::new(),
+            result: None,
+        }
+    }
+
+    /// Start the test, `output` is held during the baseline and the step is added to it
+    pub fn start(&mut self, now: Instant, output: f64) {
+        self.state = StepTestState::Baseline;
+        self.initial_output = output;
+        self.start_time = Some(now);
+        self.last_sample = None;
+        self.samples.clear();
+        self.result = None;
+    }
+
+    /// Abort the test (marks state as `Failed`)
+    pub fn stop(&mut self) {
+        if self.is_running() {
+            self.state = StepTestState::Failed;
+        }
+    }
+
+    /// Feed the current measurement and advance the test.
+    ///
+    /// Returns the output command, the initial output once the test has ended.
+    pub fn update(&mut self, measurement: f64, now: Instant) -> f64 {
+        let Some(start) = self.start_time else {
+            return self.initial_output;
+        };
+        if !self.is_running() {
+            return self.initial_output;
+        }
+
+        let elapsed = now.duration_since(start);
+        if elapsed > self.config.max_duration {
+            self.state = StepTestState::Failed;
+            return self.initial_output;
+        }
+
+        let sample_due = self
+            .last_sample
+            .is_none_or(|last| now.duration_since(last) >= self.config.sample_interval);
+        if sample_due {
+            self.samples.push((elapsed.as_secs_f64(), measurement));
+            self.last_sample = Some(now);
+        }
+
+        if self.state == StepTestState::Baseline && elapsed >= self.config.baseline_duration {
+            self.state = StepTestState::Stepped;
+        }
+
+        if self.state == StepTestState::Stepped && self.is_settled() {
+            self.result = FopdtModel::fit(
+                &self.samples,
+                self.config.baseline_duration.as_secs_f64(),
+                self.config.step,
+            );
+            self.state = match self.result {
+                Some(_) => StepTestState::Completed,
+                None => StepTestState::Failed,
+            };
+            return self.initial_output;
+        }
+
+        match self.state {
+            StepTestState::Stepped => self.initial_output + self.config.step,
+            _ => self.initial_output,
+        }
+    }
+
+    /// The step response stayed within the settle band for the settle duration
+    fn is_settled(&self) -> bool {
+        let step_time = self.config.baseline_duration.as_secs_f64();
+        let settle_duration = self.config.settle_duration.as_secs_f64();
+        let Some(&(last_time, _)) = self.samples.last() else {
+            return false;
+        };
+        if last_time - step_time < 2.0 * settle_duration {
+            return false;
+        }
+
+        let window_start = last_time - settle_duration;
+        let (min, max) = self
+            .samples
+            .iter()
+            .rev()
+            .take_while(|(t, _)| *t >= window_start)
+            .fold((f64::MAX, f64::MIN), |(min, max), (_, y)| {
+                (min.min(*y), max.max(*y))
+            });
+        max - min <= self.config.settle_tolerance
+    }
+
+    /// Returns `true` while the baseline is recorded or the step is applied
+    pub fn is_running(&self) -> bool {
+        matches!(self.state, StepTestState::Baseline | StepTestState::Stepped)
+    }
+
+    pub fn is_completed(&self) -> bool {
+        self.state == StepTestState::Completed
+    }
+
+    pub fn is_failed(&self) -> bool {
+        self.state == StepTestState::Failed
+    }
+
+    /// Current state as a static string slice
+    pub fn state(&self) -> &str {
+        match self.state {
+            StepTestState::NotStarted => "not_started",
+            StepTestState::Baseline => "baseline",
+            StepTestState::Stepped => "stepped",
+            StepTestState::Completed => "completed",
+            StepTestState::Failed => "failed",
+        }
+    }
+
+    pub fn result(&self) -> Result<&FopdtModel, AutoTuneError> {
+        match self.state {
+            StepTestState::Completed => self.result.as_ref().ok_or(AutoTuneError::Failed),
+            StepTestState::Failed => Err(AutoTuneError::Failed),
+            StepTestState::Baseline | StepTestState::Stepped => Err(AutoTuneError::Running),
+            StepTestState::NotStarted => Err(AutoTuneError::NotStarted),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAIN: f64 = 2.0;
+    const TIME_CONSTANT: f64 = 50.0;
+    const DEAD_TIME: f64 = 10.0;
+
+    /// Exact FOPDT step response to a unit step at `step_time`
+    fn response(t: f64, step_time: f64) -> f64 {
+        let t = t - step_time - DEAD_TIME;
+        if t <= 0.0 {
+            20.0
+        } else {
+            20.0 + GAIN * (1.0 - (-t / TIME_CONSTANT).exp())
+        }
+    }
+
+    fn assert_model(model: &FopdtModel) {
+        assert!((model.gain - GAIN).abs() < 0.05, "{model:?}");
+        assert!(
+            (model.time_constant - TIME_CONSTANT).abs() < 2.0,
+            "{model:?}"
+        );
+        assert!((model.dead_time - DEAD_TIME).abs() < 2.0, "{model:?}");
+    }
+
+    #[test]
+    fn test_fit_logged_step() {
+        let samples: Vec<(f64, f64)> = (0..600)
+            .map(|i| {
+                let t = i as f64;
+                (t, response(t, 30.0))
+            })
+            .collect();
+
+        let model = FopdtModel::fit(&samples, 30.0, 1.0).unwrap();
+        assert_model(&model);
+    }
+
+    #[test]
+    fn test_fit_rejects_flat_response() {
+        let samples: Vec<(f64, f64)> = (0..100).map(|i| (i as f64, 20.0)).collect();
+        assert!(FopdtModel::fit(&samples, 10.0, 1.0).is_none());
+        assert!(FopdtModel::fit(&samples, 10.0, 0.0).is_none());
+    }
+
+    #[test]
+    fn test_imc_gains() {
+        let model = FopdtModel {
+            gain: GAIN,
+            time_constant: TIME_CONSTANT,
+            dead_time: DEAD_TIME,
+        };
+
+        let (kp, ki, kd) = model.imc_pi(20.0);
+        assert!((kp - 50.0 / (2.0 * 30.0)).abs() < 1e-9);
+        assert!((ki - kp / 50.0).abs() < 1e-9);
+        assert_eq!(kd, 0.0);
+
+        let (kp, ki, kd) = model.imc_pid(20.0);
+        assert!((kp - 55.0 / (2.0 * 25.0)).abs() < 1e-9);
+        assert!((ki - kp / 55.0).abs() < 1e-9);
+        assert!((kd - kp * 50.0 * 5.0 / 55.0).abs() < 1e-9);
+
+        // a slower closed loop gives a softer controller
+        assert!(model.imc_pid(60.0).0 < kp);
+    }
+
+    #[test]
+    fn test_model_validation() {
+        let model = FopdtModel {
+            gain: GAIN,
+            time_constant: TIME_CONSTANT,
+            dead_time: DEAD_TIME,
+        };
+        assert!(model.is_valid());
+        assert!(!FopdtModel { gain: 0.0, ..model }.is_valid());
+        assert!(
+            !FopdtModel {
+                time_constant: 0.0,
+                ..model
+            }
+            .is_valid()
+        );
+        assert!(
+            !FopdtModel {
+                dead_time: -1.0,
+                ..model
+            }
+            .is_valid()
+        );
+        assert!(
+            !FopdtModel {
+                gain: f64::NAN,
+                ..model
+            }
+            .is_valid()
+        );
+    }
+
+    #[test]
+    fn test_identifier_step_test() {
+        let config = StepTestConfig {
+            step: 1.0,
+            baseline_duration: Duration::from_secs(30),
+            sample_interval: Duration::from_secs(1),
+            settle_tolerance: 0.05,
+            settle_duration: Duration::from_secs(120),
+            max_duration: Duration::from_secs(3600),
+        };
+        let mut identifier = StepResponseIdentifier::new(config);
+        let start = Instant::now();
+        identifier.start(start, 0.0);
+
+        let mut step_time = None;
+        for i in 0..3600 {
+            let now = start + Duration::from_secs(i);
+            let t = i as f64;
+            let measurement = match step_time {
+                Some(step_time) => response(t, step_time),
+                None => 20.0,
+            };
+            let output = identifier.update(measurement, now);
+            if output > 0.0 && step_time.is_none() {
+                step_time = Some(t);
+            }
+            if !identifier.is_running() {
+                break;
+            }
+        }
+
+        assert_eq!(identifier.state(), "completed");
+        assert_model(identifier.result().unwrap());
+        assert_eq!(identifier.update(20.0, start), 0.0);
+    }
+
+    #[test]
+    fn test_identifier_timeout() {
+        let config = StepTestConfig {
+            max_duration: Duration::from_secs(10),
+            ..StepTestConfig::default()
+        };
+        let mut identifier = StepResponseIdentifier::new(config);
+        let start = Instant::now();
+        identifier.start(start, 0.5);
+
+        assert_eq!(
+            identifier.update(20.0, start + Duration::from_secs(11)),
+            0.5
+        );
+        assert!(identifier.is_failed());
+        assert_eq!(identifier.result(), Err(AutoTuneError::Failed));
+    }
+}
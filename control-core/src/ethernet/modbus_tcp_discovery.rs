@@ -5,8 +5,14 @@ use interfaces::Interface;
 use std::cmp::min;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
+/// Device that answered the identification request of a scan
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ModbusTcpProbe {
     pub addr: SocketAddr,
+    /// Module number of the device, e.g. `0x2787_2144` for WAGO 2787-2144
+    pub module_number: u32,
+    /// Variant of the module number, `0` for the standard variant
+    pub module_variant: u32,
     pub serial: u16,
 }
 
@@ -62,17 +68,19 @@ async fn probe_modbus_tcp_addresses(interface: Interface) -> Vec<ModbusTcpProbe>
 async fn ping_modbus_device(addr: SocketAddr) -> Result<ModbusTcpProbe> {
     let mut device = ModbusTcpDevice::new(addr).await?;
 
-    let module_number1 = device.get_u32(0x2).await?;
-    let module_number2 = device.get_u32(0x4).await?;
-
-    if module_number1 != 0x2787_2144 || module_number2 != 0x0000_0000 {
-        bail!("Unknown modbus tcp device!");
+    // The caller decides from the module number which devices it knows
+    let module_number = device.get_u32(0x2).await?;
+    let module_variant = device.get_u32(0x4).await?;
+    if module_number == 0 {
+        bail!("Modbus TCP device without module number");
     }
 
     let serial = device.get_u32(0x000A).await?;
 
     Ok(ModbusTcpProbe {
         addr,
+        module_number,
+        module_variant,
         serial: serial as u16,
     })
 }
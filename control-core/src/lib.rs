@@ -2,7 +2,7 @@ pub mod controllers;
 pub mod converters;
 pub mod downcast;
 //pub mod ethercat;
-//pub mod ethernet;
+pub mod ethernet;
 pub mod futures;
 pub mod helpers;
 pub mod irq_handling;
@@ -1,6 +1,10 @@
+use serde::{Deserialize, Serialize};
+
 use crate::transmission::Transmission;
 
-#[derive(Debug, Clone)]
+/// Serialized as the bare ratio
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct FixedTransmission {
     ratio: f64,
 }
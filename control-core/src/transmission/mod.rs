@@ -7,6 +7,7 @@ use qitech_lib::units::{
 };
 
 pub mod fixed;
+pub mod multi_stage;
 
 /// A trait representing a mechanical transmission system that converts input to output
 /// with a specific gear ratio.
@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+
+use crate::transmission::{Transmission, fixed::FixedTransmission};
+
+/// Transmission of several stages in series, e.g. a belt followed by a gearbox.
+///
+/// The ratio is the product of the stage ratios. Without stages the output is
+/// coupled directly to the input.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct MultiStageTransmission {
+    /// Stages from the input to the output
+    stages: Vec<FixedTransmission>,
+}
+
+impl Transmission for MultiStageTransmission {
+    fn get_ratio(&self) -> f64 {
+        self.stages.iter().map(Transmission::get_ratio).product()
+    }
+}
+
+impl MultiStageTransmission {
+    /// Creates a new MultiStageTransmission from its stages, ordered from input to output.
+    pub const fn new(stages: Vec<FixedTransmission>) -> Self {
+        Self { stages }
+    }
+
+    /// Output coupled directly to the input.
+    pub const fn direct() -> Self {
+        Self { stages: Vec::new() }
+    }
+
+    /// Single stage reducing the speed by `reduction`, e.g. `5.0` for 1:5.
+    pub fn reduction(reduction: f64) -> Self {
+        Self::new(vec![FixedTransmission::new(1.0 / reduction)])
+    }
+
+    pub fn get_stages(&self) -> &[FixedTransmission] {
+        &self.stages
+    }
+
+    /// All stage ratios are finite and positive.
+    pub fn is_valid(&self) -> bool {
+        self.stages
+            .iter()
+            .all(|stage| stage.get_ratio().is_finite() && stage.get_ratio() > 0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qitech_lib::units::{f64::Velocity, velocity::meter_per_minute};
+
+    #[test]
+    fn test_stages() {
+        assert_eq!(MultiStageTransmission::direct().get_ratio(), 1.0);
+
+        // Belt doubling the speed in front of a 1:10 gearbox
+        let transmission = MultiStageTransmission::new(vec![
+            FixedTransmission::new(2.0),
+            FixedTransmission::new(0.1),
+        ]);
+        assert!((transmission.get_ratio() - 0.2).abs() < 1e-12);
+        let input = transmission
+            .calculate_linear_velocity_input(Velocity::new::<meter_per_minute>(10.0))
+            .get::<meter_per_minute>();
+        assert!((input - 50.0).abs() < 1e-9);
+
+        assert!(!MultiStageTransmission::reduction(0.0).is_valid());
+        assert!(!MultiStageTransmission::new(vec![FixedTransmission::new(-1.0)]).is_valid());
+    }
+}
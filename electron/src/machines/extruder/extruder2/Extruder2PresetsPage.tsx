@@ -8,6 +8,7 @@ import {
   previewSeparator,
 } from "@/components/preset/PresetPreviewTable";
 import { Preset } from "@/lib/preset/preset";
+import { migratePressurePidGains } from "../pressurePidMigration";
 
 const extruder1PresetDataSchema = z
   .object({
@@ -33,7 +34,10 @@ const extruder1PresetDataSchema = z
 
 type Extruder2 = z.infer<typeof extruder1PresetDataSchema>;
 
-const schemas = new Map([[1, extruder1PresetDataSchema]]);
+const schemas = new Map([
+  [1, extruder1PresetDataSchema.transform(migratePressurePidGains)],
+  [2, extruder1PresetDataSchema],
+]);
 
 const previewEntries: PresetPreviewEntries<Extruder2> = [
   {
@@ -177,9 +181,9 @@ export function Extruder2PresetsPage() {
     setExtruderPressureLimit(preset?.data?.pressureLimit ?? 100.0);
     setExtruderPressureLimitEnabled(preset?.data?.pressureLimitEnabled ?? true);
 
-    setPressurePidKp(preset?.data?.pidPressureKp ?? 0.16);
-    setPressurePidKi(preset?.data?.pidPressureKi ?? 0.0);
-    setPressurePidKd(preset?.data?.pidPressureKd ?? 0.008);
+    setPressurePidKp(preset?.data?.pidPressureKp ?? 0.008);
+    setPressurePidKi(preset?.data?.pidPressureKi ?? 0.16);
+    setPressurePidKd(preset?.data?.pidPressureKd ?? 0.0);
   };
 
   return (
@@ -187,7 +191,7 @@ export function Extruder2PresetsPage() {
       machine_identification={extruder2.machine_identification}
       currentState={toPresetData(state)}
       schemas={schemas}
-      schemaVersion={1}
+      schemaVersion={2}
       applyPreset={applyPreset}
       previewEntries={previewEntries}
       defaultState={defaults}
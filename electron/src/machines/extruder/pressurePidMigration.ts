@@ -0,0 +1,39 @@
+import { toastError } from "@/components/Toast";
+
+type PressurePidPresetData = {
+  pidPressureKp?: number;
+  pidPressureKi?: number;
+  pidPressureKd?: number;
+};
+
+/**
+ * Converts pressure PID gains of presets with schema version 1.
+ *
+ * Up to version 1 the pressure loop changed the inverter frequency by its output
+ * every cycle, so Kp acted as an integral gain and Kd as a proportional gain.
+ * Since version 2 the loop sets the screw speed directly, Kp in Hz/bar and
+ * Ki in Hz/(bar·s). The old Ki integrated twice and has no equivalent, it is dropped.
+ */
+export function migratePressurePidGains<T extends PressurePidPresetData>(
+  data: T,
+): T {
+  if (
+    data.pidPressureKp === undefined &&
+    data.pidPressureKi === undefined &&
+    data.pidPressureKd === undefined
+  ) {
+    return data;
+  }
+
+  toastError(
+    "Pressure PID Gains Converted",
+    `A preset stored pressure PID gains of the old regulation (Kp ${data.pidPressureKp ?? 0}, Ki ${data.pidPressureKi ?? 0}, Kd ${data.pidPressureKd ?? 0}). They were converted to Kp ${data.pidPressureKd ?? 0}, Ki ${data.pidPressureKp ?? 0}, Kd 0. Please check the pressure regulation and re-tune if needed.`,
+  );
+
+  return {
+    ...data,
+    pidPressureKp: data.pidPressureKd ?? 0,
+    pidPressureKi: data.pidPressureKp ?? 0,
+    pidPressureKd: 0,
+  };
+}
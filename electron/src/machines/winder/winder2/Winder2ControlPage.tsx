@@ -17,7 +17,7 @@ import {
   Mode,
   PullerRegulation,
   SpoolAutomaticActionMode,
-  getGearRatioMultiplier,
+  getTransmissionRatio,
 } from "./winder2Namespace";
 import { TensionArm } from "../TensionArm";
 import { roundDegreesToDecimals, roundToDecimals } from "@/lib/decimal";
@@ -63,12 +63,12 @@ export function Winder2ControlPage() {
     isDisabled,
   } = useWinder2();
 
-  // Calculate max speed based on gear ratio
-  const gearRatioMultiplier = getGearRatioMultiplier(
-    state?.puller_state?.gear_ratio,
+  // Calculate max speed based on the puller transmission
+  const transmissionRatio = getTransmissionRatio(
+    state?.puller_state?.transmission,
   );
   const maxMotorSpeed = 50; // Maximum motor speed in m/min
-  const maxTargetSpeed = maxMotorSpeed / gearRatioMultiplier;
+  const maxTargetSpeed = maxMotorSpeed * transmissionRatio;
 
   const handleResetProgress = () => {
     // Check if the machine is currently in Wind mode
@@ -282,6 +282,12 @@ export function Winder2ControlPage() {
                         disabled:
                           !state?.puller_state?.adaptive_reference_machine,
                       },
+                      Predictive: {
+                        children: "Predictive",
+                        icon: "lu:Timer",
+                        disabled:
+                          !state?.puller_state?.adaptive_reference_machine,
+                      },
                     }
                   : {}),
               }}
@@ -318,7 +324,8 @@ export function Winder2ControlPage() {
             </>
           )}
 
-          {state?.puller_state?.regulation === "Diameter" && (
+          {(state?.puller_state?.regulation === "Diameter" ||
+            state?.puller_state?.regulation === "Predictive") && (
             <Label label="Base Speed">
               <div className="flex flex-row items-center gap-2 py-4">
                 <span className="font-mono text-4xl font-bold">
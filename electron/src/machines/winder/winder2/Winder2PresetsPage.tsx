@@ -6,6 +6,9 @@ import { getWinder2AdaptivePullerSpeed } from "./winder2Config";
 import { PresetsPage } from "@/components/preset/PresetsPage";
 import { Preset } from "@/lib/preset/preset";
 import {
+  gearRatioSchema,
+  getGearRatioTransmission,
+  getTransmissionRatio,
   pullerStateSchema,
   spoolSpeedControllerStateSchema,
 } from "./winder2Namespace";
@@ -26,7 +29,10 @@ const winder2PresetDataSchema = z
         laserpointer: z.boolean(),
       })
       .partial(),
-    puller_state: pullerStateSchema.partial(),
+    puller_state: pullerStateSchema
+      // gear ratio of presets saved before the transmission
+      .extend({ gear_ratio: gearRatioSchema })
+      .partial(),
     spool_speed_controller_state: spoolSpeedControllerStateSchema.partial(),
   })
   .partial();
@@ -70,13 +76,15 @@ const previewEntries: PresetPreviewEntries<Winder2> = [
       data.puller_state?.forward ? "Forward" : "Backward",
   },
   {
-    name: "Puller Gear Ratio",
+    name: "Puller Transmission Ratio",
     renderValue: (data: Winder2) => {
-      const ratio = data.puller_state?.gear_ratio;
-      if (ratio === "OneToOne") return "1:1";
-      if (ratio === "OneToFive") return "1:5";
-      if (ratio === "OneToTen") return "1:10";
-      return "N/A";
+      const transmission =
+        data.puller_state?.transmission ??
+        (data.puller_state?.gear_ratio &&
+          getGearRatioTransmission(data.puller_state.gear_ratio));
+      if (!transmission) return "N/A";
+      const reduction = 1 / getTransmissionRatio(transmission);
+      return `1:${Number(reduction.toFixed(2))}`;
     },
   },
   {
@@ -174,7 +182,7 @@ export function Winder2PresetsPage() {
     setPullerRegulationMode,
     setPullerForward,
     setPullerTargetSpeed,
-    setPullerGearRatio,
+    setPullerTransmission,
 
     setSpoolRegulationMode,
     setSpoolForward,
@@ -200,13 +208,18 @@ export function Winder2PresetsPage() {
     const presetRegulation = preset.data?.puller_state?.regulation ?? "Speed";
     const adaptivePullerEnabled = getWinder2AdaptivePullerSpeed();
     setPullerRegulationMode(
-      presetRegulation === "Diameter" && !adaptivePullerEnabled
+      presetRegulation !== "Speed" && !adaptivePullerEnabled
         ? "Speed"
         : presetRegulation,
     );
     setPullerForward(preset.data?.puller_state?.forward ?? true);
     setPullerTargetSpeed(preset.data?.puller_state?.target_speed ?? 1.0);
-    setPullerGearRatio(preset.data?.puller_state?.gear_ratio ?? "OneToOne");
+    setPullerTransmission(
+      preset.data?.puller_state?.transmission ??
+        getGearRatioTransmission(
+          preset.data?.puller_state?.gear_ratio ?? "OneToOne",
+        ),
+    );
     // setPullerTargetDiameter(preset.data?.puller_state?.target_diameter ?? 1.75);
 
     setSpoolRegulationMode(
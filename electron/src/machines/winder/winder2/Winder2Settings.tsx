@@ -18,6 +18,11 @@ import {
   setWinder2AdaptivePullerSpeed,
 } from "./winder2Config";
 import { Alert, AlertDescription, AlertTitle } from "@/components/ui/alert";
+import {
+  GearRatio,
+  getGearRatioTransmission,
+  getTransmissionGearRatio,
+} from "./winder2Namespace";
 
 function fractionToPercent(value: number | null | undefined) {
   return value == null ? undefined : value * 100;
@@ -38,7 +43,7 @@ export function Winder2SettingPage() {
     setTraverseLimitOuter,
     gotoTraverseHome,
     setPullerForward,
-    setPullerGearRatio,
+    setPullerTransmission,
     setSpoolRegulationMode,
     setSpoolMinMaxMinSpeed,
     setSpoolMinMaxMaxSpeed,
@@ -53,6 +58,10 @@ export function Winder2SettingPage() {
     setPullerAdaptiveStepPercent,
     setPullerAdaptiveAcceptedDifference,
     setPullerAdaptiveReferenceMachine,
+    setPullerPredictiveExtruderToLaser,
+    setPullerPredictiveLaserToPuller,
+    setPullerPredictiveProportionalGain,
+    setPullerPredictiveIntegralGain,
     filteredMachines,
     selectedMachine,
     isLoading,
@@ -342,7 +351,9 @@ export function Winder2SettingPage() {
           </Label>
           <Label label="Gear Ratio">
             <SelectionGroup
-              value={state?.puller_state?.gear_ratio}
+              value={getTransmissionGearRatio(
+                state?.puller_state?.transmission,
+              )}
               disabled={isDisabled}
               loading={isLoading}
               options={{
@@ -360,8 +371,8 @@ export function Winder2SettingPage() {
                 },
               }}
               onChange={(value) =>
-                setPullerGearRatio(
-                  value as "OneToOne" | "OneToFive" | "OneToTen",
+                setPullerTransmission(
+                  getGearRatioTransmission(value as GearRatio),
                 )
               }
             />
@@ -377,7 +388,8 @@ export function Winder2SettingPage() {
                 icon: "lu:X",
                 disabled:
                   adaptivePullerSpeed &&
-                  state?.puller_state?.regulation === "Diameter",
+                  (state?.puller_state?.regulation === "Diameter" ||
+                    state?.puller_state?.regulation === "Predictive"),
               }}
               optionTrue={{
                 children: "Enabled",
@@ -501,6 +513,73 @@ export function Winder2SettingPage() {
               </Label>
             </ControlCard>
           )}
+
+          {adaptivePullerSpeed && (
+            <ControlCard title="Predictive Speed">
+              <Label label="Extruder to Laser">
+                <EditValue
+                  value={state?.puller_state?.predictive_extruder_to_laser}
+                  title={"Extruder to Laser"}
+                  unit="m"
+                  step={0.1}
+                  min={0}
+                  max={20}
+                  defaultValue={
+                    defaultState?.puller_state?.predictive_extruder_to_laser
+                  }
+                  renderValue={(value) => roundToDecimals(value, 2)}
+                  onChange={(value) =>
+                    setPullerPredictiveExtruderToLaser(value)
+                  }
+                />
+              </Label>
+              <Label label="Laser to Puller">
+                <EditValue
+                  value={state?.puller_state?.predictive_laser_to_puller}
+                  title={"Laser to Puller"}
+                  unit="m"
+                  step={0.1}
+                  min={0}
+                  max={20}
+                  defaultValue={
+                    defaultState?.puller_state?.predictive_laser_to_puller
+                  }
+                  renderValue={(value) => roundToDecimals(value, 2)}
+                  onChange={(value) => setPullerPredictiveLaserToPuller(value)}
+                />
+              </Label>
+              <Label label="Proportional Gain">
+                <EditValue
+                  value={state?.puller_state?.predictive_proportional_gain}
+                  title={"Proportional Gain"}
+                  step={0.01}
+                  min={0}
+                  max={10}
+                  defaultValue={
+                    defaultState?.puller_state?.predictive_proportional_gain
+                  }
+                  renderValue={(value) => roundToDecimals(value, 2)}
+                  onChange={(value) =>
+                    setPullerPredictiveProportionalGain(value)
+                  }
+                />
+              </Label>
+              <Label label="Integral Gain">
+                <EditValue
+                  value={state?.puller_state?.predictive_integral_gain}
+                  title={"Integral Gain"}
+                  step={0.001}
+                  min={0}
+                  max={1}
+                  defaultValue={
+                    defaultState?.puller_state?.predictive_integral_gain
+                  }
+                  renderValue={(value) => roundToDecimals(value, 3)}
+                  onChange={(value) => setPullerPredictiveIntegralGain(value)}
+                />
+              </Label>
+            </ControlCard>
+          )}
         </ControlCard>
       </ControlGrid>
     </Page>
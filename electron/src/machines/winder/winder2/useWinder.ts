@@ -19,8 +19,8 @@ import {
   PullerRegulation,
   SpoolAutomaticActionMode,
   spoolAutomaticActionModeSchema,
-  gearRatioSchema,
-  GearRatio,
+  transmissionSchema,
+  Transmission,
 } from "./winder2Namespace";
 import { useEffect, useMemo } from "react";
 import { produce } from "immer";
@@ -120,8 +120,8 @@ export function useWinder2() {
   const { request: requestPullerSetForward } = useMachineMutation(
     z.object({ SetPullerForward: z.boolean() }),
   );
-  const { request: requestPullerSetGearRatio } = useMachineMutation(
-    z.object({ SetPullerGearRatio: gearRatioSchema }),
+  const { request: requestPullerSetTransmission } = useMachineMutation(
+    z.object({ SetPullerTransmission: transmissionSchema }),
   );
   const { request: requestSpoolSetRegulationMode } = useMachineMutation(
     z.object({ SetSpoolRegulationMode: spoolRegulationModeSchema }),
@@ -347,10 +347,10 @@ export function useWinder2() {
     );
   };
 
-  const setPullerGearRatio = (gearRatio: GearRatio) => {
+  const setPullerTransmission = (transmission: Transmission) => {
     updateStateOptimistically(
       (current) => {
-        current.data.puller_state.gear_ratio = gearRatio;
+        current.data.puller_state.transmission = transmission;
         // Reset target speed to 0 to prevent sudden speed changes
         current.data.puller_state.target_speed = 0;
       },
@@ -360,10 +360,10 @@ export function useWinder2() {
           machine_identification_unique: machineIdentification,
           data: { SetPullerTargetSpeed: 0 },
         });
-        // Then set the gear ratio
-        await requestPullerSetGearRatio({
+        // Then set the transmission
+        await requestPullerSetTransmission({
           machine_identification_unique: machineIdentification,
-          data: { SetPullerGearRatio: gearRatio },
+          data: { SetPullerTransmission: transmission },
         });
       },
     );
@@ -558,6 +558,23 @@ export function useWinder2() {
       }),
     );
 
+  const { request: requestPullerSetPredictiveExtruderToLaser } =
+    useMachineMutation(
+      z.object({ SetPullerPredictiveExtruderToLaser: z.number() }),
+    );
+  const { request: requestPullerSetPredictiveLaserToPuller } =
+    useMachineMutation(
+      z.object({ SetPullerPredictiveLaserToPuller: z.number() }),
+    );
+  const { request: requestPullerSetPredictiveProportionalGain } =
+    useMachineMutation(
+      z.object({ SetPullerPredictiveProportionalGain: z.number() }),
+    );
+  const { request: requestPullerSetPredictiveIntegralGain } =
+    useMachineMutation(
+      z.object({ SetPullerPredictiveIntegralGain: z.number() }),
+    );
+
   // more boilerplate junk setters from requests
   const setPullerAdaptiveMaxSpeedChangePercent = (percent: number) => {
     updateStateOptimistically(
@@ -632,6 +649,58 @@ export function useWinder2() {
     );
   };
 
+  const setPullerPredictiveExtruderToLaser = (meters: number) => {
+    updateStateOptimistically(
+      (current) => {
+        current.data.puller_state.predictive_extruder_to_laser = meters;
+      },
+      () =>
+        requestPullerSetPredictiveExtruderToLaser({
+          machine_identification_unique: machineIdentification,
+          data: { SetPullerPredictiveExtruderToLaser: meters },
+        }),
+    );
+  };
+
+  const setPullerPredictiveLaserToPuller = (meters: number) => {
+    updateStateOptimistically(
+      (current) => {
+        current.data.puller_state.predictive_laser_to_puller = meters;
+      },
+      () =>
+        requestPullerSetPredictiveLaserToPuller({
+          machine_identification_unique: machineIdentification,
+          data: { SetPullerPredictiveLaserToPuller: meters },
+        }),
+    );
+  };
+
+  const setPullerPredictiveProportionalGain = (gain: number) => {
+    updateStateOptimistically(
+      (current) => {
+        current.data.puller_state.predictive_proportional_gain = gain;
+      },
+      () =>
+        requestPullerSetPredictiveProportionalGain({
+          machine_identification_unique: machineIdentification,
+          data: { SetPullerPredictiveProportionalGain: gain },
+        }),
+    );
+  };
+
+  const setPullerPredictiveIntegralGain = (gain: number) => {
+    updateStateOptimistically(
+      (current) => {
+        current.data.puller_state.predictive_integral_gain = gain;
+      },
+      () =>
+        requestPullerSetPredictiveIntegralGain({
+          machine_identification_unique: machineIdentification,
+          data: { SetPullerPredictiveIntegralGain: gain },
+        }),
+    );
+  };
+
   // Calculate loading states
   const isLoading = stateOptimistic.isOptimistic;
   const isDisabled = !stateOptimistic.isInitialized;
@@ -699,7 +768,7 @@ export function useWinder2() {
     setPullerTargetSpeed,
     setPullerRegulationMode,
     setPullerForward,
-    setPullerGearRatio,
+    setPullerTransmission,
     setSpoolAutomaticRequiredMeters,
     setSpoolAutomaticAction,
     setSpoolRegulationMode,
@@ -718,5 +787,9 @@ export function useWinder2() {
     setPullerAdaptiveStepPercent,
     setPullerAdaptiveAcceptedDifference,
     setPullerAdaptiveReferenceMachine,
+    setPullerPredictiveExtruderToLaser,
+    setPullerPredictiveLaserToPuller,
+    setPullerPredictiveProportionalGain,
+    setPullerPredictiveIntegralGain,
   };
 }
@@ -17,7 +17,7 @@ import {
   Mode,
   PullerRegulation,
   SpoolAutomaticActionMode,
-  getGearRatioMultiplier,
+  getTransmissionRatio,
 } from "./winder2Namespace";
 import { TensionArm } from "../TensionArm";
 import { roundDegreesToDecimals, roundToDecimals } from "@/lib/decimal";
@@ -63,12 +63,12 @@ export function Winder2_7031ControlPage() {
     isDisabled,
   } = useWinder2();
 
-  // Calculate max speed based on gear ratio
-  const gearRatioMultiplier = getGearRatioMultiplier(
-    state?.puller_state?.gear_ratio,
+  // Calculate max speed based on the puller transmission
+  const transmissionRatio = getTransmissionRatio(
+    state?.puller_state?.transmission,
   );
   const maxMotorSpeed = 50; // Maximum motor speed in m/min
-  const maxTargetSpeed = maxMotorSpeed / gearRatioMultiplier;
+  const maxTargetSpeed = maxMotorSpeed * transmissionRatio;
 
   const handleResetProgress = () => {
     // Check if the machine is currently in Wind mode
@@ -18,6 +18,11 @@ import {
   setWinder2AdaptivePullerSpeed,
 } from "./winder2Config";
 import { Alert, AlertDescription, AlertTitle } from "@/components/ui/alert";
+import {
+  GearRatio,
+  getGearRatioTransmission,
+  getTransmissionGearRatio,
+} from "./winder2Namespace";
 
 function fractionToPercent(value: number | null | undefined) {
   return value == null ? undefined : value * 100;
@@ -38,7 +43,7 @@ export function Winder2_7031SettingPage() {
     setTraverseLimitOuter,
     gotoTraverseHome,
     setPullerForward,
-    setPullerGearRatio,
+    setPullerTransmission,
     setSpoolRegulationMode,
     setSpoolMinMaxMinSpeed,
     setSpoolMinMaxMaxSpeed,
@@ -342,7 +347,9 @@ export function Winder2_7031SettingPage() {
           </Label>
           <Label label="Gear Ratio">
             <SelectionGroup
-              value={state?.puller_state?.gear_ratio}
+              value={getTransmissionGearRatio(
+                state?.puller_state?.transmission,
+              )}
               disabled={isDisabled}
               loading={isLoading}
               options={{
@@ -360,8 +367,8 @@ export function Winder2_7031SettingPage() {
                 },
               }}
               onChange={(value) =>
-                setPullerGearRatio(
-                  value as "OneToOne" | "OneToFive" | "OneToTen",
+                setPullerTransmission(
+                  getGearRatioTransmission(value as GearRatio),
                 )
               }
             />
@@ -19,8 +19,8 @@ import {
   PullerRegulation,
   SpoolAutomaticActionMode,
   spoolAutomaticActionModeSchema,
-  gearRatioSchema,
-  GearRatio,
+  transmissionSchema,
+  Transmission,
 } from "./winder2Namespace";
 import { useEffect, useMemo } from "react";
 import { produce } from "immer";
@@ -119,8 +119,8 @@ export function useWinder2() {
   const { request: requestPullerSetForward } = useMachineMutation(
     z.object({ SetPullerForward: z.boolean() }),
   );
-  const { request: requestPullerSetGearRatio } = useMachineMutation(
-    z.object({ SetPullerGearRatio: gearRatioSchema }),
+  const { request: requestPullerSetTransmission } = useMachineMutation(
+    z.object({ SetPullerTransmission: transmissionSchema }),
   );
   const { request: requestSpoolSetRegulationMode } = useMachineMutation(
     z.object({ SetSpoolRegulationMode: spoolRegulationModeSchema }),
@@ -346,10 +346,10 @@ export function useWinder2() {
     );
   };
 
-  const setPullerGearRatio = (gearRatio: GearRatio) => {
+  const setPullerTransmission = (transmission: Transmission) => {
     updateStateOptimistically(
       (current) => {
-        current.data.puller_state.gear_ratio = gearRatio;
+        current.data.puller_state.transmission = transmission;
         // Reset target speed to 0 to prevent sudden speed changes
         current.data.puller_state.target_speed = 0;
       },
@@ -359,10 +359,10 @@ export function useWinder2() {
           machine_identification_unique: machineIdentification,
           data: { SetPullerTargetSpeed: 0 },
         });
-        // Then set the gear ratio
-        await requestPullerSetGearRatio({
+        // Then set the transmission
+        await requestPullerSetTransmission({
           machine_identification_unique: machineIdentification,
-          data: { SetPullerGearRatio: gearRatio },
+          data: { SetPullerTransmission: transmission },
         });
       },
     );
@@ -698,7 +698,7 @@ export function useWinder2() {
     setPullerTargetSpeed,
     setPullerRegulationMode,
     setPullerForward,
-    setPullerGearRatio,
+    setPullerTransmission,
     setSpoolAutomaticRequiredMeters,
     setSpoolAutomaticAction,
     setSpoolRegulationMode,
@@ -38,7 +38,25 @@ export const pullerRegulationSchema = z.enum(["Speed", "Diameter"]);
 export type PullerRegulation = z.infer<typeof pullerRegulationSchema>;
 
 /**
- * Gear ratio enum for winding speed
+ * Drivetrain from the puller stepper to the wheel, each stage is the ratio of
+ * output over input speed
+ */
+export const transmissionSchema = z.object({
+  stages: z.array(z.number()),
+});
+export type Transmission = z.infer<typeof transmissionSchema>;
+
+/**
+ * Get the overall ratio of a transmission
+ */
+export function getTransmissionRatio(
+  transmission: Transmission | undefined,
+): number {
+  return transmission?.stages.reduce((ratio, stage) => ratio * stage, 1) ?? 1;
+}
+
+/**
+ * Single stage gear ratios offered in the settings
  */
 export const gearRatioSchema = z.enum(["OneToOne", "OneToFive", "OneToTen"]);
 export type GearRatio = z.infer<typeof gearRatioSchema>;
@@ -61,6 +79,27 @@ export function getGearRatioMultiplier(
   }
 }
 
+/**
+ * Get the single stage transmission of a gear ratio
+ */
+export function getGearRatioTransmission(gearRatio: GearRatio): Transmission {
+  const multiplier = getGearRatioMultiplier(gearRatio);
+  return { stages: multiplier === 1.0 ? [] : [1.0 / multiplier] };
+}
+
+/**
+ * Get the gear ratio matching a transmission, undefined for other drivetrains
+ */
+export function getTransmissionGearRatio(
+  transmission: Transmission | undefined,
+): GearRatio | undefined {
+  const ratio = getTransmissionRatio(transmission);
+  return gearRatioSchema.options.find(
+    (gearRatio) =>
+      Math.abs(ratio * getGearRatioMultiplier(gearRatio) - 1.0) < 1e-9,
+  );
+}
+
 /**
  * Machine operation mode enum
  */
@@ -136,7 +175,7 @@ export const pullerStateSchema = z.object({
   regulation: pullerRegulationSchema,
   target_speed: z.number(),
   forward: z.boolean(),
-  gear_ratio: gearRatioSchema,
+  transmission: transmissionSchema,
 
   // properties of adaptive speed mode
   adaptive_speed_delta_max: z.number(),
@@ -40,6 +40,7 @@ impl Machine for AquaPathV1 {
         if !left_notices.is_empty() || !right_notices.is_empty() {
             self.emit_state();
         }
+        self.maybe_emit_autotune_progress();
 
         if now.duration_since(self.last_measurement_emit) > Duration::from_secs_f64(1.0 / 30.0) {
             self.emit_live_values();
@@ -1,9 +1,12 @@
 use super::{AquaPathV1, AquaPathV1Mode, controller::CoolingMode};
 use crate::{MachineApi, MachineMessage, MachineValues};
-use control_core::socketio::{
-    event::{Event, GenericEvent},
-    namespace::{
-        CacheFn, CacheableEvents, Namespace, NamespaceCacheingLogic, cache_first_and_last_event,
+use control_core::{
+    controllers::pid_autotuner::TuningRule,
+    socketio::{
+        event::{Event, GenericEvent},
+        namespace::{
+            CacheFn, CacheableEvents, Namespace, NamespaceCacheingLogic, cache_first_and_last_event,
+        },
     },
 };
 use serde::{Deserialize, Serialize};
@@ -60,6 +63,8 @@ pub struct StateEvent {
     pub cooling_mode_states: CoolingModeStates,
     pub tolerance_states: ToleranceStates,
     pub pid_states: PidStates,
+    pub pid_autotune_states: PidAutoTuneStates,
+    pub dead_time_compensation_states: DeadTimeCompensationStates,
     pub thermal_safety_states: ThermalSafetyStates,
 }
 
@@ -154,6 +159,66 @@ pub struct PidStates {
     pub right: PidState,
 }
 
+/// Live state of a heating PID auto-tuner
+#[derive(Serialize, Debug, Clone)]
+pub struct PidAutoTuneState {
+    /// One of: `"not_started"`, `"running"`, `"completed"`, `"failed"`
+    pub state: String,
+    /// Progress percentage in the range 0 – 100
+    pub progress: f64,
+    /// Gains of the last successful run
+    pub result: Option<PidState>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PidAutoTuneStates {
+    pub left: PidAutoTuneState,
+    pub right: PidAutoTuneState,
+}
+
+/// Parameters for starting a heating PID auto-tune run
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PidAutoTuneConfig {
+    /// Temperature oscillation half-amplitude in °C around the target
+    pub tune_delta: f64,
+    /// Rule the gains are computed with, `None` keeps the default conservative gains
+    #[serde(default)]
+    pub rule: Option<TuningRule>,
+}
+
+/// Parameters for an open-loop step test of the heating loop
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct StepTestConfig {
+    /// Heating duty the heater is switched with after the baseline, `0.0` – `1.0`
+    pub step: f64,
+}
+
+/// Heating loop model identified by a step test, used by the Smith predictor
+#[derive(Serialize, Debug, Clone)]
+pub struct DeadTimeModel {
+    /// Steady-state temperature rise in °C at full heating
+    pub gain: f64,
+    /// Time constant in s
+    pub time_constant: f64,
+    /// Transport delay between heater and sensor in s
+    pub dead_time: f64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DeadTimeCompensationState {
+    pub enabled: bool,
+    /// One of: `"not_started"`, `"baseline"`, `"stepped"`, `"completed"`, `"failed"`
+    pub step_test_state: String,
+    /// Model of the last successful step test
+    pub model: Option<DeadTimeModel>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DeadTimeCompensationStates {
+    pub left: DeadTimeCompensationState,
+    pub right: DeadTimeCompensationState,
+}
+
 #[derive(Serialize, Debug, Clone)]
 pub struct ThermalSafetyState {
     pub thermal_delay: f64,
@@ -196,6 +261,18 @@ enum Mutation {
     SetRightPidKp(f64),
     SetRightPidKi(f64),
     SetRightPidKd(f64),
+    StartLeftPidAutoTune(PidAutoTuneConfig),
+    StartRightPidAutoTune(PidAutoTuneConfig),
+    StopLeftPidAutoTune {},
+    StopRightPidAutoTune {},
+    /// Identify the heating loop model used by the dead-time compensation
+    StartLeftStepTest(StepTestConfig),
+    StartRightStepTest(StepTestConfig),
+    StopLeftStepTest {},
+    StopRightStepTest {},
+    /// Needs a model from a step test
+    SetLeftDeadTimeCompensation(bool),
+    SetRightDeadTimeCompensation(bool),
     SetLeftThermalFlowSettleDuration(f64),
     SetRightThermalFlowSettleDuration(f64),
     SetLeftPumpCooldownMinTemperature(f64),
@@ -322,6 +399,36 @@ impl MachineApi for AquaPathV1 {
             Mutation::SetRightPidKd(value) => {
                 self.set_pid_kd(value, super::AquaPathSideType::Right);
             }
+            Mutation::StartLeftPidAutoTune(config) => {
+                self.start_pid_autotune(config, super::AquaPathSideType::Left)?;
+            }
+            Mutation::StartRightPidAutoTune(config) => {
+                self.start_pid_autotune(config, super::AquaPathSideType::Right)?;
+            }
+            Mutation::StopLeftPidAutoTune {} => {
+                self.stop_pid_autotune(super::AquaPathSideType::Left);
+            }
+            Mutation::StopRightPidAutoTune {} => {
+                self.stop_pid_autotune(super::AquaPathSideType::Right);
+            }
+            Mutation::StartLeftStepTest(config) => {
+                self.start_step_test(config, super::AquaPathSideType::Left)?;
+            }
+            Mutation::StartRightStepTest(config) => {
+                self.start_step_test(config, super::AquaPathSideType::Right)?;
+            }
+            Mutation::StopLeftStepTest {} => {
+                self.stop_step_test(super::AquaPathSideType::Left);
+            }
+            Mutation::StopRightStepTest {} => {
+                self.stop_step_test(super::AquaPathSideType::Right);
+            }
+            Mutation::SetLeftDeadTimeCompensation(enabled) => {
+                self.set_dead_time_compensation(enabled, super::AquaPathSideType::Left)?;
+            }
+            Mutation::SetRightDeadTimeCompensation(enabled) => {
+                self.set_dead_time_compensation(enabled, super::AquaPathSideType::Right)?;
+            }
             Mutation::SetLeftThermalFlowSettleDuration(value) => {
                 self.set_thermal_flow_settle_duration(value, super::AquaPathSideType::Left);
             }
@@ -1,5 +1,10 @@
 use crate::aquapath1::{Flow, Temperature};
-use control_core::controllers::pid::PidController;
+use control_core::controllers::{
+    pid::PidController,
+    pid_autotuner::{AutoTuneConfig, AutoTuneResult, PidAutoTuner, TuningRule},
+    smith_predictor::SmithPredictor,
+    step_response_identifier::{FopdtModel, StepResponseIdentifier, StepTestConfig},
+};
 use qitech_lib::ethercat_hal::io::analog_input::AnalogInputDevice;
 use qitech_lib::ethercat_hal::io::analog_output::AnalogOutputDevice;
 use qitech_lib::ethercat_hal::io::as006::{
@@ -23,14 +28,33 @@ pub enum ControlResetReason {
     CoolingToleranceChanged,
     PidParametersChanged,
     PumpCommandChanged,
+    DeadTimeCompensationChanged,
 }
 
 #[derive(Debug, Clone, Copy)]
 pub enum ControllerNotice {
     ControlReset(ControlResetReason),
     PumpStoppedLowFlow,
+    AutoTuneCompleted,
+    AutoTuneFailed,
+    StepTestCompleted,
+    StepTestFailed,
 }
 
+/// The reservoir reacts slowly, ten relay cycles can take well over an hour
+const AUTOTUNE_MAX_DURATION: Duration = Duration::from_secs(3 * 3600);
+
+/// The heater is stepped from off, the temperature has to stay within 0.2 °C for five
+/// minutes before the heating loop model is fitted
+const STEP_TEST_CONFIG: StepTestConfig = StepTestConfig {
+    step: 0.0,
+    baseline_duration: Duration::from_secs(60),
+    sample_interval: Duration::from_secs(1),
+    settle_tolerance: 0.2,
+    settle_duration: Duration::from_secs(300),
+    max_duration: AUTOTUNE_MAX_DURATION,
+};
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum CoolingMode {
     Low,
@@ -139,6 +163,15 @@ pub struct Controller {
     pub max_flow: VolumeRate,
     config: ControllerConfig,
     pending_notices: Vec<ControllerNotice>,
+    pid_autotuner: Option<PidAutoTuner>,
+    autotune_rule: Option<TuningRule>,
+    autotune_result: Option<AutoTuneResult>,
+    /// Identifies the heating loop model for the dead-time compensation
+    step_identifier: Option<StepResponseIdentifier>,
+    /// Heating loop model of the last successful step test
+    heating_model: Option<FopdtModel>,
+    /// Compensates the transport delay between heater and sensor when set
+    smith_predictor: Option<SmithPredictor>,
 }
 
 impl Controller {
@@ -234,6 +267,12 @@ impl Controller {
             max_flow: VolumeRate::new::<liter_per_minute>(10.0),
             config,
             pending_notices: Vec::new(),
+            pid_autotuner: None,
+            autotune_rule: None,
+            autotune_result: None,
+            step_identifier: None,
+            heating_model: None,
+            smith_predictor: None,
             cooling_controller,
             relais_controller,
             cooling_controller_port,
@@ -523,6 +562,244 @@ impl Controller {
         );
     }
 
+    /// Enable dead-time compensation with the heating loop model of the last step test
+    pub fn set_dead_time_compensation(&mut self, enabled: bool) -> Result<(), anyhow::Error> {
+        self.smith_predictor = match (enabled, self.heating_model) {
+            (false, _) => None,
+            (true, Some(model)) => {
+                Self::validate_heating_model(&model)?;
+                Some(SmithPredictor::new(model))
+            }
+            (true, None) => {
+                return Err(anyhow::anyhow!(
+                    "[{}::Controller::set_dead_time_compensation] No heating loop model, run a step test first",
+                    module_path!()
+                ));
+            }
+        };
+        self.reset_control_state(
+            Instant::now(),
+            Some(ControlResetReason::DeadTimeCompensationChanged),
+        );
+        Ok(())
+    }
+
+    /// The model gain is in °C per heating duty, heating has to raise the temperature
+    fn validate_heating_model(model: &FopdtModel) -> Result<(), anyhow::Error> {
+        if !model.is_valid() || model.gain <= 0.0 {
+            return Err(anyhow::anyhow!(
+                "[{}::Controller::validate_heating_model] Invalid heating loop model {:?}",
+                module_path!(),
+                model
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn is_dead_time_compensation_enabled(&self) -> bool {
+        self.smith_predictor.is_some()
+    }
+
+    pub fn get_heating_model(&self) -> Option<&FopdtModel> {
+        self.heating_model.as_ref()
+    }
+
+    /// Temperature the heating PID acts on, the measurement with the dead time of the heater
+    /// compensated if enabled. The model only covers the heater, cooling acts on the measurement.
+    fn heating_control_temperature(&mut self, now: Instant) -> f64 {
+        let measurement = self.current_temperature.get::<degree_celsius>();
+        match self.smith_predictor {
+            Some(ref mut predictor) => {
+                // The heater state since the last update drives the model
+                predictor.set_output(if self.temperature.heating { 1.0 } else { 0.0 });
+                predictor.update(measurement, now)
+            }
+            None => measurement,
+        }
+    }
+
+    /// Start an open-loop step test of the heating loop. The heater is held off for the
+    /// baseline and then switched with `step` duty until the temperature settles, so the
+    /// reservoir should be at a steady temperature with the heater off.
+    pub fn start_step_test(&mut self, now: Instant, step: f64) -> Result<(), anyhow::Error> {
+        if !step.is_finite() || step <= 0.0 || step > 1.0 {
+            return Err(anyhow::anyhow!(
+                "[{}::Controller::start_step_test] Heating duty step {} is not within 0 – 1",
+                module_path!(),
+                step
+            ));
+        }
+        self.stop_autotune();
+        let mut identifier = StepResponseIdentifier::new(StepTestConfig {
+            step,
+            ..STEP_TEST_CONFIG
+        });
+        identifier.start(now, 0.0);
+        self.step_identifier = Some(identifier);
+        self.reset_control_state(now, None);
+        Ok(())
+    }
+
+    /// Abort an in-progress step test
+    pub fn stop_step_test(&mut self) {
+        if let Some(ref mut identifier) = self.step_identifier {
+            if identifier.is_running() {
+                identifier.stop();
+                self.pending_notices.push(ControllerNotice::StepTestFailed);
+                self.reset_control_state(Instant::now(), None);
+            }
+        }
+    }
+
+    pub fn is_step_test_running(&self) -> bool {
+        self.step_identifier
+            .as_ref()
+            .is_some_and(|identifier| identifier.is_running())
+    }
+
+    /// Current step test state as a string slice
+    pub fn get_step_test_state(&self) -> &str {
+        match &self.step_identifier {
+            Some(identifier) => identifier.state(),
+            None => "not_started",
+        }
+    }
+
+    /// Heater step of the identifier instead of the PID, keeps the model once it completes.
+    /// A running dead-time compensation switches to the new model.
+    fn update_step_test(&mut self, now: Instant, heating_interlock_ok: bool, dt: f64) {
+        let Some(ref mut identifier) = self.step_identifier else {
+            return;
+        };
+        let duty = identifier.update(self.current_temperature.get::<degree_celsius>(), now);
+
+        if identifier.is_completed() {
+            let model = identifier.result().ok().copied();
+            match model.filter(|model| Self::validate_heating_model(model).is_ok()) {
+                Some(model) => {
+                    self.heating_model = Some(model);
+                    if self.smith_predictor.is_some() {
+                        self.smith_predictor = Some(SmithPredictor::new(model));
+                    }
+                    self.pending_notices
+                        .push(ControllerNotice::StepTestCompleted);
+                }
+                None => self.pending_notices.push(ControllerNotice::StepTestFailed),
+            }
+            self.reset_control_state(now, None);
+        } else if identifier.is_failed() {
+            self.pending_notices.push(ControllerNotice::StepTestFailed);
+            self.reset_control_state(now, None);
+        }
+
+        let mut elapsed_in_window = now.duration_since(self.window_start);
+        if elapsed_in_window >= self.pwm_period {
+            self.window_start = now;
+            elapsed_in_window = Duration::ZERO;
+        }
+
+        self.set_cooling_state(false, now);
+        let should_heat = elapsed_in_window < self.pwm_period.mul_f64(duty)
+            && self.heating_allowed
+            && heating_interlock_ok
+            && self.current_temperature <= self.max_temperature;
+        self.set_heating_state(should_heat, now);
+        if self.temperature.heating {
+            self.heating_last_active_at = Some(now);
+            self.total_energy += self.get_current_power() * dt / 3600.0;
+        }
+    }
+
+    /// Start relay auto-tuning of the heating loop around the current target.
+    /// Cooling stays off while the heater is switched between off and full power.
+    pub fn start_autotune(&mut self, now: Instant, tune_delta: f64, rule: Option<TuningRule>) {
+        self.stop_step_test();
+        let target = self.target_temperature.get::<degree_celsius>();
+        let mut tuner = PidAutoTuner::new(AutoTuneConfig {
+            tune_delta,
+            max_power: 1.0,
+            max_duration: AUTOTUNE_MAX_DURATION,
+        });
+        tuner.start(now, target);
+        self.pid_autotuner = Some(tuner);
+        self.autotune_rule = rule;
+        self.reset_control_state(now, None);
+    }
+
+    /// Abort an in-progress auto-tune run
+    pub fn stop_autotune(&mut self) {
+        if let Some(ref mut tuner) = self.pid_autotuner {
+            if tuner.is_running() {
+                tuner.stop();
+                self.pending_notices.push(ControllerNotice::AutoTuneFailed);
+                self.reset_control_state(Instant::now(), None);
+            }
+        }
+    }
+
+    pub fn is_autotune_running(&self) -> bool {
+        self.pid_autotuner
+            .as_ref()
+            .is_some_and(|tuner| tuner.is_running())
+    }
+
+    /// Current auto-tuner state as a string slice
+    pub fn get_autotune_state(&self) -> &str {
+        match &self.pid_autotuner {
+            Some(tuner) => tuner.state(),
+            None => "not_started",
+        }
+    }
+
+    /// Auto-tune progress as a percentage (0 – 100)
+    pub fn get_autotune_progress(&self) -> f64 {
+        match &self.pid_autotuner {
+            Some(tuner) => tuner.get_progress_percent(),
+            None => 0.0,
+        }
+    }
+
+    /// Gains of the last completed auto-tune run, if any
+    pub fn get_autotune_result(&self) -> Option<&AutoTuneResult> {
+        self.autotune_result.as_ref()
+    }
+
+    /// Relay step of the auto-tuner instead of the PID, applies the gains once it completes
+    fn update_autotune(&mut self, now: Instant, heating_interlock_ok: bool, dt: f64) {
+        let Some(ref mut tuner) = self.pid_autotuner else {
+            return;
+        };
+        let duty = tuner.update(self.current_temperature.get::<degree_celsius>(), now);
+
+        if tuner.is_completed() {
+            if let Ok(result) = tuner.result() {
+                let result = match self.autotune_rule {
+                    Some(rule) => result.with_rule(rule),
+                    None => result.clone(),
+                };
+                self.pid.configure(result.ki, result.kp, result.kd);
+                self.autotune_result = Some(result);
+            }
+            self.pending_notices
+                .push(ControllerNotice::AutoTuneCompleted);
+            self.reset_control_state(now, None);
+        } else if tuner.is_failed() {
+            self.pending_notices.push(ControllerNotice::AutoTuneFailed);
+            self.reset_control_state(now, None);
+        }
+
+        self.set_cooling_state(false, now);
+        let should_heat = duty > 0.0
+            && self.heating_allowed
+            && heating_interlock_ok
+            && self.current_temperature <= self.max_temperature;
+        self.set_heating_state(should_heat, now);
+        if self.temperature.heating {
+            self.heating_last_active_at = Some(now);
+            self.total_energy += self.get_current_power() * dt / 3600.0;
+        }
+    }
+
     pub fn drain_notices(&mut self) -> Vec<ControllerNotice> {
         std::mem::take(&mut self.pending_notices)
     }
@@ -625,6 +902,7 @@ impl Controller {
         self.flow.should_pump = should_flow;
 
         self.current_temperature = self.get_temp_in();
+        let heating_control_temperature = self.heating_control_temperature(now);
 
         let pump_cooldown_min_temperature = self.config.pump_cooldown_min_temperature;
         let pump_is_still_hot = self.current_temperature.get::<degree_celsius>()
@@ -678,11 +956,35 @@ impl Controller {
             self.turn_heating_off();
         }
 
-        // Calculate PID error once
-        let error = self.target_temperature.get::<degree_celsius>()
-            - self.current_temperature.get::<degree_celsius>();
+        if !self.heating_allowed {
+            if self.is_autotune_running() {
+                self.stop_autotune();
+            }
+            if self.is_step_test_running() {
+                self.stop_step_test();
+            }
+        }
 
-        let control = self.pid.update(error, now);
+        let flow_stable_long_enough =
+            self.shared_thermal_delay_elapsed(self.flow_became_valid_at, now);
+        let cooling_interlock_ok = has_flow_for_thermal && pump_is_running;
+        let heating_interlock_ok = cooling_interlock_ok && flow_stable_long_enough;
+
+        if self.is_autotune_running() {
+            self.update_autotune(now, heating_interlock_ok, dt);
+            return;
+        }
+        if self.is_step_test_running() {
+            self.update_step_test(now, heating_interlock_ok, dt);
+            return;
+        }
+
+        // Calculate PID errors once, only heating is dead-time compensated
+        let target = self.target_temperature.get::<degree_celsius>();
+        let heating_error = target - heating_control_temperature;
+        let error = target - self.current_temperature.get::<degree_celsius>();
+
+        let control = self.pid.update(heating_error, now);
         self.temperature_pid_output = if control.is_finite() { control } else { 0.0 };
 
         let mut elapsed_in_window = now.duration_since(self.window_start);
@@ -690,20 +992,16 @@ impl Controller {
             self.window_start = now;
             elapsed_in_window = Duration::ZERO;
         }
-        let flow_stable_long_enough =
-            self.shared_thermal_delay_elapsed(self.flow_became_valid_at, now);
-        let cooling_interlock_ok = has_flow_for_thermal && pump_is_running;
-        let heating_interlock_ok = cooling_interlock_ok && flow_stable_long_enough;
 
         // Decide whether to heat or cool based on error
-        if error > self.heating_tolerance.get::<degree_celsius>() {
+        if heating_error > self.heating_tolerance.get::<degree_celsius>() {
             // Need heating (current < target)
             if self.temperature.cooling {
                 self.set_cooling_state(false, now);
             }
 
             if self.heating_allowed && heating_interlock_ok {
-                if error >= self.config.heating_full_power_error.get::<degree_celsius>() {
+                if heating_error >= self.config.heating_full_power_error.get::<degree_celsius>() {
                     // Warmup phase: force full heating when far below target.
                     self.set_heating_state(true, now);
                 } else {
@@ -729,8 +1027,7 @@ impl Controller {
                 self.set_cooling_state(true, now);
                 let max_revolutions = self.get_max_revolutions();
                 let max_rpm = max_revolutions.get::<revolution_per_minute>();
-                let temp_offset = self.current_temperature.get::<degree_celsius>()
-                    - self.target_temperature.get::<degree_celsius>();
+                let temp_offset = -error;
 
                 let (target_revolutions, cooling_mode) =
                     Self::cooling_target_rpm(temp_offset, max_rpm, self.config.cooling);
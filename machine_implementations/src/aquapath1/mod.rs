@@ -2,9 +2,12 @@ use crate::{
     MACHINE_AQUAPATH_V1, MachineMessage, VENDOR_QITECH,
     aquapath1::{
         api::{
-            AquaPathV1Events, AquaPathV1Namespace, CoolingModeState, CoolingModeStates, FanState,
-            FanStates, FlowState, FlowStates, LiveValuesEvent, ModeState, NoticeEvent, PidState,
-            PidStates, StateEvent, TempState, TempStates, ThermalSafetyState, ThermalSafetyStates,
+            AquaPathV1Events, AquaPathV1Namespace, CoolingModeState, CoolingModeStates,
+            DeadTimeCompensationState, DeadTimeCompensationStates, DeadTimeModel, FanState,
+            FanStates, FlowState, FlowStates, LiveValuesEvent, ModeState, NoticeEvent,
+            PidAutoTuneConfig, PidAutoTuneState, PidAutoTuneStates, PidState, PidStates,
+            StateEvent, StepTestConfig, TempState, TempStates, ThermalSafetyState,
+            ThermalSafetyStates,
         },
         controller::{ControlResetReason, Controller, ControllerNotice},
     },
@@ -85,6 +88,8 @@ pub struct AquaPathV1 {
     last_measurement_emit: Instant,
     left_controller: Controller,
     right_controller: Controller,
+    /// last emitted auto-tune progress (left, right)
+    last_autotune_progress: (f64, f64),
 }
 
 impl AquaPathV1 {
@@ -281,6 +286,14 @@ impl AquaPathV1 {
                     kd: self.right_controller.get_pid_kd(),
                 },
             },
+            pid_autotune_states: PidAutoTuneStates {
+                left: Self::get_autotune_state(&self.left_controller),
+                right: Self::get_autotune_state(&self.right_controller),
+            },
+            dead_time_compensation_states: DeadTimeCompensationStates {
+                left: Self::get_dead_time_compensation_state(&self.left_controller),
+                right: Self::get_dead_time_compensation_state(&self.right_controller),
+            },
             thermal_safety_states: ThermalSafetyStates {
                 left: ThermalSafetyState {
                     thermal_delay: self
@@ -306,6 +319,30 @@ impl AquaPathV1 {
         }
     }
 
+    fn get_autotune_state(controller: &Controller) -> PidAutoTuneState {
+        PidAutoTuneState {
+            state: controller.get_autotune_state().to_string(),
+            progress: controller.get_autotune_progress(),
+            result: controller.get_autotune_result().map(|result| PidState {
+                kp: result.kp,
+                ki: result.ki,
+                kd: result.kd,
+            }),
+        }
+    }
+
+    fn get_dead_time_compensation_state(controller: &Controller) -> DeadTimeCompensationState {
+        DeadTimeCompensationState {
+            enabled: controller.is_dead_time_compensation_enabled(),
+            step_test_state: controller.get_step_test_state().to_string(),
+            model: controller.get_heating_model().map(|model| DeadTimeModel {
+                gain: model.gain,
+                time_constant: model.time_constant,
+                dead_time: model.dead_time,
+            }),
+        }
+    }
+
     pub fn emit_state(&mut self) {
         let event = self.get_state().build();
         self.namespace.emit(AquaPathV1Events::State(event));
@@ -339,6 +376,9 @@ impl AquaPathV1 {
                     ControlResetReason::PumpCommandChanged => {
                         "Pump command changed. PID control state and heater PWM timing were reset."
                     }
+                    ControlResetReason::DeadTimeCompensationChanged => {
+                        "Dead-time compensation changed. PID control state and heater PWM timing were reset."
+                    }
                 };
 
                 self.emit_notice(format!("{side_label}: Thermal Control Reset"), message);
@@ -349,6 +389,30 @@ impl AquaPathV1 {
                     "Flow fell below the minimum thermal threshold while the pump was enabled. The pump was turned off and PID control state was reset.",
                 );
             }
+            ControllerNotice::AutoTuneCompleted => {
+                self.emit_notice(
+                    format!("{side_label}: PID Auto-Tune Completed"),
+                    "The computed PID settings were applied. PID control state and heater PWM timing were reset.",
+                );
+            }
+            ControllerNotice::StepTestCompleted => {
+                self.emit_notice(
+                    format!("{side_label}: Step Test Completed"),
+                    "The heating loop model was identified and can be used for dead-time compensation. PID control state and heater PWM timing were reset.",
+                );
+            }
+            ControllerNotice::StepTestFailed => {
+                self.emit_notice(
+                    format!("{side_label}: Step Test Failed"),
+                    "The step test was aborted or the temperature did not settle. The previous heating loop model is kept.",
+                );
+            }
+            ControllerNotice::AutoTuneFailed => {
+                self.emit_notice(
+                    format!("{side_label}: PID Auto-Tune Failed"),
+                    "Auto-tuning was aborted or did not converge. The previous PID settings are kept.",
+                );
+            }
         }
     }
 
@@ -638,6 +702,90 @@ impl AquaPathV1 {
         self.emit_state();
     }
 
+    fn start_pid_autotune(
+        &mut self,
+        config: PidAutoTuneConfig,
+        side: AquaPathSideType,
+    ) -> Result<(), anyhow::Error> {
+        // The relay test needs a positive amplitude to switch around the target
+        if !config.tune_delta.is_finite() || config.tune_delta <= 0.0 {
+            return Err(anyhow::anyhow!(
+                "[{}::AquaPathV1::start_pid_autotune] Invalid tune delta {}",
+                module_path!(),
+                config.tune_delta
+            ));
+        }
+        let now = Instant::now();
+        match side {
+            AquaPathSideType::Right => {
+                self.right_controller
+                    .start_autotune(now, config.tune_delta, config.rule)
+            }
+            AquaPathSideType::Left => {
+                self.left_controller
+                    .start_autotune(now, config.tune_delta, config.rule)
+            }
+        }
+        self.emit_state();
+        Ok(())
+    }
+
+    fn stop_pid_autotune(&mut self, side: AquaPathSideType) {
+        match side {
+            AquaPathSideType::Right => self.right_controller.stop_autotune(),
+            AquaPathSideType::Left => self.left_controller.stop_autotune(),
+        }
+        self.emit_state();
+    }
+
+    fn start_step_test(
+        &mut self,
+        config: StepTestConfig,
+        side: AquaPathSideType,
+    ) -> Result<(), anyhow::Error> {
+        let now = Instant::now();
+        match side {
+            AquaPathSideType::Right => self.right_controller.start_step_test(now, config.step)?,
+            AquaPathSideType::Left => self.left_controller.start_step_test(now, config.step)?,
+        }
+        self.emit_state();
+        Ok(())
+    }
+
+    fn stop_step_test(&mut self, side: AquaPathSideType) {
+        match side {
+            AquaPathSideType::Right => self.right_controller.stop_step_test(),
+            AquaPathSideType::Left => self.left_controller.stop_step_test(),
+        }
+        self.emit_state();
+    }
+
+    /// Uses the heating loop model of the last step test of the side
+    fn set_dead_time_compensation(
+        &mut self,
+        enabled: bool,
+        side: AquaPathSideType,
+    ) -> Result<(), anyhow::Error> {
+        match side {
+            AquaPathSideType::Right => self.right_controller.set_dead_time_compensation(enabled)?,
+            AquaPathSideType::Left => self.left_controller.set_dead_time_compensation(enabled)?,
+        }
+        self.emit_state();
+        Ok(())
+    }
+
+    /// Progress only advances once per relay switch, so this emits rarely
+    fn maybe_emit_autotune_progress(&mut self) {
+        let progress = (
+            self.left_controller.get_autotune_progress(),
+            self.right_controller.get_autotune_progress(),
+        );
+        if progress != self.last_autotune_progress {
+            self.last_autotune_progress = progress;
+            self.emit_state();
+        }
+    }
+
     fn set_thermal_flow_settle_duration(&mut self, duration: f64, side: AquaPathSideType) {
         if !self.mode_allows_standby_only_config() {
             return;
@@ -116,6 +116,7 @@ impl MachineNew for AquaPathV1 {
             last_measurement_emit: Instant::now(),
             left_controller,
             right_controller,
+            last_autotune_progress: (0.0, 0.0),
         };
         machine.emit_state();
         Ok(machine)
@@ -1,17 +1,17 @@
 use std::time::Instant;
 
-use qitech_lib::machines::{Machine, MachineDataRegistry};
 use crate::MachineApi;
+use qitech_lib::machines::{
+    Machine, MachineDataRegistry, MachineError, MachineIdentificationUnique,
+};
 
 use super::Sorter1;
 
 impl Machine for Sorter1 {
-    fn act(&mut self, _reg : Option<&mut MachineDataRegistry> ) -> Result<(), MachineError> {
+    fn act(&mut self, _reg: Option<&mut MachineDataRegistry>) -> Result<(), MachineError> {
         let msg = self.api_receiver.try_recv();
         match msg {
-            Ok(msg) => {
-                let _res = self.act_machine_message(msg);
-            }
+            Ok(msg) => self.act_machine_message(msg),
             Err(_) => (),
         };
 
@@ -23,15 +23,17 @@ impl Machine for Sorter1 {
         for (index, valve_controller) in self.valve_controllers.iter_mut().enumerate() {
             if valve_controller.update(now) {
                 air_valves.set_output(index, valve_controller.is_active());
+                self.air_valve_states[index] = valve_controller.is_active();
                 valve_state_changed = true;
             }
         }
         drop(air_valves);
-        
+
         if valve_state_changed {
             self.emit_state();
         }
 
+        self.poll_color_detections();
         self.trigger_valves(now);
 
         if now.duration_since(self.last_measurement_emit).as_millis() >= 100 {
@@ -41,10 +43,9 @@ impl Machine for Sorter1 {
         Ok(())
     }
 
-    fn react(&mut self, _registry: &qitech_lib::machines::MachineDataRegistry) {
-    }
+    fn react(&mut self, _registry: &MachineDataRegistry) {}
 
-    fn get_identification(&self) -> qitech_lib::machines::MachineIdentificationUnique {
-        return self.machine_identification_unique
+    fn get_identification(&self) -> MachineIdentificationUnique {
+        self.machine_identification_unique
     }
 }
@@ -2,8 +2,13 @@ use std::sync::Arc;
 
 use crate::{MachineApi, MachineMessage, MachineValues};
 
-use super::{ColorBoundsHsl, ColorBoundsState, Sorter1, Sorter1Mode};
-use control_core::socketio::{event::{Event, GenericEvent}, namespace::{CacheFn, CacheableEvents, Namespace, NamespaceCacheingLogic, cache_first_and_last_event}};
+use super::{ColorBoundsState, ColorRgb, Sorter1, Sorter1Mode, ValveColorMap};
+use control_core::socketio::{
+    event::{Event, GenericEvent},
+    namespace::{
+        CacheFn, CacheableEvents, Namespace, NamespaceCacheingLogic, cache_first_and_last_event,
+    },
+};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tracing::instrument;
@@ -96,10 +101,9 @@ pub struct Sorter1Namespace {
     pub namespace: Option<Namespace>,
 }
 
-pub fn convert_hsl_to_state(colors: [ColorBoundsHsl; 8]) -> Vec<ColorBoundsState> {
+pub fn convert_hsl_to_state(colors: &ValveColorMap) -> Vec<ColorBoundsState> {
     colors
-        .into_iter()
-        .enumerate()
+        .iter()
         .map(|(idx, c)| ColorBoundsState {
             h_min: c.h_min,
             h_max: c.h_max,
@@ -135,6 +139,16 @@ enum Mutation {
         duration_ms: u64,
     },
     SetColor(ColorBoundsState),
+    ClearColor {
+        valve_index: usize,
+    },
+    /// Bottle cap crossed the detection line, for detectors outside the server process
+    ReportColorDetection {
+        id: u32,
+        r: u8,
+        g: u8,
+        b: u8,
+    },
 }
 
 impl MachineApi for Sorter1 {
@@ -156,6 +170,12 @@ impl MachineApi for Sorter1 {
             Mutation::SetColor(color_bounds_state) => {
                 self.assign_colour_bounds_to_valve(color_bounds_state)
             }
+            Mutation::ClearColor { valve_index } => {
+                self.clear_colour_bounds_of_valve(valve_index);
+            }
+            Mutation::ReportColorDetection { id, r, g, b } => {
+                self.report_color_detection(id, ColorRgb { r, g, b });
+            }
         }
         Ok(())
     }
@@ -165,7 +185,7 @@ impl MachineApi for Sorter1 {
             MachineMessage::SubscribeNamespace(namespace) => {
                 self.namespace.namespace = Some(namespace);
                 self.emit_state();
-                tracing::info!("extruder1 received subscribe");
+                tracing::info!("sorter1 received subscribe");
             }
             MachineMessage::UnsubscribeNamespace => self.namespace.namespace = None,
             MachineMessage::HttpApiJsonRequest(value) => {
@@ -191,5 +211,4 @@ impl MachineApi for Sorter1 {
     fn api_event_namespace(&mut self) -> Option<Namespace> {
         self.namespace.namespace.clone()
     }
-
 }
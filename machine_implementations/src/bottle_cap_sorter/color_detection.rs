@@ -0,0 +1,107 @@
+use std::time::Instant;
+
+use tokio::sync::mpsc::{Receiver, Sender, error::TrySendError};
+
+use super::ColorRgb;
+
+/// A bottle cap crossing the detection line with its measured color
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorDetection {
+    pub id: u32,
+    pub crossing_time: Instant,
+    pub color: ColorRgb,
+}
+
+/// Source of color detections (camera pipeline, color sensor, ...)
+///
+/// Polled from `act`, so implementations must not block.
+pub trait ColorDetectionInput {
+    /// Returns the next pending detection, if any
+    fn poll_detection(&mut self) -> Option<ColorDetection>;
+}
+
+/// Color detection input fed through a bounded channel
+///
+/// The sending half can be handed to whatever produces detections outside the
+/// control loop.
+#[derive(Debug)]
+pub struct ChannelColorDetectionInput {
+    receiver: Receiver<ColorDetection>,
+}
+
+impl ChannelColorDetectionInput {
+    pub fn new(capacity: usize) -> (ColorDetectionSender, Self) {
+        let (sender, receiver) = tokio::sync::mpsc::channel(capacity);
+        (ColorDetectionSender { sender }, Self { receiver })
+    }
+}
+
+impl ColorDetectionInput for ChannelColorDetectionInput {
+    fn poll_detection(&mut self) -> Option<ColorDetection> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+/// Sending half of [`ChannelColorDetectionInput`]
+#[derive(Debug, Clone)]
+pub struct ColorDetectionSender {
+    sender: Sender<ColorDetection>,
+}
+
+impl ColorDetectionSender {
+    /// Report a detection without blocking
+    ///
+    /// Fails if the channel is full or the machine was dropped.
+    pub fn report(&self, detection: ColorDetection) -> Result<(), anyhow::Error> {
+        match self.sender.try_send(detection) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(detection)) => Err(anyhow::anyhow!(
+                "[{}::ColorDetectionSender::report] Detection queue full, dropped bottle cap ID {}",
+                module_path!(),
+                detection.id
+            )),
+            Err(TrySendError::Closed(_)) => Err(anyhow::anyhow!(
+                "[{}::ColorDetectionSender::report] Detection input was closed",
+                module_path!()
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detection(id: u32) -> ColorDetection {
+        ColorDetection {
+            id,
+            crossing_time: Instant::now(),
+            color: ColorRgb { r: 255, g: 0, b: 0 },
+        }
+    }
+
+    #[test]
+    fn test_channel_input_delivers_in_order() {
+        let (sender, mut input) = ChannelColorDetectionInput::new(4);
+        sender.report(detection(1)).unwrap();
+        sender.report(detection(2)).unwrap();
+
+        assert_eq!(input.poll_detection().map(|d| d.id), Some(1));
+        assert_eq!(input.poll_detection().map(|d| d.id), Some(2));
+        assert_eq!(input.poll_detection(), None);
+    }
+
+    #[test]
+    fn test_channel_input_full() {
+        let (sender, _input) = ChannelColorDetectionInput::new(1);
+        sender.report(detection(1)).unwrap();
+        assert!(sender.report(detection(2)).is_err());
+    }
+
+    #[test]
+    fn test_channel_input_closed() {
+        let (sender, input) = ChannelColorDetectionInput::new(1);
+        drop(input);
+        assert!(sender.report(detection(1)).is_err());
+    }
+}
@@ -6,9 +6,9 @@ use control_core::{
 };
 
 use qitech_lib::units::{
-    ConstZero,
-    Acceleration, AngularVelocity, Jerk, Velocity,
-    acceleration::meter_per_second_squared, jerk::meter_per_second_cubed, velocity::meter_per_second,
+    Acceleration, AngularVelocity, ConstZero, Jerk, Velocity,
+    acceleration::meter_per_second_squared, jerk::meter_per_second_cubed,
+    velocity::meter_per_second,
 };
 
 #[derive(Debug)]
@@ -1,5 +1,6 @@
 pub mod act;
 pub mod api;
+pub mod color_detection;
 pub mod conveyer_belt_controller;
 pub mod new;
 pub mod valve_controller;
@@ -29,6 +30,36 @@ pub struct ColorHsl {
     pub l: f32,
 }
 
+impl From<ColorRgb> for ColorHsl {
+    /// Hue in degrees (0-360), saturation and lightness in 0-1
+    fn from(color: ColorRgb) -> Self {
+        let r = color.r as f32 / 255.0;
+        let g = color.g as f32 / 255.0;
+        let b = color.b as f32 / 255.0;
+
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+        let l = (max + min) / 2.0;
+
+        // Achromatic (gray), hue and saturation are undefined
+        if delta == 0.0 {
+            return Self { h: 0.0, s: 0.0, l };
+        }
+
+        let s = delta / (1.0 - (2.0 * l - 1.0).abs());
+        let h = if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+
+        Self { h, s, l }
+    }
+}
+
 #[derive(Debug, Default, Copy, Clone)]
 pub struct ColorBoundsHsl {
     pub h_min: f32,
@@ -47,7 +78,7 @@ impl ColorBoundsHsl {
         s_max: f32,
         l_min: f32,
         l_max: f32,
-    ) -> Result<Self,anyhow::Error> {
+    ) -> Result<Self, anyhow::Error> {
         // Check hue bounds (0-360 range, allowing wrap-around)
         if h_min < 0.0 || h_min > 360.0 || h_max < 0.0 || h_max > 360.0 {
             return Err(anyhow::anyhow!("Hue values must be between 0.0 and 360.0"));
@@ -77,6 +108,18 @@ impl ColorBoundsHsl {
         })
     }
 
+    /// Convert bounds from the frontend, where saturation and lightness are in percent
+    pub fn from_state(state: &ColorBoundsState) -> Result<Self, anyhow::Error> {
+        Self::new(
+            state.h_min,
+            state.h_max,
+            safe_divide(100.0, state.s_min),
+            safe_divide(100.0, state.s_max),
+            safe_divide(100.0, state.l_min),
+            safe_divide(100.0, state.l_max),
+        )
+    }
+
     pub fn contains(&self, color: &ColorHsl) -> bool {
         let h_in_range = if self.h_min <= self.h_max {
             // Normal range: h_min <= h <= h_max
@@ -127,16 +170,91 @@ impl ColorBoundsHsl {
     }
 }
 
+/// Color bounds assigned to each air valve
+///
+/// Bounds of different valves must not overlap, otherwise a bottle cap could
+/// be claimed by more than one valve.
+#[derive(Debug, Default, Clone)]
+pub struct ValveColorMap {
+    bounds: [Option<ColorBoundsHsl>; 8],
+}
+
+impl ValveColorMap {
+    pub fn get(&self, valve_index: usize) -> Option<ColorBoundsHsl> {
+        self.bounds.get(valve_index).copied().flatten()
+    }
+
+    /// Assign bounds to a valve, replacing any previous bounds of that valve
+    pub fn assign(
+        &mut self,
+        valve_index: usize,
+        bounds: ColorBoundsHsl,
+    ) -> Result<(), anyhow::Error> {
+        if valve_index >= self.bounds.len() {
+            return Err(anyhow::anyhow!(
+                "[{}::ValveColorMap::assign] Valve index {} out of range",
+                module_path!(),
+                valve_index
+            ));
+        }
+
+        if let Some((other_index, _)) = self
+            .bounds
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != valve_index)
+            .find(|(_, other)| other.is_some_and(|other| other.overlaps(&bounds)))
+        {
+            return Err(anyhow::anyhow!(
+                "[{}::ValveColorMap::assign] Color bounds for valve {} overlap with valve {}",
+                module_path!(),
+                valve_index,
+                other_index
+            ));
+        }
+
+        self.bounds[valve_index] = Some(bounds);
+        Ok(())
+    }
+
+    pub fn clear(&mut self, valve_index: usize) {
+        if let Some(bounds) = self.bounds.get_mut(valve_index) {
+            *bounds = None;
+        }
+    }
+
+    /// Returns the valve whose bounds contain the color
+    pub fn classify(&self, color: &ColorHsl) -> Option<usize> {
+        self.bounds
+            .iter()
+            .position(|bounds| bounds.is_some_and(|bounds| bounds.contains(color)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (usize, ColorBoundsHsl)> + '_ {
+        self.bounds
+            .iter()
+            .enumerate()
+            .filter_map(|(index, bounds)| bounds.map(|bounds| (index, bounds)))
+    }
+}
 
-use api::{ConveyorBeltState, LiveValuesEvent, ModeState, Sorter1Events, Sorter1Namespace, StateEvent};
+use api::{
+    ConveyorBeltState, LiveValuesEvent, ModeState, Sorter1Events, Sorter1Namespace, StateEvent,
+    convert_hsl_to_state,
+};
+use color_detection::{ColorDetection, ColorDetectionInput, ColorDetectionSender};
 use control_core::socketio::namespace::NamespaceCacheingLogic;
 use conveyer_belt_controller::ConveyorBeltController;
-use qitech_lib::{ethercat_hal::io::{digital_output::DigitalOutputDevice, stepper_velocity_el70x1::StepperVelocityEL70x1Device}, machines::{MachineIdentification, MachineIdentificationUnique}, units::{Length, Velocity, length::centimeter, time::second, velocity::meter_per_second}};
+use qitech_lib::{
+    ethercat_hal::io::{
+        digital_output::DigitalOutputDevice, stepper_velocity_el70x1::StepperVelocityEL70x1Device,
+    },
+    machines::{MachineIdentification, MachineIdentificationUnique},
+    units::{Length, Velocity, length::centimeter, time::second, velocity::meter_per_second},
+};
 use serde::{Deserialize, Serialize};
+use std::{cell::RefCell, collections::HashMap, fmt::Debug, rc::Rc, time::Instant};
 use tokio::sync::mpsc::{Receiver, Sender};
-use std::{
-    cell::RefCell, collections::HashMap, fmt::Debug, rc::Rc, time::Instant
-};
 use valve_controller::ValveController;
 
 use crate::{MACHINE_SORTER_V1, MachineMessage, QiTechMachine, VENDOR_QITECH};
@@ -165,7 +283,6 @@ impl ScheduledEjection {
     ) -> Self {
         let valve_distance = Length::new::<centimeter>(VALVE_DISTANCES_CM[valve_index]);
         let travel_time = valve_distance / conveyor_speed;
-        tracing::info!("{}", travel_time.get::<second>());
         let trigger_time =
             crossing_time + std::time::Duration::from_secs_f64(travel_time.get::<second>());
 
@@ -178,6 +295,28 @@ impl ScheduledEjection {
         }
     }
 
+    /// Plan the ejection for a detected bottle cap
+    ///
+    /// Returns `None` if no valve is assigned to the color or the belt is not moving.
+    pub fn from_detection(
+        detection: &ColorDetection,
+        colors: &ValveColorMap,
+        conveyor_speed: Velocity,
+    ) -> Option<Self> {
+        if conveyor_speed.get::<meter_per_second>() <= 0.0 {
+            return None;
+        }
+
+        let valve_index = colors.classify(&detection.color.into())?;
+        Some(Self::new(
+            detection.id,
+            detection.crossing_time,
+            valve_index,
+            conveyor_speed,
+            detection.color,
+        ))
+    }
+
     /// Check if the ejection is ready to be triggered
     pub fn is_ready_to_trigger(&self, current_time: Instant) -> bool {
         current_time >= self.trigger_time
@@ -195,22 +334,25 @@ impl ScheduledEjection {
     }
 }
 
-#[derive(Debug)]
 pub struct Sorter1 {
-	api_receiver: Receiver<MachineMessage>,
+    api_receiver: Receiver<MachineMessage>,
     api_sender: Sender<MachineMessage>,
     // drivers
     pub conveyor_belt: Rc<RefCell<dyn StepperVelocityEL70x1Device>>,
-    
+
     pub air_valve_outputs: Rc<RefCell<dyn DigitalOutputDevice>>,
     pub air_valve_states: [bool; 8],
 
     pub conveyor_belt_controller: ConveyorBeltController,
     pub valve_controllers: [ValveController; 8],
-    
+
+    pub color_detection_input: Box<dyn ColorDetectionInput>,
+    color_detection_sender: ColorDetectionSender,
+    pub colors: ValveColorMap,
+
     namespace: Sorter1Namespace,
     last_measurement_emit: Instant,
-    
+
     pub machine_identification_unique: MachineIdentificationUnique,
     pub mode: Sorter1Mode,
     pub conveyor_belt_mode: ConveyorBeltMode,
@@ -227,19 +369,18 @@ impl Sorter1 {
 
 /// Implement Core Functionality
 impl Sorter1 {
-	pub fn get_live_values(&mut self) -> LiveValuesEvent {
+    pub fn get_live_values(&mut self) -> LiveValuesEvent {
         // Get current conveyor belt speed in m/s
         let conveyor_belt_speed = self
             .conveyor_belt_controller
             .get_current_speed()
             .get::<meter_per_second>();
 
-
         LiveValuesEvent {
             conveyor_belt_speed,
             air_valve_states: self.air_valve_states,
         }
-	}
+    }
 
     pub fn emit_live_values(&mut self) {
         let event = self.get_live_values().build();
@@ -247,7 +388,7 @@ impl Sorter1 {
     }
 
     pub fn get_state(&mut self) -> StateEvent {
-    	StateEvent {
+        StateEvent {
             is_default_state: !std::mem::replace(&mut self.emitted_default_state, true),
             mode_state: ModeState {
                 mode: self.mode.clone().into(),
@@ -259,12 +400,12 @@ impl Sorter1 {
                     .get_target_speed()
                     .get::<meter_per_second>(),
             },
-            colors_state: vec![],
+            colors_state: convert_hsl_to_state(&self.colors),
         }
     }
 
     pub fn emit_state(&mut self) {
-    	let state = self.get_state();
+        let state = self.get_state();
         let event = state.build();
         self.namespace.emit(Sorter1Events::State(event));
     }
@@ -290,7 +431,7 @@ impl Sorter1 {
                 ConveyorBeltMode::Running => {
                     // Enable conveyor belt
                     let mut conveyor_belt = self.conveyor_belt.borrow_mut();
-                    conveyor_belt.set_enabled(0,true);
+                    conveyor_belt.set_enabled(0, true);
                     drop(conveyor_belt);
                     self.conveyor_belt_controller.set_enabled(true);
                 }
@@ -299,7 +440,7 @@ impl Sorter1 {
                 ConveyorBeltMode::Standby => {
                     // Disable conveyor belt
                     let mut conveyor_belt = self.conveyor_belt.borrow_mut();
-                    conveyor_belt.set_enabled(0,false);
+                    conveyor_belt.set_enabled(0, false);
                     drop(conveyor_belt);
                     self.conveyor_belt_controller.set_enabled(false);
                 }
@@ -313,33 +454,30 @@ impl Sorter1 {
 }
 
 fn safe_divide(divisor: f32, dividend: f32) -> f32 {
-    if divisor.abs() == f32::EPSILON {
+    if divisor.abs() <= f32::EPSILON {
         return 0.0;
     }
 
-    return dividend / divisor;
+    dividend / divisor
 }
 
 /// Implement Color Range control
 impl Sorter1 {
     pub fn assign_colour_bounds_to_valve(&mut self, colour_bounds: ColorBoundsState) {
-        if colour_bounds.valve_index >= 8 {
-            tracing::info!(
-                "assign_colour_bounds_to_valve: valve_index was out of range: {}",
-                colour_bounds.valve_index
-            );
-            return;
+        let result = ColorBoundsHsl::from_state(&colour_bounds)
+            .and_then(|bounds| self.colors.assign(colour_bounds.valve_index, bounds));
+        match result {
+            Ok(()) => tracing::info!(
+                "colour bound {:?}",
+                self.colors.get(colour_bounds.valve_index)
+            ),
+            Err(e) => tracing::warn!("assign_colour_bounds_to_valve: {}", e),
         }
-/*
-        self.colors[colour_bounds.valve_index] = ColorBoundsHsl {
-            h_min: colour_bounds.h_min,
-            h_max: colour_bounds.h_max,
-            s_min: safe_divide(100.0, colour_bounds.s_min),
-            s_max: safe_divide(100.0, colour_bounds.s_max),
-            l_min: safe_divide(100.0, colour_bounds.l_min),
-            l_max: safe_divide(100.0, colour_bounds.l_max),
-        };
-        tracing::info!("colour bound{:?}", self.colors[colour_bounds.valve_index]);*/
+        self.emit_state();
+    }
+
+    pub fn clear_colour_bounds_of_valve(&mut self, valve_index: usize) {
+        self.colors.clear(valve_index);
         self.emit_state();
     }
 }
@@ -359,7 +497,7 @@ impl Sorter1 {
         // Invert speed ebcaus emotor is mounted in reverse
         let speed = -speed * 2.0;
         let mut conveyer_belt = self.conveyor_belt.borrow_mut();
-        let _ = conveyer_belt.set_speed(0,speed);
+        let _ = conveyer_belt.set_speed(0, speed);
         drop(conveyer_belt);
     }
 }
@@ -369,20 +507,22 @@ impl Sorter1 {
     /// Set air valve state
     pub fn set_air_valve(&mut self, valve_index: usize, state: bool) {
         if valve_index < 8 {
-        	let mut air_valv_outputs = self.air_valve_outputs.borrow_mut();
-            air_valv_outputs.set_output(valve_index,state);
+            let mut air_valv_outputs = self.air_valve_outputs.borrow_mut();
+            air_valv_outputs.set_output(valve_index, state);
             drop(air_valv_outputs);
+            self.air_valve_states[valve_index] = state;
         }
         self.emit_state();
     }
 
     /// Set all air valves
     pub fn set_all_air_valves(&mut self, states: &[bool; 8]) {
-    	let mut air_valv_outputs = self.air_valve_outputs.borrow_mut();
+        let mut air_valv_outputs = self.air_valve_outputs.borrow_mut();
         for (i, &state) in states.iter().enumerate() {
-            air_valv_outputs.set_output(i,state);
+            air_valv_outputs.set_output(i, state);
         }
         drop(air_valv_outputs);
+        self.air_valve_states = *states;
         self.emit_state();
     }
 
@@ -391,8 +531,10 @@ impl Sorter1 {
         if valve_index < 8 {
             // Activate the valve controller in pulse mode
             self.valve_controllers[valve_index].activate_pulse(duration_ms);
-        	let mut air_valv_outputs = self.air_valve_outputs.borrow_mut();
-            air_valv_outputs.set_output(valve_index,true);
+            let mut air_valv_outputs = self.air_valve_outputs.borrow_mut();
+            air_valv_outputs.set_output(valve_index, true);
+            drop(air_valv_outputs);
+            self.air_valve_states[valve_index] = true;
         }
         self.emit_state();
     }
@@ -400,47 +542,70 @@ impl Sorter1 {
 
 /// Implement Bottle Cap Scheduling
 impl Sorter1 {
-    fn color_to_index(&mut self, color: &ColorHsl) -> Option<usize> {
-        tracing::info!(
-            "[color_to_index] Checking color HSL(h={:.1}°, s={:.3}, l={:.3})",
-            color.h,
-            color.s,
-            color.l
-        );
+    /// Sender for reporting detections from outside the control loop
+    pub fn get_color_detection_sender(&self) -> ColorDetectionSender {
+        self.color_detection_sender.clone()
+    }
+
+    /// Report a detection that crossed the line just now
+    pub fn report_color_detection(&mut self, id: u32, color: ColorRgb) {
+        let detection = ColorDetection {
+            id,
+            crossing_time: Instant::now(),
+            color,
+        };
+        if let Err(e) = self.color_detection_sender.report(detection) {
+            tracing::warn!("{}", e);
+        }
+    }
 
-        return None;
+    /// called by `act`
+    pub fn poll_color_detections(&mut self) {
+        while let Some(detection) = self.color_detection_input.poll_detection() {
+            self.schedule_bottle_cap(detection);
+        }
     }
 
     /// Schedule a bottle cap for processing
     /// This is called when a bottle cap crosses the middle line in the camera detection system
-    pub fn schedule_bottle_cap(&mut self, id: u32, crossing_time: Instant, color: ColorRgb) {
-        // convert to HSL for color-based sorting
-        let color_hsl: ColorHsl = ColorHsl { h: 0.0, s: 0.0, l: 0.0 };
-
-        let valve_index = 0;
+    pub fn schedule_bottle_cap(&mut self, detection: ColorDetection) {
+        let color_hsl: ColorHsl = detection.color.into();
 
         // Get current conveyor belt speed for calculation
         let belt_speed = self.conveyor_belt_controller.get_current_speed();
 
         // Create ejection with physics-based trigger time calculation
-        let ejection = ScheduledEjection::new(id, crossing_time, valve_index, belt_speed, color);
+        let Some(ejection) =
+            ScheduledEjection::from_detection(&detection, &self.colors, belt_speed)
+        else {
+            tracing::debug!(
+                "[{}] Not scheduling bottle cap ID {} at {:.3} m/s. hue={:.1}°, saturation={:.3}, lightness={:.3}",
+                module_path!(),
+                detection.id,
+                belt_speed.get::<meter_per_second>(),
+                color_hsl.h,
+                color_hsl.s,
+                color_hsl.l
+            );
+            return;
+        };
 
-        let valve_distance = VALVE_DISTANCES_CM[valve_index];
+        let valve_distance = VALVE_DISTANCES_CM[ejection.valve_index];
         let travel_time_ms =
             ((valve_distance / 100.0) / belt_speed.get::<meter_per_second>()) * 1000.0;
 
         tracing::info!(
             "[{}] Scheduling bottle cap ID {} at valve {} in {}ms. hue={:.1}°, saturation={:.3}, lightness={:.3}",
             module_path!(),
-            id,
-            valve_index,
+            detection.id,
+            ejection.valve_index,
             travel_time_ms,
             color_hsl.h,
             color_hsl.s,
             color_hsl.l
         );
 
-        self.scheduled_ejections.insert(id, ejection);
+        self.scheduled_ejections.insert(detection.id, ejection);
 
         tracing::debug!(
             "[Sorter1::schedule_bottle_cap] Total scheduled ejections: {} (IDs: {:?})",
@@ -534,7 +699,7 @@ impl Sorter1 {
                 ejection.color.b
             );
 
-            // Trigger the specified valve for 50ms
+            // Trigger the specified valve for 20ms
             self.activate_air_valve_pulse(ejection.valve_index, 20);
 
             // Remove the processed ejection
@@ -570,4 +735,163 @@ impl From<Sorter1Mode> for ConveyorBeltMode {
     }
 }
 
-impl QiTechMachine for Sorter1{}
\ No newline at end of file
+impl QiTechMachine for Sorter1 {}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const RED: ColorRgb = ColorRgb {
+        r: 220,
+        g: 20,
+        b: 30,
+    };
+    const GREEN: ColorRgb = ColorRgb {
+        r: 30,
+        g: 200,
+        b: 40,
+    };
+    const BLUE: ColorRgb = ColorRgb {
+        r: 20,
+        g: 40,
+        b: 210,
+    };
+    const WHITE: ColorRgb = ColorRgb {
+        r: 250,
+        g: 250,
+        b: 250,
+    };
+
+    fn bounds(h_min: f32, h_max: f32) -> ColorBoundsHsl {
+        ColorBoundsHsl::new(h_min, h_max, 0.3, 1.0, 0.2, 0.8).unwrap()
+    }
+
+    fn detection(id: u32, color: ColorRgb) -> ColorDetection {
+        ColorDetection {
+            id,
+            crossing_time: Instant::now(),
+            color,
+        }
+    }
+
+    fn rgb_valves() -> ValveColorMap {
+        let mut colors = ValveColorMap::default();
+        colors.assign(0, bounds(340.0, 20.0)).unwrap();
+        colors.assign(3, bounds(90.0, 150.0)).unwrap();
+        colors.assign(7, bounds(210.0, 270.0)).unwrap();
+        colors
+    }
+
+    #[test]
+    fn test_rgb_to_hsl() {
+        let red: ColorHsl = ColorRgb { r: 255, g: 0, b: 0 }.into();
+        assert!((red.h - 0.0).abs() < 1e-3);
+        assert!((red.s - 1.0).abs() < 1e-3);
+        assert!((red.l - 0.5).abs() < 1e-3);
+
+        let blue: ColorHsl = ColorRgb { r: 0, g: 0, b: 255 }.into();
+        assert!((blue.h - 240.0).abs() < 1e-3);
+
+        let gray: ColorHsl = ColorRgb {
+            r: 128,
+            g: 128,
+            b: 128,
+        }
+        .into();
+        assert_eq!(gray.s, 0.0);
+    }
+
+    #[test]
+    fn test_bounds_validation() {
+        assert!(ColorBoundsHsl::new(-1.0, 20.0, 0.0, 1.0, 0.0, 1.0).is_err());
+        assert!(ColorBoundsHsl::new(0.0, 20.0, 0.8, 0.2, 0.0, 1.0).is_err());
+        assert!(ColorBoundsHsl::new(0.0, 20.0, 0.0, 1.0, 0.0, 1.5).is_err());
+        assert!(ColorBoundsHsl::new(340.0, 20.0, 0.0, 1.0, 0.0, 1.0).is_ok());
+    }
+
+    #[test]
+    fn test_bounds_from_state_percent() {
+        let state = ColorBoundsState {
+            h_min: 10.0,
+            h_max: 50.0,
+            s_min: 20.0,
+            s_max: 100.0,
+            l_min: 0.0,
+            l_max: 50.0,
+            valve_index: 2,
+        };
+        let bounds = ColorBoundsHsl::from_state(&state).unwrap();
+        assert!((bounds.s_min - 0.2).abs() < 1e-6);
+        assert!((bounds.s_max - 1.0).abs() < 1e-6);
+        assert!((bounds.l_max - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_overlapping_bounds_rejected() {
+        let mut colors = rgb_valves();
+
+        // overlaps the wrapping red range of valve 0
+        assert!(colors.assign(1, bounds(10.0, 40.0)).is_err());
+        assert_eq!(colors.get(1).map(|b| b.h_min), None);
+
+        // replacing the bounds of the same valve is allowed
+        assert!(colors.assign(0, bounds(350.0, 30.0)).is_ok());
+
+        // disjoint in lightness does not overlap
+        let dark_red = ColorBoundsHsl::new(340.0, 20.0, 0.3, 1.0, 0.0, 0.1).unwrap();
+        assert!(colors.assign(1, dark_red).is_ok());
+
+        // out of range valve
+        assert!(colors.assign(8, bounds(60.0, 70.0)).is_err());
+    }
+
+    #[test]
+    fn test_cleared_bounds_can_be_reassigned() {
+        let mut colors = rgb_valves();
+        assert!(colors.assign(1, bounds(100.0, 120.0)).is_err());
+        colors.clear(3);
+        assert!(colors.assign(1, bounds(100.0, 120.0)).is_ok());
+    }
+
+    #[test]
+    fn test_classify_synthetic_detections() {
+        let colors = rgb_valves();
+        assert_eq!(colors.classify(&RED.into()), Some(0));
+        assert_eq!(colors.classify(&GREEN.into()), Some(3));
+        assert_eq!(colors.classify(&BLUE.into()), Some(7));
+        assert_eq!(colors.classify(&WHITE.into()), None);
+    }
+
+    #[test]
+    fn test_ejection_from_detection() {
+        let colors = rgb_valves();
+        let speed = Velocity::new::<meter_per_second>(0.5);
+
+        let red = detection(1, RED);
+        let ejection = ScheduledEjection::from_detection(&red, &colors, speed).unwrap();
+        assert_eq!(ejection.id, 1);
+        assert_eq!(ejection.valve_index, 0);
+        // 13 cm at 0.5 m/s
+        let travel = ejection.trigger_time - red.crossing_time;
+        assert!((travel.as_secs_f64() - 0.26).abs() < 1e-6);
+
+        let blue = detection(2, BLUE);
+        let ejection = ScheduledEjection::from_detection(&blue, &colors, speed).unwrap();
+        assert_eq!(ejection.valve_index, 7);
+        // 65 cm at 0.5 m/s
+        let travel = ejection.trigger_time - blue.crossing_time;
+        assert!((travel.as_secs_f64() - 1.3).abs() < 1e-6);
+        assert!(!ejection.is_ready_to_trigger(blue.crossing_time));
+        assert!(ejection.is_ready_to_trigger(blue.crossing_time + Duration::from_millis(1300)));
+    }
+
+    #[test]
+    fn test_no_ejection_for_unassigned_color_or_stopped_belt() {
+        let colors = rgb_valves();
+        let speed = Velocity::new::<meter_per_second>(0.5);
+        assert!(ScheduledEjection::from_detection(&detection(1, WHITE), &colors, speed).is_none());
+
+        let stopped = Velocity::new::<meter_per_second>(0.0);
+        assert!(ScheduledEjection::from_detection(&detection(2, RED), &colors, stopped).is_none());
+    }
+}
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, time::Instant};
+use crate::{MachineHardware, MachineMessage, MachineNew};
 use anyhow::Error;
 use control_core::converters::linear_step_converter::LinearStepConverter;
 use qitech_lib::ethercat_hal::coe::ConfigurableDevice;
@@ -8,20 +8,18 @@ use qitech_lib::ethercat_hal::devices::el7041_0052::pdo::EL7041_0052PredefinedPd
 use qitech_lib::ethercat_hal::shared_config;
 use qitech_lib::ethercat_hal::shared_config::el70x1::{EL70x1OperationMode, StmMotorConfiguration};
 use qitech_lib::units::length::millimeter;
-use qitech_lib::units::{Length, Velocity};
 use qitech_lib::units::velocity::meter_per_second;
-use crate::{MachineHardware, MachineMessage, MachineNew};
+use qitech_lib::units::{Length, Velocity};
+use std::{collections::HashMap, time::Instant};
 
 use super::api::Sorter1Namespace;
+use super::color_detection::ChannelColorDetectionInput;
 use super::conveyer_belt_controller::ConveyorBeltController;
 use super::valve_controller::ValveController;
-use super::{Sorter1, Sorter1Mode};
+use super::{Sorter1, Sorter1Mode, ValveColorMap};
 
 // Required for the new HAL abstraction
-use qitech_lib::ethercat_hal::{
-    EtherCATThreadChannel, 
-    devices::{el2008::EL2008}
-};
+use qitech_lib::ethercat_hal::{EtherCATThreadChannel, devices::el2008::EL2008};
 
 impl MachineNew for Sorter1 {
     fn new(hw: MachineHardware) -> Result<Self, Error> {
@@ -30,7 +28,8 @@ impl MachineNew for Sorter1 {
         let el2008 = hw.try_get_ethercat_device_by_role::<EL2008>(2)?;
 
         // 2. Get EtherCAT interface for configuration writing
-        let interface: EtherCATThreadChannel = hw.ethercat_interface
+        let interface: EtherCATThreadChannel = hw
+            .ethercat_interface
             .as_ref()
             .cloned()
             .ok_or_else(|| anyhow::anyhow!("[Sorter1] No EtherCAT Interface supplied"))?;
@@ -56,6 +55,7 @@ impl MachineNew for Sorter1 {
         drop(el7041_ref);
         let mode = Sorter1Mode::Standby;
         let (tx, rx) = tokio::sync::mpsc::channel::<MachineMessage>(2);
+        let (color_detection_sender, color_detection_input) = ChannelColorDetectionInput::new(64);
 
         let mut new = Self {
             conveyor_belt: el7041,
@@ -63,10 +63,7 @@ impl MachineNew for Sorter1 {
             air_valve_states: [false; 8],
             conveyor_belt_controller: ConveyorBeltController::new(
                 Velocity::new::<meter_per_second>(0.1),
-                LinearStepConverter::from_diameter(
-                    200,
-                    Length::new::<millimeter>(33.3),
-                ),
+                LinearStepConverter::from_diameter(200, Length::new::<millimeter>(33.3)),
             ),
             valve_controllers: [
                 ValveController::new(),
@@ -78,6 +75,9 @@ impl MachineNew for Sorter1 {
                 ValveController::new(),
                 ValveController::new(),
             ],
+            color_detection_input: Box::new(color_detection_input),
+            color_detection_sender,
+            colors: ValveColorMap::default(),
             namespace: Sorter1Namespace { namespace: None },
             last_measurement_emit: Instant::now(),
             machine_identification_unique: hw.identification.clone(),
@@ -93,4 +93,4 @@ impl MachineNew for Sorter1 {
         new.emit_state();
         Ok(new)
     }
-}
\ No newline at end of file
+}
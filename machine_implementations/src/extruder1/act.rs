@@ -1,8 +1,10 @@
-use crate::extruder1::ExtruderV2Mode;
-use crate::{MachineApi, extruder1::ExtruderV2};
+use crate::extruder1::{ExtruderV2Mode, diameter_controller::PullerFeedback};
+use crate::winder2::Winder2Data;
+use crate::{MachineApi, extruder1::ExtruderV2, laser::LaserData};
 use qitech_lib::machines::{
     Machine, MachineDataRegistry, MachineError, MachineIdentificationUnique,
 };
+use qitech_lib::units::{Length, Velocity, length::millimeter, velocity::meter_per_minute};
 use std::time::{Duration, Instant};
 
 impl Machine for ExtruderV2 {
@@ -95,5 +97,50 @@ impl Machine for ExtruderV2 {
         self.machine_identification_unique.clone()
     }
 
-    fn react(&mut self, _registry: &MachineDataRegistry) {}
+    fn react(&mut self, registry: &MachineDataRegistry) {
+        let now = Instant::now();
+        if self.mode != ExtruderV2Mode::Extrude {
+            self.diameter_controller.hold(now);
+            return;
+        }
+
+        let laser_data: Result<LaserData, &'static str> = match self.laser_ident {
+            Some(ident) => registry.load(&ident),
+            None => {
+                self.diameter_controller.hold(now);
+                return;
+            }
+        };
+        let laser_data = match laser_data {
+            Ok(laser_data) => laser_data,
+            Err(_e) => {
+                self.laser_ident = None;
+                self.diameter_controller.hold(now);
+                return;
+            }
+        };
+
+        let winder_data: Option<Result<Winder2Data, &'static str>> =
+            self.winder_ident.map(|ident| registry.load(&ident));
+        let puller = match winder_data {
+            Some(Ok(winder_data)) => Some(PullerFeedback {
+                speed: Velocity::new::<meter_per_minute>(winder_data.puller_speed),
+                diameter_regulation: winder_data.diameter_regulation,
+                speed_deviation: winder_data.puller_speed_deviation,
+            }),
+            Some(Err(_e)) => {
+                self.winder_ident = None;
+                None
+            }
+            None => None,
+        };
+
+        let trim = self.diameter_controller.update(
+            Length::new::<millimeter>(laser_data.live_values.diameter),
+            Length::new::<millimeter>(laser_data.state.laser_state.target_diameter),
+            puller,
+            now,
+        );
+        self.screw_speed_controller.set_throughput_trim(trim);
+    }
 }
@@ -8,8 +8,10 @@ use super::ExtruderV2;
 #[cfg(not(feature = "mock-machine"))]
 use crate::{MachineMessage, extruder1::HeatingType};
 
+use crate::machine_identification::QiTechMachineIdentificationUnique;
 #[cfg(not(feature = "mock-machine"))]
 use crate::{MachineApi, MachineValues};
+use control_core::controllers::pid_autotuner::TuningRule;
 use control_core::socketio::{
     event::{Event, GenericEvent},
     namespace::{
@@ -105,6 +107,12 @@ pub struct StateEvent {
     pub pid_settings: PidSettingsStates,
     /// pressure PID auto-tuner state
     pub pid_autotune_state: PidAutoTuneState,
+    /// temperature PID auto-tuner states
+    pub temperature_pid_autotune_states: TemperaturePidAutoTuneStates,
+    /// temperature step test states
+    pub temperature_step_test_states: TemperatureStepTestStates,
+    /// diameter control state
+    pub diameter_control_state: DiameterControlState,
 }
 
 impl StateEvent {
@@ -203,6 +211,19 @@ pub struct TemperaturePid {
     pub kp: f64,
     pub kd: f64,
     pub zone: String,
+    /// Gains over the target temperature, interpolated between the points.
+    /// Empty uses the fixed `kp`/`ki`/`kd`.
+    #[serde(default)]
+    pub schedule: Vec<TemperaturePidSchedulePoint>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct TemperaturePidSchedulePoint {
+    /// target temperature in °C
+    pub temperature: f64,
+    pub ki: f64,
+    pub kp: f64,
+    pub kd: f64,
 }
 
 #[derive(Serialize, Debug, Clone, PartialEq)]
@@ -222,9 +243,38 @@ pub struct PressureAutoTuneConfig {
     /// Keep this small relative to the steady-state operating frequency to avoid
     /// over-pressing the machine (e.g. 3 – 8 Hz for a typical extruder).
     pub frequency_step_hz: f64,
+    /// Rule the gains are computed with, `None` keeps the default conservative gains
+    #[serde(default)]
+    pub rule: Option<TuningRule>,
 }
 
-/// Live state of the pressure PID auto-tuner, broadcast as part of the machine
+/// Parameters for starting a temperature PID auto-tune run on one heating zone.
+/// The heater is switched between off and full duty around the current target.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct TemperatureAutoTuneConfig {
+    /// One of: `"front"`, `"middle"`, `"back"`, `"nozzle"`
+    pub zone: String,
+    /// Temperature oscillation half-amplitude in °C (e.g. `5.0` for ±5 °C)
+    pub tune_delta: f64,
+    /// Rule the gains are computed with, `None` keeps the default conservative gains
+    #[serde(default)]
+    pub rule: Option<TuningRule>,
+}
+
+/// Parameters for an open-loop step test of one heating zone. The heater duty is held,
+/// raised by `step` and held again until the temperature settles. PID gains are then
+/// computed from the identified model with IMC tuning.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct TemperatureStepTestConfig {
+    /// One of: `"front"`, `"middle"`, `"back"`, `"nozzle"`
+    pub zone: String,
+    /// Duty cycle change (e.g. `0.1` for +10 %)
+    pub step: f64,
+    /// Desired closed-loop time constant in s, larger values give softer gains
+    pub lambda: f64,
+}
+
+/// Live state of a PID auto-tuner, broadcast as part of the machine
 /// state.  The `result` field is populated once `state == "completed"`.
 #[derive(Serialize, Debug, Clone, PartialEq)]
 pub struct PidAutoTuneState {
@@ -232,10 +282,47 @@ pub struct PidAutoTuneState {
     pub state: String,
     /// Progress percentage in the range 0 – 100
     pub progress: f64,
-    /// Computed PID parameters – only present after a successful run
+    /// Computed PID parameters of the last successful run, kept across later runs
     pub result: Option<PidSettings>,
 }
 
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct TemperaturePidAutoTuneStates {
+    pub front: PidAutoTuneState,
+    pub middle: PidAutoTuneState,
+    pub back: PidAutoTuneState,
+    pub nozzle: PidAutoTuneState,
+}
+
+/// First-order-plus-dead-time model identified by a step test
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct FopdtModelState {
+    /// °C per duty cycle
+    pub gain: f64,
+    /// time constant in s
+    pub time_constant: f64,
+    /// dead time in s
+    pub dead_time: f64,
+}
+
+/// Live state of a step test. The gains computed from the model are reported
+/// as the `result` of the zone's [`PidAutoTuneState`].
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct StepTestState {
+    /// One of: `"not_started"`, `"baseline"`, `"stepped"`, `"completed"`, `"failed"`
+    pub state: String,
+    /// Model of the last successful test, kept across later tests
+    pub model: Option<FopdtModelState>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct TemperatureStepTestStates {
+    pub front: StepTestState,
+    pub middle: StepTestState,
+    pub back: StepTestState,
+    pub nozzle: StepTestState,
+}
+
 impl Default for PidAutoTuneState {
     fn default() -> Self {
         Self {
@@ -246,6 +333,24 @@ impl Default for PidAutoTuneState {
     }
 }
 
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DiameterControlState {
+    pub enabled: bool,
+    /// distance from the die to the laser in m
+    pub laser_distance: f64,
+    /// fraction of the full correction applied per step
+    pub gain: f64,
+    /// trim limit in %
+    pub max_trim: f64,
+    /// diameter deviation in mm that is not corrected
+    pub accepted_difference: f64,
+    /// current trim of the rpm or pressure setpoint in %
+    pub trim: f64,
+    pub laser_machine: Option<QiTechMachineIdentificationUnique>,
+    /// winder providing the line speed
+    pub winder_machine: Option<QiTechMachineIdentificationUnique>,
+}
+
 pub enum ExtruderV2Events {
     LiveValues(Event<LiveValuesEvent>),
     State(Event<StateEvent>),
@@ -281,6 +386,33 @@ pub enum Mutation {
     StartPressurePidAutoTune(PressureAutoTuneConfig),
     StopPressurePidAutoTune {},
 
+    // Temperature PID Auto-Tune
+    /// Start relay auto-tuning of one heating zone around its target temperature.
+    StartTemperaturePidAutoTune(TemperatureAutoTuneConfig),
+    StopTemperaturePidAutoTune {
+        zone: String,
+    },
+    /// Start an open-loop step test of one heating zone, an alternative to relay auto-tuning
+    /// that doesn't oscillate the temperature.
+    StartTemperatureStepTest(TemperatureStepTestConfig),
+    StopTemperatureStepTest {
+        zone: String,
+    },
+
+    // Diameter control
+    /// Trim the screw from the laser diameter
+    SetDiameterControlEnabled(bool),
+    /// Distance from the die to the laser in m
+    SetDiameterControlLaserDistance(f64),
+    /// Fraction of the full correction applied per step (0 – 1)
+    SetDiameterControlGain(f64),
+    /// Trim limit in %
+    SetDiameterControlMaxTrim(f64),
+    /// Diameter deviation in mm that is not corrected
+    SetDiameterControlAcceptedDifference(f64),
+    SetDiameterControlLaserMachine(Option<QiTechMachineIdentificationUnique>),
+    SetDiameterControlWinderMachine(Option<QiTechMachineIdentificationUnique>),
+
     // Reset
     ResetInverter(bool),
 
@@ -400,6 +532,39 @@ impl MachineApi for ExtruderV2 {
             Mutation::StopPressurePidAutoTune {} => {
                 self.stop_pressure_pid_autotune();
             }
+            Mutation::StartTemperaturePidAutoTune(config) => {
+                self.start_temperature_pid_autotune(config)?;
+            }
+            Mutation::StopTemperaturePidAutoTune { zone } => {
+                self.stop_temperature_pid_autotune(&zone);
+            }
+            Mutation::StartTemperatureStepTest(config) => {
+                self.start_temperature_step_test(config)?;
+            }
+            Mutation::StopTemperatureStepTest { zone } => {
+                self.stop_temperature_step_test(&zone);
+            }
+            Mutation::SetDiameterControlEnabled(enabled) => {
+                self.set_diameter_control_enabled(enabled);
+            }
+            Mutation::SetDiameterControlLaserDistance(distance) => {
+                self.set_diameter_control_laser_distance(distance)?;
+            }
+            Mutation::SetDiameterControlGain(gain) => {
+                self.set_diameter_control_gain(gain);
+            }
+            Mutation::SetDiameterControlMaxTrim(percent) => {
+                self.set_diameter_control_max_trim(percent);
+            }
+            Mutation::SetDiameterControlAcceptedDifference(difference) => {
+                self.set_diameter_control_accepted_difference(difference);
+            }
+            Mutation::SetDiameterControlLaserMachine(machine) => {
+                self.set_diameter_control_laser_machine(machine);
+            }
+            Mutation::SetDiameterControlWinderMachine(machine) => {
+                self.set_diameter_control_winder_machine(machine);
+            }
         }
         Ok(())
     }
@@ -0,0 +1,244 @@
+use std::time::Instant;
+
+use qitech_lib::units::{
+    ConstZero,
+    f64::{Length, Velocity},
+    length::{meter, millimeter},
+    velocity::meter_per_second,
+};
+
+/// Puller state of the winder on the same line
+#[derive(Debug, Clone, Copy)]
+pub struct PullerFeedback {
+    /// Line speed at the puller
+    pub speed: Velocity,
+    /// Puller regulates the diameter itself
+    pub diameter_regulation: bool,
+    /// Relative deviation of the puller from its base speed, `0.1` is 10 % faster
+    pub speed_deviation: f64,
+}
+
+/// Trims the extruder throughput from the laser diameter measurement.
+///
+/// # Behaviour
+/// - **Transport delay**: material extruded after a correction needs `laser_distance`
+///   of line travel to reach the laser. Line travel is accumulated from the puller
+///   speed and one correction is applied per `laser_distance`, so the result of the
+///   previous correction is always measured before the next one.
+/// - **Correction**: the throughput goes with the square of the diameter at a
+///   constant line speed, a relative diameter error `e` needs a throughput change
+///   of `2e`. `gain` is the fraction of that applied per step.
+/// - **Puller coordination**: if the puller regulates the diameter itself, it
+///   handles the fast corrections and the extruder only brings the puller back to
+///   its base speed by the deviation it reports. Both loops act on different
+///   errors and don't fight.
+/// - Without puller feedback the transport delay is unknown and the trim is held.
+#[derive(Debug)]
+pub struct DiameterController {
+    enabled: bool,
+    /// Distance from the die to the laser
+    laser_distance: Length,
+    gain: f64,
+    /// Trim limit as a fraction of the setpoint
+    max_trim: f64,
+    /// Diameter errors within this band are not corrected
+    accepted_difference: Length,
+
+    /// Relative throughput trim, `0.05` is 5 % more
+    trim: f64,
+    distance_since_last_correction: Length,
+    last_update: Instant,
+}
+
+impl Default for DiameterController {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            laser_distance: Length::new::<meter>(2.0),
+            gain: 0.5,
+            max_trim: 0.1,
+            accepted_difference: Length::new::<millimeter>(0.01),
+            trim: 0.0,
+            distance_since_last_correction: Length::ZERO,
+            last_update: Instant::now(),
+        }
+    }
+}
+
+impl DiameterController {
+    /// Relative throughput trim to apply to the active setpoint
+    pub fn update(
+        &mut self,
+        current: Length,
+        target: Length,
+        puller: Option<PullerFeedback>,
+        now: Instant,
+    ) -> f64 {
+        let dt = now.duration_since(self.last_update).as_secs_f64();
+        self.last_update = now;
+
+        if !self.enabled {
+            return self.trim;
+        }
+        let Some(puller) = puller else {
+            self.distance_since_last_correction = Length::ZERO;
+            return self.trim;
+        };
+
+        // Wait until the material of the last correction reached the laser
+        let travelled = puller.speed.abs().get::<meter_per_second>() * dt;
+        self.distance_since_last_correction += Length::new::<meter>(travelled);
+        if self.distance_since_last_correction < self.laser_distance {
+            return self.trim;
+        }
+        self.distance_since_last_correction = Length::ZERO;
+
+        let step = if puller.diameter_regulation {
+            // A faster puller compensates too much material
+            -puller.speed_deviation
+        } else {
+            let error = target - current;
+            if error.abs() <= self.accepted_difference || target <= Length::ZERO {
+                return self.trim;
+            }
+            2.0 * error.get::<millimeter>() / target.get::<millimeter>()
+        };
+
+        self.trim = (self.trim + self.gain * step).clamp(-self.max_trim, self.max_trim);
+        self.trim
+    }
+
+    /// Stop accumulating line travel, for example while not extruding
+    pub fn hold(&mut self, now: Instant) {
+        self.last_update = now;
+        self.distance_since_last_correction = Length::ZERO;
+    }
+
+    pub const fn get_trim(&self) -> f64 {
+        self.trim
+    }
+}
+
+// getters + setters
+impl DiameterController {
+    pub const fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Disabling drops the trim
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.trim = 0.0;
+        self.distance_since_last_correction = Length::ZERO;
+    }
+
+    pub fn laser_distance(&self) -> Length {
+        self.laser_distance
+    }
+
+    /// Distance from the die to the laser, has to be positive
+    pub fn set_laser_distance(&mut self, value: Length) -> Result<(), anyhow::Error> {
+        if !value.is_finite() || value <= Length::ZERO {
+            return Err(anyhow::anyhow!(
+                "[{}::DiameterController::set_laser_distance] Invalid laser distance {:?}",
+                module_path!(),
+                value
+            ));
+        }
+        self.laser_distance = value;
+        Ok(())
+    }
+
+    pub const fn gain(&self) -> f64 {
+        self.gain
+    }
+
+    pub fn set_gain(&mut self, value: f64) {
+        self.gain = value.clamp(0.0, 1.0);
+    }
+
+    pub const fn max_trim(&self) -> f64 {
+        self.max_trim
+    }
+
+    pub fn set_max_trim(&mut self, value: f64) {
+        self.max_trim = value.clamp(0.0, 1.0);
+        self.trim = self.trim.clamp(-self.max_trim, self.max_trim);
+    }
+
+    pub fn accepted_difference(&self) -> Length {
+        self.accepted_difference
+    }
+
+    pub fn set_accepted_difference(&mut self, value: Length) {
+        self.accepted_difference = value.max(Length::ZERO);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qitech_lib::units::velocity::meter_per_minute;
+    use std::time::Duration;
+
+    fn puller(speed: f64, diameter_regulation: bool, speed_deviation: f64) -> PullerFeedback {
+        PullerFeedback {
+            speed: Velocity::new::<meter_per_minute>(speed),
+            diameter_regulation,
+            speed_deviation,
+        }
+    }
+
+    fn mm(value: f64) -> Length {
+        Length::new::<millimeter>(value)
+    }
+
+    #[test]
+    fn test_waits_for_transport_delay() {
+        let mut controller = DiameterController::default();
+        controller.set_enabled(true);
+        let start = Instant::now();
+        controller.hold(start);
+
+        // Just over 1 m/s with the laser 2 m away, corrects every 2 s
+        let line = Some(puller(61.0, false, 0.0));
+        let trim = controller.update(mm(1.70), mm(1.75), line, start + Duration::from_secs(1));
+        assert_eq!(trim, 0.0);
+
+        let trim = controller.update(mm(1.70), mm(1.75), line, start + Duration::from_secs(2));
+        let expected = 0.5 * 2.0 * 0.05 / 1.75;
+        assert!((trim - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_follows_puller_when_it_regulates() {
+        let mut controller = DiameterController::default();
+        controller.set_enabled(true);
+        let start = Instant::now();
+        controller.hold(start);
+
+        // Diameter on target, but the puller runs 4 % fast to get there
+        let line = Some(puller(61.0, true, 0.04));
+        let trim = controller.update(mm(1.75), mm(1.75), line, start + Duration::from_secs(2));
+        assert!((trim + 0.02).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_holds_without_puller_and_limits_trim() {
+        let mut controller = DiameterController::default();
+        controller.set_enabled(true);
+        let start = Instant::now();
+        controller.hold(start);
+
+        let trim = controller.update(mm(1.0), mm(1.75), None, start + Duration::from_secs(60));
+        assert_eq!(trim, 0.0);
+
+        let line = Some(puller(61.0, false, 0.0));
+        let mut trim = 0.0;
+        for i in 1..=20 {
+            let now = start + Duration::from_secs(60 + 2 * i);
+            trim = controller.update(mm(1.0), mm(1.75), line, now);
+        }
+        assert_eq!(trim, controller.max_trim());
+    }
+}
@@ -4,12 +4,15 @@ use qitech_lib::ethercat_hal::io::digital_output::DigitalOutputDevice;
 use crate::extruder1::{
     ExtruderV2, ExtruderV2Mode, HeatingType,
     api::{
-        ExtruderSettingsState, ExtruderV2Events, HeatingState, HeatingStates, InverterStatusState,
-        LiveValuesEvent, ModeState, PidAutoTuneState, PidSettings, PidSettingsStates,
-        PressureAutoTuneConfig, PressureState, RegulationState, RotationState, ScrewState,
-        StateEvent, TemperaturePid,
+        DiameterControlState, ExtruderSettingsState, ExtruderV2Events, HeatingState, HeatingStates,
+        InverterStatusState, LiveValuesEvent, ModeState, PidAutoTuneState, PidSettings,
+        PidSettingsStates, PressureAutoTuneConfig, PressureState, RegulationState, RotationState,
+        ScrewState, StateEvent, TemperatureAutoTuneConfig, TemperaturePid,
+        TemperaturePidAutoTuneStates, TemperatureStepTestConfig, TemperatureStepTestStates,
     },
+    temperature_controller::TemperatureController,
 };
+use crate::machine_identification::QiTechMachineIdentificationUnique;
 
 #[cfg(not(feature = "mock-machine"))]
 impl ExtruderV2 {
@@ -109,51 +112,102 @@ impl ExtruderV2 {
                         kp: self.temperature_controller_front.pid.get_kp(),
                         kd: self.temperature_controller_front.pid.get_kd(),
                         zone: String::from("front"),
+                        schedule: self.temperature_controller_front.get_pid_schedule(),
                     },
                     middle: TemperaturePid {
                         ki: self.temperature_controller_middle.pid.get_ki(),
                         kp: self.temperature_controller_middle.pid.get_kp(),
                         kd: self.temperature_controller_middle.pid.get_kd(),
                         zone: String::from("middle"),
+                        schedule: self.temperature_controller_middle.get_pid_schedule(),
                     },
                     back: TemperaturePid {
                         ki: self.temperature_controller_back.pid.get_ki(),
                         kp: self.temperature_controller_back.pid.get_kp(),
                         kd: self.temperature_controller_back.pid.get_kd(),
                         zone: String::from("back"),
+                        schedule: self.temperature_controller_back.get_pid_schedule(),
                     },
                     nozzle: TemperaturePid {
                         ki: self.temperature_controller_nozzle.pid.get_ki(),
                         kp: self.temperature_controller_nozzle.pid.get_kp(),
                         kd: self.temperature_controller_nozzle.pid.get_kd(),
                         zone: String::from("nozzle"),
+                        schedule: self.temperature_controller_nozzle.get_pid_schedule(),
                     },
                 },
-                pressure: PidSettings {
-                    ki: self.screw_speed_controller.pid.get_ki(),
-                    kp: self.screw_speed_controller.pid.get_kp(),
-                    kd: self.screw_speed_controller.pid.get_kd(),
-                },
-            },
-            pid_autotune_state: {
-                let result = self.screw_speed_controller.get_autotune_result();
-                PidAutoTuneState {
-                    state: self.screw_speed_controller.get_autotune_state().to_string(),
-                    progress: self.screw_speed_controller.get_autotune_progress(),
-                    result,
-                }
+                pressure: self.screw_speed_controller.get_pressure_pid_settings(),
             },
+            pid_autotune_state: self.get_pressure_autotune_state(),
+            temperature_pid_autotune_states: self.get_temperature_autotune_states(),
+            temperature_step_test_states: self.get_temperature_step_test_states(),
+            diameter_control_state: self.get_diameter_control_state(),
+        }
+    }
+
+    fn get_diameter_control_state(&self) -> DiameterControlState {
+        use qitech_lib::units::length::{meter, millimeter};
+
+        let controller = &self.diameter_controller;
+        DiameterControlState {
+            enabled: controller.is_enabled(),
+            laser_distance: controller.laser_distance().get::<meter>(),
+            gain: controller.gain(),
+            max_trim: controller.max_trim() * 100.0,
+            accepted_difference: controller.accepted_difference().get::<millimeter>(),
+            trim: self.screw_speed_controller.get_throughput_trim() * 100.0,
+            laser_machine: self.laser_ident.map(Into::into),
+            winder_machine: self.winder_ident.map(Into::into),
+        }
+    }
+
+    fn get_pressure_autotune_state(&self) -> PidAutoTuneState {
+        PidAutoTuneState {
+            state: self.screw_speed_controller.get_autotune_state().to_string(),
+            progress: self.screw_speed_controller.get_autotune_progress(),
+            result: self.screw_speed_controller.get_autotune_result(),
+        }
+    }
+
+    fn get_temperature_autotune_states(&self) -> TemperaturePidAutoTuneStates {
+        TemperaturePidAutoTuneStates {
+            front: self.temperature_controller_front.get_autotune_state(),
+            middle: self.temperature_controller_middle.get_autotune_state(),
+            back: self.temperature_controller_back.get_autotune_state(),
+            nozzle: self.temperature_controller_nozzle.get_autotune_state(),
         }
     }
 
+    fn get_temperature_step_test_states(&self) -> TemperatureStepTestStates {
+        TemperatureStepTestStates {
+            front: self.temperature_controller_front.get_step_test_state(),
+            middle: self.temperature_controller_middle.get_step_test_state(),
+            back: self.temperature_controller_back.get_step_test_state(),
+            nozzle: self.temperature_controller_nozzle.get_step_test_state(),
+        }
+    }
+
+    /// Changes of the inverter status, of any auto-tuner or of the diameter trim trigger a state event
+    fn get_status_hash(&mut self) -> u64 {
+        use control_core::helpers::hasher_serializer::hash_with_serde_model;
+
+        let autotune_states = (
+            self.get_pressure_autotune_state(),
+            self.get_temperature_autotune_states(),
+            self.get_temperature_step_test_states(),
+        );
+        hash_with_serde_model((
+            self.screw_speed_controller.get_inverter_status(),
+            autotune_states,
+            self.get_diameter_control_state(),
+        ))
+    }
+
     pub fn emit_state(&mut self) {
-        use control_core::{
-            helpers::hasher_serializer::hash_with_serde_model,
-            socketio::namespace::NamespaceCacheingLogic,
-        };
+        use control_core::socketio::namespace::NamespaceCacheingLogic;
 
         let state = self.get_state();
-        let hash = hash_with_serde_model(self.screw_speed_controller.get_inverter_status());
+        let hash = self.get_status_hash();
         self.last_status_hash = Some(hash);
         let event = state.build();
         self.namespace.emit(ExtruderV2Events::State(event));
@@ -161,8 +215,6 @@ impl ExtruderV2 {
     }
 
     pub fn maybe_emit_state_event(&mut self) {
-        use control_core::helpers::hasher_serializer::hash_with_serde_model;
-
         let old_status_hash = match self.last_status_hash {
             Some(event) => event,
             None => {
@@ -170,8 +222,7 @@ impl ExtruderV2 {
                 return;
             }
         };
-        let status = self.screw_speed_controller.get_inverter_status();
-        let new_status_hash = hash_with_serde_model(status);
+        let new_status_hash = self.get_status_hash();
         if new_status_hash != old_status_hash {
             self.emit_state();
         }
@@ -335,8 +386,7 @@ impl ExtruderV2 {
 
     pub fn configure_pressure_pid(&mut self, settings: PidSettings) {
         self.screw_speed_controller
-            .pid
-            .configure(settings.ki, settings.kp, settings.kd);
+            .configure_pressure_pid(&settings);
         self.emit_state();
     }
 
@@ -358,38 +408,136 @@ impl ExtruderV2 {
         self.emit_state();
     }
 
-    pub fn configure_temperature_pid(&mut self, settings: TemperaturePid) {
-        match settings.zone.as_str() {
-            "front" => {
-                self.temperature_controller_front.pid.configure(
-                    settings.ki,
-                    settings.kp,
-                    settings.kd,
-                );
-            }
-            "middle" => {
-                self.temperature_controller_middle.pid.configure(
-                    settings.ki,
-                    settings.kp,
-                    settings.kd,
-                );
+    fn get_temperature_controller_mut(&mut self, zone: &str) -> Option<&mut TemperatureController> {
+        match zone {
+            "front" => Some(&mut self.temperature_controller_front),
+            "middle" => Some(&mut self.temperature_controller_middle),
+            "back" => Some(&mut self.temperature_controller_back),
+            "nozzle" => Some(&mut self.temperature_controller_nozzle),
+            _ => {
+                tracing::warn!("Unknown zone: {}", zone);
+                None
             }
-            "back" => {
-                self.temperature_controller_back.pid.configure(
-                    settings.ki,
-                    settings.kp,
-                    settings.kd,
-                );
-            }
-            "nozzle" => {
-                self.temperature_controller_nozzle.pid.configure(
-                    settings.ki,
-                    settings.kp,
-                    settings.kd,
-                );
-            }
-            _ => tracing::warn!("Unknown zone: {}", settings.zone),
         }
+    }
+
+    pub fn configure_temperature_pid(&mut self, settings: TemperaturePid) {
+        let Some(controller) = self.get_temperature_controller_mut(&settings.zone) else {
+            return;
+        };
+        if let Err(e) = controller.set_pid_settings(&settings) {
+            tracing::warn!("Invalid PID settings for zone {}: {}", settings.zone, e);
+        }
+        self.emit_state();
+    }
+
+    /// Start temperature PID auto-tuning of one zone.
+    ///
+    /// Heating has to be enabled, the run aborts when it gets disabled.
+    pub fn start_temperature_pid_autotune(
+        &mut self,
+        config: TemperatureAutoTuneConfig,
+    ) -> anyhow::Result<()> {
+        use std::time::Instant;
+        // The relay test needs a positive amplitude to switch around the target
+        if !config.tune_delta.is_finite() || config.tune_delta <= 0.0 {
+            return Err(anyhow::anyhow!(
+                "[{}::ExtruderV2::start_temperature_pid_autotune] Invalid tune delta {}",
+                module_path!(),
+                config.tune_delta
+            ));
+        }
+        let now = Instant::now();
+        let Some(controller) = self.get_temperature_controller_mut(&config.zone) else {
+            return Ok(());
+        };
+        controller.start_autotune(now, config.tune_delta, config.rule)?;
+        self.emit_state();
+        Ok(())
+    }
+
+    /// Abort the current temperature PID auto-tune run of one zone
+    pub fn stop_temperature_pid_autotune(&mut self, zone: &str) {
+        let Some(controller) = self.get_temperature_controller_mut(zone) else {
+            return;
+        };
+        controller.stop_autotune();
+        self.emit_state();
+    }
+
+    /// Start an open-loop step test of one zone, the gains are applied once it completes
+    pub fn start_temperature_step_test(
+        &mut self,
+        config: TemperatureStepTestConfig,
+    ) -> anyhow::Result<()> {
+        use std::time::Instant;
+        let now = Instant::now();
+        let Some(controller) = self.get_temperature_controller_mut(&config.zone) else {
+            return Ok(());
+        };
+        controller.start_step_test(now, config.step, config.lambda)?;
+        self.emit_state();
+        Ok(())
+    }
+
+    /// Abort the current step test of one zone
+    pub fn stop_temperature_step_test(&mut self, zone: &str) {
+        let Some(controller) = self.get_temperature_controller_mut(zone) else {
+            return;
+        };
+        controller.stop_step_test();
+        self.emit_state();
+    }
+
+    pub fn set_diameter_control_enabled(&mut self, enabled: bool) {
+        self.diameter_controller.set_enabled(enabled);
+        self.screw_speed_controller
+            .set_throughput_trim(self.diameter_controller.get_trim());
+        self.emit_state();
+    }
+
+    pub fn set_diameter_control_laser_distance(&mut self, distance: f64) -> anyhow::Result<()> {
+        use qitech_lib::units::{Length, length::meter};
+
+        self.diameter_controller
+            .set_laser_distance(Length::new::<meter>(distance))?;
+        self.emit_state();
+        Ok(())
+    }
+
+    pub fn set_diameter_control_gain(&mut self, gain: f64) {
+        self.diameter_controller.set_gain(gain);
+        self.emit_state();
+    }
+
+    pub fn set_diameter_control_max_trim(&mut self, percent: f64) {
+        self.diameter_controller.set_max_trim(percent / 100.0);
+        self.screw_speed_controller
+            .set_throughput_trim(self.diameter_controller.get_trim());
+        self.emit_state();
+    }
+
+    pub fn set_diameter_control_accepted_difference(&mut self, difference: f64) {
+        use qitech_lib::units::{Length, length::millimeter};
+
+        self.diameter_controller
+            .set_accepted_difference(Length::new::<millimeter>(difference));
+        self.emit_state();
+    }
+
+    pub fn set_diameter_control_laser_machine(
+        &mut self,
+        machine_uid: Option<QiTechMachineIdentificationUnique>,
+    ) {
+        self.laser_ident = machine_uid.map(Into::into);
+        self.emit_state();
+    }
+
+    pub fn set_diameter_control_winder_machine(
+        &mut self,
+        machine_uid: Option<QiTechMachineIdentificationUnique>,
+    ) {
+        self.winder_ident = machine_uid.map(Into::into);
         self.emit_state();
     }
 }
@@ -1,5 +1,6 @@
 pub mod act;
 pub mod api;
+pub mod diameter_controller;
 pub mod emit;
 pub mod mitsubishi_cs80;
 pub mod new;
@@ -10,6 +11,7 @@ pub mod temperature_controller;
 use crate::{MACHINE_EXTRUDER_V1, MACHINE_EXTRUDER_V2, VENDOR_QITECH};
 use crate::{MachineMessage, QiTechMachine};
 use api::ExtruderV2Namespace;
+use diameter_controller::DiameterController;
 use qitech_lib::machines::MachineIdentification;
 use qitech_lib::machines::MachineIdentificationUnique;
 use qitech_lib::units::{ThermodynamicTemperature, thermodynamic_temperature::degree_celsius};
@@ -86,6 +88,12 @@ pub struct ExtruderV2 {
     temperature_controller_back: TemperatureController,
     temperature_controller_nozzle: TemperatureController,
 
+    /// Trims the screw from the laser diameter
+    diameter_controller: DiameterController,
+    laser_ident: Option<MachineIdentificationUnique>,
+    /// Winder on the same line, provides the line speed
+    winder_ident: Option<MachineIdentificationUnique>,
+
     /// Energy tracking for total consumption calculation
     total_energy_kwh: f64,
     last_energy_calculation_time: Option<Instant>,
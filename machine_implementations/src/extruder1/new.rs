@@ -1,6 +1,7 @@
 use super::{
-    ExtruderV2, Heating, api::ExtruderV2Namespace, mitsubishi_cs80::MitsubishiCS80,
-    screw_speed_controller::ScrewSpeedController, temperature_controller::TemperatureController,
+    ExtruderV2, Heating, api::ExtruderV2Namespace, diameter_controller::DiameterController,
+    mitsubishi_cs80::MitsubishiCS80, screw_speed_controller::ScrewSpeedController,
+    temperature_controller::TemperatureController,
 };
 use crate::{
     MACHINE_EXTRUDER_V1, MACHINE_EXTRUDER_V2, MachineHardware, MachineMessage, MachineNew,
@@ -199,6 +200,9 @@ impl MachineNew for ExtruderV2 {
             temperature_controller_back,
             temperature_controller_nozzle,
             screw_speed_controller,
+            diameter_controller: DiameterController::default(),
+            laser_ident: None,
+            winder_ident: None,
             emitted_default_state: false,
             last_status_hash: None,
 
@@ -7,8 +7,12 @@ use super::{
 use crate::extruder1::mitsubishi_cs80::MitsubishiCS80Status;
 use control_core::{
     controllers::{
-        clamping_timeagnostic_pid::ClampingTimeagnosticPidController,
-        pid_autotuner::{AutoTuneConfig, PidAutoTuner},
+        first_degree_motion::{
+            acceleration_speed_controller::AccelerationSpeedController,
+            angular_acceleration_speed_controller::AngularAccelerationSpeedController,
+        },
+        pid::{AntiWindup, Limits, PidConfig, PidController},
+        pid_autotuner::{AutoTuneConfig, PidAutoTuner, TuningRule},
     },
     helpers::interpolation::normalize,
     transmission::{Transmission, fixed::FixedTransmission},
@@ -19,15 +23,44 @@ use qitech_lib::{
         io::{analog_input::AnalogInputDevice, serial_interface::SerialInterfaceDevice},
     },
     units::{
-        AngularVelocity, Frequency, Pressure, angular_velocity::revolution_per_minute,
-        electric_current::milliampere, frequency::hertz, pressure::bar,
+        AngularAcceleration, AngularVelocity, Frequency, Pressure,
+        angular_acceleration::revolution_per_minute_per_second,
+        angular_velocity::revolution_per_minute, electric_current::milliampere, frequency::hertz,
+        pressure::bar,
     },
 };
 const AUTOTUNE_MAX_DURATION: Duration = Duration::from_secs(30);
-
+/// Rate limit of the screw rpm setpoint, in rpm/s
+const SCREW_RPM_RATE_LIMIT: f64 = 10.0;
+/// Rate limit of the inverter frequency, in Hz/s
+const FREQUENCY_RATE_LIMIT: f64 = 10.0;
+/// Default pressure gains in Hz/bar and Hz/(bar·s), the regulation of the former
+/// incremental loop: its kd of 0.02 acted proportionally and its kp of 0.01 integrally
+const PRESSURE_KP: f64 = 0.02;
+const PRESSURE_KI: f64 = 0.01;
+
+/// Screw speed in direct rpm mode or as a cascade for pressure regulation.
+///
+/// The outer loop turns the pressure error into a screw rpm setpoint, which is converted
+/// to the inverter frequency through the transmission. The inner loop is not closed: the
+/// inverter only reports its output frequency, not a measured speed, and the screw has no
+/// speed sensor, so the frequency is pure feed forward of the rpm setpoint.
+/// In rpm mode the target rpm is the setpoint. The rpm setpoint and the frequency are
+/// rate limited, so switching between the modes continues from the current speed.
 #[derive(Debug)]
 pub struct ScrewSpeedController {
-    pub pid: ClampingTimeagnosticPidController,
+    /// Outer loop, pressure in bar → screw rpm. The gains are in rpm/bar internally
+    /// and in Hz/bar at the API, see [`Self::get_pressure_pid_settings`].
+    pid: PidController,
+    rpm_ramp: AngularAccelerationSpeedController,
+    frequency_ramp: AccelerationSpeedController,
+    /// Rate limited screw rpm setpoint of the inner loop
+    rpm_setpoint: AngularVelocity,
+    /// Screw rpm when pressure regulation took over, the outer loop starts from it
+    pressure_bias_rpm: f64,
+    pressure_loop_active: bool,
+    /// Relative trim of the active setpoint from the diameter control
+    throughput_trim: f64,
     pub target_pressure: Pressure,
     pub target_rpm: AngularVelocity,
     pub motor_poles: usize,
@@ -37,6 +70,8 @@ pub struct ScrewSpeedController {
     forward_rotation: bool,
     transmission: FixedTransmission,
     pid_autotuner: Option<PidAutoTuner>,
+    autotune_rule: Option<TuningRule>,
+    autotune_result: Option<PidSettings>,
     frequency: Frequency,
     pub pressure: Pressure,
     maximum_frequency: Frequency,
@@ -44,8 +79,9 @@ pub struct ScrewSpeedController {
     motor_on: bool,
     nozzle_pressure_limit: Pressure,
     nozzle_pressure_limit_enabled: bool,
-    autotune_high_frequency: Frequency,
-    autotune_low_frequency: Frequency,
+    /// Relay setpoints of the outer loop while auto-tuning
+    autotune_high_rpm: AngularVelocity,
+    autotune_low_rpm: AngularVelocity,
     pub wiring_error: bool,
 }
 
@@ -58,10 +94,51 @@ impl ScrewSpeedController {
         motor_poles: usize,
     ) -> Self {
         let now = Instant::now();
+        let maximum_frequency = Frequency::new::<hertz>(60.0);
+        let minimum_frequency = Frequency::new::<hertz>(0.0);
+        let max_rpm = Self::frequency_to_screw_rpm(&transmission, motor_poles, maximum_frequency);
+        let min_rpm = Self::frequency_to_screw_rpm(&transmission, motor_poles, minimum_frequency);
+
+        // need to tune, derivative on the measured pressure
+        let rpm_per_hertz =
+            Self::frequency_to_screw_rpm(&transmission, motor_poles, Frequency::new::<hertz>(1.0))
+                .get::<revolution_per_minute>();
+        let mut pressure_config = PidConfig::new(
+            PRESSURE_KP * rpm_per_hertz,
+            PRESSURE_KI * rpm_per_hertz,
+            0.0,
+        );
+        pressure_config.setpoint_weight_d = 0.0;
+        pressure_config.output_limits = Limits::new(
+            Some(min_rpm.get::<revolution_per_minute>()),
+            Some(max_rpm.get::<revolution_per_minute>()),
+        );
+        pressure_config.anti_windup = AntiWindup::ConditionalIntegration;
+
+        let rpm_rate_limit =
+            AngularAcceleration::new::<revolution_per_minute_per_second>(SCREW_RPM_RATE_LIMIT);
+
         Self {
             inverter,
-            // need to tune
-            pid: ClampingTimeagnosticPidController::simple_new(0.01, 0.0, 0.02),
+            pid: PidController::with_config(pressure_config),
+            rpm_ramp: AngularAccelerationSpeedController::new(
+                Some(min_rpm),
+                Some(max_rpm),
+                -rpm_rate_limit,
+                rpm_rate_limit,
+                AngularVelocity::new::<revolution_per_minute>(0.0),
+            ),
+            frequency_ramp: AccelerationSpeedController::new(
+                Some(minimum_frequency.get::<hertz>()),
+                Some(maximum_frequency.get::<hertz>()),
+                -FREQUENCY_RATE_LIMIT,
+                FREQUENCY_RATE_LIMIT,
+                0.0,
+            ),
+            rpm_setpoint: AngularVelocity::new::<revolution_per_minute>(0.0),
+            pressure_bias_rpm: 0.0,
+            pressure_loop_active: false,
+            throughput_trim: 0.0,
             last_update: now,
             target_pressure,
             target_rpm,
@@ -72,14 +149,16 @@ impl ScrewSpeedController {
             nozzle_pressure_limit: Pressure::new::<bar>(100.0),
             nozzle_pressure_limit_enabled: true,
             frequency: Frequency::new::<hertz>(0.0),
-            maximum_frequency: Frequency::new::<hertz>(60.0),
-            minimum_frequency: Frequency::new::<hertz>(0.0),
+            maximum_frequency,
+            minimum_frequency,
             motor_poles,
             wiring_error: false,
             pressure: Pressure::new::<bar>(0.0),
             pid_autotuner: None,
-            autotune_high_frequency: Frequency::new::<hertz>(0.0),
-            autotune_low_frequency: Frequency::new::<hertz>(0.0),
+            autotune_rule: None,
+            autotune_result: None,
+            autotune_high_rpm: AngularVelocity::new::<revolution_per_minute>(0.0),
+            autotune_low_rpm: AngularVelocity::new::<revolution_per_minute>(0.0),
         }
     }
 
@@ -122,29 +201,83 @@ impl ScrewSpeedController {
         }
     }
 
+    /// The outer loop restarts from the current rpm setpoint
     pub fn set_target_pressure(&mut self, target_pressure: Pressure) {
+        self.pressure_bias_rpm = self.rpm_setpoint.get::<revolution_per_minute>();
         self.reset_pid();
         self.target_pressure = target_pressure;
     }
 
+    /// The inner loop ramps to the new target in rpm mode
     pub fn set_target_screw_rpm(
         &mut self,
         target_rpm: AngularVelocity,
         _motor_rpm_rating: AngularVelocity,
         motor_poles: usize,
     ) {
-        // Use uom here and perhaps clamp it
-        let target_motor_rpm = self
-            .transmission
-            .calculate_angular_velocity_input(target_rpm);
-
         self.target_rpm = target_rpm;
+        self.motor_poles = motor_poles;
+    }
+
+    /// Scales the target rpm or the target pressure, whichever is regulated,
+    /// `0.05` extrudes 5 % more
+    pub const fn set_throughput_trim(&mut self, trim: f64) {
+        self.throughput_trim = trim;
+    }
 
-        let target_frequency: Frequency = Frequency::new::<hertz>(
-            target_motor_rpm.get::<revolution_per_minute>() as f64 / 120.0 * motor_poles as f64,
+    pub const fn get_throughput_trim(&self) -> f64 {
+        self.throughput_trim
+    }
+
+    fn frequency_to_screw_rpm(
+        transmission: &FixedTransmission,
+        motor_poles: usize,
+        frequency: Frequency,
+    ) -> AngularVelocity {
+        let motor_rpm = AngularVelocity::new::<revolution_per_minute>(
+            frequency.get::<hertz>() * 120.0 / motor_poles as f64,
+        );
+        transmission.calculate_angular_velocity_output(motor_rpm)
+    }
+
+    /// Screw rpm per Hz of inverter frequency
+    fn rpm_per_hertz(&self) -> f64 {
+        Self::frequency_to_screw_rpm(
+            &self.transmission,
+            self.motor_poles,
+            Frequency::new::<hertz>(1.0),
+        )
+        .get::<revolution_per_minute>()
+    }
+
+    /// Pressure gains in Hz/bar, the unit they were always set and stored in
+    pub fn get_pressure_pid_settings(&self) -> PidSettings {
+        let rpm_per_hertz = self.rpm_per_hertz();
+        PidSettings {
+            ki: self.pid.get_ki() / rpm_per_hertz,
+            kp: self.pid.get_kp() / rpm_per_hertz,
+            kd: self.pid.get_kd() / rpm_per_hertz,
+        }
+    }
+
+    /// Set the pressure gains in Hz/bar and restart the outer loop
+    pub fn configure_pressure_pid(&mut self, settings: &PidSettings) {
+        let rpm_per_hertz = self.rpm_per_hertz();
+        self.pid.configure(
+            settings.ki * rpm_per_hertz,
+            settings.kp * rpm_per_hertz,
+            settings.kd * rpm_per_hertz,
         );
+        self.pressure_loop_active = false;
+    }
 
-        self.inverter.set_frequency_target(target_frequency);
+    fn screw_rpm_to_frequency(&self, screw_rpm: AngularVelocity) -> Frequency {
+        let motor_rpm = self
+            .transmission
+            .calculate_angular_velocity_input(screw_rpm);
+        Frequency::new::<hertz>(
+            motor_rpm.get::<revolution_per_minute>() / 120.0 * self.motor_poles as f64,
+        )
     }
 
     pub const fn get_uses_rpm(&self) -> bool {
@@ -159,6 +292,7 @@ impl ScrewSpeedController {
     pub fn turn_motor_off(&mut self) {
         self.inverter.stop_motor();
         self.motor_on = false;
+        self.reset_cascade();
     }
 
     pub fn turn_motor_on(&mut self) {
@@ -167,17 +301,12 @@ impl ScrewSpeedController {
     }
 
     pub fn get_motor_status(&self) -> MotorStatus {
-        let frequency = self.inverter.motor_status.frequency;
-        let motor_rpm = AngularVelocity::new::<revolution_per_minute>(
-            frequency.get::<hertz>() * 120.0 / self.motor_poles as f64,
-        );
-
-        let screw_rpm = self
-            .transmission
-            .calculate_angular_velocity_output(motor_rpm);
-
         let mut status = self.inverter.motor_status;
-        status.rpm = screw_rpm;
+        status.rpm = Self::frequency_to_screw_rpm(
+            &self.transmission,
+            self.motor_poles,
+            self.inverter.motor_status.frequency,
+        );
 
         status
     }
@@ -219,6 +348,17 @@ impl ScrewSpeedController {
         self.pid.reset()
     }
 
+    /// Restart the outer loop and the ramps from standstill
+    fn reset_cascade(&mut self) {
+        let zero = AngularVelocity::new::<revolution_per_minute>(0.0);
+        self.pid.reset();
+        self.rpm_ramp.reset(zero);
+        self.frequency_ramp.reset(0.0);
+        self.rpm_setpoint = zero;
+        self.frequency = Frequency::new::<hertz>(0.0);
+        self.pressure_loop_active = false;
+    }
+
     pub fn get_pressure(&self, pressure_sensor: &dyn AnalogInputDevice) -> Pressure {
         let phys = pressure_sensor.get_input(0);
         let current_result = match phys {
@@ -277,78 +417,132 @@ impl ScrewSpeedController {
             self.turn_motor_on();
         }
 
-        if !self.uses_rpm && is_extruding {
-            // --- PID auto-tune active? ---
-            let mut tuner_done = false;
-            if let Some(ref mut tuner) = self.pid_autotuner {
-                if tuner.is_running() {
-                    let pressure_bar = measured_pressure.get::<bar>();
-                    let duty = tuner.update(pressure_bar, now);
+        if !self.motor_on {
+            self.last_update = now;
+            return;
+        }
 
-                    // Duty > 0 → drive high; duty == 0 → drive low
-                    let target_freq = if duty > 0.0 {
-                        self.autotune_high_frequency
-                    } else {
-                        self.autotune_low_frequency
-                    };
-                    self.inverter.set_frequency_target(target_freq);
-                    self.frequency = target_freq;
-                    self.last_update = now;
-                    return;
-                } else if tuner.is_completed() {
+        let frequency = self.update_cascade(now, is_extruding, measured_pressure);
+        self.inverter.set_frequency_target(frequency);
+        self.last_update = now;
+    }
+
+    /// Runs the outer loop in pressure mode and returns the inverter frequency
+    fn update_cascade(
+        &mut self,
+        now: Instant,
+        is_extruding: bool,
+        measured_pressure: Pressure,
+    ) -> Frequency {
+        let target_rpm = if !self.uses_rpm && is_extruding {
+            self.update_pressure_loop(now, measured_pressure)
+        } else {
+            self.pressure_loop_active = false;
+            self.target_rpm * (1.0 + self.throughput_trim)
+        };
+
+        self.rpm_setpoint = self.rpm_ramp.update(target_rpm, now);
+        self.update_frequency(now)
+    }
+
+    /// Outer loop, returns the screw rpm target for the inner loop
+    fn update_pressure_loop(
+        &mut self,
+        now: Instant,
+        measured_pressure: Pressure,
+    ) -> AngularVelocity {
+        // Bumpless handover, the outer loop continues from the speed the inverter is
+        // commanded, which can lag behind the rpm setpoint while the frequency is rate limited
+        if !self.pressure_loop_active {
+            let current_rpm =
+                Self::frequency_to_screw_rpm(&self.transmission, self.motor_poles, self.frequency);
+            self.pressure_loop_active = true;
+            self.pressure_bias_rpm = current_rpm.get::<revolution_per_minute>();
+            self.rpm_ramp.reset(current_rpm);
+            self.rpm_setpoint = current_rpm;
+            self.pid.reset();
+        }
+
+        // --- PID auto-tune active? ---
+        if let Some(ref mut tuner) = self.pid_autotuner {
+            if tuner.is_running() {
+                let pressure_bar = measured_pressure.get::<bar>();
+                let duty = tuner.update(pressure_bar, now);
+
+                if tuner.is_completed() {
                     // Apply the computed PID gains and switch back to normal control
                     if let Ok(result) = tuner.result() {
+                        let result = match self.autotune_rule {
+                            Some(rule) => result.with_rule(rule),
+                            None => result.clone(),
+                        };
+                        // The relay swings the rpm setpoint, the result is in rpm/bar
                         self.pid.configure(result.ki, result.kp, result.kd);
+                        let settings = self.get_pressure_pid_settings();
                         tracing::info!(
-                            "Pressure PID auto-tune completed: kp={:.4}, ki={:.4}, kd={:.4}",
-                            result.kp,
-                            result.ki,
-                            result.kd,
+                            "Pressure PID auto-tune completed: kp={:.4}, ki={:.4}, kd={:.4} Hz/bar",
+                            settings.kp,
+                            settings.ki,
+                            settings.kd,
                         );
+                        self.autotune_result = Some(settings);
                     }
-                    tuner_done = true;
+                    self.pressure_bias_rpm = self.rpm_setpoint.get::<revolution_per_minute>();
+                    self.pid.reset();
                 } else if tuner.is_failed() {
                     tracing::warn!("Pressure PID auto-tune failed");
-                    tuner_done = true;
+                    self.pressure_bias_rpm = self.rpm_setpoint.get::<revolution_per_minute>();
+                    self.pid.reset();
+                } else {
+                    // Duty > 0 → drive high; duty == 0 → drive low
+                    return if duty > 0.0 {
+                        self.autotune_high_rpm
+                    } else {
+                        self.autotune_low_rpm
+                    };
                 }
             }
-            if tuner_done {
-                self.pid_autotuner = None;
-                // Sync frequency to current inverter state before resuming PID control
-                self.frequency = self.inverter.motor_status.frequency;
-                self.pid.reset();
-            }
-
-            // Normal PID pressure regulation
-            let error = self.target_pressure - measured_pressure;
-            let freq_change = self.pid.update(error.get::<bar>(), now);
+        }
 
-            self.frequency += Frequency::new::<hertz>(freq_change);
-            self.frequency = Self::clamp_frequency(
-                self.frequency,
-                self.minimum_frequency,
-                self.maximum_frequency,
-            );
+        let rpm = self.pid.update_with(
+            self.target_pressure.get::<bar>() * (1.0 + self.throughput_trim),
+            measured_pressure.get::<bar>(),
+            self.pressure_bias_rpm,
+            now,
+        );
+        AngularVelocity::new::<revolution_per_minute>(rpm)
+    }
 
-            self.inverter.set_frequency_target(self.frequency);
-        }
-        self.last_update = now;
+    /// Converts the rpm setpoint to the rate limited inverter frequency
+    fn update_frequency(&mut self, now: Instant) -> Frequency {
+        let feed_forward = self.screw_rpm_to_frequency(self.rpm_setpoint);
+        let frequency = self.frequency_ramp.update(feed_forward.get::<hertz>(), now);
+        self.frequency = Self::clamp_frequency(
+            Frequency::new::<hertz>(frequency),
+            self.minimum_frequency,
+            self.maximum_frequency,
+        );
+        self.frequency
     }
 
+    /// Hand over to pressure regulation on the next update
     pub fn start_pressure_regulation(&mut self) {
         self.last_update = Instant::now();
-        self.frequency = self.inverter.motor_status.frequency;
-        self.pid.reset();
+        self.pressure_loop_active = false;
     }
 
     pub fn reset(&mut self) {
         self.pid.reset();
+        self.pressure_loop_active = false;
         self.last_update = Instant::now();
     }
 
     pub fn start_pressure_autotune(&mut self, now: Instant, config: PressureAutoTuneConfig) {
-        // Snapshot the current inverter frequency as the relay centre point
-        let base_hz = self.inverter.motor_status.frequency.get::<hertz>();
+        // The relay swings the rpm setpoint of the inner loop around the current one,
+        // the step is given in Hz since that is what the operator sees on the inverter
+        let base_hz = self
+            .screw_rpm_to_frequency(self.rpm_setpoint)
+            .get::<hertz>();
         let step_hz = config.frequency_step_hz;
 
         let high = Self::clamp_frequency(
@@ -362,18 +556,23 @@ impl ScrewSpeedController {
             self.maximum_frequency,
         );
 
-        self.autotune_high_frequency = high;
-        self.autotune_low_frequency = low;
+        self.autotune_high_rpm =
+            Self::frequency_to_screw_rpm(&self.transmission, self.motor_poles, high);
+        self.autotune_low_rpm =
+            Self::frequency_to_screw_rpm(&self.transmission, self.motor_poles, low);
 
-        // Use the actual Hz swing as max_power so the Ziegler–Nichols result
-        // is in the same units (Hz/bar) that the PID controller expects.
-        let hz_swing = (high - low).get::<hertz>().max(0.01); // guard against zero
+        // Use the actual rpm swing as max_power so the result is in the same
+        // units (rpm/bar) that the outer loop runs with.
+        let rpm_swing = (self.autotune_high_rpm - self.autotune_low_rpm)
+            .get::<revolution_per_minute>()
+            .max(0.01); // guard against zero
         let auto_config = AutoTuneConfig {
             tune_delta: config.tune_delta,
-            max_power: hz_swing,
+            max_power: rpm_swing,
             max_duration: AUTOTUNE_MAX_DURATION,
         };
         let mut tuner = PidAutoTuner::new(auto_config);
+        self.autotune_rule = config.rule;
         let target_pressure = self.target_pressure.get::<bar>();
         tuner.start(now, target_pressure);
         self.pid_autotuner = Some(tuner);
@@ -394,7 +593,13 @@ impl ScrewSpeedController {
     /// Abort an in-progress auto-tune run
     pub fn stop_autotune(&mut self) {
         if let Some(ref mut tuner) = self.pid_autotuner {
-            tuner.stop();
+            if tuner.is_running() {
+                tuner.stop();
+                tracing::warn!("Pressure PID auto-tune aborted");
+                // Resume pressure regulation from the current setpoint
+                self.pressure_bias_rpm = self.rpm_setpoint.get::<revolution_per_minute>();
+                self.pid.reset();
+            }
         }
     }
 
@@ -416,13 +621,136 @@ impl ScrewSpeedController {
 
     /// Returns PID values from the last completed auto-tune run, if any
     pub fn get_autotune_result(&self) -> Option<PidSettings> {
-        self.pid_autotuner
-            .as_ref()
-            .and_then(|t| t.result().ok())
-            .map(|result| PidSettings {
-                ki: result.ki,
-                kp: result.kp,
-                kd: result.kd,
+        self.autotune_result.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: Duration = Duration::from_millis(100);
+
+    /// 4 pole motor behind a 1:34 gearbox, 30 / 34 screw rpm per Hz
+    fn controller() -> ScrewSpeedController {
+        let mut controller = ScrewSpeedController::new(
+            MitsubishiCS80::new(),
+            Pressure::new::<bar>(50.0),
+            AngularVelocity::new::<revolution_per_minute>(30.0),
+            FixedTransmission::new(1.0 / 34.0),
+            4,
+        );
+        controller.motor_on = true;
+        controller
+    }
+
+    /// Runs the cascade once per 100 ms with a constant pressure, returns the frequencies in Hz
+    fn run(
+        controller: &mut ScrewSpeedController,
+        start: Instant,
+        steps: std::ops::Range<u32>,
+        pressure: f64,
+    ) -> Vec<f64> {
+        steps
+            .map(|i| {
+                controller
+                    .update_cascade(start + DT * i, true, Pressure::new::<bar>(pressure))
+                    .get::<hertz>()
             })
+            .collect()
+    }
+
+    #[test]
+    fn test_rate_limits() {
+        let mut controller = controller();
+        let start = Instant::now();
+
+        // 10 rpm/s allow 11.3 Hz/s, the frequency is limited to 10 Hz/s
+        let frequencies = run(&mut controller, start, 0..11, 0.0);
+        let rpm_setpoint = controller.rpm_setpoint.get::<revolution_per_minute>();
+        assert!((rpm_setpoint - 10.0).abs() < 1e-9);
+        assert!((frequencies[10] - 10.0).abs() < 1e-9);
+        assert!(
+            frequencies
+                .windows(2)
+                .all(|pair| pair[1] - pair[0] <= FREQUENCY_RATE_LIMIT * 0.1 + 1e-9)
+        );
+
+        // Settles at the feed forward of the target
+        let frequencies = run(&mut controller, start, 11..100, 0.0);
+        assert!((frequencies.last().unwrap() - 34.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_handover_is_bumpless() {
+        let mut controller = controller();
+        let start = Instant::now();
+
+        // Mid ramp the rpm setpoint is ahead of the rate limited frequency
+        let frequencies = run(&mut controller, start, 0..11, 0.0);
+        controller.set_uses_rpm(false);
+        let after = run(&mut controller, start, 11..12, 50.0);
+        assert!((after[0] - frequencies[10]).abs() < 1e-9);
+
+        // At the target pressure the speed is held
+        let after = run(&mut controller, start, 12..50, 50.0);
+        assert!(after.iter().all(|f| (f - frequencies[10]).abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_pressure_cascade() {
+        let mut controller = controller();
+        let start = Instant::now();
+        run(&mut controller, start, 0..100, 0.0);
+        controller.set_uses_rpm(false);
+        run(&mut controller, start, 100..101, 50.0);
+
+        // Too little pressure speeds the screw up, rate limited
+        let frequencies = run(&mut controller, start, 101..150, 40.0);
+        assert!(*frequencies.last().unwrap() > 34.0);
+        assert!(
+            frequencies
+                .windows(2)
+                .all(|pair| (pair[1] - pair[0]).abs() <= FREQUENCY_RATE_LIMIT * 0.1 + 1e-9)
+        );
+
+        // Too much pressure slows it down
+        let frequencies = run(&mut controller, start, 150..400, 60.0);
+        assert!(*frequencies.last().unwrap() < 34.0);
+    }
+
+    #[test]
+    fn test_new_target_pressure_continues_from_the_current_speed() {
+        let mut controller = controller();
+        let start = Instant::now();
+        run(&mut controller, start, 0..100, 0.0);
+        controller.set_uses_rpm(false);
+        let frequencies = run(&mut controller, start, 100..150, 40.0);
+        assert!(*frequencies.last().unwrap() > 34.0);
+
+        // Already at the new target, the speed is held instead of falling back
+        controller.set_target_pressure(Pressure::new::<bar>(40.0));
+        let after = run(&mut controller, start, 150..160, 40.0);
+        assert!(
+            after
+                .iter()
+                .all(|f| (f - frequencies.last().unwrap()).abs() < 1e-9)
+        );
+    }
+
+    #[test]
+    fn test_pressure_gains_in_hertz_per_bar() {
+        let mut controller = controller();
+        let settings = PidSettings {
+            ki: 0.2,
+            kp: 1.0,
+            kd: 0.0,
+        };
+        controller.configure_pressure_pid(&settings);
+
+        assert!((controller.pid.get_kp() - 30.0 / 34.0).abs() < 1e-9);
+        let read_back = controller.get_pressure_pid_settings();
+        assert!((read_back.kp - settings.kp).abs() < 1e-9);
+        assert!((read_back.ki - settings.ki).abs() < 1e-9);
     }
 }
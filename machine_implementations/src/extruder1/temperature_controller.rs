@@ -1,5 +1,18 @@
-use super::Heating;
-use control_core::controllers::pid::PidController;
+use super::{
+    Heating,
+    api::{
+        FopdtModelState, PidAutoTuneState, PidSettings, StepTestState, TemperaturePid,
+        TemperaturePidSchedulePoint,
+    },
+};
+use control_core::controllers::{
+    gain_scheduled_pid::{
+        GainSchedule, GainSchedulePoint, GainScheduledPidController, SchedulingVariable,
+    },
+    pid::{AntiWindup, Limits, PidConfig},
+    pid_autotuner::{AutoTuneConfig, PidAutoTuner, TuningRule},
+    step_response_identifier::{FopdtModel, StepResponseIdentifier, StepTestConfig},
+};
 use qitech_lib::{
     ethercat_hal::io::{
         digital_output::DigitalOutputDevice, temperature_input::TemperatureInputDevice,
@@ -8,8 +21,22 @@ use qitech_lib::{
 };
 use std::time::{Duration, Instant};
 
+/// Heating zones oscillate slowly, ten relay cycles can take well over an hour
+const AUTOTUNE_MAX_DURATION: Duration = Duration::from_secs(3 * 3600);
+
+/// Heating zones settle within minutes, a step test records one minute before the step
+/// and needs the temperature to stay within 0.5 °C for five minutes afterwards
+const STEP_TEST_CONFIG: StepTestConfig = StepTestConfig {
+    step: 0.0,
+    baseline_duration: Duration::from_secs(60),
+    sample_interval: Duration::from_secs(1),
+    settle_tolerance: 0.5,
+    settle_duration: Duration::from_secs(300),
+    max_duration: AUTOTUNE_MAX_DURATION,
+};
+
 pub struct TemperatureController {
-    pub pid: PidController,
+    pub pid: GainScheduledPidController,
     pub heating: Heating,
     pub target_temp: ThermodynamicTemperature,
     pub digital_port: usize,
@@ -22,6 +49,13 @@ pub struct TemperatureController {
     heating_element_wattage: f64,
     max_clamp: f64,
     target_temp_enabled: bool, // Sets whether the frontend should display a target temperature setter for this temp controller
+    pid_autotuner: Option<PidAutoTuner>,
+    autotune_rule: Option<TuningRule>,
+    autotune_result: Option<PidSettings>,
+    step_identifier: Option<StepResponseIdentifier>,
+    /// Closed-loop time constant the step test gains are computed for
+    step_lambda: f64,
+    step_model: Option<FopdtModel>,
 }
 
 impl TemperatureController {
@@ -44,8 +78,14 @@ impl TemperatureController {
         digital_port: usize,
         temperature_port: usize,
     ) -> Self {
+        // Duty cycle output, derivative on the measured temperature so setpoint changes don't kick
+        let mut pid_config = PidConfig::new(kp, ki, kd);
+        pid_config.setpoint_weight_d = 0.0;
+        pid_config.output_limits = Limits::new(Some(0.0), Some(max_clamp));
+        pid_config.anti_windup = AntiWindup::ConditionalIntegration;
         Self {
-            pid: PidController::new(kp, ki, kd),
+            // Gains are scheduled over the target, the zone behaves very differently across the range
+            pid: GainScheduledPidController::new(pid_config, SchedulingVariable::Setpoint),
             target_temp,
             window_start: Instant::now(),
             heating,
@@ -58,7 +98,254 @@ impl TemperatureController {
             target_temp_enabled: true,
             digital_port,
             temperature_port,
+            pid_autotuner: None,
+            autotune_rule: None,
+            autotune_result: None,
+            step_identifier: None,
+            step_lambda: 0.0,
+            step_model: None,
+        }
+    }
+
+    /// Apply fixed gains, or the schedule if it has points
+    pub fn set_pid_settings(&mut self, settings: &TemperaturePid) -> Result<(), anyhow::Error> {
+        if settings.schedule.is_empty() {
+            self.pid.set_gains(settings.kp, settings.ki, settings.kd);
+            return Ok(());
+        }
+
+        let points = settings
+            .schedule
+            .iter()
+            .map(|point| GainSchedulePoint {
+                at: point.temperature,
+                kp: point.kp,
+                ki: point.ki,
+                kd: point.kd,
+            })
+            .collect();
+        self.pid.set_schedule(Some(GainSchedule::new(points)?));
+        Ok(())
+    }
+
+    pub fn get_pid_schedule(&self) -> Vec<TemperaturePidSchedulePoint> {
+        self.pid
+            .get_schedule()
+            .map(|schedule| {
+                schedule
+                    .points()
+                    .iter()
+                    .map(|point| TemperaturePidSchedulePoint {
+                        temperature: point.at,
+                        ki: point.ki,
+                        kp: point.kp,
+                        kd: point.kd,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Start relay auto-tuning around the current target, the heater switches between
+    /// off and `max_clamp` until enough oscillations are recorded
+    pub fn start_autotune(
+        &mut self,
+        now: Instant,
+        tune_delta: f64,
+        rule: Option<TuningRule>,
+    ) -> Result<(), anyhow::Error> {
+        if !self.heating_allowed {
+            return Err(anyhow::anyhow!(
+                "[{}::TemperatureController::start_autotune] Auto-tune needs heating to be enabled",
+                module_path!()
+            ));
+        }
+
+        self.stop_step_test();
+        let target = self.heating.target_temperature.get::<degree_celsius>();
+        let mut tuner = PidAutoTuner::new(AutoTuneConfig {
+            tune_delta,
+            max_power: self.max_clamp,
+            max_duration: AUTOTUNE_MAX_DURATION,
+        });
+        tuner.start(now, target);
+        self.pid_autotuner = Some(tuner);
+        self.autotune_rule = rule;
+        self.pid.reset();
+
+        tracing::info!(
+            "Temperature PID auto-tune started: target={:.1} °C, delta=±{:.1} °C",
+            target,
+            tune_delta,
+        );
+        Ok(())
+    }
+
+    /// Abort an in-progress auto-tune run
+    pub fn stop_autotune(&mut self) {
+        if let Some(ref mut tuner) = self.pid_autotuner {
+            if tuner.is_running() {
+                tuner.stop();
+                self.pid.reset();
+                tracing::warn!("Temperature PID auto-tune aborted");
+            }
+        }
+    }
+
+    pub fn get_autotune_state(&self) -> PidAutoTuneState {
+        match &self.pid_autotuner {
+            Some(tuner) => PidAutoTuneState {
+                state: tuner.state().to_string(),
+                progress: tuner.get_progress_percent(),
+                result: self.autotune_result.clone(),
+            },
+            // Gains of a step test are reported without a relay run
+            None => PidAutoTuneState {
+                result: self.autotune_result.clone(),
+                ..PidAutoTuneState::default()
+            },
+        }
+    }
+
+    /// Start an open-loop step test from the current duty cycle. The zone should be at a
+    /// steady temperature, the baseline is recorded before the step.
+    pub fn start_step_test(
+        &mut self,
+        now: Instant,
+        step: f64,
+        lambda: f64,
+    ) -> Result<(), anyhow::Error> {
+        let duty = self.temperature_pid_output;
+        if !step.is_normal() || !(0.0..=self.max_clamp).contains(&(duty + step)) {
+            return Err(anyhow::anyhow!(
+                "[{}::TemperatureController::start_step_test] Step {} from duty {} leaves 0 – {}",
+                module_path!(),
+                step,
+                duty,
+                self.max_clamp
+            ));
+        }
+        if !lambda.is_finite() || lambda <= 0.0 {
+            return Err(anyhow::anyhow!(
+                "[{}::TemperatureController::start_step_test] Invalid closed-loop time constant {}",
+                module_path!(),
+                lambda
+            ));
         }
+        if !self.heating_allowed {
+            return Err(anyhow::anyhow!(
+                "[{}::TemperatureController::start_step_test] Step test needs heating to be enabled",
+                module_path!()
+            ));
+        }
+
+        self.stop_autotune();
+        let mut identifier = StepResponseIdentifier::new(StepTestConfig {
+            step,
+            ..STEP_TEST_CONFIG
+        });
+        identifier.start(now, duty);
+        self.step_identifier = Some(identifier);
+        self.step_lambda = lambda;
+
+        tracing::info!(
+            "Temperature step test started: duty={:.2}, step={:+.2}, lambda={:.0} s",
+            duty,
+            step,
+            lambda,
+        );
+        Ok(())
+    }
+
+    /// Abort an in-progress step test
+    pub fn stop_step_test(&mut self) {
+        if let Some(ref mut identifier) = self.step_identifier {
+            if identifier.is_running() {
+                identifier.stop();
+                self.pid.reset();
+                tracing::warn!("Temperature step test aborted");
+            }
+        }
+    }
+
+    pub fn get_step_test_state(&self) -> StepTestState {
+        StepTestState {
+            state: self
+                .step_identifier
+                .as_ref()
+                .map_or("not_started", |identifier| identifier.state())
+                .to_string(),
+            model: self.step_model.map(|model| FopdtModelState {
+                gain: model.gain,
+                time_constant: model.time_constant,
+                dead_time: model.dead_time,
+            }),
+        }
+    }
+
+    /// Advances the running step test and applies the IMC gains of the identified model
+    /// once it completes. Returns the heater duty cycle.
+    fn update_step_test(&mut self, now: Instant) -> f64 {
+        let Some(ref mut identifier) = self.step_identifier else {
+            return 0.0;
+        };
+        let duty = identifier.update(self.heating.temperature.get::<degree_celsius>(), now);
+
+        if identifier.is_completed() {
+            if let Ok(model) = identifier.result() {
+                let (kp, ki, kd) = model.imc_pid(self.step_lambda);
+                self.pid.set_gains(kp, ki, kd);
+                self.pid.reset();
+                tracing::info!(
+                    "Temperature step test completed: gain={:.2}, tau={:.1} s, dead time={:.1} s, kp={:.4}, ki={:.4}, kd={:.4}",
+                    model.gain,
+                    model.time_constant,
+                    model.dead_time,
+                    kp,
+                    ki,
+                    kd,
+                );
+                self.step_model = Some(*model);
+                self.autotune_result = Some(PidSettings { ki, kp, kd });
+            }
+        } else if identifier.is_failed() {
+            tracing::warn!("Temperature step test failed");
+        }
+        duty
+    }
+
+    /// Advances the running tuner and applies its gains once it completes.
+    /// Returns the heater duty cycle.
+    fn update_autotune(&mut self, now: Instant) -> f64 {
+        let Some(ref mut tuner) = self.pid_autotuner else {
+            return 0.0;
+        };
+        let duty = tuner.update(self.heating.temperature.get::<degree_celsius>(), now);
+
+        if tuner.is_completed() {
+            if let Ok(result) = tuner.result() {
+                let result = match self.autotune_rule {
+                    Some(rule) => result.with_rule(rule),
+                    None => result.clone(),
+                };
+                self.pid.set_gains(result.kp, result.ki, result.kd);
+                self.pid.reset();
+                tracing::info!(
+                    "Temperature PID auto-tune completed: kp={:.4}, ki={:.4}, kd={:.4}",
+                    result.kp,
+                    result.ki,
+                    result.kd,
+                );
+                self.autotune_result = Some(PidSettings {
+                    ki: result.ki,
+                    kp: result.kp,
+                    kd: result.kd,
+                });
+            }
+        } else if tuner.is_failed() {
+            tracing::warn!("Temperature PID auto-tune failed");
+        }
+        duty
     }
 
     pub fn set_target_temperature(&mut self, temp: ThermodynamicTemperature) {
@@ -108,12 +395,33 @@ impl TemperatureController {
             return;
         }
 
-        if self.heating_allowed {
-            let error: f64 = self.heating.target_temperature.get::<degree_celsius>()
-                - self.heating.temperature.get::<degree_celsius>();
+        if !self.heating_allowed {
+            self.stop_autotune();
+            self.stop_step_test();
+        }
 
-            let control = self.pid.update(error, now); // PID output
-            // Clamp PID output to 0.0 – 1.0 (as duty cycle)
+        if self.heating_allowed {
+            let autotune_running = self
+                .pid_autotuner
+                .as_ref()
+                .is_some_and(|tuner| tuner.is_running());
+            let step_test_running = self
+                .step_identifier
+                .as_ref()
+                .is_some_and(|identifier| identifier.is_running());
+            let control = if autotune_running {
+                self.update_autotune(now)
+            } else if step_test_running {
+                self.update_step_test(now)
+            } else {
+                self.pid.update_with(
+                    self.heating.target_temperature.get::<degree_celsius>(),
+                    self.heating.temperature.get::<degree_celsius>(),
+                    0.0,
+                    now,
+                )
+            };
+            // Output is limited to 0.0 – max_clamp (as duty cycle)
             let duty = control.clamp(0.0, self.max_clamp);
 
             self.temperature_pid_output = duty;
@@ -10,15 +10,19 @@ use qitech_lib::{
     modbus::ModbusDevice,
 };
 use serde::Serialize;
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, net::SocketAddr, rc::Rc};
 use tokio::sync::mpsc::Sender;
 
 pub mod aquapath1;
+pub mod bottle_cap_sorter;
 pub mod extruder1;
 pub mod laser;
 pub mod machine_identification;
-//pub mod minimal_machines;
+pub mod minimal_machines;
+pub mod persist;
 pub mod registry;
+pub mod wago_power;
+pub mod wago_serial_machine;
 pub mod winder2;
 
 pub const VENDOR_QITECH: u16 = 0x0001;
@@ -46,6 +50,8 @@ pub const MOTOR_TEST_MACHINE: u16 = 0x0011;
 pub const WAGO_DO_TEST_MACHINE: u16 = 0x000E;
 pub const WAGO_750_501_TEST_MACHINE: u16 = 0x0042;
 pub const TEST_MACHINE_BOTTLECAPS: u16 = 0x0039;
+pub const MACHINE_SORTER_V1: u16 = 0x0038;
+pub const WAGO_SERIAL_MACHINE: u16 = 0x0067;
 
 #[derive(Serialize, Debug, Clone)]
 pub struct MachineValues {
@@ -78,10 +84,18 @@ pub struct IdentifiedModbus {
     pub hw: Rc<RefCell<dyn ModbusDevice>>,
 }
 
+/// Modbus TCP device found by `probe_modbus_tcp`, connected by the machine itself
+#[derive(Clone, Debug)]
+pub struct IdentifiedModbusTcp {
+    pub addr: SocketAddr,
+    pub serial: u16,
+}
+
 #[derive(Clone)]
 pub enum Hardware {
     Ethercat(IdentifiedEthercat),
     Modbus(IdentifiedModbus),
+    ModbusTcp(IdentifiedModbusTcp),
 }
 
 #[derive(Clone)]
@@ -167,6 +181,20 @@ impl MachineHardware {
         }
     }
 
+    pub fn try_get_modbus_tcp_by_index(
+        &self,
+        index: usize,
+    ) -> Result<IdentifiedModbusTcp, anyhow::Error> {
+        match self.hw.get(index) {
+            Some(Hardware::ModbusTcp(identified_modbus_tcp)) => Ok(identified_modbus_tcp.clone()),
+            Some(_) => Err(anyhow::anyhow!(
+                "index {} not a modbus tcp device in hardware",
+                index
+            )),
+            None => Err(anyhow::anyhow!("index {} not found in hardware", index)),
+        }
+    }
+
     pub fn try_get_ethercat_device_and_addr_by_role<T>(
         &self,
         role: u16,
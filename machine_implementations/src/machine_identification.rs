@@ -101,6 +101,7 @@ impl MachineIdentification {
             x if x == MACHINE_BUFFER_V1 => "buffer_v1".to_string(),
             x if x == MACHINE_EXTRUDER_V2 => "extruder_v2".to_string(),
             x if x == MACHINE_WAGO_POWER_V1 => "wago_power_v1".to_string(),
+            x if x == MACHINE_SORTER_V1 => "sorter_v1".to_string(),
             x if x == TEST_MACHINE => "test_machine".to_string(),
             x if x == IP20_TEST_MACHINE => "ip20_test_machine".to_string(),
             x if x == ANALOG_INPUT_TEST_MACHINE => "analog_input_test_machine".to_string(),
@@ -115,6 +116,8 @@ impl MachineIdentification {
             x if x == DIGITAL_INPUT_TEST_MACHINE => "digital_input_test_machine".to_string(),
             x if x == WAGO_8CH_IO_TEST_MACHINE => "wago_8ch_io_test_machine".to_string(),
             x if x == TEST_MACHINE_BOTTLECAPS => "bottlecaps_test_machine".to_string(),
+            x if x == TEST_MACHINE_STEPPER => "test_machine_stepper".to_string(),
+            x if x == WAGO_SERIAL_MACHINE => "wago_serial_machine".to_string(),
             _ => unreachable!("Unknown machine id {}", self.machine),
         }
     }
@@ -215,12 +218,14 @@ use crate::MACHINE_EXTRUDER_V1;
 use crate::MACHINE_EXTRUDER_V2;
 use crate::MACHINE_LASER_V1;
 use crate::MACHINE_MOCK;
+use crate::MACHINE_SORTER_V1;
 use crate::MACHINE_WAGO_POWER_V1;
 use crate::MACHINE_WINDER_V1;
 use crate::MACHINE_WINDER_V1_7031_0030_SPOOL;
 use crate::MOTOR_TEST_MACHINE;
 use crate::TEST_MACHINE;
 use crate::TEST_MACHINE_BOTTLECAPS;
+use crate::TEST_MACHINE_STEPPER;
 use crate::VENDOR_QITECH;
 use crate::WAGO_8CH_IO_TEST_MACHINE;
 use crate::WAGO_750_430_DI_MACHINE;
@@ -230,6 +235,7 @@ use crate::WAGO_750_531_MACHINE;
 use crate::WAGO_750_553_MACHINE;
 use crate::WAGO_AI_TEST_MACHINE;
 use crate::WAGO_DO_TEST_MACHINE;
+use crate::WAGO_SERIAL_MACHINE;
 use anyhow::Error;
 
 #[derive(Debug)]
@@ -6,7 +6,7 @@
 //   • MyMachineEvents — enum wrapping all event types for this machine
 //   • Mutation        — the actions the frontend can send to this machine
 //   • MyMachineNamespace — the socket.io namespace handle
-//   • MachineApi impl — wires mutations and namespace access
+//   • MachineApi impl — wires mutations, namespace access and message handling
 // ============================================================================
 
 use std::sync::Arc;
@@ -19,9 +19,10 @@ use control_core::socketio::{
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use tokio::sync::mpsc::Sender;
 
-use crate::{MachineApi, MachineMessage};
 use super::MyMachine;
+use crate::{MachineApi, MachineMessage, MachineValues};
 
 // ----------------------------------------------------------------------------
 // Step 1 — Define the state that gets broadcast to the frontend.
@@ -110,7 +111,7 @@ impl CacheableEvents<MyMachineEvents> for MyMachineEvents {
 // Step 5 — Implement MachineApi (mandatory plumbing + your mutation logic).
 // ----------------------------------------------------------------------------
 impl MachineApi for MyMachine {
-    fn api_get_sender(&self) -> smol::channel::Sender<MachineMessage> {
+    fn get_api_sender(&self) -> Sender<MachineMessage> {
         self.api_sender.clone()
     }
 
@@ -131,4 +132,35 @@ impl MachineApi for MyMachine {
     fn api_event_namespace(&mut self) -> Option<Namespace> {
         self.namespace.namespace.clone()
     }
+
+    /// Handle a single inbound message. This is mandatory plumbing — the only
+    /// arms that typically need editing are `SubscribeNamespace` (send initial
+    /// state to a new subscriber) and `RequestValues` (serialize state for
+    /// HTTP polling).
+    fn act_machine_message(&mut self, msg: MachineMessage) {
+        match msg {
+            MachineMessage::SubscribeNamespace(namespace) => {
+                self.namespace.namespace = Some(namespace);
+                // Send the current state immediately so the new subscriber
+                // doesn't wait up to 33 ms for the next cycle.
+                self.emit_state();
+            }
+            MachineMessage::UnsubscribeNamespace => {
+                self.namespace.namespace = None;
+            }
+            MachineMessage::HttpApiJsonRequest(value) => {
+                let _res = self.api_mutate(value);
+            }
+            MachineMessage::RequestValues(sender) => {
+                // Called by the HTTP polling path. Serialize current state.
+                sender
+                    .send(MachineValues {
+                        state: serde_json::to_value(self.get_state())
+                            .expect("Failed to serialize state"),
+                        live_values: serde_json::Value::Null,
+                    })
+                    .expect("Failed to send values");
+            }
+        }
+    }
 }
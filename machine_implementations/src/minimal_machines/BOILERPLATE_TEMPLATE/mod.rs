@@ -1,7 +1,7 @@
 // ============================================================================
 // mod.rs — Core struct definition
 // ============================================================================
-// This file defines the machine struct and implements the `Machine` trait.
+// This file defines the machine struct and marks it as a `QiTechMachine`.
 //
 // FIND & REPLACE to adapt this template:
 //   MyMachine      → YourMachineName  (e.g. WagoDiMachine)
@@ -11,13 +11,11 @@
 use std::time::Instant;
 
 use control_core::socketio::namespace::NamespaceCacheingLogic;
-use smol::channel::{Receiver, Sender};
+use qitech_lib::machines::{MachineIdentification, MachineIdentificationUnique};
+use tokio::sync::mpsc::{Receiver, Sender};
 
-use crate::{
-    AsyncThreadMessage, Machine, MachineMessage, MY_MACHINE_ID, VENDOR_QITECH,
-    machine_identification::{MachineIdentification, MachineIdentificationUnique},
-};
 use self::api::{MyMachineEvents, MyMachineNamespace, StateEvent};
+use crate::{MY_MACHINE_ID, MachineMessage, QiTechMachine, VENDOR_QITECH};
 
 pub mod act;
 pub mod api;
@@ -30,40 +28,25 @@ pub mod new;
 // AnalogInput, etc.) as fields. The first five fields are mandatory plumbing
 // for every machine — do not remove them.
 // ----------------------------------------------------------------------------
-#[derive(Debug)]
 pub struct MyMachine {
     // --- mandatory plumbing (keep as-is) ------------------------------------
     pub api_receiver: Receiver<MachineMessage>,
     pub api_sender: Sender<MachineMessage>,
     pub machine_identification_unique: MachineIdentificationUnique,
-    pub main_sender: Option<Sender<AsyncThreadMessage>>,
     pub namespace: MyMachineNamespace,
     pub last_state_emit: Instant,
 
     // --- TODO: add your hardware handles here --------------------------------
     // Examples:
-    //   pub douts: [DigitalOutput; 4],
-    //   pub dins:  [DigitalInput; 4],
-    //   pub ain:   AnalogInput,
+    //   pub douts: Rc<RefCell<dyn DigitalOutputDevice>>,
+    //   pub dins:  Rc<RefCell<dyn DigitalInputDevice>>,
+    //   pub ain:   Rc<RefCell<dyn AnalogInputDevice>>,
 }
 
 // ----------------------------------------------------------------------------
-// Step 2 — Implement the `Machine` trait (mandatory, do not change).
-// ----------------------------------------------------------------------------
-impl Machine for MyMachine {
-    fn get_machine_identification_unique(&self) -> MachineIdentificationUnique {
-        self.machine_identification_unique.clone()
-    }
-
-    fn get_main_sender(&self) -> Option<Sender<AsyncThreadMessage>> {
-        self.main_sender.clone()
-    }
-}
-
-// ----------------------------------------------------------------------------
-// Step 3 — Set the unique machine ID constant.
+// Step 2 — Set the unique machine ID constant.
 //
-// The constant MY_MACHINE_ID must be declared in machines/src/lib.rs and must
+// The constant MY_MACHINE_ID must be declared in machine_implementations/src/lib.rs and must
 // be a unique u16 value not used by any other machine. See lib.rs for the
 // existing values and pick the next available one.
 // ----------------------------------------------------------------------------
@@ -75,7 +58,7 @@ impl MyMachine {
 }
 
 // ----------------------------------------------------------------------------
-// Step 4 — Add your machine's business logic methods here.
+// Step 3 — Add your machine's business logic methods here.
 //
 // `get_state()` and `emit_state()` are called by act.rs and api.rs — keep
 // their signatures stable.  Add any other helpers your machine needs.
@@ -97,7 +80,14 @@ impl MyMachine {
     // TODO: add domain-specific helpers, e.g.:
     //
     // pub fn set_output(&mut self, index: usize, on: bool) {
-    //     self.douts[index].set(on);
+    //     self.douts.borrow_mut().set_output(index, on);
     //     self.emit_state();
     // }
 }
+
+// ----------------------------------------------------------------------------
+// Step 4 — Mark the machine as a `QiTechMachine` (mandatory, do not change).
+//
+// `Machine` is implemented in act.rs, `MachineApi` in api.rs.
+// ----------------------------------------------------------------------------
+impl QiTechMachine for MyMachine {}
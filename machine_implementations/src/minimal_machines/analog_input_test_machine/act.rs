@@ -1,35 +1,15 @@
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use crate::{
-    MachineAct, MachineMessage, MachineValues,
-    minimal_machines::analog_input_test_machine::AnalogInputTestMachine,
+use qitech_lib::ethercat_hal::io::analog_input::physical::AnalogInputValue;
+use qitech_lib::machines::{
+    Machine, MachineDataRegistry, MachineError, MachineIdentificationUnique,
 };
 
-impl MachineAct for AnalogInputTestMachine {
-    fn act_machine_message(&mut self, msg: MachineMessage) {
-        match msg {
-            MachineMessage::SubscribeNamespace(namespace) => {
-                self.namespace.namespace = Some(namespace);
-                self.emit_measurement_rate();
-            }
-            MachineMessage::UnsubscribeNamespace => self.namespace.namespace = None,
-            MachineMessage::HttpApiJsonRequest(value) => {
-                use crate::MachineApi;
-                let _res = self.api_mutate(value);
-            }
-            MachineMessage::RequestValues(sender) => {
-                sender
-                    .send_blocking(MachineValues {
-                        state: serde_json::Value::Null,
-                        live_values: serde_json::Value::Null,
-                    })
-                    .expect("Failed to send values");
-                sender.close();
-            }
-        }
-    }
+use crate::{MachineApi, minimal_machines::analog_input_test_machine::AnalogInputTestMachine};
 
-    fn act(&mut self, now: std::time::Instant) {
+impl Machine for AnalogInputTestMachine {
+    fn act(&mut self, _registry: Option<&mut MachineDataRegistry>) -> Result<(), MachineError> {
+        let now = Instant::now();
         let recv = self.api_receiver.try_recv();
         if let Ok(msg) = recv {
             self.act_machine_message(msg);
@@ -37,14 +17,16 @@ impl MachineAct for AnalogInputTestMachine {
         if now.duration_since(self.last_measurement)
             > Duration::from_secs_f64(1.0 / self.measurement_rate_hz)
         {
-            let measured_value = self.analog_input.get_physical();
+            let analog_input = self.analog_input.borrow();
+            let measured_value = analog_input
+                .get_input(0)
+                .map(|input| input.get_physical(&analog_input.analog_input_range()));
+            drop(analog_input);
             match measured_value {
-                ethercat_hal::io::analog_input::physical::AnalogInputValue::Potential(
-                    _quantity,
-                ) => {
+                Ok(AnalogInputValue::Potential(_quantity)) | Err(_) => {
                     // Don't do anything
                 }
-                ethercat_hal::io::analog_input::physical::AnalogInputValue::Current(quantity) => {
+                Ok(AnalogInputValue::Current(quantity)) => {
                     let now_milliseconds = SystemTime::now()
                         .duration_since(UNIX_EPOCH)
                         .expect("Now is expected to be after UNIX_EPOCH")
@@ -54,5 +36,12 @@ impl MachineAct for AnalogInputTestMachine {
             }
             self.last_measurement = Instant::now();
         }
+        Ok(())
+    }
+
+    fn react(&mut self, _registry: &MachineDataRegistry) {}
+
+    fn get_identification(&self) -> MachineIdentificationUnique {
+        self.machine_identification_unique
     }
 }
@@ -8,9 +8,10 @@ use control_core::socketio::{
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use tokio::sync::mpsc::Sender;
 
 use super::BottlecapsTestMachine;
-use crate::{MachineApi, MachineMessage};
+use crate::{MachineApi, MachineMessage, MachineValues};
 
 #[derive(Serialize, Debug, Clone)]
 pub struct StateEvent {
@@ -70,7 +71,7 @@ impl CacheableEvents<BottlecapsTestMachineEvents> for BottlecapsTestMachineEvent
 }
 
 impl MachineApi for BottlecapsTestMachine {
-    fn api_get_sender(&self) -> smol::channel::Sender<MachineMessage> {
+    fn get_api_sender(&self) -> Sender<MachineMessage> {
         self.api_sender.clone()
     }
 
@@ -90,4 +91,28 @@ impl MachineApi for BottlecapsTestMachine {
     fn api_event_namespace(&mut self) -> Option<Namespace> {
         self.namespace.namespace.clone()
     }
+
+    fn act_machine_message(&mut self, msg: MachineMessage) {
+        match msg {
+            MachineMessage::SubscribeNamespace(namespace) => {
+                self.namespace.namespace = Some(namespace);
+                self.emit_state();
+            }
+            MachineMessage::UnsubscribeNamespace => {
+                self.namespace.namespace = None;
+            }
+            MachineMessage::HttpApiJsonRequest(value) => {
+                let _res = self.api_mutate(value);
+            }
+            MachineMessage::RequestValues(sender) => {
+                sender
+                    .send(MachineValues {
+                        state: serde_json::to_value(self.get_state())
+                            .expect("Failed to serialize state"),
+                        live_values: serde_json::Value::Null,
+                    })
+                    .expect("Failed to send values");
+            }
+        }
+    }
 }
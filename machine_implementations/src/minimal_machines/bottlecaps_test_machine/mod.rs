@@ -1,49 +1,35 @@
-use std::time::Instant;
+use std::{cell::RefCell, rc::Rc, time::Instant};
 
 use control_core::socketio::namespace::NamespaceCacheingLogic;
-use ethercat_hal::io::{
-    digital_input::DigitalInput, digital_output::DigitalOutput,
+use qitech_lib::ethercat_hal::io::{
+    digital_input::DigitalInputDevice, digital_output::DigitalOutputDevice,
     stepper_velocity_wago_750_671::StepperVelocityWago750671,
 };
-use smol::channel::{Receiver, Sender};
+use qitech_lib::machines::{MachineIdentification, MachineIdentificationUnique};
+use tokio::sync::mpsc::{Receiver, Sender};
 
 use self::api::{BottlecapsTestMachineEvents, BottlecapsTestMachineNamespace, StateEvent};
-use crate::{
-    AsyncThreadMessage, Machine, MachineMessage, TEST_MACHINE_BOTTLECAPS, VENDOR_QITECH,
-    machine_identification::{MachineIdentification, MachineIdentificationUnique},
-};
+use crate::{MachineMessage, QiTechMachine, TEST_MACHINE_BOTTLECAPS, VENDOR_QITECH};
 
 pub mod act;
 pub mod api;
 pub mod new;
 
-#[derive(Debug)]
 pub struct BottlecapsTestMachine {
     pub api_receiver: Receiver<MachineMessage>,
     pub api_sender: Sender<MachineMessage>,
     pub machine_identification_unique: MachineIdentificationUnique,
-    pub main_sender: Option<Sender<AsyncThreadMessage>>,
     pub namespace: BottlecapsTestMachineNamespace,
     pub last_state_emit: Instant,
 
     pub outputs: [bool; 8],
     pub inputs: [bool; 8],
     pub override_inputs: [bool; 8],
-    pub douts: [DigitalOutput; 8],
-    pub dins: [DigitalInput; 8],
+    pub douts: Rc<RefCell<dyn DigitalOutputDevice>>,
+    pub dins: Rc<RefCell<dyn DigitalInputDevice>>,
     pub stepper: StepperVelocityWago750671,
 }
 
-impl Machine for BottlecapsTestMachine {
-    fn get_machine_identification_unique(&self) -> MachineIdentificationUnique {
-        self.machine_identification_unique.clone()
-    }
-
-    fn get_main_sender(&self) -> Option<Sender<AsyncThreadMessage>> {
-        self.main_sender.clone()
-    }
-}
-
 impl BottlecapsTestMachine {
     pub const MACHINE_IDENTIFICATION: MachineIdentification = MachineIdentification {
         vendor: VENDOR_QITECH,
@@ -72,8 +58,9 @@ impl BottlecapsTestMachine {
     }
 
     pub fn read_inputs(&mut self) {
-        for (i, din) in self.dins.iter().enumerate() {
-            self.inputs[i] = din.get_value().unwrap_or(false);
+        let dins = self.dins.borrow();
+        for (port, input) in self.inputs.iter_mut().enumerate() {
+            *input = dins.get_input(port).unwrap_or(false);
         }
     }
 
@@ -86,7 +73,7 @@ impl BottlecapsTestMachine {
     pub fn set_output(&mut self, index: usize, on: bool) {
         if index < self.outputs.len() {
             self.outputs[index] = on;
-            self.douts[index].set(on);
+            self.douts.borrow_mut().set_output(index, on);
             self.emit_state();
         }
     }
@@ -111,3 +98,5 @@ impl BottlecapsTestMachine {
         self.emit_state();
     }
 }
+
+impl QiTechMachine for BottlecapsTestMachine {}
@@ -6,7 +6,7 @@ use control_core::socketio::{
 };
 use std::sync::Arc;
 
-use crate::MachineApi;
+use crate::{MachineApi, MachineValues};
 use crate::{
     MachineMessage, minimal_machines::digital_input_test_machine::DigitalInputTestMachine,
 };
@@ -87,8 +87,20 @@ impl MachineApi for DigitalInputTestMachine {
             MachineMessage::UnsubscribeNamespace => {
                 self.namespace.namespace = None;
             }
-            MachineMessage::HttpApiJsonRequest(value) => (),
-            MachineMessage::RequestValues(sender) => (),
+            MachineMessage::HttpApiJsonRequest(value) => {
+                let _res = self.api_mutate(value);
+            }
+            MachineMessage::RequestValues(sender) => {
+                sender
+                    .send(MachineValues {
+                        state: serde_json::to_value(StateEvent {
+                            led_on: self.led_on,
+                        })
+                        .expect("Failed to serialize state"),
+                        live_values: serde_json::Value::Null,
+                    })
+                    .expect("Failed to send values");
+            }
         }
     }
 }
@@ -1,9 +1,13 @@
 use super::IP20TestMachine;
-use crate::{MachineAct, MachineMessage, MachineValues};
+use crate::MachineApi;
+use qitech_lib::machines::{
+    Machine, MachineDataRegistry, MachineError, MachineIdentificationUnique,
+};
 use std::time::{Duration, Instant};
 
-impl MachineAct for IP20TestMachine {
-    fn act(&mut self, now: Instant) {
+impl Machine for IP20TestMachine {
+    fn act(&mut self, _registry: Option<&mut MachineDataRegistry>) -> Result<(), MachineError> {
+        let now = Instant::now();
         if let Ok(msg) = self.api_receiver.try_recv() {
             self.act_machine_message(msg);
         }
@@ -22,31 +26,12 @@ impl MachineAct for IP20TestMachine {
             self.emit_live_values();
             self.last_live_values_emit = now;
         }
+        Ok(())
     }
 
-    fn act_machine_message(&mut self, msg: MachineMessage) {
-        match msg {
-            MachineMessage::SubscribeNamespace(namespace) => {
-                self.namespace.namespace = Some(namespace);
-                self.emit_state();
-                self.emit_live_values();
-            }
-            MachineMessage::UnsubscribeNamespace => self.namespace.namespace = None,
-            MachineMessage::HttpApiJsonRequest(value) => {
-                use crate::MachineApi;
-                let _res = self.api_mutate(value);
-            }
-            MachineMessage::RequestValues(sender) => {
-                sender
-                    .send_blocking(MachineValues {
-                        state: serde_json::to_value(self.get_state())
-                            .expect("Failed to serialize state"),
-                        live_values: serde_json::to_value(self.get_live_values())
-                            .expect("Failed to serialize live values"),
-                    })
-                    .expect("Failed to send values");
-                sender.close();
-            }
-        }
+    fn react(&mut self, _registry: &MachineDataRegistry) {}
+
+    fn get_identification(&self) -> MachineIdentificationUnique {
+        self.machine_identification_unique
     }
 }
@@ -1,5 +1,5 @@
 use super::IP20TestMachine;
-use crate::{MachineApi, MachineMessage};
+use crate::{MachineApi, MachineMessage, MachineValues};
 use control_core::socketio::{
     event::{Event, GenericEvent},
     namespace::{
@@ -9,6 +9,7 @@ use control_core::socketio::{
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::sync::Arc;
+use tokio::sync::mpsc::Sender;
 
 #[derive(Serialize, Debug, Clone)]
 pub struct StateEvent {
@@ -73,7 +74,7 @@ impl CacheableEvents<IP20TestMachineEvents> for IP20TestMachineEvents {
 }
 
 impl MachineApi for IP20TestMachine {
-    fn api_get_sender(&self) -> smol::channel::Sender<MachineMessage> {
+    fn get_api_sender(&self) -> Sender<MachineMessage> {
         self.api_sender.clone()
     }
 
@@ -90,4 +91,28 @@ impl MachineApi for IP20TestMachine {
     fn api_event_namespace(&mut self) -> Option<Namespace> {
         self.namespace.namespace.clone()
     }
+
+    fn act_machine_message(&mut self, msg: MachineMessage) {
+        match msg {
+            MachineMessage::SubscribeNamespace(namespace) => {
+                self.namespace.namespace = Some(namespace);
+                self.emit_state();
+                self.emit_live_values();
+            }
+            MachineMessage::UnsubscribeNamespace => self.namespace.namespace = None,
+            MachineMessage::HttpApiJsonRequest(value) => {
+                let _res = self.api_mutate(value);
+            }
+            MachineMessage::RequestValues(sender) => {
+                sender
+                    .send(MachineValues {
+                        state: serde_json::to_value(self.get_state())
+                            .expect("Failed to serialize state"),
+                        live_values: serde_json::to_value(self.get_live_values())
+                            .expect("Failed to serialize live values"),
+                    })
+                    .expect("Failed to send values");
+            }
+        }
+    }
 }
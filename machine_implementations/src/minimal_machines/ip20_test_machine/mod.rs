@@ -1,20 +1,21 @@
-use crate::machine_identification::{MachineIdentification, MachineIdentificationUnique};
 use crate::minimal_machines::ip20_test_machine::api::{
     IP20TestMachineEvents, LiveValuesEvent, StateEvent,
 };
-use crate::{AsyncThreadMessage, Machine, MachineMessage};
+use crate::{MachineMessage, QiTechMachine};
 use control_core::socketio::namespace::NamespaceCacheingLogic;
-use ethercat_hal::io::digital_input::DigitalInput;
-use ethercat_hal::io::digital_output::DigitalOutput;
-use smol::channel::{Receiver, Sender};
+use qitech_lib::ethercat_hal::io::digital_input::DigitalInputDevice;
+use qitech_lib::ethercat_hal::io::digital_output::DigitalOutputDevice;
+use qitech_lib::machines::{MachineIdentification, MachineIdentificationUnique};
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::time::Instant;
+use tokio::sync::mpsc::{Receiver, Sender};
 pub mod act;
 pub mod api;
 pub mod new;
 use crate::minimal_machines::ip20_test_machine::api::IP20TestMachineNamespace;
 use crate::{IP20_TEST_MACHINE, VENDOR_QITECH};
 
-#[derive(Debug)]
 pub struct IP20TestMachine {
     pub api_receiver: Receiver<MachineMessage>,
     pub api_sender: Sender<MachineMessage>,
@@ -24,19 +25,8 @@ pub struct IP20TestMachine {
     pub last_live_values_emit: Instant,
     pub outputs: [bool; 8],
     pub inputs: [bool; 8],
-    pub main_sender: Option<Sender<AsyncThreadMessage>>,
-    pub douts: [DigitalOutput; 8],
-    pub dins: [DigitalInput; 8],
-}
-
-impl Machine for IP20TestMachine {
-    fn get_machine_identification_unique(&self) -> MachineIdentificationUnique {
-        self.machine_identification_unique.clone()
-    }
-
-    fn get_main_sender(&self) -> Option<Sender<AsyncThreadMessage>> {
-        self.main_sender.clone()
-    }
+    pub douts: Rc<RefCell<dyn DigitalOutputDevice>>,
+    pub dins: Rc<RefCell<dyn DigitalInputDevice>>,
 }
 
 impl IP20TestMachine {
@@ -74,7 +64,7 @@ impl IP20TestMachine {
     pub fn set_output(&mut self, index: usize, on: bool) {
         if index < self.outputs.len() {
             self.outputs[index] = on;
-            self.douts[index].set(on);
+            self.douts.borrow_mut().set_output(index, on);
             self.emit_state();
         }
     }
@@ -82,16 +72,21 @@ impl IP20TestMachine {
     /// Set all outputs at once
     pub fn set_all_outputs(&mut self, on: bool) {
         self.outputs = [on; 8];
-        for (dout, &value) in self.douts.iter().zip(self.outputs.iter()) {
-            dout.set(value);
+        let mut douts = self.douts.borrow_mut();
+        for (port, &value) in self.outputs.iter().enumerate() {
+            douts.set_output(port, value);
         }
+        drop(douts);
         self.emit_state();
     }
 
     /// Read all digital inputs
     pub fn read_inputs(&mut self) {
-        for (i, din) in self.dins.iter().enumerate() {
-            self.inputs[i] = din.get_value().unwrap_or(false);
+        let dins = self.dins.borrow();
+        for (port, input) in self.inputs.iter_mut().enumerate() {
+            *input = dins.get_input(port).unwrap_or(false);
         }
     }
 }
+
+impl QiTechMachine for IP20TestMachine {}
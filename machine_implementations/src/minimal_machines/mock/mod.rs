@@ -1,25 +1,20 @@
-use crate::AsyncThreadMessage;
-use crate::{
-    MACHINE_MOCK, Machine, MachineMessage, VENDOR_QITECH,
-    machine_identification::{MachineIdentification, MachineIdentificationUnique},
-};
+use crate::{MACHINE_MOCK, MachineMessage, QiTechMachine, VENDOR_QITECH};
 use api::{LiveValuesEvent, MockEvents, MockMachineNamespace, Mode, ModeState, StateEvent};
 use control_core::socketio::event::BuildEvent;
 use control_core::socketio::namespace::NamespaceCacheingLogic;
-use smol::channel::{Receiver, Sender};
+use qitech_lib::machines::{MachineIdentification, MachineIdentificationUnique};
+use qitech_lib::units::f64::*;
+use qitech_lib::units::frequency::{hertz, millihertz};
 use std::time::Instant;
+use tokio::sync::mpsc::{Receiver, Sender};
 use tracing::info;
-use units::f64::*;
-use units::frequency::{hertz, millihertz};
 
 pub mod act;
 pub mod api;
 pub mod new;
 
-#[derive(Debug)]
 pub struct MockMachine {
     machine_identification_unique: MachineIdentificationUnique,
-    main_sender: Option<Sender<AsyncThreadMessage>>,
 
     // socketio
     namespace: MockMachineNamespace,
@@ -42,16 +37,6 @@ pub struct MockMachine {
     api_receiver: Receiver<MachineMessage>,
 }
 
-impl Machine for MockMachine {
-    fn get_machine_identification_unique(&self) -> MachineIdentificationUnique {
-        self.machine_identification_unique.clone()
-    }
-
-    fn get_main_sender(&self) -> Option<Sender<AsyncThreadMessage>> {
-        self.main_sender.clone()
-    }
-}
-
 impl MockMachine {
     pub const MACHINE_IDENTIFICATION: MachineIdentification = MachineIdentification {
         machine: MACHINE_MOCK,
@@ -137,3 +122,5 @@ impl MockMachine {
         self.emit_state();
     }
 }
+
+impl QiTechMachine for MockMachine {}
@@ -1,10 +1,20 @@
-/*pub mod analog_input_test_machine;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use qitech_lib::ethercat_hal::devices::{
+    EthercatDevice, downcast_rc_refcell, wago_750_354::Wago750_354,
+};
+
+use crate::MachineHardware;
+
+pub mod analog_input_test_machine;
+pub mod bottlecaps_test_machine;
+pub mod digital_input_test_machine;
 pub mod ip20_test_machine;
 pub mod mock;
 pub mod motor_test_machine;
 pub mod test_machine;
 pub mod test_machine_stepper;
-
 pub mod wago_750_430_di_machine;
 pub mod wago_750_460_machine;
 pub mod wago_750_501_test_machine;
@@ -14,6 +24,22 @@ pub mod wago_8ch_dio_test_machine;
 pub mod wago_ai_test_machine;
 pub mod wago_do_test_machine;
 
-*/
-
-pub mod digital_input_test_machine;
+/// Returns the module in `slot` of the WAGO 750-354 coupler at role 0
+pub fn get_wago_module<T: EthercatDevice>(
+    hw: &MachineHardware,
+    slot: usize,
+) -> Result<Rc<RefCell<T>>, anyhow::Error> {
+    let coupler = hw.try_get_ethercat_device_by_role::<Wago750_354>(0)?;
+    let device = coupler
+        .borrow()
+        .slot_devices
+        .get(slot)
+        .cloned()
+        .flatten()
+        .ok_or(anyhow::anyhow!(
+            "[{}::get_wago_module] Expected module in slot {}, but slot is empty",
+            module_path!(),
+            slot
+        ))?;
+    Ok(downcast_rc_refcell::<T>(device)?)
+}
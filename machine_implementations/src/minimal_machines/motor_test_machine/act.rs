@@ -1,40 +1,32 @@
-use super::MotorTestMachine;
-use crate::{MachineAct, MachineMessage};
-use std::time::Instant;
+use super::{MOTOR_PORT, MotorTestMachine};
+use crate::MachineApi;
+use qitech_lib::machines::{
+    Machine, MachineDataRegistry, MachineError, MachineIdentificationUnique,
+};
 
-impl MachineAct for MotorTestMachine {
-    fn act(&mut self, _now_ts: Instant) {
-        // println!("[{}::act] Running act", module_path!());
+impl Machine for MotorTestMachine {
+    fn act(&mut self, _registry: Option<&mut MachineDataRegistry>) -> Result<(), MachineError> {
         if let Ok(msg) = self.api_receiver.try_recv() {
             self.act_machine_message(msg);
         }
 
-        self.motor_driver.set_enabled(self.motor_state.enabled);
+        let mut motor_driver = self.motor_driver.borrow_mut();
+        motor_driver.set_enabled(MOTOR_PORT, self.motor_state.enabled);
 
         if self.motor_state.enabled {
             let steps_per_rev = 200.0; // Adjust to match motor
             let steps_per_second = (self.motor_state.target_velocity as f64) * steps_per_rev / 60.0;
 
-            let _ = self.motor_driver.set_speed(steps_per_second);
+            let _ = motor_driver.set_speed(MOTOR_PORT, steps_per_second);
         } else {
-            let _ = self.motor_driver.set_speed(0.0);
+            let _ = motor_driver.set_speed(MOTOR_PORT, 0.0);
         }
+        Ok(())
     }
 
-    fn act_machine_message(&mut self, msg: MachineMessage) {
-        match msg {
-            MachineMessage::SubscribeNamespace(ns) => {
-                self.namespace.namespace = Some(ns);
-                self.emit_state();
-            }
-            MachineMessage::UnsubscribeNamespace => {
-                self.namespace.namespace = None;
-            }
-            MachineMessage::HttpApiJsonRequest(value) => {
-                use crate::MachineApi;
-                let _ = self.api_mutate(value);
-            }
-            _ => {}
-        }
+    fn react(&mut self, _registry: &MachineDataRegistry) {}
+
+    fn get_identification(&self) -> MachineIdentificationUnique {
+        self.machine_identification_unique
     }
 }
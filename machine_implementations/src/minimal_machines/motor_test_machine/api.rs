@@ -1,5 +1,5 @@
 use super::MotorTestMachine;
-use crate::{MachineApi, MachineMessage};
+use crate::{MachineApi, MachineMessage, MachineValues};
 use control_core::socketio::{
     event::{Event, GenericEvent},
     namespace::{
@@ -8,8 +8,8 @@ use control_core::socketio::{
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use smol::channel::Sender;
 use std::sync::Arc;
+use tokio::sync::mpsc::Sender;
 use tracing::instrument;
 
 #[derive(Serialize, Debug, Clone)]
@@ -64,7 +64,7 @@ impl CacheableEvents<BeckhoffEvents> for BeckhoffEvents {
 }
 
 impl MachineApi for MotorTestMachine {
-    fn api_get_sender(&self) -> Sender<MachineMessage> {
+    fn get_api_sender(&self) -> Sender<MachineMessage> {
         self.api_sender.clone()
     }
     fn api_event_namespace(&mut self) -> Option<Namespace> {
@@ -89,4 +89,28 @@ impl MachineApi for MotorTestMachine {
         }
         Ok(())
     }
+
+    fn act_machine_message(&mut self, msg: MachineMessage) {
+        match msg {
+            MachineMessage::SubscribeNamespace(ns) => {
+                self.namespace.namespace = Some(ns);
+                self.emit_state();
+            }
+            MachineMessage::UnsubscribeNamespace => {
+                self.namespace.namespace = None;
+            }
+            MachineMessage::HttpApiJsonRequest(value) => {
+                let _ = self.api_mutate(value);
+            }
+            MachineMessage::RequestValues(sender) => {
+                sender
+                    .send(MachineValues {
+                        state: serde_json::to_value(self.get_state())
+                            .expect("Failed to serialize state"),
+                        live_values: serde_json::Value::Null,
+                    })
+                    .expect("Failed to send values");
+            }
+        }
+    }
 }
@@ -1,53 +1,50 @@
-use crate::machine_identification::{MachineIdentification, MachineIdentificationUnique};
-use crate::{AsyncThreadMessage, Machine, MachineMessage};
 use crate::{MOTOR_TEST_MACHINE, VENDOR_QITECH};
+use crate::{MachineMessage, QiTechMachine};
 use control_core::socketio::namespace::NamespaceCacheingLogic;
-use ethercat_hal::io::stepper_velocity_el70x1::StepperVelocityEL70x1;
-use smol::channel::{Receiver, Sender};
+use qitech_lib::ethercat_hal::io::stepper_velocity_el70x1::StepperVelocityEL70x1Device;
+use qitech_lib::machines::{MachineIdentification, MachineIdentificationUnique};
+use std::cell::RefCell;
+use std::rc::Rc;
+use tokio::sync::mpsc::{Receiver, Sender};
 
 pub mod act;
 pub mod api;
 pub mod new;
 
+/// Port of the EL7031-0030 stepper channel
+pub const MOTOR_PORT: usize = 0;
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct MotorState {
     pub enabled: bool,
     pub target_velocity: i32,
 }
 
-#[derive(Debug)]
 pub struct MotorTestMachine {
     api_receiver: Receiver<MachineMessage>,
     api_sender: Sender<MachineMessage>,
-    main_sender: Option<Sender<AsyncThreadMessage>>,
     machine_identification_unique: MachineIdentificationUnique,
     namespace: api::BeckhoffNamespace,
 
-    pub motor_driver: StepperVelocityEL70x1,
+    pub motor_driver: Rc<RefCell<dyn StepperVelocityEL70x1Device>>,
     pub motor_state: MotorState,
 }
 
-impl Machine for MotorTestMachine {
-    fn get_machine_identification_unique(&self) -> MachineIdentificationUnique {
-        self.machine_identification_unique.clone()
-    }
-
-    fn get_main_sender(&self) -> Option<Sender<AsyncThreadMessage>> {
-        self.main_sender.clone()
-    }
-}
-
 impl MotorTestMachine {
     pub const MACHINE_IDENTIFICATION: MachineIdentification = MachineIdentification {
         vendor: VENDOR_QITECH,
         machine: MOTOR_TEST_MACHINE,
     };
 
-    pub fn emit_state(&mut self) {
-        let event = api::StateEvent {
+    pub fn get_state(&self) -> api::StateEvent {
+        api::StateEvent {
             motor_enabled: self.motor_state.enabled,
             motor_velocity: self.motor_state.target_velocity,
-        };
+        }
+    }
+
+    pub fn emit_state(&mut self) {
+        let event = self.get_state();
         self.namespace
             .emit(api::BeckhoffEvents::State(event.build()));
     }
@@ -62,3 +59,5 @@ impl MotorTestMachine {
         self.emit_state();
     }
 }
+
+impl QiTechMachine for MotorTestMachine {}
@@ -1,5 +1,5 @@
 use super::TestMachine;
-use crate::{MachineApi, MachineMessage};
+use crate::{MachineApi, MachineMessage, MachineValues};
 use control_core::socketio::{
     event::{Event, GenericEvent},
     namespace::{
@@ -9,6 +9,7 @@ use control_core::socketio::{
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::sync::Arc;
+use tokio::sync::mpsc::Sender;
 
 #[derive(Serialize, Debug, Clone)]
 pub struct StateEvent {
@@ -60,7 +61,7 @@ impl CacheableEvents<TestMachineEvents> for TestMachineEvents {
 }
 
 impl MachineApi for TestMachine {
-    fn api_get_sender(&self) -> smol::channel::Sender<MachineMessage> {
+    fn get_api_sender(&self) -> Sender<MachineMessage> {
         self.api_sender.clone()
     }
 
@@ -71,9 +72,7 @@ impl MachineApi for TestMachine {
             Mutation::SetAllLeds { on } => self.set_all_leds(on),
         }
 
-        for (led, &on) in self.douts.iter().zip(self.led_on.iter()) {
-            led.set(on);
-        }
+        self.write_outputs();
 
         Ok(())
     }
@@ -81,4 +80,26 @@ impl MachineApi for TestMachine {
     fn api_event_namespace(&mut self) -> Option<Namespace> {
         self.namespace.namespace.clone()
     }
+
+    fn act_machine_message(&mut self, msg: MachineMessage) {
+        match msg {
+            MachineMessage::SubscribeNamespace(namespace) => {
+                self.namespace.namespace = Some(namespace);
+                self.emit_state();
+            }
+            MachineMessage::UnsubscribeNamespace => self.namespace.namespace = None,
+            MachineMessage::HttpApiJsonRequest(value) => {
+                let _res = self.api_mutate(value);
+            }
+            MachineMessage::RequestValues(sender) => {
+                sender
+                    .send(MachineValues {
+                        state: serde_json::to_value(self.get_state())
+                            .expect("Failed to serialize state"),
+                        live_values: serde_json::Value::Null,
+                    })
+                    .expect("Failed to send values");
+            }
+        }
+    }
 }
@@ -1,17 +1,18 @@
-use crate::machine_identification::{MachineIdentification, MachineIdentificationUnique};
 use crate::minimal_machines::test_machine::api::{StateEvent, TestMachineEvents};
-use crate::{AsyncThreadMessage, Machine, MachineMessage};
+use crate::{MachineMessage, QiTechMachine};
 use control_core::socketio::namespace::NamespaceCacheingLogic;
-use ethercat_hal::io::digital_output::DigitalOutput;
-use smol::channel::{Receiver, Sender};
+use qitech_lib::ethercat_hal::io::digital_output::DigitalOutputDevice;
+use qitech_lib::machines::{MachineIdentification, MachineIdentificationUnique};
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::time::Instant;
+use tokio::sync::mpsc::{Receiver, Sender};
 pub mod act;
 pub mod api;
 pub mod new;
 use crate::minimal_machines::test_machine::api::TestMachineNamespace;
 use crate::{TEST_MACHINE, VENDOR_QITECH};
 
-#[derive(Debug)]
 pub struct TestMachine {
     pub api_receiver: Receiver<MachineMessage>,
     pub api_sender: Sender<MachineMessage>,
@@ -19,19 +20,9 @@ pub struct TestMachine {
     pub namespace: TestMachineNamespace,
     pub last_state_emit: Instant,
     pub led_on: [bool; 4],
-    pub main_sender: Option<Sender<AsyncThreadMessage>>,
-    pub douts: [DigitalOutput; 4],
+    pub douts: Rc<RefCell<dyn DigitalOutputDevice>>,
 }
 
-impl Machine for TestMachine {
-    fn get_machine_identification_unique(&self) -> MachineIdentificationUnique {
-        self.machine_identification_unique.clone()
-    }
-
-    fn get_main_sender(&self) -> Option<Sender<AsyncThreadMessage>> {
-        self.main_sender.clone()
-    }
-}
 impl TestMachine {
     pub const MACHINE_IDENTIFICATION: MachineIdentification = MachineIdentification {
         vendor: VENDOR_QITECH,
@@ -64,4 +55,14 @@ impl TestMachine {
         self.led_on = [on; 4];
         self.emit_state();
     }
+
+    /// Write the LED state to the EL2004 outputs
+    fn write_outputs(&mut self) {
+        let mut douts = self.douts.borrow_mut();
+        for (port, &on) in self.led_on.iter().enumerate() {
+            douts.set_output(port, on);
+        }
+    }
 }
+
+impl QiTechMachine for TestMachine {}
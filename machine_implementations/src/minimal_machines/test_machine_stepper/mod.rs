@@ -1,11 +1,11 @@
-use crate::machine_identification::{MachineIdentification, MachineIdentificationUnique};
 use crate::minimal_machines::test_machine_stepper::api::{StateEvent, TestMachineStepperEvents};
-use crate::{AsyncThreadMessage, Machine, MachineMessage};
+use crate::{MachineMessage, QiTechMachine};
 use control_core::socketio::namespace::NamespaceCacheingLogic;
-use ethercat_hal::io::stepper_velocity_wago_750_671::StepperVelocityWago750671;
-use ethercat_hal::io::stepper_velocity_wago_750_672::StepperVelocityWago750672;
-use smol::channel::{Receiver, Sender};
+use qitech_lib::ethercat_hal::io::stepper_velocity_wago_750_671::StepperVelocityWago750671;
+use qitech_lib::ethercat_hal::io::stepper_velocity_wago_750_672::StepperVelocityWago750672;
+use qitech_lib::machines::{MachineIdentification, MachineIdentificationUnique};
 use std::time::Instant;
+use tokio::sync::mpsc::{Receiver, Sender};
 pub mod act;
 pub mod api;
 pub mod new;
@@ -18,26 +18,15 @@ pub enum Stepper {
     Wago750_671(StepperVelocityWago750671),
 }
 
-#[derive(Debug)]
 pub struct TestMachineStepper {
     pub api_receiver: Receiver<MachineMessage>,
     pub api_sender: Sender<MachineMessage>,
     pub machine_identification_unique: MachineIdentificationUnique,
     pub namespace: TestMachineStepperNamespace,
     pub last_state_emit: Instant,
-    pub main_sender: Option<Sender<AsyncThreadMessage>>,
     pub stepper: Stepper,
 }
 
-impl Machine for TestMachineStepper {
-    fn get_machine_identification_unique(&self) -> MachineIdentificationUnique {
-        self.machine_identification_unique.clone()
-    }
-
-    fn get_main_sender(&self) -> Option<Sender<AsyncThreadMessage>> {
-        self.main_sender.clone()
-    }
-}
 impl TestMachineStepper {
     pub const MACHINE_IDENTIFICATION: MachineIdentification = MachineIdentification {
         vendor: VENDOR_QITECH,
@@ -117,3 +106,5 @@ impl TestMachineStepper {
         self.emit_state();
     }
 }
+
+impl QiTechMachine for TestMachineStepper {}
@@ -7,8 +7,12 @@ use control_core::socketio::{
     },
 };
 use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Sender;
 
-use crate::{MachineApi, minimal_machines::wago_750_430_di_machine::Wago750_430DiMachine};
+use crate::{
+    MachineApi, MachineMessage, MachineValues,
+    minimal_machines::wago_750_430_di_machine::Wago750_430DiMachine,
+};
 
 #[derive(Serialize, Debug, Clone)]
 pub struct StateEvent {
@@ -57,7 +61,7 @@ impl CacheableEvents<Wago750_430DiMachineEvents> for Wago750_430DiMachineEvents
 }
 
 impl MachineApi for Wago750_430DiMachine {
-    fn api_get_sender(&self) -> smol::channel::Sender<crate::MachineMessage> {
+    fn get_api_sender(&self) -> Sender<MachineMessage> {
         self.api_sender.clone()
     }
 
@@ -69,4 +73,26 @@ impl MachineApi for Wago750_430DiMachine {
     fn api_event_namespace(&mut self) -> Option<Namespace> {
         self.namespace.namespace.clone()
     }
+
+    fn act_machine_message(&mut self, msg: MachineMessage) {
+        match msg {
+            MachineMessage::SubscribeNamespace(namespace) => {
+                self.namespace.namespace = Some(namespace);
+                self.emit_state();
+            }
+            MachineMessage::UnsubscribeNamespace => self.namespace.namespace = None,
+            MachineMessage::HttpApiJsonRequest(value) => {
+                let _res = self.api_mutate(value);
+            }
+            MachineMessage::RequestValues(sender) => {
+                sender
+                    .send(MachineValues {
+                        state: serde_json::to_value(self.get_state())
+                            .expect("Failed to serialize state"),
+                        live_values: serde_json::Value::Null,
+                    })
+                    .expect("Failed to send values");
+            }
+        }
+    }
 }
@@ -1,39 +1,25 @@
-use std::time::Instant;
+use std::{cell::RefCell, rc::Rc, time::Instant};
 
 use control_core::socketio::namespace::NamespaceCacheingLogic;
-use ethercat_hal::io::digital_input::DigitalInput;
-use smol::channel::{Receiver, Sender};
+use qitech_lib::ethercat_hal::io::digital_input::DigitalInputDevice;
+use qitech_lib::machines::{MachineIdentification, MachineIdentificationUnique};
+use tokio::sync::mpsc::{Receiver, Sender};
 
 use self::api::{StateEvent, Wago750_430DiMachineEvents, Wago750_430DiMachineNamespace};
-use crate::{
-    AsyncThreadMessage, Machine, MachineMessage, VENDOR_QITECH, WAGO_750_430_DI_MACHINE,
-    machine_identification::{MachineIdentification, MachineIdentificationUnique},
-};
+use crate::{MachineMessage, QiTechMachine, VENDOR_QITECH, WAGO_750_430_DI_MACHINE};
 
 pub mod act;
 pub mod api;
 pub mod new;
 
-#[derive(Debug)]
 pub struct Wago750_430DiMachine {
     pub api_receiver: Receiver<MachineMessage>,
     pub api_sender: Sender<MachineMessage>,
     pub machine_identification_unique: MachineIdentificationUnique,
-    pub main_sender: Option<Sender<AsyncThreadMessage>>,
     pub namespace: Wago750_430DiMachineNamespace,
     pub last_state_emit: Instant,
     pub inputs: [bool; 8],
-    pub digital_input: [DigitalInput; 8],
-}
-
-impl Machine for Wago750_430DiMachine {
-    fn get_machine_identification_unique(&self) -> MachineIdentificationUnique {
-        self.machine_identification_unique.clone()
-    }
-
-    fn get_main_sender(&self) -> Option<Sender<AsyncThreadMessage>> {
-        self.main_sender.clone()
-    }
+    pub digital_input: Rc<RefCell<dyn DigitalInputDevice>>,
 }
 
 impl Wago750_430DiMachine {
@@ -49,12 +35,14 @@ impl Wago750_430DiMachine {
     }
 
     pub fn emit_state(&mut self) {
-        for (i, di) in self.digital_input.iter().enumerate() {
-            self.inputs[i] = match di.get_value() {
+        let digital_input = self.digital_input.borrow();
+        for (port, input) in self.inputs.iter_mut().enumerate() {
+            *input = match digital_input.get_input(port) {
                 Ok(v) => v,
                 Err(_) => false,
             };
         }
+        drop(digital_input);
 
         // let aaah = self.inputs;
         // println!("{aaah:?}");
@@ -67,3 +55,5 @@ impl Wago750_430DiMachine {
             .emit(Wago750_430DiMachineEvents::State(event));
     }
 }
+
+impl QiTechMachine for Wago750_430DiMachine {}
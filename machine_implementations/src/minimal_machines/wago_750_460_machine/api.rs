@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use tokio::sync::mpsc::Sender;
 
 use control_core::socketio::{
     event::{Event, GenericEvent},
@@ -10,7 +11,7 @@ use serde::Serialize;
 use serde_json::Value;
 
 use super::Wago750_460Machine;
-use crate::{MachineApi, MachineMessage};
+use crate::{MachineApi, MachineMessage, MachineValues};
 
 // ----------------------------------------------------------------------------
 // StateEvent — broadcast to frontend on every cycle
@@ -67,7 +68,7 @@ impl CacheableEvents<Wago750_460MachineEvents> for Wago750_460MachineEvents {
 
 // This machine is read-only — no mutations.
 impl MachineApi for Wago750_460Machine {
-    fn api_get_sender(&self) -> smol::channel::Sender<MachineMessage> {
+    fn get_api_sender(&self) -> Sender<MachineMessage> {
         self.api_sender.clone()
     }
 
@@ -78,4 +79,26 @@ impl MachineApi for Wago750_460Machine {
     fn api_event_namespace(&mut self) -> Option<Namespace> {
         self.namespace.namespace.clone()
     }
+
+    fn act_machine_message(&mut self, msg: MachineMessage) {
+        match msg {
+            MachineMessage::SubscribeNamespace(namespace) => {
+                self.namespace.namespace = Some(namespace);
+                self.emit_state();
+            }
+            MachineMessage::UnsubscribeNamespace => self.namespace.namespace = None,
+            MachineMessage::HttpApiJsonRequest(value) => {
+                let _res = self.api_mutate(value);
+            }
+            MachineMessage::RequestValues(sender) => {
+                sender
+                    .send(MachineValues {
+                        state: serde_json::to_value(self.get_state())
+                            .expect("Failed to serialize state"),
+                        live_values: serde_json::Value::Null,
+                    })
+                    .expect("Failed to send values");
+            }
+        }
+    }
 }
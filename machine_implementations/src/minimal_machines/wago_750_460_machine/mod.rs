@@ -1,41 +1,27 @@
-use std::time::Instant;
+use std::{cell::RefCell, rc::Rc, time::Instant};
 
 use control_core::socketio::namespace::NamespaceCacheingLogic;
-use ethercat_hal::io::temperature_input::{TemperatureInput, TemperatureInputError};
-use smol::channel::{Receiver, Sender};
+use qitech_lib::ethercat_hal::io::temperature_input::TemperatureInputDevice;
+use qitech_lib::machines::{MachineIdentification, MachineIdentificationUnique};
+use tokio::sync::mpsc::{Receiver, Sender};
 
 use self::api::{StateEvent, Wago750_460MachineEvents, Wago750_460MachineNamespace};
-use crate::{
-    AsyncThreadMessage, Machine, MachineMessage, VENDOR_QITECH, WAGO_750_460_MACHINE,
-    machine_identification::{MachineIdentification, MachineIdentificationUnique},
-};
+use crate::{MachineMessage, QiTechMachine, VENDOR_QITECH, WAGO_750_460_MACHINE};
 
 pub mod act;
 pub mod api;
 pub mod new;
 
-#[derive(Debug)]
 pub struct Wago750_460Machine {
     // --- mandatory plumbing -------------------------------------------------
     pub api_receiver: Receiver<MachineMessage>,
     pub api_sender: Sender<MachineMessage>,
     pub machine_identification_unique: MachineIdentificationUnique,
-    pub main_sender: Option<Sender<AsyncThreadMessage>>,
     pub namespace: Wago750_460MachineNamespace,
     pub last_state_emit: Instant,
 
     // --- hardware -----------------------------------------------------------
-    pub temperature_inputs: [TemperatureInput; 4],
-}
-
-impl Machine for Wago750_460Machine {
-    fn get_machine_identification_unique(&self) -> MachineIdentificationUnique {
-        self.machine_identification_unique.clone()
-    }
-
-    fn get_main_sender(&self) -> Option<Sender<AsyncThreadMessage>> {
-        self.main_sender.clone()
-    }
+    pub temperature_inputs: Rc<RefCell<dyn TemperatureInputDevice>>,
 }
 
 impl Wago750_460Machine {
@@ -47,14 +33,12 @@ impl Wago750_460Machine {
     pub fn get_state(&self) -> StateEvent {
         let mut temperatures = [None; 4];
         let mut errors = [false; 4];
-        for (i, ti) in self.temperature_inputs.iter().enumerate() {
-            match ti.get_temperature() {
-                Ok(t) => temperatures[i] = Some(t),
-                // WireBreak is not currently returned by the 750-460 driver,
-                // but we handle it defensively for forward-compatibility.
-                Err(TemperatureInputError::WireBreak)
-                | Err(TemperatureInputError::OverVoltage)
-                | Err(TemperatureInputError::UnderVoltage) => errors[i] = true,
+        let temperature_inputs = self.temperature_inputs.borrow();
+        for port in 0..4 {
+            match temperature_inputs.get_input(port) {
+                Ok(t) => temperatures[port] = Some(t.temperature as f64),
+                // Wire-break, over- and undervoltage are all reported as errors
+                Err(_) => errors[port] = true,
             }
         }
         StateEvent {
@@ -68,3 +52,5 @@ impl Wago750_460Machine {
         self.namespace.emit(Wago750_460MachineEvents::State(event));
     }
 }
+
+impl QiTechMachine for Wago750_460Machine {}
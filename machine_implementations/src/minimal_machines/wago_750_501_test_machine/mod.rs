@@ -1,39 +1,25 @@
-use std::time::Instant;
+use std::{cell::RefCell, rc::Rc, time::Instant};
 
 use control_core::socketio::namespace::NamespaceCacheingLogic;
-use ethercat_hal::io::digital_output::DigitalOutput;
-use smol::channel::{Receiver, Sender};
+use qitech_lib::ethercat_hal::io::digital_output::DigitalOutputDevice;
+use qitech_lib::machines::{MachineIdentification, MachineIdentificationUnique};
+use tokio::sync::mpsc::{Receiver, Sender};
 
 use self::api::{StateEvent, Wago750_501TestMachineEvents, Wago750_501TestMachineNamespace};
-use crate::{
-    AsyncThreadMessage, Machine, MachineMessage, VENDOR_QITECH, WAGO_750_501_TEST_MACHINE,
-    machine_identification::{MachineIdentification, MachineIdentificationUnique},
-};
+use crate::{MachineMessage, QiTechMachine, VENDOR_QITECH, WAGO_750_501_TEST_MACHINE};
 
 pub mod act;
 pub mod api;
 pub mod new;
 
-#[derive(Debug)]
 pub struct Wago750_501TestMachine {
     pub api_receiver: Receiver<MachineMessage>,
     pub api_sender: Sender<MachineMessage>,
     pub machine_identification_unique: MachineIdentificationUnique,
-    pub main_sender: Option<Sender<AsyncThreadMessage>>,
     pub namespace: Wago750_501TestMachineNamespace,
     pub last_state_emit: Instant,
     pub outputs: [bool; 2],
-    pub douts: [DigitalOutput; 2],
-}
-
-impl Machine for Wago750_501TestMachine {
-    fn get_machine_identification_unique(&self) -> MachineIdentificationUnique {
-        self.machine_identification_unique.clone()
-    }
-
-    fn get_main_sender(&self) -> Option<Sender<AsyncThreadMessage>> {
-        self.main_sender.clone()
-    }
+    pub douts: Rc<RefCell<dyn DigitalOutputDevice>>,
 }
 
 impl Wago750_501TestMachine {
@@ -65,4 +51,14 @@ impl Wago750_501TestMachine {
         self.outputs = [on; 2];
         self.emit_state();
     }
+
+    /// Write the output state to the module
+    fn write_outputs(&mut self) {
+        let mut douts = self.douts.borrow_mut();
+        for (port, &on) in self.outputs.iter().enumerate() {
+            douts.set_output(port, on);
+        }
+    }
 }
+
+impl QiTechMachine for Wago750_501TestMachine {}
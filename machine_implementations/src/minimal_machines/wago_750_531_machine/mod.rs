@@ -1,39 +1,25 @@
-use std::time::Instant;
+use std::{cell::RefCell, rc::Rc, time::Instant};
 
 use control_core::socketio::namespace::NamespaceCacheingLogic;
-use ethercat_hal::io::digital_output::DigitalOutput;
-use smol::channel::{Receiver, Sender};
+use qitech_lib::ethercat_hal::io::digital_output::DigitalOutputDevice;
+use qitech_lib::machines::{MachineIdentification, MachineIdentificationUnique};
+use tokio::sync::mpsc::{Receiver, Sender};
 
 use self::api::{StateEvent, Wago750_531MachineEvents, Wago750_531MachineNamespace};
-use crate::{
-    AsyncThreadMessage, Machine, MachineMessage, VENDOR_QITECH, WAGO_750_531_MACHINE,
-    machine_identification::{MachineIdentification, MachineIdentificationUnique},
-};
+use crate::{MachineMessage, QiTechMachine, VENDOR_QITECH, WAGO_750_531_MACHINE};
 
 pub mod act;
 pub mod api;
 pub mod new;
 
-#[derive(Debug)]
 pub struct Wago750_531Machine {
     pub api_receiver: Receiver<MachineMessage>,
     pub api_sender: Sender<MachineMessage>,
     pub machine_identification_unique: MachineIdentificationUnique,
-    pub main_sender: Option<Sender<AsyncThreadMessage>>,
     pub namespace: Wago750_531MachineNamespace,
     pub last_state_emit: Instant,
     pub outputs_on: [bool; 4],
-    pub douts: [DigitalOutput; 4],
-}
-
-impl Machine for Wago750_531Machine {
-    fn get_machine_identification_unique(&self) -> MachineIdentificationUnique {
-        self.machine_identification_unique.clone()
-    }
-
-    fn get_main_sender(&self) -> Option<Sender<AsyncThreadMessage>> {
-        self.main_sender.clone()
-    }
+    pub douts: Rc<RefCell<dyn DigitalOutputDevice>>,
 }
 
 impl Wago750_531Machine {
@@ -56,16 +42,20 @@ impl Wago750_531Machine {
     pub fn set_output(&mut self, index: usize, on: bool) {
         if index < self.outputs_on.len() {
             self.outputs_on[index] = on;
-            self.douts[index].set(on);
+            self.douts.borrow_mut().set_output(index, on);
             self.emit_state();
         }
     }
 
     pub fn set_all_outputs(&mut self, on: bool) {
         self.outputs_on = [on; 4];
-        for dout in &self.douts {
-            dout.set(on);
+        let mut douts = self.douts.borrow_mut();
+        for port in 0..self.outputs_on.len() {
+            douts.set_output(port, on);
         }
+        drop(douts);
         self.emit_state();
     }
 }
+
+impl QiTechMachine for Wago750_531Machine {}
@@ -1,10 +1,14 @@
 use std::time::{Duration, Instant};
 
 use super::Wago750_553Machine;
-use crate::{MachineAct, MachineMessage, MachineValues};
+use crate::MachineApi;
+use qitech_lib::machines::{
+    Machine, MachineDataRegistry, MachineError, MachineIdentificationUnique,
+};
 
-impl MachineAct for Wago750_553Machine {
-    fn act(&mut self, now: Instant) {
+impl Machine for Wago750_553Machine {
+    fn act(&mut self, _registry: Option<&mut MachineDataRegistry>) -> Result<(), MachineError> {
+        let now = Instant::now();
         if let Ok(msg) = self.api_receiver.try_recv() {
             self.act_machine_message(msg);
         }
@@ -13,35 +17,12 @@ impl MachineAct for Wago750_553Machine {
             self.emit_state();
             self.last_state_emit = now;
         }
+        Ok(())
     }
 
-    fn act_machine_message(&mut self, msg: MachineMessage) {
-        match msg {
-            MachineMessage::SubscribeNamespace(namespace) => {
-                self.namespace.namespace = Some(namespace);
-                self.emit_state();
-            }
-            MachineMessage::UnsubscribeNamespace => {
-                self.namespace.namespace = None;
-            }
-            MachineMessage::HttpApiJsonRequest(value) => {
-                use crate::MachineApi;
-                let _res = self.api_mutate(value);
-            }
-            MachineMessage::RequestValues(sender) => {
-                let state_json =
-                    serde_json::to_value(self.get_state()).expect("Failed to serialize state");
-                smol::spawn(async move {
-                    let _ = sender
-                        .send(MachineValues {
-                            state: state_json,
-                            live_values: serde_json::Value::Null,
-                        })
-                        .await;
-                    sender.close();
-                })
-                .detach();
-            }
-        }
+    fn react(&mut self, _registry: &MachineDataRegistry) {}
+
+    fn get_identification(&self) -> MachineIdentificationUnique {
+        self.machine_identification_unique
     }
 }
@@ -1,40 +1,26 @@
-use std::time::Instant;
+use std::{cell::RefCell, rc::Rc, time::Instant};
 
 use control_core::socketio::namespace::NamespaceCacheingLogic;
-use ethercat_hal::io::analog_output::AnalogOutput;
-use smol::channel::{Receiver, Sender};
+use qitech_lib::ethercat_hal::io::analog_output::AnalogOutputDevice;
+use qitech_lib::machines::{MachineIdentification, MachineIdentificationUnique};
+use tokio::sync::mpsc::{Receiver, Sender};
 
 use self::api::{StateEvent, Wago750_553MachineEvents, Wago750_553MachineNamespace};
-use crate::{
-    AsyncThreadMessage, Machine, MachineMessage, VENDOR_QITECH, WAGO_750_553_MACHINE,
-    machine_identification::{MachineIdentification, MachineIdentificationUnique},
-};
+use crate::{MachineMessage, QiTechMachine, VENDOR_QITECH, WAGO_750_553_MACHINE};
 
 pub mod act;
 pub mod api;
 pub mod new;
 
-#[derive(Debug)]
 pub struct Wago750_553Machine {
     pub api_receiver: Receiver<MachineMessage>,
     pub api_sender: Sender<MachineMessage>,
     pub machine_identification_unique: MachineIdentificationUnique,
-    pub main_sender: Option<Sender<AsyncThreadMessage>>,
     pub namespace: Wago750_553MachineNamespace,
     pub last_state_emit: Instant,
 
     pub outputs: [f32; 4],
-    pub aouts: [AnalogOutput; 4],
-}
-
-impl Machine for Wago750_553Machine {
-    fn get_machine_identification_unique(&self) -> MachineIdentificationUnique {
-        self.machine_identification_unique.clone()
-    }
-
-    fn get_main_sender(&self) -> Option<Sender<AsyncThreadMessage>> {
-        self.main_sender.clone()
-    }
+    pub aouts: Rc<RefCell<dyn AnalogOutputDevice>>,
 }
 
 impl Wago750_553Machine {
@@ -59,7 +45,7 @@ impl Wago750_553Machine {
         if index < self.outputs.len() {
             let clamped = value.clamp(0.0, 1.0);
             self.outputs[index] = clamped;
-            self.aouts[index].set(clamped);
+            self.aouts.borrow_mut().set_output(index, clamped.into());
             self.emit_state();
         }
     }
@@ -67,9 +53,13 @@ impl Wago750_553Machine {
     pub fn set_all_outputs(&mut self, value: f32) {
         let clamped = value.clamp(0.0, 1.0);
         self.outputs = [clamped; 4];
-        for aout in &self.aouts {
-            aout.set(clamped);
+        let mut aouts = self.aouts.borrow_mut();
+        for port in 0..self.outputs.len() {
+            aouts.set_output(port, clamped.into());
         }
+        drop(aouts);
         self.emit_state();
     }
 }
+
+impl QiTechMachine for Wago750_553Machine {}
@@ -1,41 +1,30 @@
-use std::time::Instant;
+use std::{cell::RefCell, rc::Rc, time::Instant};
 
 use control_core::socketio::namespace::NamespaceCacheingLogic;
-use ethercat_hal::io::{digital_input::DigitalInput, digital_output::DigitalOutput};
-use smol::channel::{Receiver, Sender};
+use qitech_lib::ethercat_hal::io::{
+    digital_input::DigitalInputDevice, digital_output::DigitalOutputDevice,
+};
+use qitech_lib::machines::{MachineIdentification, MachineIdentificationUnique};
+use tokio::sync::mpsc::{Receiver, Sender};
 
 use self::api::{
     StateEvent, Wago8chDigitalIOTestMachineEvents, Wago8chDigitalIOTestMachineNamespace,
 };
-use crate::{
-    AsyncThreadMessage, Machine, MachineMessage, VENDOR_QITECH, WAGO_8CH_IO_TEST_MACHINE,
-    machine_identification::{MachineIdentification, MachineIdentificationUnique},
-};
+use crate::{MachineMessage, QiTechMachine, VENDOR_QITECH, WAGO_8CH_IO_TEST_MACHINE};
 
 pub mod act;
 pub mod api;
 pub mod new;
 
-#[derive(Debug)]
 pub struct Wago8chDigitalIOTestMachine {
     pub api_receiver: Receiver<MachineMessage>,
     pub api_sender: Sender<MachineMessage>,
     pub machine_identification_unique: MachineIdentificationUnique,
-    pub main_sender: Option<Sender<AsyncThreadMessage>>,
     pub namespace: Wago8chDigitalIOTestMachineNamespace,
     pub last_state_emit: Instant,
-    pub digital_output: [DigitalOutput; 8],
-    pub digital_input: [DigitalInput; 8],
-}
-
-impl Machine for Wago8chDigitalIOTestMachine {
-    fn get_machine_identification_unique(&self) -> MachineIdentificationUnique {
-        self.machine_identification_unique.clone()
-    }
-
-    fn get_main_sender(&self) -> Option<Sender<AsyncThreadMessage>> {
-        self.main_sender.clone()
-    }
+    pub outputs: [bool; 8],
+    pub digital_output: Rc<RefCell<dyn DigitalOutputDevice>>,
+    pub digital_input: Rc<RefCell<dyn DigitalInputDevice>>,
 }
 
 impl Wago8chDigitalIOTestMachine {
@@ -45,26 +34,14 @@ impl Wago8chDigitalIOTestMachine {
     };
 
     pub fn get_state(&self) -> StateEvent {
+        let digital_input = self.digital_input.borrow();
         StateEvent {
-            digital_input: (0..8)
-                .map(|i| {
-                    self.digital_input[i]
-                        .get_value()
-                        .expect("digital input value should be available")
-                })
-                .collect::<Vec<_>>()
-                .try_into()
-                .expect("bool vector into array[8] should work"),
-            digital_output: [
-                self.digital_output[0].get(),
-                self.digital_output[1].get(),
-                self.digital_output[2].get(),
-                self.digital_output[3].get(),
-                self.digital_output[4].get(),
-                self.digital_output[5].get(),
-                self.digital_output[6].get(),
-                self.digital_output[7].get(),
-            ],
+            digital_input: std::array::from_fn(|port| {
+                digital_input
+                    .get_input(port)
+                    .expect("digital input value should be available")
+            }),
+            digital_output: self.outputs,
         }
     }
 
@@ -75,6 +52,9 @@ impl Wago8chDigitalIOTestMachine {
     }
 
     pub fn set_output(&mut self, i: usize, value: bool) {
-        self.digital_output[i].set(value);
+        self.outputs[i] = value;
+        self.digital_output.borrow_mut().set_output(i, value);
     }
 }
+
+impl QiTechMachine for Wago8chDigitalIOTestMachine {}
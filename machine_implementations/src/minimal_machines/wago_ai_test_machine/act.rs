@@ -1,35 +1,15 @@
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use crate::{
-    MachineAct, MachineMessage, MachineValues,
-    minimal_machines::wago_ai_test_machine::WagoAiTestMachine,
+use qitech_lib::ethercat_hal::io::analog_input::physical::AnalogInputValue;
+use qitech_lib::machines::{
+    Machine, MachineDataRegistry, MachineError, MachineIdentificationUnique,
 };
 
-impl MachineAct for WagoAiTestMachine {
-    fn act_machine_message(&mut self, msg: MachineMessage) {
-        match msg {
-            MachineMessage::SubscribeNamespace(namespace) => {
-                self.namespace.namespace = Some(namespace);
-                self.emit_measurement_rate();
-            }
-            MachineMessage::UnsubscribeNamespace => self.namespace.namespace = None,
-            MachineMessage::HttpApiJsonRequest(value) => {
-                use crate::MachineApi;
-                let _res = self.api_mutate(value);
-            }
-            MachineMessage::RequestValues(sender) => {
-                sender
-                    .send_blocking(MachineValues {
-                        state: serde_json::Value::Null,
-                        live_values: serde_json::Value::Null,
-                    })
-                    .expect("Failed to send values");
-                sender.close();
-            }
-        }
-    }
+use crate::{MachineApi, minimal_machines::wago_ai_test_machine::WagoAiTestMachine};
 
-    fn act(&mut self, now: std::time::Instant) {
+impl Machine for WagoAiTestMachine {
+    fn act(&mut self, _registry: Option<&mut MachineDataRegistry>) -> Result<(), MachineError> {
+        let now = Instant::now();
         let recv = self.api_receiver.try_recv();
         if let Ok(msg) = recv {
             self.act_machine_message(msg);
@@ -41,23 +21,27 @@ impl MachineAct for WagoAiTestMachine {
             let mut wiring_errors = [false; 4];
 
             // Read all 4 analog inputs
-            for (i, ai) in self.analog_inputs.iter().enumerate() {
-                let measured_value = ai.get_physical();
-                wiring_errors[i] = ai.get_wiring_error();
+            let analog_inputs = self.analog_inputs.borrow();
+            let range = analog_inputs.analog_input_range();
+            for i in 0..4 {
+                let measured_value = match analog_inputs.get_input(i) {
+                    Ok(input) => input.get_physical(&range),
+                    Err(_) => {
+                        wiring_errors[i] = true;
+                        continue;
+                    }
+                };
 
                 match measured_value {
-                    ethercat_hal::io::analog_input::physical::AnalogInputValue::Potential(
-                        _quantity,
-                    ) => {
+                    AnalogInputValue::Potential(_quantity) => {
                         // Don't do anything - this module is current input only
                     }
-                    ethercat_hal::io::analog_input::physical::AnalogInputValue::Current(
-                        quantity,
-                    ) => {
+                    AnalogInputValue::Current(quantity) => {
                         values[i] = quantity.value;
                     }
                 }
             }
+            drop(analog_inputs);
 
             let now_milliseconds = SystemTime::now()
                 .duration_since(UNIX_EPOCH)
@@ -68,5 +52,12 @@ impl MachineAct for WagoAiTestMachine {
             self.emit_wiring_errors(wiring_errors);
             self.last_measurement = Instant::now();
         }
+        Ok(())
+    }
+
+    fn react(&mut self, _registry: &MachineDataRegistry) {}
+
+    fn get_identification(&self) -> MachineIdentificationUnique {
+        self.machine_identification_unique
     }
 }
@@ -1,41 +1,27 @@
-use std::time::Instant;
+use std::{cell::RefCell, rc::Rc, time::Instant};
 
 use control_core::socketio::{event::Event, namespace::NamespaceCacheingLogic};
-use ethercat_hal::io::analog_input::AnalogInput;
-use smol::channel::{Receiver, Sender};
+use qitech_lib::ethercat_hal::io::analog_input::AnalogInputDevice;
+use qitech_lib::machines::{MachineIdentification, MachineIdentificationUnique};
+use tokio::sync::mpsc::{Receiver, Sender};
 
 use self::api::{AnalogInputsEvent, WagoAiTestMachineEvents, WagoAiTestMachineNamespace};
-use crate::{
-    AsyncThreadMessage, Machine, MachineMessage, VENDOR_QITECH, WAGO_AI_TEST_MACHINE,
-    machine_identification::{MachineIdentification, MachineIdentificationUnique},
-};
+use crate::{MachineMessage, QiTechMachine, VENDOR_QITECH, WAGO_AI_TEST_MACHINE};
 
 pub mod act;
 pub mod api;
 pub mod new;
 
-#[derive(Debug)]
 pub struct WagoAiTestMachine {
     api_receiver: Receiver<MachineMessage>,
     api_sender: Sender<MachineMessage>,
     machine_identification_unique: MachineIdentificationUnique,
-    main_sender: Option<Sender<AsyncThreadMessage>>,
     namespace: WagoAiTestMachineNamespace,
 
     last_measurement: Instant,
     measurement_rate_hz: f64,
 
-    analog_inputs: [AnalogInput; 4],
-}
-
-impl Machine for WagoAiTestMachine {
-    fn get_machine_identification_unique(&self) -> MachineIdentificationUnique {
-        self.machine_identification_unique.clone()
-    }
-
-    fn get_main_sender(&self) -> Option<Sender<AsyncThreadMessage>> {
-        self.main_sender.clone()
-    }
+    analog_inputs: Rc<RefCell<dyn AnalogInputDevice>>,
 }
 
 impl WagoAiTestMachine {
@@ -77,3 +63,5 @@ impl WagoAiTestMachine {
             )));
     }
 }
+
+impl QiTechMachine for WagoAiTestMachine {}
@@ -1,39 +1,25 @@
-use std::time::Instant;
+use std::{cell::RefCell, rc::Rc, time::Instant};
 
 use control_core::socketio::namespace::NamespaceCacheingLogic;
-use ethercat_hal::io::digital_output::DigitalOutput;
-use smol::channel::{Receiver, Sender};
+use qitech_lib::ethercat_hal::io::digital_output::DigitalOutputDevice;
+use qitech_lib::machines::{MachineIdentification, MachineIdentificationUnique};
+use tokio::sync::mpsc::{Receiver, Sender};
 
 use self::api::{StateEvent, WagoDOTestMachineEvents, WagoDOTestMachineNamespace};
-use crate::{
-    AsyncThreadMessage, Machine, MachineMessage, VENDOR_QITECH, WAGO_DO_TEST_MACHINE,
-    machine_identification::{MachineIdentification, MachineIdentificationUnique},
-};
+use crate::{MachineMessage, QiTechMachine, VENDOR_QITECH, WAGO_DO_TEST_MACHINE};
 
 pub mod act;
 pub mod api;
 pub mod new;
 
-#[derive(Debug)]
 pub struct WagoDOTestMachine {
     pub api_receiver: Receiver<MachineMessage>,
     pub api_sender: Sender<MachineMessage>,
     pub machine_identification_unique: MachineIdentificationUnique,
-    pub main_sender: Option<Sender<AsyncThreadMessage>>,
     pub namespace: WagoDOTestMachineNamespace,
     pub last_state_emit: Instant,
     pub led_on: [bool; 8],
-    pub douts: [DigitalOutput; 8],
-}
-
-impl Machine for WagoDOTestMachine {
-    fn get_machine_identification_unique(&self) -> MachineIdentificationUnique {
-        self.machine_identification_unique.clone()
-    }
-
-    fn get_main_sender(&self) -> Option<Sender<AsyncThreadMessage>> {
-        self.main_sender.clone()
-    }
+    pub douts: Rc<RefCell<dyn DigitalOutputDevice>>,
 }
 
 impl WagoDOTestMachine {
@@ -66,4 +52,14 @@ impl WagoDOTestMachine {
         self.led_on = [on; 8];
         self.emit_state();
     }
+
+    /// Write the output state to the module
+    fn write_outputs(&mut self) {
+        let mut douts = self.douts.borrow_mut();
+        for (port, &on) in self.led_on.iter().enumerate() {
+            douts.set_output(port, on);
+        }
+    }
 }
+
+impl QiTechMachine for WagoDOTestMachine {}
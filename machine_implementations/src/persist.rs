@@ -0,0 +1,11 @@
+use std::path::PathBuf;
+
+/// Base directory of persisted machine data, e.g. the machine device info and winder
+/// configurations
+pub fn data_dir() -> PathBuf {
+    let base = std::env::var("STATE_DIRECTORY")
+        .or(std::env::var("XDG_DATA_HOME"))
+        .or(std::env::var("HOME"))
+        .unwrap_or(".".to_string());
+    PathBuf::from(base)
+}
@@ -1,7 +1,22 @@
+use crate::bottle_cap_sorter::Sorter1;
 use crate::extruder1::ExtruderV2;
+#[cfg(feature = "mock-machine")]
+use crate::minimal_machines::mock::MockMachine;
+use crate::minimal_machines::{
+    analog_input_test_machine::AnalogInputTestMachine,
+    bottlecaps_test_machine::BottlecapsTestMachine,
+    digital_input_test_machine::DigitalInputTestMachine, ip20_test_machine::IP20TestMachine,
+    motor_test_machine::MotorTestMachine, test_machine::TestMachine,
+    test_machine_stepper::TestMachineStepper,
+    wago_8ch_dio_test_machine::Wago8chDigitalIOTestMachine,
+    wago_750_430_di_machine::Wago750_430DiMachine, wago_750_460_machine::Wago750_460Machine,
+    wago_750_501_test_machine::Wago750_501TestMachine, wago_750_531_machine::Wago750_531Machine,
+    wago_750_553_machine::Wago750_553Machine, wago_ai_test_machine::WagoAiTestMachine,
+    wago_do_test_machine::WagoDOTestMachine,
+};
 use crate::{
     MachineHardware, MachineNew, QiTechMachine, aquapath1::AquaPathV1, laser::LaserMachine,
-    winder2::Winder2,
+    wago_power::WagoPower, wago_serial_machine::WagoSerialMachine, winder2::Winder2,
 };
 use anyhow::Error;
 use lazy_static::lazy_static;
@@ -82,7 +97,16 @@ lazy_static! {
         #[cfg(not(feature = "mock-machine"))]
         mc.register::<AquaPathV1>(vec![AquaPathV1::MACHINE_IDENTIFICATION]);
 
-        /*mc.register::<TestMachine>(vec![TestMachine::MACHINE_IDENTIFICATION]);
+        #[cfg(not(feature = "mock-machine"))]
+        mc.register::<Sorter1>(vec![Sorter1::MACHINE_IDENTIFICATION]);
+
+        #[cfg(not(feature = "mock-machine"))]
+        mc.register::<WagoPower>(vec![WagoPower::MACHINE_IDENTIFICATION]);
+
+        #[cfg(feature = "mock-machine")]
+        mc.register::<MockMachine>(vec![MockMachine::MACHINE_IDENTIFICATION]);
+
+        mc.register::<TestMachine>(vec![TestMachine::MACHINE_IDENTIFICATION]);
 
         mc.register::<IP20TestMachine>(vec![IP20TestMachine::MACHINE_IDENTIFICATION]);
 
@@ -92,7 +116,6 @@ lazy_static! {
 
         mc.register::<MotorTestMachine>(vec![MotorTestMachine::MACHINE_IDENTIFICATION]);
 
-
         mc.register::<DigitalInputTestMachine>(vec![
             DigitalInputTestMachine::MACHINE_IDENTIFICATION,
         ]);
@@ -107,12 +130,12 @@ lazy_static! {
             Wago8chDigitalIOTestMachine::MACHINE_IDENTIFICATION,
         ]);
 
-        mc.register::<WagoSerialMachine>(vec![WagoSerialMachine::MACHINE_IDENTIFICATION]);
         mc.register::<TestMachineStepper>(vec![TestMachineStepper::MACHINE_IDENTIFICATION]);
         mc.register::<Wago750_430DiMachine>(vec![Wago750_430DiMachine::MACHINE_IDENTIFICATION]);
         mc.register::<Wago750_460Machine>(vec![Wago750_460Machine::MACHINE_IDENTIFICATION]);
         mc.register::<Wago750_553Machine>(vec![Wago750_553Machine::MACHINE_IDENTIFICATION]);
-        mc.register::<BottlecapsTestMachine>(vec![BottlecapsTestMachine::MACHINE_IDENTIFICATION]);*/
+        mc.register::<BottlecapsTestMachine>(vec![BottlecapsTestMachine::MACHINE_IDENTIFICATION]);
+        mc.register::<WagoSerialMachine>(vec![WagoSerialMachine::MACHINE_IDENTIFICATION]);
         mc
     };
 }
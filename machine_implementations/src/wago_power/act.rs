@@ -0,0 +1,51 @@
+use std::time::{Duration, Instant};
+
+use qitech_lib::machines::{
+    Machine, MachineDataRegistry, MachineError, MachineIdentificationUnique,
+};
+
+use super::{WagoPower, device::WagoPowerReport};
+use crate::MachineApi;
+
+impl Machine for WagoPower {
+    fn act(&mut self, _registry: Option<&mut MachineDataRegistry>) -> Result<(), MachineError> {
+        let now = Instant::now();
+
+        if let Ok(msg) = self.api_receiver.try_recv() {
+            self.act_machine_message(msg);
+        }
+
+        while let Some(report) = self.device.try_recv() {
+            match report {
+                WagoPowerReport::LiveValues(live_values) => {
+                    self.last_live_values = Some(live_values)
+                }
+                WagoPowerReport::Mode(mode) => {
+                    self.mode = mode;
+                    self.emit_state();
+                }
+                // Drop the machine so it is rediscovered once the device is reachable again
+                WagoPowerReport::Failed(e) => {
+                    return Err(MachineError::IrrecoverableFailure(format!(
+                        "[{}::WagoPower::act] Lost connection to power supply: {}",
+                        module_path!(),
+                        e
+                    )));
+                }
+            }
+        }
+
+        if now.duration_since(self.last_emit) > Duration::from_secs_f64(1.0 / 30.0) {
+            self.emit_live_values();
+            self.last_emit = now;
+        }
+
+        Ok(())
+    }
+
+    fn react(&mut self, _registry: &MachineDataRegistry) {}
+
+    fn get_identification(&self) -> MachineIdentificationUnique {
+        self.machine_identification_unique
+    }
+}
@@ -0,0 +1,125 @@
+use super::{Mode, WagoPower};
+use crate::{MachineApi, MachineMessage, MachineValues};
+use control_core::socketio::{
+    event::{Event, GenericEvent},
+    namespace::{
+        CacheFn, CacheableEvents, Namespace, NamespaceCacheingLogic, cache_duration,
+        cache_first_and_last_event,
+    },
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{sync::Arc, time::Duration};
+use tokio::sync::mpsc::Sender;
+use tracing::instrument;
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct LiveValuesEvent {
+    /// output voltage in V
+    pub voltage: f64,
+    /// output current in mA
+    pub current: f64,
+}
+
+impl LiveValuesEvent {
+    pub fn build(&self) -> Event<Self> {
+        Event::new("LiveValuesEvent", self.clone())
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct StateEvent {
+    pub mode: Mode,
+    pub is_default_state: bool,
+}
+
+impl StateEvent {
+    pub fn build(&self) -> Event<Self> {
+        Event::new("StateEvent", self.clone())
+    }
+}
+
+pub enum WagoPowerEvents {
+    LiveValues(Event<LiveValuesEvent>),
+    State(Event<StateEvent>),
+}
+
+impl CacheableEvents<Self> for WagoPowerEvents {
+    fn event_value(&self) -> GenericEvent {
+        match self {
+            Self::LiveValues(event) => event.clone().into(),
+            Self::State(event) => event.clone().into(),
+        }
+    }
+
+    fn event_cache_fn(&self) -> CacheFn {
+        match self {
+            Self::LiveValues(_) => {
+                cache_duration(Duration::from_secs(60 * 60), Duration::from_secs(1))
+            }
+            Self::State(_) => cache_first_and_last_event(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct WagoPowerNamespace {
+    pub namespace: Option<Namespace>,
+}
+
+impl NamespaceCacheingLogic<WagoPowerEvents> for WagoPowerNamespace {
+    #[instrument(skip_all)]
+    fn emit(&mut self, events: WagoPowerEvents) {
+        let event = Arc::new(events.event_value());
+        let buffer_fn = events.event_cache_fn();
+        if let Some(ns) = &mut self.namespace {
+            ns.emit(event, &buffer_fn);
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+pub enum Mutation {
+    SetMode(Mode),
+}
+
+impl MachineApi for WagoPower {
+    fn get_api_sender(&self) -> Sender<MachineMessage> {
+        self.api_sender.clone()
+    }
+
+    fn api_mutate(&mut self, request_body: Value) -> Result<(), anyhow::Error> {
+        let mutation: Mutation = serde_json::from_value(request_body)?;
+        match mutation {
+            Mutation::SetMode(mode) => self.set_mode(mode)?,
+        }
+        Ok(())
+    }
+
+    fn api_event_namespace(&mut self) -> Option<Namespace> {
+        self.namespace.namespace.clone()
+    }
+
+    fn act_machine_message(&mut self, msg: MachineMessage) {
+        match msg {
+            MachineMessage::SubscribeNamespace(namespace) => {
+                self.namespace.namespace = Some(namespace);
+                self.emit_state();
+            }
+            MachineMessage::UnsubscribeNamespace => self.namespace.namespace = None,
+            MachineMessage::HttpApiJsonRequest(value) => {
+                let _res = self.api_mutate(value);
+            }
+            MachineMessage::RequestValues(sender) => {
+                sender
+                    .send(MachineValues {
+                        state: serde_json::to_value(self.get_state())
+                            .expect("Failed to serialize state"),
+                        live_values: serde_json::to_value(self.last_live_values.clone())
+                            .expect("Failed to serialize live values"),
+                    })
+                    .expect("Failed to send values");
+            }
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use std::{
+    net::SocketAddr,
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use control_core::modbus::tcp::ModbusTcpDevice;
+use qitech_lib::units::{
+    ElectricCurrent, ElectricPotential,
+    electric_current::milliampere,
+    electric_potential::{millivolt, volt},
+};
+use smol::{Timer, future::FutureExt};
+use tokio::sync::mpsc::{
+    Receiver, Sender,
+    error::{TryRecvError, TrySendError},
+};
+
+use super::{Mode, api::LiveValuesEvent};
+
+/// Holding register block for the output configuration
+/// (voltage, warning threshold, control bits, delay)
+const REGISTER_OUTPUT_CONFIG: u16 = 0x0088;
+/// Holding registers with measured voltage (mV) and current (mA)
+const REGISTER_MEASUREMENT: u16 = 0x0500;
+
+const OUTPUT_VOLTAGE_MV: u16 = 24000;
+const WARNING_THRESHOLD_MA: u16 = 5000; // For now
+const SWITCH_DELAY_MS: u16 = 100; // For now
+
+const POLL_INTERVAL: Duration = Duration::from_millis(33);
+/// A single Modbus transaction taking longer than this counts as a lost device
+const REQUEST_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Report from the Modbus worker to the machine
+#[derive(Debug)]
+pub enum WagoPowerReport {
+    LiveValues(LiveValuesEvent),
+    /// Output state of the supply read when the worker connected
+    Mode(Mode),
+    /// The worker stopped, the device has to be rediscovered
+    Failed(String),
+}
+
+/// Handle to the Modbus TCP worker of a WAGO power supply
+///
+/// All network I/O happens on a separate thread so `act` never blocks on the
+/// device. The worker stops when this handle is dropped.
+#[derive(Debug)]
+pub struct WagoPowerDevice {
+    mode_sender: Sender<Mode>,
+    report_receiver: Receiver<WagoPowerReport>,
+    _worker: JoinHandle<()>,
+}
+
+impl WagoPowerDevice {
+    pub fn spawn(addr: SocketAddr) -> Result<Self> {
+        let (mode_sender, mode_receiver) = tokio::sync::mpsc::channel(4);
+        let (report_sender, report_receiver) = tokio::sync::mpsc::channel(4);
+
+        let worker = std::thread::Builder::new()
+            .name(format!("wago-power-{}", addr))
+            .spawn(move || {
+                let res = smol::block_on(run_worker(addr, mode_receiver, &report_sender));
+                if let Err(e) = res {
+                    let _ = report_sender.try_send(WagoPowerReport::Failed(e.to_string()));
+                }
+            })?;
+
+        Ok(Self {
+            mode_sender,
+            report_receiver,
+            _worker: worker,
+        })
+    }
+
+    /// Queue a mode change, applied with the next worker cycle
+    pub fn set_mode(&self, mode: Mode) -> Result<()> {
+        match self.mode_sender.try_send(mode) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(_)) => Err(anyhow::anyhow!(
+                "[{}::WagoPowerDevice::set_mode] Too many pending mode changes",
+                module_path!()
+            )),
+            Err(TrySendError::Closed(_)) => Err(anyhow::anyhow!(
+                "[{}::WagoPowerDevice::set_mode] Worker has stopped",
+                module_path!()
+            )),
+        }
+    }
+
+    /// Returns the next pending report without blocking
+    pub fn try_recv(&mut self) -> Option<WagoPowerReport> {
+        match self.report_receiver.try_recv() {
+            Ok(report) => Some(report),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(WagoPowerReport::Failed(
+                "Modbus worker exited unexpectedly".to_owned(),
+            )),
+        }
+    }
+}
+
+async fn run_worker(
+    addr: SocketAddr,
+    mut mode_receiver: Receiver<Mode>,
+    report_sender: &Sender<WagoPowerReport>,
+) -> Result<()> {
+    let mut device = ModbusTcpDevice::new(addr).await?;
+
+    let mode = read_mode(&mut device).await?;
+    let _ = report_sender.try_send(WagoPowerReport::Mode(mode));
+
+    loop {
+        let cycle_start = Instant::now();
+
+        loop {
+            match mode_receiver.try_recv() {
+                Ok(mode) => transmit_mode(&mut device, &mode).await?,
+                Err(TryRecvError::Empty) => break,
+                // machine was dropped
+                Err(TryRecvError::Disconnected) => return Ok(()),
+            }
+        }
+
+        let live_values = read_live_values(&mut device).await?;
+        match report_sender.try_send(WagoPowerReport::LiveValues(live_values)) {
+            // machine did not pick up the last values yet, skip this sample
+            Ok(()) | Err(TrySendError::Full(_)) => (),
+            Err(TrySendError::Closed(_)) => return Ok(()),
+        }
+
+        if let Some(remaining) = POLL_INTERVAL.checked_sub(cycle_start.elapsed()) {
+            Timer::after(remaining).await;
+        }
+    }
+}
+
+async fn transmit_mode(device: &mut ModbusTcpDevice, mode: &Mode) -> Result<()> {
+    let registers = [
+        OUTPUT_VOLTAGE_MV,
+        WARNING_THRESHOLD_MA,
+        mode.as_u16(),
+        SWITCH_DELAY_MS,
+    ];
+    with_timeout(device.set_holding_registers(REGISTER_OUTPUT_CONFIG, &registers)).await
+}
+
+async fn read_mode(device: &mut ModbusTcpDevice) -> Result<Mode> {
+    // Control bits follow the voltage and the warning threshold
+    let control = with_timeout(device.get_holding_registers(REGISTER_OUTPUT_CONFIG + 2, 1)).await?;
+    Ok(Mode::from_u16(control[0]))
+}
+
+async fn read_live_values(device: &mut ModbusTcpDevice) -> Result<LiveValuesEvent> {
+    let electric = with_timeout(device.get_holding_registers(REGISTER_MEASUREMENT, 2)).await?;
+
+    let voltage = ElectricPotential::new::<millivolt>(f64::from(electric[0]));
+    let current = ElectricCurrent::new::<milliampere>(f64::from(electric[1]));
+
+    Ok(LiveValuesEvent {
+        voltage: voltage.get::<volt>(),
+        current: current.get::<milliampere>(),
+    })
+}
+
+async fn with_timeout<T>(future: impl Future<Output = Result<T>>) -> Result<T> {
+    let timeout = async {
+        Timer::after(REQUEST_TIMEOUT).await;
+        Err(anyhow::anyhow!(
+            "[{}::with_timeout] Modbus request timed out",
+            module_path!()
+        ))
+    };
+    future.or(timeout).await
+}
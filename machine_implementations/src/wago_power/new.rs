@@ -0,0 +1,30 @@
+use std::time::Instant;
+
+use anyhow::Error;
+
+use super::{Mode, WagoPower, api::WagoPowerNamespace, device::WagoPowerDevice};
+use crate::{MachineHardware, MachineNew};
+
+impl MachineNew for WagoPower {
+    fn new(hw: MachineHardware) -> Result<Self, Error> {
+        let modbus_tcp = hw.try_get_modbus_tcp_by_index(0)?;
+        let device = WagoPowerDevice::spawn(modbus_tcp.addr)?;
+
+        let (sender, receiver) = tokio::sync::mpsc::channel(2);
+        let machine = Self {
+            api_receiver: receiver,
+            api_sender: sender,
+            machine_identification_unique: hw.identification,
+            namespace: WagoPowerNamespace { namespace: None },
+            device,
+            mode: Mode::Off,
+            last_emit: Instant::now(),
+            emitted_default_state: false,
+            last_live_values: None,
+        };
+
+        // The supply keeps its output state, so a reconnect doesn't switch it.
+        // The worker reports the actual mode once it is connected.
+        Ok(machine)
+    }
+}
@@ -1,45 +1,34 @@
 use api::{StateEvent, WagoSerialMachineEvents, WagoSerialMachineNamespace};
 use control_core::socketio::namespace::NamespaceCacheingLogic;
-use ethercat_hal::io::serial_interface::SerialInterface;
-use smol::channel::{Receiver, Sender};
-use std::time::Instant;
+use qitech_lib::ethercat_hal::io::serial_interface::SerialInterfaceDevice;
+use qitech_lib::machines::{MachineIdentification, MachineIdentificationUnique};
+use std::{cell::RefCell, rc::Rc, time::Instant};
+use tokio::sync::mpsc::{Receiver, Sender};
 
-use crate::{
-    AsyncThreadMessage, Machine, MachineMessage, VENDOR_QITECH,
-    machine_identification::{MachineIdentification, MachineIdentificationUnique},
-};
+use crate::{MachineMessage, QiTechMachine, VENDOR_QITECH, WAGO_SERIAL_MACHINE};
 
 pub mod act;
 pub mod api;
 pub mod new;
 
-#[derive(Debug)]
+/// Port of the WAGO 750-652 used by this machine
+const SERIAL_PORT: usize = 0;
+
 pub struct WagoSerialMachine {
     pub api_receiver: Receiver<MachineMessage>,
     pub api_sender: Sender<MachineMessage>,
     pub machine_identification_unique: MachineIdentificationUnique,
-    pub main_sender: Option<Sender<AsyncThreadMessage>>,
     pub namespace: WagoSerialMachineNamespace,
     pub last_state_emit: Instant,
-    pub serial_device: SerialInterface,
+    pub serial_device: Rc<RefCell<dyn SerialInterfaceDevice>>,
     serial_init_is_complete: bool,
     pub current_message: Option<String>,
 }
 
-impl Machine for WagoSerialMachine {
-    fn get_machine_identification_unique(&self) -> MachineIdentificationUnique {
-        self.machine_identification_unique.clone()
-    }
-
-    fn get_main_sender(&self) -> Option<Sender<AsyncThreadMessage>> {
-        self.main_sender.clone()
-    }
-}
-
 impl WagoSerialMachine {
     pub const MACHINE_IDENTIFICATION: MachineIdentification = MachineIdentification {
         vendor: VENDOR_QITECH,
-        machine: 0x67,
+        machine: WAGO_SERIAL_MACHINE,
     };
 
     pub fn get_state(&self) -> StateEvent {
@@ -53,8 +42,25 @@ impl WagoSerialMachine {
         self.namespace.emit(WagoSerialMachineEvents::State(event));
     }
 
-    pub fn send_message(&mut self, msg: String) {
+    pub fn send_message(&mut self, msg: String) -> Result<(), anyhow::Error> {
         let msg_bytes = msg.into_bytes();
-        let _res = smol::block_on((self.serial_device.write_message)(msg_bytes));
+        self.serial_device
+            .borrow_mut()
+            .serial_interface_write_message(SERIAL_PORT, msg_bytes)?;
+        Ok(())
+    }
+
+    fn read_serial(&mut self) {
+        let mut serial_device = self.serial_device.borrow_mut();
+        if !self.serial_init_is_complete {
+            self.serial_init_is_complete = serial_device.serial_interface_initialize(SERIAL_PORT);
+            return;
+        }
+
+        if let Some(msg) = serial_device.serial_interface_read_message(SERIAL_PORT) {
+            self.current_message = Some(String::from_utf8(msg).unwrap_or_default());
+        }
     }
 }
+
+impl QiTechMachine for WagoSerialMachine {}
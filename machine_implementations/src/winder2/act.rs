@@ -1,8 +1,34 @@
-use super::Winder2;
+use super::{Winder2, Winder2Data, puller_speed_controller::PullerRegulationMode};
 use crate::{MachineApi, laser::LaserData};
 use qitech_lib::machines::{Machine, MachineError, MachineIdentificationUnique};
+use qitech_lib::units::velocity::meter_per_minute;
 use std::time::{Duration, Instant};
 
+const REG_ERR_MESSAGE: &str = "Winder Couldnt write to the MachineDataRegistry";
+
+impl Winder2 {
+    fn get_machine_data(&self) -> Winder2Data {
+        let puller = &self.puller_speed_controller;
+        let puller_speed = puller.get_line_speed();
+        let diameter_regulation = matches!(
+            puller.regulation_mode,
+            PullerRegulationMode::Diameter | PullerRegulationMode::Predictive
+        );
+        let puller_speed_deviation = match puller.regulation_mode {
+            PullerRegulationMode::Speed => 0.0,
+            PullerRegulationMode::Diameter => {
+                puller.adaptive.modulation() * puller.adaptive.speed_delta_max()
+            }
+            PullerRegulationMode::Predictive => puller.predictive.deviation(),
+        };
+        Winder2Data {
+            puller_speed: puller_speed.get::<meter_per_minute>().abs(),
+            diameter_regulation,
+            puller_speed_deviation,
+        }
+    }
+}
+
 impl Machine for Winder2 {
     fn get_identification(&self) -> MachineIdentificationUnique {
         self.machine_identification_unique.clone()
@@ -10,7 +36,7 @@ impl Machine for Winder2 {
 
     fn act(
         &mut self,
-        _machine_data: Option<&mut qitech_lib::machines::MachineDataRegistry>,
+        machine_data: Option<&mut qitech_lib::machines::MachineDataRegistry>,
     ) -> Result<(), MachineError> {
         let now = std::time::Instant::now();
         let machine_message = self.api_receiver.try_recv();
@@ -21,6 +47,9 @@ impl Machine for Winder2 {
         // sync the spool speed
         self.sync_spool_speed(now);
 
+        // stop winding on tension arm faults
+        self.supervise_tension_arm(now);
+
         // sync the puller speed
         self.sync_puller_speed(now);
 
@@ -30,6 +59,12 @@ impl Machine for Winder2 {
         // automatically stops or pulls after N Meters if enabled
         self.stop_or_pull_spool(now);
 
+        // advance the spool changeover sequence
+        self.sync_changeover(now);
+
+        // announce saved spool reports
+        self.sync_spool_reports();
+
         if self.traverse_controller.did_change_state() {
             self.emit_state();
         }
@@ -39,6 +74,17 @@ impl Machine for Winder2 {
             self.emit_live_values();
             self.last_measurement_emit = now;
         }
+
+        if let Some(reg) = machine_data {
+            let data = self.get_machine_data();
+            // Only the diameter control of the extruder depends on it, keep winding
+            if reg
+                .store(self.machine_identification_unique, &data)
+                .is_err()
+            {
+                tracing::error!("{}", REG_ERR_MESSAGE);
+            }
+        }
         Ok(())
     }
 
@@ -56,7 +102,10 @@ impl Machine for Winder2 {
                 let target = laser_data.state.laser_state.target_diameter;
                 let lower = laser_data.state.laser_state.lower_tolerance;
                 let upper = laser_data.state.laser_state.higher_tolerance;
-                let last_speed = self.puller_speed_controller.last_speed;
+                if let Some(spool_job) = &mut self.spool_job {
+                    spool_job.record_diameter(current, target, lower, upper);
+                }
+                let line_speed = self.puller_speed_controller.get_line_speed();
                 self.puller_speed_controller
                     .adaptive
                     .update_with_measurement(
@@ -64,12 +113,18 @@ impl Machine for Winder2 {
                         target,
                         lower,
                         upper,
-                        last_speed,
+                        line_speed,
                         Instant::now(),
                     );
+                self.puller_speed_controller
+                    .predictive
+                    .update_with_measurement(current, target);
             }
             Err(_e) => {
                 self.laser_ident = None;
+                if let Some(spool_job) = &mut self.spool_job {
+                    spool_job.clear_diameter();
+                }
             }
         }
     }
@@ -10,7 +10,6 @@ use control_core::{
 };
 use core::f64;
 use qitech_lib::units::ConstZero;
-use qitech_lib::units::angle::degree;
 use qitech_lib::units::angular_acceleration::radian_per_second_squared;
 use qitech_lib::units::angular_velocity::{radian_per_second, revolution_per_minute};
 use qitech_lib::units::f64::*;
@@ -71,12 +70,6 @@ impl AdaptiveSpoolSpeedController {
     /// Maximum number of speed samples stored in the moving time window
     const SPEED_WINDOW_MAX_SAMPLES: usize = 10;
 
-    /// Maximum angle (in degrees) of the tension arm, representing loosest filament tension
-    const TENSION_ARM_MAX_ANGLE_DEG: f64 = 90.0;
-
-    /// Minimum angle (in degrees) of the tension arm, representing tightest filament tension
-    const TENSION_ARM_MIN_ANGLE_DEG: f64 = 20.0;
-
     /// Target normalized tension value (0.0-1.0) that the controller tries to maintain
     const TENSION_TARGET: f64 = 0.7;
 
@@ -118,10 +111,7 @@ impl AdaptiveSpoolSpeedController {
                 AngularAcceleration::ZERO,  // Will be dynamically adjusted
                 AngularVelocity::ZERO,
             ),
-            filament_calc: FilamentTensionCalculator::new(
-                Angle::new::<degree>(Self::TENSION_ARM_MAX_ANGLE_DEG),
-                Angle::new::<degree>(Self::TENSION_ARM_MIN_ANGLE_DEG),
-            ),
+            filament_calc: FilamentTensionCalculator::default(),
             speed_time_window: MovingTimeWindow::new(
                 std::time::Duration::from_secs(Self::SPEED_WINDOW_DURATION_SECS),
                 Self::SPEED_WINDOW_MAX_SAMPLES,
@@ -150,17 +140,28 @@ impl AdaptiveSpoolSpeedController {
     /// This ensures the spool's rim speed is properly scaled relative to the puller's
     /// linear speed, with the multiplier allowing for tension control adjustments.
     ///
+    /// With a diameter estimate v / r is known directly and replaces the learned factor.
+    ///
     /// # Parameters
     /// - `puller_speed_controller`: Reference to puller for baseline speed calculation
+    /// - `winding_speed`: Spool speed matching the line speed at the estimated radius
     ///
     /// # Returns
     /// Current maximum angular velocity for the spool in radians per second
-    fn get_max_speed(&self, puller_speed_controller: &PullerSpeedController) -> AngularVelocity {
-        AngularVelocity::new::<radian_per_second>(
-            (puller_speed_controller.last_speed.get::<meter_per_second>()
-                / self.speed_factor.get::<meter>())
-                * self.max_speed_multiplier,
-        )
+    fn get_max_speed(
+        &self,
+        puller_speed_controller: &PullerSpeedController,
+        winding_speed: Option<AngularVelocity>,
+    ) -> AngularVelocity {
+        let base_speed = winding_speed.unwrap_or_else(|| {
+            AngularVelocity::new::<radian_per_second>(
+                puller_speed_controller
+                    .get_line_speed()
+                    .get::<meter_per_second>()
+                    / self.speed_factor.get::<meter>(),
+            )
+        });
+        base_speed * self.max_speed_multiplier
     }
 
     /// Calculates the desired spool speed based on filament tension feedback.
@@ -171,12 +172,18 @@ impl AdaptiveSpoolSpeedController {
         t: Instant,
         tension_arm: &TensionArm,
         puller_speed_controller: &PullerSpeedController,
+        winding_speed: Option<AngularVelocity>,
     ) -> AngularVelocity {
         let min_speed = AngularVelocity::ZERO;
-        let max_speed = self.get_max_speed(puller_speed_controller).abs();
+        let max_speed = self
+            .get_max_speed(puller_speed_controller, winding_speed)
+            .abs();
 
         // Calculate filament tension from arm angle
-        let tension_arm_angle = tension_arm.get_angle().unwrap();
+        // Unreadable arm, the tension arm monitor stops winding
+        let Ok(tension_arm_angle) = tension_arm.get_angle() else {
+            return min_speed;
+        };
         let (clamped_angle, clamping_state) = clamp_revolution_uom(
             tension_arm_angle,
             self.filament_calc.get_max_angle(), // Inverted because min angle = max tension
@@ -211,10 +218,11 @@ impl AdaptiveSpoolSpeedController {
         &mut self,
         target_speed: AngularVelocity,
         puller_speed_controller: &PullerSpeedController,
+        winding_speed: Option<AngularVelocity>,
         t: Instant,
     ) -> AngularVelocity {
         let target_speed_rad_s = target_speed.get::<radian_per_second>();
-        let current_max_speed = self.get_max_speed(puller_speed_controller);
+        let current_max_speed = self.get_max_speed(puller_speed_controller, winding_speed);
         let max_speed_rad_s = current_max_speed.get::<radian_per_second>();
 
         // Base acceleration proportional to current max operating speed
@@ -313,6 +321,7 @@ impl AdaptiveSpoolSpeedController {
     /// - `t`: Current timestamp for time-based calculations
     /// - `tension_arm`: Reference to tension arm for feedback
     /// - `puller_speed_controller`: Reference for adaptive speed scaling
+    /// - `winding_speed`: Spool speed matching the line speed, from the diameter estimate
     ///
     /// # Returns
     /// Final commanded angular velocity for the spool motor
@@ -321,8 +330,10 @@ impl AdaptiveSpoolSpeedController {
         t: Instant,
         tension_arm: &TensionArm,
         puller_speed_controller: &PullerSpeedController,
+        winding_speed: Option<AngularVelocity>,
     ) -> AngularVelocity {
-        let target_speed = self.calculate_speed(t, tension_arm, puller_speed_controller);
+        let target_speed =
+            self.calculate_speed(t, tension_arm, puller_speed_controller, winding_speed);
 
         let enabled_speed = if self.enabled {
             target_speed
@@ -330,7 +341,8 @@ impl AdaptiveSpoolSpeedController {
             AngularVelocity::ZERO
         };
 
-        let accelerated_speed = self.accelerate_speed(enabled_speed, puller_speed_controller, t);
+        let accelerated_speed =
+            self.accelerate_speed(enabled_speed, puller_speed_controller, winding_speed, t);
 
         // Store speed before clamping to preserve the actual commanded value
         self.last_speed = accelerated_speed;
@@ -401,6 +413,10 @@ impl AdaptiveSpoolSpeedController {
         self.tension_target
     }
 
+    pub const fn get_filament_calc(&self) -> &FilamentTensionCalculator {
+        &self.filament_calc
+    }
+
     pub const fn set_tension_target(&mut self, tension_target: f64) {
         self.tension_target = tension_target.clamp(0.0, 1.0);
     }
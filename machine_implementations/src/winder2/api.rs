@@ -1,13 +1,21 @@
 mod winder2_imports {
-    pub use super::super::puller_speed_controller::{GearRatio, PullerRegulationMode};
+    pub use super::super::changeover::ChangeoverStep;
+    pub use super::super::geometry::Winder2Geometry;
+    pub use super::super::puller_speed_controller::PullerRegulationMode;
+    pub use super::super::ramp_profile::RampProfile;
+    pub use super::super::spool_profile::SpoolProfile;
+    pub use super::super::step_loss_detector::StepLossReaction;
+    pub use super::super::tension_arm_monitor::TensionArmFault;
+    pub use super::super::winding_pattern::WindingPattern;
     pub use super::super::{Winder2, Winder2Mode};
     pub use control_core::socketio::{
         event::{Event, GenericEvent},
         namespace::{
             CacheFn, CacheableEvents, Namespace, NamespaceCacheingLogic, cache_duration,
-            cache_first_and_last_event,
+            cache_first_and_last_event, cache_n_events,
         },
     };
+    pub use control_core::transmission::multi_stage::MultiStageTransmission;
 
     pub use serde::{Deserialize, Serialize};
     pub use serde_json::Value;
@@ -65,6 +73,22 @@ pub enum Mutation {
     SetTraverseStepSize(f64),
     /// Padding in mm for traverse movement limits
     SetTraversePadding(f64),
+    /// Standstill in s at the reversal points
+    SetTraverseDwellTime(f64),
+    /// Shift of the reversal points towards the flanks in mm
+    SetTraverseOvershootCompensation(f64),
+    SetTraverseWindingPattern(WindingPattern),
+    /// Crossing angle in degrees for random and stepped precision winding
+    SetTraverseCrossingAngle(f64),
+    /// Allowed drop of the crossing angle in degrees before stepped precision winding steps
+    SetTraverseCrossingAngleTolerance(f64),
+    /// Pitch modulation of wave winding in %
+    SetTraverseWaveAmplitude(f64),
+    /// Strokes per period of wave winding
+    SetTraverseWavePeriod(u32),
+    SetTraverseStepLossReaction(StepLossReaction),
+    /// Allowed difference in mm between the stepper counter and the commanded position
+    SetTraverseStepLossTolerance(f64),
     GotoTraverseLimitOuter,
     GotoTraverseLimitInner,
     /// Find home point
@@ -77,7 +101,24 @@ pub enum Mutation {
     SetPullerTargetSpeed(f64),
     SetPullerTargetDiameter(f64),
     SetPullerForward(bool),
-    SetPullerGearRatio(GearRatio),
+    /// Drivetrain from the puller stepper to the wheel
+    SetPullerTransmission(MultiStageTransmission),
+    /// Ramp up with a profile by name when the puller starts, `None` for the default limits
+    SelectPullerRampProfile(Option<String>),
+    /// Add or replace a custom ramp profile
+    SavePullerRampProfile(RampProfile),
+    DeletePullerRampProfile(String),
+    /// Pull at the threading speed instead of the target speed
+    SetPullerThreading(bool),
+    /// threading speed in m/min
+    SetPullerThreadingSpeed(f64),
+
+    // Length Metering
+    StartLengthCalibration,
+    /// Actually pulled length in m since the calibration started
+    FinishLengthCalibration(f64),
+    AbortLengthCalibration,
+    ResetLengthCalibration,
 
     // Spool Speed Controller
     SetSpoolRegulationMode(super::spool_speed_controller::SpoolSpeedControllerType),
@@ -92,13 +133,49 @@ pub enum Mutation {
     SetSpoolAdaptiveAccelerationFactor(f64),
     SetSpoolAdaptiveDeaccelerationUrgencyMultiplier(f64),
 
+    // Spool Diameter Estimation
+    /// core diameter in mm
+    SetSpoolCoreDiameter(f64),
+    /// full diameter in mm
+    SetSpoolFullDiameter(f64),
+    /// filament diameter in mm
+    SetSpoolFilamentDiameter(f64),
+    /// filament density in g/cm³
+    SetSpoolFilamentDensity(f64),
+    ResetSpoolDiameterEstimate,
+
+    // Spool Profiles
+    /// Set traverse limits, padding, diameters and required meters from a profile by name
+    SelectSpoolProfile(String),
+    /// Add or replace a custom profile
+    SaveSpoolProfile(SpoolProfile),
+    DeleteSpoolProfile(String),
+
     // Spool Auto Stop/Pull
     SetSpoolAutomaticRequiredMeters(f64),
     SetSpoolAutomaticAction(SpoolAutomaticActionMode),
     ResetSpoolProgress,
 
+    // Spool Job
+    StartSpoolJob,
+    FinishSpoolJob,
+
+    // Spool Changeover
+    StartChangeover,
+    ConfirmSpoolMounted,
+    AbortChangeover,
+    /// puller speed in m/min
+    SetChangeoverPullerSpeed(f64),
+    SetChangeoverRezeroTensionArm(bool),
+    SetChangeoverRehomeTraverse(bool),
+
+    // Geometry
+    /// Persisted as override of the machine profile, applies when the machine is created again
+    SetGeometry(Winder2Geometry),
+
     // Tension Arm
     ZeroTensionArmAngle,
+    ClearTensionArmFault,
 
     // Mode
     SetMode(Mode),
@@ -113,6 +190,14 @@ pub enum Mutation {
     /// Inner deadzone: max deviation from target (mm) that requires no correction
     SetPullerAdaptiveAcceptedDifference(f64),
     SetPullerAdaptiveReferenceMachine(Option<QiTechMachineIdentificationUnique>),
+
+    // Predictive puller regulation
+    /// Line length from the extruder to the laser in m
+    SetPullerPredictiveExtruderToLaser(f64),
+    /// Line length from the laser to the puller in m
+    SetPullerPredictiveLaserToPuller(f64),
+    SetPullerPredictiveProportionalGain(f64),
+    SetPullerPredictiveIntegralGain(f64),
 }
 
 #[derive(Serialize, Debug, Clone, Default)]
@@ -127,6 +212,24 @@ pub struct LiveValuesEvent {
     pub tension_arm_angle: f64,
     // spool progress in meters (pulled distance of filament)
     pub spool_progress: f64,
+    /// estimated wound spool diameter in mm
+    pub spool_diameter: f64,
+    /// standard deviation of the spool diameter in mm
+    pub spool_diameter_uncertainty: f64,
+    /// wound filament mass in g
+    pub filament_mass: f64,
+    /// filament length until the spool is full in m
+    pub remaining_capacity: Option<f64>,
+    /// time until the spool is full in s
+    pub time_to_full: Option<f64>,
+    /// diameter being drawn at the extruder predicted from the laser in mm
+    pub predicted_diameter: Option<f64>,
+    /// diameter of the filament arriving at the puller in mm
+    pub puller_diameter: Option<f64>,
+    /// the puller ramps up with the selected ramp profile
+    pub puller_ramping: bool,
+    /// the ramp waits for the spool to catch up
+    pub puller_ramp_held: bool,
 }
 
 impl LiveValuesEvent {
@@ -150,6 +253,20 @@ pub struct StateEvent {
     pub tension_arm_state: TensionArmState,
     /// spool speed controller state
     pub spool_speed_controller_state: SpoolSpeedControllerState,
+    /// spool diameter estimation state
+    pub spool_diameter_state: SpoolDiameterState,
+    /// spool profile library and selection
+    pub spool_profile_state: SpoolProfileState,
+    /// spool job state
+    pub spool_job_state: SpoolJobState,
+    /// spool changeover state
+    pub changeover_state: ChangeoverState,
+    /// length metering state
+    pub length_meter_state: LengthMeterState,
+    /// active mechanics of this winder
+    pub geometry: Winder2Geometry,
+    /// saved mechanics that apply after the machine is restarted
+    pub pending_geometry: Option<Winder2Geometry>,
     /// Is a Machine Connected?
     pub puller_reference_machine: Option<QiTechMachineIdentificationUnique>,
 }
@@ -186,6 +303,28 @@ pub struct TraverseState {
     pub step_size: f64,
     /// padding in mm
     pub padding: f64,
+    /// dwell time at the reversal points in s
+    pub dwell_time: f64,
+    /// shift of the reversal points towards the flanks in mm
+    pub overshoot_compensation: f64,
+    /// lay pattern
+    pub winding_pattern: WindingPattern,
+    /// current pitch per spool revolution in mm
+    pub pitch: f64,
+    /// crossing angle in degrees
+    pub crossing_angle: f64,
+    /// crossing angle tolerance of stepped precision winding in degrees
+    pub crossing_angle_tolerance: f64,
+    /// wave winding pitch modulation in %
+    pub wave_amplitude: f64,
+    /// wave winding period in strokes
+    pub wave_period: u32,
+    /// reaction to lost steps
+    pub step_loss_reaction: StepLossReaction,
+    /// allowed distance of the stepper counter from home when the endstop switches in mm
+    pub step_loss_tolerance: f64,
+    /// counter distance from home in mm when lost steps were detected, cleared by homing
+    pub step_loss_deviation: Option<f64>,
     /// can go in (to inner limit)
     pub can_go_in: bool,
     /// can go out (to outer limit)
@@ -202,8 +341,16 @@ pub struct PullerState {
     pub target_speed: f64,
     /// forward rotation direction
     pub forward: bool,
-    /// gear ratio for winding speed
-    pub gear_ratio: GearRatio,
+    /// drivetrain from the puller stepper to the wheel
+    pub transmission: MultiStageTransmission,
+    /// built-in ramp profiles followed by the custom ones
+    pub ramp_profiles: Vec<RampProfile>,
+    /// name of the selected ramp profile, `None` uses the default limits
+    pub ramp_profile: Option<String>,
+    /// pulling at the threading speed
+    pub threading: bool,
+    /// threading speed in m/min
+    pub threading_speed: f64,
 
     /// Maximum speed change as a percentage of base speed (0.0–100.0)
     pub adaptive_speed_delta_max: f64,
@@ -213,6 +360,14 @@ pub struct PullerState {
     pub adaptive_change_per_step: f64,
     /// Inner deadzone: max deviation from target (mm) that requires no correction
     pub allowed_diameter_deviation: f64,
+    /// Line length from the extruder to the laser in m
+    pub predictive_extruder_to_laser: f64,
+    /// Line length from the laser to the puller in m
+    pub predictive_laser_to_puller: f64,
+    /// Speed change per relative cross-section error
+    pub predictive_proportional_gain: f64,
+    /// Speed change per relative cross-section error and meter pulled
+    pub predictive_integral_gain: f64,
     pub adaptive_reference_machine: Option<QiTechMachineIdentificationUnique>,
 }
 
@@ -222,6 +377,8 @@ pub enum SpoolAutomaticActionMode {
     NoAction,
     Pull,
     Hold,
+    /// Run the spool changeover sequence
+    Changeover,
 }
 
 #[derive(Serialize, Debug, Clone, Default)]
@@ -242,6 +399,8 @@ pub struct ModeState {
 pub struct TensionArmState {
     /// is zeroed
     pub zeroed: bool,
+    /// latched fault, winding is blocked until it is cleared
+    pub fault: Option<TensionArmFault>,
 }
 
 #[derive(Serialize, Debug, Clone, Default)]
@@ -266,9 +425,83 @@ pub struct SpoolSpeedControllerState {
     pub forward: bool,
 }
 
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ChangeoverState {
+    /// current step of the sequence
+    pub step: ChangeoverStep,
+    /// puller speed during the changeover in m/min
+    pub puller_speed: f64,
+    /// re-zero the tension arm after the spool is mounted
+    pub rezero_tension_arm: bool,
+    /// re-home the traverse after the spool is mounted
+    pub rehome_traverse: bool,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct LengthMeterState {
+    /// calibrated correction factor of the metered length
+    pub correction_factor: f64,
+    /// a calibration run is in progress
+    pub is_calibrating: bool,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct SpoolProfileState {
+    /// built-in profiles followed by the custom ones
+    pub profiles: Vec<SpoolProfile>,
+    /// name of the last selected profile
+    pub selected: Option<String>,
+    /// filament length the selected profile holds with the current filament in m
+    pub capacity: Option<f64>,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct SpoolJobState {
+    /// start of the running job in ms since the unix epoch
+    pub started_at: Option<u64>,
+    /// id of the last finished spool report
+    pub last_report_id: Option<u64>,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct SpoolDiameterState {
+    /// empty spool core diameter in mm
+    pub core_diameter: f64,
+    /// diameter of a full spool in mm
+    pub full_diameter: f64,
+    /// filament diameter in mm
+    pub filament_diameter: f64,
+    /// filament density in g/cm³
+    pub filament_density: f64,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Winder2Alarm {
+    /// The spool is full, the operator has to mount a new one
+    SpoolChangeover,
+    /// Winding was stopped by the tension arm supervision
+    TensionArm(TensionArmFault),
+    /// The traverse lost steps and has to be re-homed
+    TraverseStepLoss,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct AlarmEvent {
+    pub alarm: Winder2Alarm,
+    /// raised or cleared
+    pub active: bool,
+}
+
+impl AlarmEvent {
+    pub fn build(&self) -> Event<Self> {
+        Event::new("AlarmEvent", self.clone())
+    }
+}
+
 pub enum Winder2Events {
     LiveValues(Event<LiveValuesEvent>),
     State(Event<StateEvent>),
+    Alarm(Event<AlarmEvent>),
 }
 
 #[derive(Debug)]
@@ -293,6 +526,7 @@ impl CacheableEvents<Self> for Winder2Events {
         match self {
             Self::LiveValues(event) => event.into(),
             Self::State(event) => event.into(),
+            Self::Alarm(event) => event.into(),
         }
     }
 
@@ -301,6 +535,7 @@ impl CacheableEvents<Self> for Winder2Events {
         match self {
             Self::LiveValues(_) => cache_first_and_last,
             Self::State(_) => cache_first_and_last,
+            Self::Alarm(_) => cache_n_events(10),
         }
     }
 }
@@ -314,11 +549,36 @@ impl MachineApi for Winder2 {
         let mutation: Mutation = serde_json::from_value(request_body)?;
         match mutation {
             Mutation::EnableTraverseLaserpointer(enable) => self.set_laser(enable),
-            Mutation::SetMode(mode) => self.set_mode(&mode.into()),
+            Mutation::SetMode(mode) => {
+                // A manual mode change takes over from the changeover sequence
+                self.changeover_abort();
+                self.set_mode(&mode.into())
+            }
             Mutation::SetTraverseLimitOuter(limit) => self.traverse_set_limit_outer(limit),
             Mutation::SetTraverseLimitInner(limit) => self.traverse_set_limit_inner(limit),
             Mutation::SetTraverseStepSize(size) => self.traverse_set_step_size(size),
             Mutation::SetTraversePadding(padding) => self.traverse_set_padding(padding),
+            Mutation::SetTraverseDwellTime(dwell_time) => self.traverse_set_dwell_time(dwell_time),
+            Mutation::SetTraverseOvershootCompensation(compensation) => {
+                self.traverse_set_overshoot_compensation(compensation)
+            }
+            Mutation::SetTraverseWindingPattern(pattern) => {
+                self.traverse_set_winding_pattern(pattern)
+            }
+            Mutation::SetTraverseCrossingAngle(angle) => self.traverse_set_crossing_angle(angle)?,
+            Mutation::SetTraverseCrossingAngleTolerance(tolerance) => {
+                self.traverse_set_crossing_angle_tolerance(tolerance)?
+            }
+            Mutation::SetTraverseWaveAmplitude(amplitude) => {
+                self.traverse_set_wave_amplitude(amplitude)?
+            }
+            Mutation::SetTraverseWavePeriod(period) => self.traverse_set_wave_period(period),
+            Mutation::SetTraverseStepLossReaction(reaction) => {
+                self.traverse_set_step_loss_reaction(reaction)
+            }
+            Mutation::SetTraverseStepLossTolerance(tolerance) => {
+                self.traverse_set_step_loss_tolerance(tolerance)
+            }
             Mutation::GotoTraverseLimitOuter => self.traverse_goto_limit_outer(),
             Mutation::GotoTraverseLimitInner => self.traverse_goto_limit_inner(),
             Mutation::GotoTraverseHome => self.traverse_goto_home(),
@@ -326,7 +586,22 @@ impl MachineApi for Winder2 {
             Mutation::SetPullerTargetSpeed(value) => self.puller_set_target_speed(value),
             Mutation::SetPullerTargetDiameter(_) => todo!(),
             Mutation::SetPullerForward(value) => self.puller_set_forward(value),
-            Mutation::SetPullerGearRatio(gear_ratio) => self.puller_set_gear_ratio(gear_ratio),
+            Mutation::SetPullerTransmission(transmission) => {
+                self.puller_set_transmission(transmission)?
+            }
+            Mutation::SelectPullerRampProfile(name) => {
+                self.puller_select_ramp_profile(name.as_deref())?
+            }
+            Mutation::SavePullerRampProfile(profile) => self.puller_save_ramp_profile(profile)?,
+            Mutation::DeletePullerRampProfile(name) => self.puller_delete_ramp_profile(&name)?,
+            Mutation::SetPullerThreading(threading) => self.puller_set_threading(threading),
+            Mutation::SetPullerThreadingSpeed(speed) => self.puller_set_threading_speed(speed)?,
+            Mutation::StartLengthCalibration => self.length_meter_start_calibration(),
+            Mutation::FinishLengthCalibration(length) => {
+                self.length_meter_finish_calibration(length)?
+            }
+            Mutation::AbortLengthCalibration => self.length_meter_abort_calibration(),
+            Mutation::ResetLengthCalibration => self.length_meter_reset_calibration(),
             Mutation::SetSpoolRegulationMode(mode) => self.spool_set_regulation_mode(mode),
             Mutation::SetSpoolMinMaxMinSpeed(speed) => self.spool_set_minmax_min_speed(speed),
             Mutation::SetSpoolMinMaxMaxSpeed(speed) => self.spool_set_minmax_max_speed(speed),
@@ -346,12 +621,34 @@ impl MachineApi for Winder2 {
             Mutation::SetSpoolAdaptiveDeaccelerationUrgencyMultiplier(value) => {
                 self.spool_set_adaptive_deacceleration_urgency_multiplier(value)
             }
+            Mutation::SetSpoolCoreDiameter(value) => self.spool_set_core_diameter(value),
+            Mutation::SetSpoolFullDiameter(value) => self.spool_set_full_diameter(value),
+            Mutation::SetSpoolFilamentDiameter(value) => self.spool_set_filament_diameter(value),
+            Mutation::SetSpoolFilamentDensity(value) => self.spool_set_filament_density(value),
+            Mutation::ResetSpoolDiameterEstimate => self.spool_reset_diameter_estimate(),
+            Mutation::SelectSpoolProfile(name) => self.spool_select_profile(&name)?,
+            Mutation::SaveSpoolProfile(profile) => self.spool_save_profile(profile)?,
+            Mutation::DeleteSpoolProfile(name) => self.spool_delete_profile(&name)?,
             Mutation::SetSpoolAutomaticRequiredMeters(meters) => {
                 self.set_spool_automatic_required_meters(meters)
             }
             Mutation::SetSpoolAutomaticAction(mode) => self.set_spool_automatic_mode(mode),
             Mutation::ResetSpoolProgress => self.stop_or_pull_spool_reset(Instant::now()),
+            Mutation::StartSpoolJob => self.spool_start_job(Instant::now()),
+            Mutation::FinishSpoolJob => self.spool_finish_job(Instant::now()),
+            Mutation::StartChangeover => self.changeover_start(Instant::now()),
+            Mutation::ConfirmSpoolMounted => self.changeover_confirm_spool_mounted(),
+            Mutation::AbortChangeover => self.changeover_abort(),
+            Mutation::SetChangeoverPullerSpeed(speed) => self.changeover_set_puller_speed(speed)?,
+            Mutation::SetChangeoverRezeroTensionArm(rezero) => {
+                self.changeover_set_rezero_tension_arm(rezero)
+            }
+            Mutation::SetChangeoverRehomeTraverse(rehome) => {
+                self.changeover_set_rehome_traverse(rehome)
+            }
+            Mutation::SetGeometry(geometry) => self.set_geometry(geometry)?,
             Mutation::ZeroTensionArmAngle => self.tension_arm_zero(),
+            Mutation::ClearTensionArmFault => self.tension_arm_clear_fault(),
 
             // puller adaptive speed algorithm
             Mutation::SetPullerAdaptiveMaxSpeedChangePercent(v) => {
@@ -367,6 +664,20 @@ impl MachineApi for Winder2 {
             Mutation::SetPullerAdaptiveReferenceMachine(v) => {
                 self.puller_set_adaptive_reference_machine(v)?
             }
+
+            // predictive puller regulation
+            Mutation::SetPullerPredictiveExtruderToLaser(v) => {
+                self.puller_set_predictive_extruder_to_laser(v)?
+            }
+            Mutation::SetPullerPredictiveLaserToPuller(v) => {
+                self.puller_set_predictive_laser_to_puller(v)?
+            }
+            Mutation::SetPullerPredictiveProportionalGain(v) => {
+                self.puller_set_predictive_proportional_gain(v)?
+            }
+            Mutation::SetPullerPredictiveIntegralGain(v) => {
+                self.puller_set_predictive_integral_gain(v)?
+            }
         }
         Ok(())
     }
@@ -0,0 +1,159 @@
+use qitech_lib::units::f64::Velocity;
+use qitech_lib::units::velocity::meter_per_minute;
+use serde::{Deserialize, Serialize};
+
+/// Step of the automatic spool changeover
+///
+/// The steps run in order, only [`ChangeoverStep::WaitingForSpool`] needs the operator.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChangeoverStep {
+    /// No changeover in progress
+    #[default]
+    Idle,
+    /// Puller slows down to the changeover speed
+    SlowingPuller,
+    /// Traverse moves to the outer limit
+    ParkingTraverse,
+    /// Spool comes to a standstill
+    StoppingSpool,
+    /// Operator is signalled and mounts a new spool
+    WaitingForSpool,
+    /// Tension arm is re-zeroed and the traverse re-homed
+    Rehoming,
+    /// Winding restarts while the puller ramps back up to its target speed
+    RampingUp,
+}
+
+/// Sequence that swaps a full spool for an empty one without stopping the line
+#[derive(Debug)]
+pub struct Changeover {
+    step: ChangeoverStep,
+    /// Puller speed while the spool is swapped
+    puller_speed: Velocity,
+    /// Re-zero the tension arm once the new spool is mounted
+    rezero_tension_arm: bool,
+    /// Re-home the traverse once the new spool is mounted
+    rehome_traverse: bool,
+    /// A spool job was running when the changeover started
+    resume_spool_job: bool,
+}
+
+impl Default for Changeover {
+    fn default() -> Self {
+        Self {
+            step: ChangeoverStep::Idle,
+            puller_speed: Velocity::new::<meter_per_minute>(2.0),
+            rezero_tension_arm: true,
+            rehome_traverse: true,
+            resume_spool_job: false,
+        }
+    }
+}
+
+impl Changeover {
+    /// Start the sequence, does nothing while one is running
+    ///
+    /// Returns if the sequence was started.
+    pub const fn start(&mut self, resume_spool_job: bool) -> bool {
+        if self.is_active() {
+            return false;
+        }
+        self.resume_spool_job = resume_spool_job;
+        self.advance(ChangeoverStep::SlowingPuller);
+        true
+    }
+
+    pub const fn advance(&mut self, step: ChangeoverStep) {
+        self.step = step;
+    }
+
+    /// The operator mounted a new spool
+    ///
+    /// Returns if the sequence was waiting for it.
+    pub const fn confirm_spool_mounted(&mut self) -> bool {
+        if !self.is_waiting_for_spool() {
+            return false;
+        }
+        self.advance(ChangeoverStep::Rehoming);
+        true
+    }
+
+    /// Back to idle, after the sequence completed or was aborted
+    pub const fn reset(&mut self) {
+        self.step = ChangeoverStep::Idle;
+        self.resume_spool_job = false;
+    }
+
+    /// Puller runs at the changeover speed, `line_speed` is the actual material speed
+    pub fn is_puller_slow(&self, line_speed: Velocity) -> bool {
+        let tolerance = Velocity::new::<meter_per_minute>(0.05);
+        line_speed.abs() <= self.puller_speed + tolerance
+    }
+
+    pub const fn is_active(&self) -> bool {
+        !matches!(self.step, ChangeoverStep::Idle)
+    }
+
+    pub const fn is_waiting_for_spool(&self) -> bool {
+        matches!(self.step, ChangeoverStep::WaitingForSpool)
+    }
+}
+
+// getters + setters
+impl Changeover {
+    pub const fn get_step(&self) -> ChangeoverStep {
+        self.step
+    }
+
+    pub fn get_puller_speed(&self) -> Velocity {
+        self.puller_speed
+    }
+
+    pub fn set_puller_speed(&mut self, speed: Velocity) {
+        self.puller_speed = speed.abs();
+    }
+
+    pub const fn get_rezero_tension_arm(&self) -> bool {
+        self.rezero_tension_arm
+    }
+
+    pub const fn set_rezero_tension_arm(&mut self, rezero: bool) {
+        self.rezero_tension_arm = rezero;
+    }
+
+    pub const fn get_rehome_traverse(&self) -> bool {
+        self.rehome_traverse
+    }
+
+    pub const fn set_rehome_traverse(&mut self, rehome: bool) {
+        self.rehome_traverse = rehome;
+    }
+
+    pub const fn get_resume_spool_job(&self) -> bool {
+        self.resume_spool_job
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confirmation_only_while_waiting() {
+        let mut changeover = Changeover::default();
+        assert!(!changeover.confirm_spool_mounted());
+
+        assert!(changeover.start(true));
+        assert!(!changeover.start(false));
+        assert!(changeover.get_resume_spool_job());
+        assert!(!changeover.confirm_spool_mounted());
+
+        changeover.advance(ChangeoverStep::WaitingForSpool);
+        assert!(changeover.confirm_spool_mounted());
+        assert_eq!(changeover.get_step(), ChangeoverStep::Rehoming);
+
+        changeover.reset();
+        assert!(!changeover.is_active());
+        assert!(!changeover.get_resume_spool_job());
+    }
+}
@@ -0,0 +1,96 @@
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::machine_identification::QiTechMachineIdentificationUnique;
+use crate::persist::data_dir;
+
+/// Configuration of one winder, stored as one JSON file per kind of configuration
+#[derive(Debug, Clone)]
+pub struct ConfigStore {
+    path: PathBuf,
+}
+
+impl ConfigStore {
+    /// `name` is the kind of configuration, e.g. `length_calibration`
+    pub fn new(name: &str, machine: &QiTechMachineIdentificationUnique) -> Self {
+        Self::with_dir(data_dir().join(name), machine)
+    }
+
+    pub fn with_dir(base: PathBuf, machine: &QiTechMachineIdentificationUnique) -> Self {
+        let name = format!(
+            "{}_{}.json",
+            machine.machine_identification.slug(),
+            machine.serial
+        );
+        Self {
+            path: base.join(name),
+        }
+    }
+
+    /// `None` if nothing was stored yet
+    pub fn load<T: DeserializeOwned>(&self) -> Result<Option<T>> {
+        if !fs::exists(&self.path)? {
+            return Ok(None);
+        }
+        let json = fs::read_to_string(&self.path)?;
+        let config = serde_json::from_str(&json).with_context(|| {
+            format!(
+                "[{}::ConfigStore::load] Can't parse {}",
+                module_path!(),
+                self.path.display()
+            )
+        })?;
+        Ok(Some(config))
+    }
+
+    pub fn save<T: Serialize>(&self, config: &T) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).with_context(|| {
+                format!(
+                    "[{}::ConfigStore::save] Can't create {}",
+                    module_path!(),
+                    dir.display()
+                )
+            })?;
+        }
+        let json = serde_json::to_string_pretty(config)?;
+        fs::write(&self.path, json)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::machine_identification::MachineIdentification;
+    use crate::{MACHINE_WINDER_V1, VENDOR_QITECH};
+    use serde::Deserialize;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Config {
+        value: f64,
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let base = std::env::temp_dir().join(format!("config_store_{}", std::process::id()));
+        let machine = QiTechMachineIdentificationUnique {
+            machine_identification: MachineIdentification {
+                vendor: VENDOR_QITECH,
+                machine: MACHINE_WINDER_V1,
+            },
+            serial: 7,
+        };
+        let store = ConfigStore::with_dir(base.clone(), &machine);
+        assert_eq!(store.load::<Config>().unwrap(), None);
+
+        store.save(&Config { value: 1.05 }).unwrap();
+        assert_eq!(store.load().unwrap(), Some(Config { value: 1.05 }));
+
+        fs::remove_dir_all(base).unwrap();
+    }
+}
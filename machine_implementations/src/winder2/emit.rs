@@ -3,15 +3,24 @@ use crate::machine_identification::QiTechMachineIdentificationUnique;
 use crate::winder2::Winder2Mode;
 
 pub use super::api::{
-    LiveValuesEvent, ModeState, PullerState, SpoolAutomaticActionMode, SpoolAutomaticActionState,
-    SpoolSpeedControllerState, StateEvent, TensionArmState, TraverseState, Winder2Events,
+    AlarmEvent, ChangeoverState, ChangeoverStep, LengthMeterState, LiveValuesEvent, ModeState,
+    MultiStageTransmission, PullerState, RampProfile, SpoolAutomaticActionMode,
+    SpoolAutomaticActionState, SpoolDiameterState, SpoolJobState, SpoolProfile, SpoolProfileState,
+    SpoolSpeedControllerState, StateEvent, StepLossReaction, TensionArmState, TraverseState,
+    Winder2Alarm, Winder2Events, Winder2Geometry, WindingPattern,
 };
+use super::config_store::ConfigStore;
+use super::length_meter::LengthCalibration;
+use super::ramp_profile::RampProfileSelection;
+use super::spool_job::SpoolJob;
+use super::spool_profile::SpoolProfileFit;
 use super::{
-    LASER_PORT, PULLER_PORT, SPOOL_PORT, TRAVERSE_PORT, TraverseMode, Winder2,
-    api::PullerRegulationMode, spool_speed_controller,
+    CHANGEOVER_SIGNAL_PORT, LASER_PORT, PULLER_PORT, SPOOL_PORT, TRAVERSE_PORT, TraverseMode,
+    Winder2, api::PullerRegulationMode, spool_speed_controller,
 };
 pub use control_core::socketio::event::BuildEvent;
 pub use control_core::socketio::namespace::NamespaceCacheingLogic;
+use control_core::transmission::Transmission;
 use qitech_lib::ethercat_hal::io::digital_output::DigitalOutputDevice;
 #[cfg(not(feature = "mock-machine"))]
 pub use qitech_lib::units::{
@@ -21,19 +30,31 @@ pub use qitech_lib::units::{
     length::{meter, millimeter},
 };
 use std::cell::RefMut;
-pub use std::time::Instant;
+pub use std::time::{Duration, Instant};
 
 pub use qitech_lib::units::Velocity;
 pub use qitech_lib::units::velocity::meter_per_minute;
 
+/// Name of the persisted length calibration
+const LENGTH_CALIBRATION_CONFIG: &str = "length_calibration";
+
+/// Name of the persisted ramp profile selection
+const RAMP_PROFILE_SELECTION_CONFIG: &str = "ramp_profile_selection";
+
+/// Below this the spool counts as stopped during a changeover
+const SPOOL_STOPPED_RPM: f64 = 0.5;
+
 impl Winder2 {
     /// Implement Spool
     /// called by `act`
     pub fn sync_spool_speed(&mut self, t: Instant) {
+        self.update_spool_diameter_estimate(t);
+
         let angular_velocity = self.spool_speed_controller.update_speed(
             t,
             &self.tension_arm,
             &self.puller_speed_controller,
+            self.spool_diameter_estimator.get_confident_radius(),
         );
 
         // Apply direction based on forward setting
@@ -43,13 +64,21 @@ impl Winder2 {
             -angular_velocity
         };
 
-        let steps_per_second = self
-            .spool_step_converter
-            .angular_velocity_to_steps(directed_angular_velocity);
+        let steps_per_second = self.spool_speed_to_steps(directed_angular_velocity);
         let spool_ref = &mut *self.spool.borrow_mut();
         let _ = spool_ref.set_speed(SPOOL_PORT, steps_per_second);
     }
 
+    /// Feed the measured line and spool speed into the diameter estimate
+    fn update_spool_diameter_estimate(&mut self, t: Instant) {
+        let line_speed = self.puller_speed_controller.get_line_speed();
+        let steps_per_second = self.spool.borrow_mut().get_speed(SPOOL_PORT) as f64;
+        let spool_speed = self.steps_to_spool_speed(steps_per_second);
+        let arm_angle = self.tension_arm.get_angle().ok();
+        self.spool_diameter_estimator
+            .update(line_speed, spool_speed, arm_angle, t);
+    }
+
     pub fn stop_or_pull_spool(&mut self, now: Instant) {
         if matches!(
             self.spool_automatic_action.mode,
@@ -63,7 +92,8 @@ impl Winder2 {
             Winder2Mode::Pull => self.calculate_spool_auto_progress_(now),
             Winder2Mode::Wind => self.calculate_spool_auto_progress_(now),
             _ => {
-                self.spool_automatic_action.progress_last_check = now;
+                // Keep the length meter in sync without counting progress
+                self.meter_length(now);
                 return;
             }
         }
@@ -72,16 +102,109 @@ impl Winder2 {
             match self.spool_automatic_action.mode {
                 SpoolAutomaticActionMode::NoAction => (),
                 SpoolAutomaticActionMode::Pull => {
+                    self.spool_finish_job(now);
                     self.stop_or_pull_spool_reset(now);
                     self.set_mode(&Winder2Mode::Pull);
                 }
                 SpoolAutomaticActionMode::Hold => {
+                    self.spool_finish_job(now);
                     self.stop_or_pull_spool_reset(now);
                     self.set_mode(&Winder2Mode::Hold);
                 }
+                SpoolAutomaticActionMode::Changeover => {
+                    self.changeover_start(now);
+                    self.stop_or_pull_spool_reset(now);
+                }
             }
         }
     }
+    /// Advance the spool changeover
+    /// called by `act`
+    pub fn sync_changeover(&mut self, now: Instant) {
+        let step = self.changeover.get_step();
+        match step {
+            ChangeoverStep::Idle | ChangeoverStep::WaitingForSpool => {}
+            ChangeoverStep::SlowingPuller => {
+                self.puller_speed_controller
+                    .set_speed_limit(Some(self.changeover.get_puller_speed()));
+                let line_speed = self.puller_speed_controller.get_line_speed();
+                if self.changeover.is_puller_slow(line_speed) {
+                    self.traverse_controller.goto_limit_outer();
+                    self.changeover.advance(ChangeoverStep::ParkingTraverse);
+                }
+            }
+            ChangeoverStep::ParkingTraverse => {
+                if !self.traverse_controller.is_going_out() {
+                    // Stop the spool while the puller keeps pulling. The traverse stays parked,
+                    // a regular transition into hold would send it home.
+                    self.mode = Winder2Mode::Pull;
+                    self.set_spool_mode(&Winder2Mode::Pull);
+                    self.traverse_mode = TraverseMode::Hold;
+                    self.changeover.advance(ChangeoverStep::StoppingSpool);
+                }
+            }
+            ChangeoverStep::StoppingSpool => {
+                let spool_speed = self.spool_speed_controller.get_speed();
+                if spool_speed.abs().get::<revolution_per_minute>() < SPOOL_STOPPED_RPM {
+                    self.set_changeover_signal(true);
+                    self.changeover.advance(ChangeoverStep::WaitingForSpool);
+                }
+            }
+            ChangeoverStep::Rehoming => {
+                if self.can_wind() {
+                    // New spool, start counting and estimating from scratch
+                    match self.changeover.get_resume_spool_job() {
+                        true => self.spool_start_job(now),
+                        false => {
+                            self.stop_or_pull_spool_reset(now);
+                            self.spool_diameter_estimator.reset();
+                        }
+                    }
+                    self.puller_speed_controller.set_speed_limit(None);
+                    self.set_mode(&Winder2Mode::Wind);
+                    self.changeover.advance(ChangeoverStep::RampingUp);
+                }
+            }
+            ChangeoverStep::RampingUp => {
+                if self.puller_speed_controller.is_at_speed() {
+                    self.changeover.reset();
+                }
+            }
+        }
+
+        if self.changeover.get_step() != step {
+            self.emit_state();
+        }
+    }
+
+    /// Stop winding when the tension arm faults or the filament breaks
+    /// called by `act`
+    pub fn supervise_tension_arm(&mut self, now: Instant) {
+        if self.mode != Winder2Mode::Wind {
+            self.tension_arm_monitor.reset();
+            return;
+        }
+
+        let volts = self.tension_arm.get_volts().ok();
+        let angle = self.tension_arm.get_angle().ok();
+        let spool_speed = self.spool_speed_controller.get_speed();
+        let Some(fault) = self
+            .tension_arm_monitor
+            .update(volts, angle, spool_speed, now)
+        else {
+            return;
+        };
+
+        tracing::error!(
+            "[{}::Winder2::supervise_tension_arm] {:?}, stopping",
+            module_path!(),
+            fault
+        );
+        self.changeover_abort();
+        self.set_mode(&Winder2Mode::Hold);
+        self.emit_alarm(Winder2Alarm::TensionArm(fault), true);
+    }
+
     /// Implement Mode
     pub fn set_mode(&mut self, mode: &Winder2Mode) {
         let should_update = *mode != Winder2Mode::Wind || self.can_wind();
@@ -111,6 +234,19 @@ impl Winder2 {
         self.emit_state();
     }
 
+    /// Signal the operator through the digital output and the alarm stream
+    fn set_changeover_signal(&mut self, active: bool) {
+        let mut output = self.get_laser();
+        output.set_output(CHANGEOVER_SIGNAL_PORT, active);
+        drop(output);
+        self.emit_alarm(Winder2Alarm::SpoolChangeover, active);
+    }
+
+    pub fn emit_alarm(&mut self, alarm: Winder2Alarm, active: bool) {
+        let event = AlarmEvent { alarm, active }.build();
+        self.namespace.emit(Winder2Events::Alarm(event));
+    }
+
     pub fn traverse_set_limit_inner(&mut self, limit: f64) {
         let new_inner = Length::new::<millimeter>(limit);
         let current_outer = self.traverse_controller.get_limit_outer();
@@ -121,6 +257,7 @@ impl Winder2 {
             return;
         }
         self.traverse_controller.set_limit_inner(new_inner);
+        self.spool_profile = None;
         self.emit_state();
     }
 
@@ -135,6 +272,7 @@ impl Winder2 {
         }
 
         self.traverse_controller.set_limit_outer(new_outer);
+        self.spool_profile = None;
         self.emit_state();
     }
 
@@ -147,9 +285,139 @@ impl Winder2 {
     pub fn traverse_set_padding(&mut self, padding: f64) {
         let padding = Length::new::<millimeter>(padding);
         self.traverse_controller.set_padding(padding);
+        self.spool_profile = None;
         self.emit_state();
     }
 
+    pub fn traverse_set_dwell_time(&mut self, dwell_time: f64) {
+        // Reject negative or non-finite values, keep the current value
+        let Ok(dwell_time) = Duration::try_from_secs_f64(dwell_time) else {
+            return;
+        };
+        self.traverse_controller.set_dwell_time(dwell_time);
+        self.emit_state();
+    }
+
+    pub fn traverse_set_overshoot_compensation(&mut self, compensation: f64) {
+        if !compensation.is_finite() {
+            return;
+        }
+        let compensation = Length::new::<millimeter>(compensation);
+        self.traverse_controller
+            .set_overshoot_compensation(compensation);
+        self.emit_state();
+    }
+
+    pub fn traverse_set_winding_pattern(&mut self, pattern: WindingPattern) {
+        self.traverse_controller
+            .get_winding_pattern_mut()
+            .set_pattern(pattern);
+        self.emit_state();
+    }
+
+    pub fn traverse_set_crossing_angle(&mut self, angle: f64) -> Result<(), anyhow::Error> {
+        // A crossing angle of 90° would need an infinite traverse speed
+        if !(angle > 0.0 && angle < 90.0) {
+            return Err(anyhow::anyhow!(
+                "[{}::Winder2::traverse_set_crossing_angle] Invalid crossing angle {}°",
+                module_path!(),
+                angle
+            ));
+        }
+        self.traverse_controller
+            .get_winding_pattern_mut()
+            .set_crossing_angle(Angle::new::<degree>(angle));
+        self.emit_state();
+        Ok(())
+    }
+
+    pub fn traverse_set_crossing_angle_tolerance(
+        &mut self,
+        tolerance: f64,
+    ) -> Result<(), anyhow::Error> {
+        if !(0.0..90.0).contains(&tolerance) {
+            return Err(anyhow::anyhow!(
+                "[{}::Winder2::traverse_set_crossing_angle_tolerance] Invalid tolerance {}°",
+                module_path!(),
+                tolerance
+            ));
+        }
+        self.traverse_controller
+            .get_winding_pattern_mut()
+            .set_crossing_angle_tolerance(Angle::new::<degree>(tolerance));
+        self.emit_state();
+        Ok(())
+    }
+
+    pub fn traverse_set_wave_amplitude(&mut self, amplitude: f64) -> Result<(), anyhow::Error> {
+        if !amplitude.is_finite() {
+            return Err(anyhow::anyhow!(
+                "[{}::Winder2::traverse_set_wave_amplitude] Invalid amplitude {} %",
+                module_path!(),
+                amplitude
+            ));
+        }
+        // Convert percentage to fraction
+        self.traverse_controller
+            .get_winding_pattern_mut()
+            .set_wave_amplitude(amplitude / 100.0);
+        self.emit_state();
+        Ok(())
+    }
+
+    pub fn traverse_set_wave_period(&mut self, period: u32) {
+        self.traverse_controller
+            .get_winding_pattern_mut()
+            .set_wave_period(period);
+        self.emit_state();
+    }
+
+    pub fn traverse_set_step_loss_reaction(&mut self, reaction: StepLossReaction) {
+        self.traverse_controller
+            .get_step_loss_mut()
+            .set_reaction(reaction);
+        self.emit_state();
+    }
+
+    pub fn traverse_set_step_loss_tolerance(&mut self, tolerance: f64) {
+        if !tolerance.is_finite() || tolerance <= 0.0 {
+            return;
+        }
+        self.traverse_controller
+            .get_step_loss_mut()
+            .set_tolerance(Length::new::<millimeter>(tolerance));
+        self.emit_state();
+    }
+
+    /// React to lost traverse steps according to the configured [`StepLossReaction`]
+    /// called by `sync_traverse_speed`
+    pub fn traverse_step_loss_changed(&mut self, detected: bool) {
+        self.emit_alarm(Winder2Alarm::TraverseStepLoss, detected);
+
+        let reaction = self.traverse_controller.get_step_loss().get_reaction();
+        if detected && reaction == StepLossReaction::Stop && self.mode == Winder2Mode::Wind {
+            tracing::error!(
+                "[{}::Winder2::traverse_step_loss_changed] Traverse lost steps, stopping",
+                module_path!()
+            );
+            // Holding re-homes the traverse
+            self.changeover_abort();
+            self.set_mode(&Winder2Mode::Hold);
+            return;
+        }
+        self.emit_state();
+    }
+
+    /// Re-home for lost steps with [`StepLossReaction::RehomeAtSpoolChange`] when a spool is started
+    ///
+    /// Homing stops traversing, so a spool started while winding keeps the re-home pending.
+    fn traverse_rehome_due(&self) -> bool {
+        let step_loss = self.traverse_controller.get_step_loss();
+        step_loss.is_detected()
+            && step_loss.get_reaction() == StepLossReaction::RehomeAtSpoolChange
+            && self.mode != Winder2Mode::Wind
+    }
+
     pub fn traverse_goto_limit_inner(&mut self) {
         if self.can_go_in() {
             self.traverse_controller.goto_limit_inner();
@@ -172,7 +440,10 @@ impl Winder2 {
     }
 
     pub fn get_live_values(&self) -> LiveValuesEvent {
-        let angle_deg = self.tension_arm.get_angle().unwrap().get::<degree>();
+        let angle_deg = self
+            .tension_arm
+            .get_angle()
+            .map_or(0.0, |angle| angle.get::<degree>());
 
         // Wrap [270;<360] to [-90; 0]
         // This is done to reduce flicker in the graphs around the zero point
@@ -194,13 +465,15 @@ impl Winder2 {
             .puller_speed_controller
             .angular_velocity_to_speed(angular_velocity);
 
-        // Divide by gear ratio to get actual puller/material speed
-        let puller_speed = motor_speed / self.puller_speed_controller.get_gear_ratio().multiplier();
+        // Through the drivetrain to the actual puller/material speed
+        let puller_speed = self
+            .puller_speed_controller
+            .get_transmission()
+            .calculate_linear_velocity_output(motor_speed);
         let spool_ref = &mut *self.spool.borrow_mut();
         // Calculate spool RPM from current motor steps (always positive regardless of direction)
         let spool_rpm = self
-            .spool_step_converter
-            .steps_to_angular_velocity(spool_ref.get_speed(SPOOL_PORT) as f64)
+            .steps_to_spool_speed(spool_ref.get_speed(SPOOL_PORT) as f64)
             .get::<revolution_per_minute>()
             .abs();
 
@@ -213,6 +486,27 @@ impl Winder2 {
             spool_rpm,
             tension_arm_angle: angle_deg,
             spool_progress: self.spool_automatic_action.progress.get::<meter>(),
+            spool_diameter: self
+                .spool_diameter_estimator
+                .get_diameter()
+                .get::<millimeter>(),
+            spool_diameter_uncertainty: self
+                .spool_diameter_estimator
+                .get_diameter_uncertainty()
+                .get::<millimeter>(),
+            filament_mass: self.spool_diameter_estimator.get_filament_mass(),
+            remaining_capacity: self
+                .spool_diameter_estimator
+                .get_remaining_capacity()
+                .map(|x| x.get::<meter>()),
+            time_to_full: self
+                .spool_diameter_estimator
+                .get_time_to_full(puller_speed)
+                .map(|x| x.as_secs_f64()),
+            predicted_diameter: self.puller_speed_controller.predictive.predicted_diameter(),
+            puller_diameter: self.puller_speed_controller.predictive.puller_diameter(),
+            puller_ramping: self.puller_speed_controller.is_ramping(),
+            puller_ramp_held: self.puller_speed_controller.is_ramp_held(),
         }
     }
 
@@ -222,6 +516,8 @@ impl Winder2 {
     }
 
     pub fn build_state_event(&mut self) -> StateEvent {
+        let winding_pattern = self.traverse_controller.get_winding_pattern();
+        let step_loss = self.traverse_controller.get_step_loss();
         StateEvent {
             is_default_state: !std::mem::replace(&mut self.emitted_default_state, true),
             traverse_state: TraverseState {
@@ -249,6 +545,24 @@ impl Winder2 {
                 laserpointer: self.laser_enabled,
                 step_size: self.traverse_controller.get_step_size().get::<millimeter>(),
                 padding: self.traverse_controller.get_padding().get::<millimeter>(),
+                dwell_time: self.traverse_controller.get_dwell_time().as_secs_f64(),
+                overshoot_compensation: self
+                    .traverse_controller
+                    .get_overshoot_compensation()
+                    .get::<millimeter>(),
+                winding_pattern: winding_pattern.get_pattern(),
+                pitch: winding_pattern.get_pitch().get::<millimeter>(),
+                crossing_angle: winding_pattern.get_crossing_angle().get::<degree>(),
+                crossing_angle_tolerance: winding_pattern
+                    .get_crossing_angle_tolerance()
+                    .get::<degree>(),
+                wave_amplitude: winding_pattern.get_wave_amplitude() * 100.0,
+                wave_period: winding_pattern.get_wave_period(),
+                step_loss_reaction: step_loss.get_reaction(),
+                step_loss_tolerance: step_loss.get_tolerance().get::<millimeter>(),
+                step_loss_deviation: step_loss
+                    .get_detected_deviation()
+                    .map(|deviation| deviation.get::<millimeter>()),
                 can_go_in: self.can_go_in(),
                 can_go_out: self.can_go_out(),
                 can_go_home: self.can_go_home(),
@@ -260,7 +574,17 @@ impl Winder2 {
                     .target_speed
                     .get::<meter_per_minute>(),
                 forward: self.puller_speed_controller.forward,
-                gear_ratio: self.puller_speed_controller.gear_ratio,
+                transmission: self.puller_speed_controller.get_transmission().clone(),
+                ramp_profiles: self.ramp_profiles.get_profiles(),
+                ramp_profile: self
+                    .puller_speed_controller
+                    .get_ramp_profile()
+                    .map(|x| x.name.clone()),
+                threading: self.puller_speed_controller.is_threading(),
+                threading_speed: self
+                    .puller_speed_controller
+                    .get_threading_speed()
+                    .get::<meter_per_minute>(),
                 adaptive_speed_delta_max: self.puller_speed_controller.adaptive.speed_delta_max(),
                 adaptive_adjustment_distance: self
                     .puller_speed_controller
@@ -273,6 +597,21 @@ impl Winder2 {
                     .adaptive
                     .tolerance_limit()
                     .get::<millimeter>(),
+                predictive_extruder_to_laser: self
+                    .puller_speed_controller
+                    .predictive
+                    .extruder_to_laser()
+                    .get::<meter>(),
+                predictive_laser_to_puller: self
+                    .puller_speed_controller
+                    .predictive
+                    .laser_to_puller()
+                    .get::<meter>(),
+                predictive_proportional_gain: self
+                    .puller_speed_controller
+                    .predictive
+                    .proportional_gain(),
+                predictive_integral_gain: self.puller_speed_controller.predictive.integral_gain(),
                 adaptive_reference_machine: match self.laser_ident {
                     Some(ident) => Some(ident.into()),
                     None => None,
@@ -284,6 +623,7 @@ impl Winder2 {
             },
             tension_arm_state: TensionArmState {
                 zeroed: self.tension_arm.zeroed,
+                fault: self.tension_arm_monitor.get_fault(),
             },
             spool_speed_controller_state: SpoolSpeedControllerState {
                 regulation_mode: self.spool_speed_controller.get_type().clone(),
@@ -310,6 +650,44 @@ impl Winder2 {
                     .get_adaptive_deacceleration_urgency_multiplier(),
                 forward: self.spool_speed_controller.get_forward(),
             },
+            spool_diameter_state: SpoolDiameterState {
+                core_diameter: self
+                    .spool_diameter_estimator
+                    .get_core_diameter()
+                    .get::<millimeter>(),
+                full_diameter: self
+                    .spool_diameter_estimator
+                    .get_full_diameter()
+                    .get::<millimeter>(),
+                filament_diameter: self
+                    .spool_diameter_estimator
+                    .get_filament_diameter()
+                    .get::<millimeter>(),
+                filament_density: self.spool_diameter_estimator.get_filament_density(),
+            },
+            changeover_state: ChangeoverState {
+                step: self.changeover.get_step(),
+                puller_speed: self.changeover.get_puller_speed().get::<meter_per_minute>(),
+                rezero_tension_arm: self.changeover.get_rezero_tension_arm(),
+                rehome_traverse: self.changeover.get_rehome_traverse(),
+            },
+            length_meter_state: LengthMeterState {
+                correction_factor: self.length_meter.get_correction_factor(),
+                is_calibrating: self.length_meter.is_calibrating(),
+            },
+            geometry: self.geometry.clone(),
+            pending_geometry: self.pending_geometry.clone(),
+            spool_profile_state: SpoolProfileState {
+                profiles: self.spool_profiles.get_profiles(),
+                selected: self.spool_profile.clone(),
+                capacity: self
+                    .spool_profile_fit()
+                    .map(|fit| fit.capacity.get::<meter>()),
+            },
+            spool_job_state: SpoolJobState {
+                started_at: self.spool_job.as_ref().map(|x| x.get_started_at()),
+                last_report_id: self.last_spool_report,
+            },
             spool_automatic_action_state: SpoolAutomaticActionState {
                 spool_required_meters: self.spool_automatic_action.target_length.get::<meter>(),
                 spool_automatic_action_mode: self.spool_automatic_action.mode.clone(),
@@ -410,8 +788,28 @@ impl Winder2 {
         self.emit_state();
     }
 
+    /// Persist a geometry override, the converters are only built when the machine is created
+    pub fn set_geometry(&mut self, geometry: Winder2Geometry) -> Result<(), anyhow::Error> {
+        geometry.save(&self.machine_identification_unique.into())?;
+        tracing::info!(
+            "[{}::Winder2::set_geometry] Geometry saved, it applies after the machine is restarted",
+            module_path!()
+        );
+        self.pending_geometry = (geometry != self.geometry).then_some(geometry);
+        self.emit_state();
+        Ok(())
+    }
+
+    pub fn tension_arm_clear_fault(&mut self) {
+        if let Some(fault) = self.tension_arm_monitor.clear_fault() {
+            self.emit_alarm(Winder2Alarm::TensionArm(fault), false);
+        }
+        self.emit_state();
+    }
+
     pub fn set_spool_automatic_required_meters(&mut self, meters: f64) {
         self.spool_automatic_action.target_length = Length::new::<meter>(meters);
+        self.spool_profile = None;
         self.emit_state();
     }
 
@@ -440,10 +838,148 @@ impl Winder2 {
         self.emit_state();
     }
 
-    /// Set gear ratio for winding speed
-    pub fn puller_set_gear_ratio(&mut self, gear_ratio: super::puller_speed_controller::GearRatio) {
-        self.puller_speed_controller.set_gear_ratio(gear_ratio);
+    /// Set the drivetrain from the puller stepper to the wheel and persist it in the geometry
+    pub fn puller_set_transmission(
+        &mut self,
+        transmission: MultiStageTransmission,
+    ) -> Result<(), anyhow::Error> {
+        if !transmission.is_valid() {
+            return Err(anyhow::anyhow!(
+                "[{}::Winder2::puller_set_transmission] Invalid transmission {:?}",
+                module_path!(),
+                transmission
+            ));
+        }
+
+        // A pending geometry override is saved as a whole, only its transmission is replaced
+        let mut saved = self
+            .pending_geometry
+            .clone()
+            .unwrap_or_else(|| self.geometry.clone());
+        saved.puller_transmission = transmission.clone();
+        saved.save(&self.machine_identification_unique.into())?;
+
+        self.geometry.puller_transmission = transmission.clone();
+        self.pending_geometry = (saved != self.geometry).then_some(saved);
+        self.puller_speed_controller.set_transmission(transmission);
+        self.emit_state();
+        Ok(())
+    }
+
+    /// Select the ramp profile of the next start by name, `None` for the default limits
+    pub fn puller_select_ramp_profile(&mut self, name: Option<&str>) -> Result<(), anyhow::Error> {
+        let profile = match name {
+            Some(name) => match self.ramp_profiles.get(name) {
+                Some(profile) => Some(profile),
+                None => {
+                    return Err(anyhow::anyhow!(
+                        "[{}::Winder2::puller_select_ramp_profile] No ramp profile {}",
+                        module_path!(),
+                        name
+                    ));
+                }
+            },
+            None => None,
+        };
+        self.puller_speed_controller.set_ramp_profile(profile);
+        self.puller_save_ramp_profile_selection();
+        self.emit_state();
+        Ok(())
+    }
+
+    /// Restore the ramp profile selected on this winder
+    pub fn puller_load_ramp_profile_selection(&mut self) {
+        let machine = self.machine_identification_unique.into();
+        let selection = match ConfigStore::new(RAMP_PROFILE_SELECTION_CONFIG, &machine)
+            .load::<RampProfileSelection>()
+        {
+            Ok(selection) => selection,
+            Err(e) => {
+                tracing::error!(
+                    "[{}::Winder2::puller_load_ramp_profile_selection] Loading ramp profile selection failed: {:?}",
+                    module_path!(),
+                    e
+                );
+                None
+            }
+        };
+        let Some(name) = selection.and_then(|x| x.name) else {
+            return;
+        };
+        match self.ramp_profiles.get(&name) {
+            Some(profile) => self.puller_speed_controller.set_ramp_profile(Some(profile)),
+            None => tracing::warn!(
+                "[{}::Winder2::puller_load_ramp_profile_selection] Selected ramp profile {} doesn't exist anymore",
+                module_path!(),
+                name
+            ),
+        }
+    }
+
+    fn puller_save_ramp_profile_selection(&self) {
+        let machine = self.machine_identification_unique.into();
+        let selection = RampProfileSelection {
+            name: self
+                .puller_speed_controller
+                .get_ramp_profile()
+                .map(|x| x.name.clone()),
+        };
+        if let Err(e) = ConfigStore::new(RAMP_PROFILE_SELECTION_CONFIG, &machine).save(&selection) {
+            tracing::error!(
+                "[{}::Winder2::puller_save_ramp_profile_selection] Saving ramp profile selection failed: {:?}",
+                module_path!(),
+                e
+            );
+        }
+    }
+
+    /// Add or replace a custom ramp profile, a selected profile is updated
+    pub fn puller_save_ramp_profile(&mut self, profile: RampProfile) -> Result<(), anyhow::Error> {
+        self.ramp_profiles.save(profile.clone())?;
+        let is_selected = self
+            .puller_speed_controller
+            .get_ramp_profile()
+            .is_some_and(|x| x.name == profile.name);
+        if is_selected {
+            self.puller_speed_controller.set_ramp_profile(Some(profile));
+        }
+        self.emit_state();
+        Ok(())
+    }
+
+    pub fn puller_delete_ramp_profile(&mut self, name: &str) -> Result<(), anyhow::Error> {
+        self.ramp_profiles.delete(name)?;
+        let is_selected = self
+            .puller_speed_controller
+            .get_ramp_profile()
+            .is_some_and(|x| x.name == name);
+        if is_selected {
+            self.puller_speed_controller.set_ramp_profile(None);
+            self.puller_save_ramp_profile_selection();
+        }
+        self.emit_state();
+        Ok(())
+    }
+
+    /// Pull at the threading speed, leaving it ramps up to the target speed
+    pub fn puller_set_threading(&mut self, threading: bool) {
+        self.puller_speed_controller.set_threading(threading);
+        self.emit_state();
+    }
+
+    /// Set the threading speed in m/min
+    pub fn puller_set_threading_speed(&mut self, speed: f64) -> Result<(), anyhow::Error> {
+        if !speed.is_finite() {
+            return Err(anyhow::anyhow!(
+                "[{}::Winder2::puller_set_threading_speed] Invalid speed {} m/min",
+                module_path!(),
+                speed
+            ));
+        }
+        self.puller_speed_controller
+            .set_threading_speed(Velocity::new::<meter_per_minute>(speed));
         self.emit_state();
+        Ok(())
     }
 
     // Spool Speed Controller API methods
@@ -516,15 +1052,334 @@ impl Winder2 {
         self.spool_speed_controller.set_forward(forward);
         self.emit_state();
     }
+
+    /// Set the empty spool core diameter in mm, applies to the next spool
+    pub fn spool_set_core_diameter(&mut self, diameter: f64) {
+        let full_diameter = self
+            .spool_diameter_estimator
+            .get_full_diameter()
+            .get::<millimeter>();
+        if !(diameter > 0.0 && diameter < full_diameter) {
+            return;
+        }
+        self.spool_diameter_estimator
+            .set_core_diameter(Length::new::<millimeter>(diameter));
+        self.spool_profile = None;
+        self.emit_state();
+    }
+
+    /// Set the diameter of a full spool in mm
+    pub fn spool_set_full_diameter(&mut self, diameter: f64) {
+        let core_diameter = self
+            .spool_diameter_estimator
+            .get_core_diameter()
+            .get::<millimeter>();
+        if !(diameter.is_finite() && diameter > core_diameter) {
+            return;
+        }
+        self.spool_diameter_estimator
+            .set_full_diameter(Length::new::<millimeter>(diameter));
+        self.spool_profile = None;
+        self.emit_state();
+    }
+
+    /// Set the filament diameter in mm
+    pub fn spool_set_filament_diameter(&mut self, diameter: f64) {
+        if !(diameter.is_finite() && diameter > 0.0) {
+            return;
+        }
+        self.spool_diameter_estimator
+            .set_filament_diameter(Length::new::<millimeter>(diameter));
+        self.spool_update_profile_capacity();
+        self.emit_state();
+    }
+
+    /// Set the filament density in g/cm³
+    pub fn spool_set_filament_density(&mut self, density: f64) {
+        if !(density.is_finite() && density > 0.0) {
+            return;
+        }
+        self.spool_diameter_estimator.set_filament_density(density);
+        self.spool_update_profile_capacity();
+        self.emit_state();
+    }
+
+    /// Fit of the selected spool profile for the current filament
+    fn spool_profile_fit(&self) -> Option<SpoolProfileFit> {
+        let profile = self.spool_profiles.get(self.spool_profile.as_ref()?)?;
+        profile.fit(
+            self.geometry.get_spool_seat(),
+            self.spool_diameter_estimator.get_filament_diameter(),
+            self.spool_diameter_estimator.get_filament_density(),
+        )
+    }
+
+    /// The required meters follow the filament while a spool profile is selected
+    fn spool_update_profile_capacity(&mut self) {
+        if let Some(fit) = self.spool_profile_fit() {
+            self.spool_automatic_action.target_length = fit.capacity;
+        }
+    }
+
+    /// Set traverse limits, padding, spool diameters and required meters from a spool profile
+    ///
+    /// The core diameter applies to the next spool, like [`Self::spool_set_core_diameter`],
+    /// everything else right away. Changing any of them by hand deselects the profile.
+    pub fn spool_select_profile(&mut self, name: &str) -> Result<(), anyhow::Error> {
+        let Some(profile) = self.spool_profiles.get(name) else {
+            return Err(anyhow::anyhow!(
+                "[{}::Winder2::spool_select_profile] No spool profile {}",
+                module_path!(),
+                name
+            ));
+        };
+        let Some(fit) = profile.fit(
+            self.geometry.get_spool_seat(),
+            self.spool_diameter_estimator.get_filament_diameter(),
+            self.spool_diameter_estimator.get_filament_density(),
+        ) else {
+            return Err(anyhow::anyhow!(
+                "[{}::Winder2::spool_select_profile] Spool profile {} doesn't fit this winder",
+                module_path!(),
+                name
+            ));
+        };
+
+        self.traverse_controller.set_limit_inner(fit.limit_inner);
+        self.traverse_controller.set_limit_outer(fit.limit_outer);
+        self.traverse_controller.set_padding(fit.padding);
+        self.spool_diameter_estimator
+            .set_core_diameter(profile.get_core_diameter());
+        self.spool_diameter_estimator
+            .set_full_diameter(profile.get_full_diameter());
+        self.spool_automatic_action.target_length = fit.capacity;
+        self.spool_profile = Some(profile.name);
+        self.emit_state();
+        Ok(())
+    }
+
+    /// Add or replace a custom spool profile
+    pub fn spool_save_profile(&mut self, profile: SpoolProfile) -> Result<(), anyhow::Error> {
+        self.spool_profiles.save(profile)?;
+        self.emit_state();
+        Ok(())
+    }
+
+    pub fn spool_delete_profile(&mut self, name: &str) -> Result<(), anyhow::Error> {
+        self.spool_profiles.delete(name)?;
+        if self.spool_profile.as_deref() == Some(name) {
+            self.spool_profile = None;
+        }
+        self.emit_state();
+        Ok(())
+    }
+
+    /// Restore the correction factor of this winder
+    pub fn length_meter_load_calibration(&mut self) {
+        let machine = self.machine_identification_unique.into();
+        match ConfigStore::new(LENGTH_CALIBRATION_CONFIG, &machine).load::<LengthCalibration>() {
+            Ok(Some(calibration)) => {
+                let correction_factor = calibration.correction_factor;
+                if correction_factor.is_finite() && correction_factor > 0.0 {
+                    self.length_meter.set_correction_factor(correction_factor);
+                } else {
+                    tracing::warn!(
+                        "[{}::Winder2::length_meter_load_calibration] Invalid correction factor {}, using 1.0",
+                        module_path!(),
+                        correction_factor
+                    );
+                    self.length_meter.set_correction_factor(1.0);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => tracing::error!(
+                "[{}::Winder2::length_meter_load_calibration] Loading length calibration failed: {:?}",
+                module_path!(),
+                e
+            ),
+        }
+    }
+
+    fn length_meter_save_calibration(&self) {
+        let machine = self.machine_identification_unique.into();
+        let calibration = LengthCalibration {
+            correction_factor: self.length_meter.get_correction_factor(),
+        };
+        if let Err(e) = ConfigStore::new(LENGTH_CALIBRATION_CONFIG, &machine).save(&calibration) {
+            tracing::error!(
+                "[{}::Winder2::length_meter_save_calibration] Saving length calibration failed: {:?}",
+                module_path!(),
+                e
+            );
+        }
+    }
+
+    pub fn length_meter_start_calibration(&mut self) {
+        self.length_meter.start_calibration();
+        self.emit_state();
+    }
+
+    pub fn length_meter_abort_calibration(&mut self) {
+        self.length_meter.abort_calibration();
+        self.emit_state();
+    }
+
+    /// `actual_length` in m, measured by the operator
+    pub fn length_meter_finish_calibration(
+        &mut self,
+        actual_length: f64,
+    ) -> Result<(), anyhow::Error> {
+        self.length_meter
+            .finish_calibration(Length::new::<meter>(actual_length))?;
+        self.length_meter_save_calibration();
+        self.emit_state();
+        Ok(())
+    }
+
+    pub fn length_meter_reset_calibration(&mut self) {
+        self.length_meter.abort_calibration();
+        self.length_meter.set_correction_factor(1.0);
+        self.length_meter_save_calibration();
+        self.emit_state();
+    }
+
+    /// Start tracking a new spool, finishes the running one
+    pub fn spool_start_job(&mut self, now: Instant) {
+        if self.spool_job.is_some() {
+            self.spool_finish_job(now);
+        }
+        self.stop_or_pull_spool_reset(now);
+        self.spool_diameter_estimator.reset();
+        if self.traverse_rehome_due() {
+            self.traverse_controller.goto_home();
+        }
+        self.spool_job = Some(SpoolJob::start(now));
+        self.emit_state();
+    }
+
+    /// Finish the running spool and queue its report for saving
+    pub fn spool_finish_job(&mut self, now: Instant) {
+        let Some(spool_job) = self.spool_job.take() else {
+            return;
+        };
+        let filament_mass = self
+            .spool_diameter_estimator
+            .filament_mass(spool_job.get_length());
+        let report = spool_job.finish(
+            now,
+            self.machine_identification_unique.into(),
+            filament_mass,
+        );
+        if let Err(e) = self.spool_report_writer.save(report) {
+            tracing::error!(
+                "[{}::Winder2::spool_finish_job] Can't save the spool report: {:?}",
+                module_path!(),
+                e
+            );
+        }
+        self.emit_state();
+    }
+
+    /// The last report is only announced once it was saved
+    pub fn sync_spool_reports(&mut self) {
+        while let Some((id, result)) = self.spool_report_writer.try_recv() {
+            match result {
+                Ok(()) => {
+                    self.last_spool_report = Some(id);
+                    self.emit_state();
+                }
+                Err(e) => tracing::error!(
+                    "[{}::Winder2::sync_spool_reports] Saving spool report {} failed: {:?}",
+                    module_path!(),
+                    id,
+                    e
+                ),
+            }
+        }
+    }
+
+    /// Start the spool changeover, finishes the running spool job
+    pub fn changeover_start(&mut self, now: Instant) {
+        if self.mode != Winder2Mode::Wind || self.changeover.is_active() {
+            self.emit_state();
+            return;
+        }
+        let resume_spool_job = self.spool_job.is_some();
+        self.spool_finish_job(now);
+        self.changeover.start(resume_spool_job);
+        self.emit_state();
+    }
+
+    /// The operator mounted a new spool
+    pub fn changeover_confirm_spool_mounted(&mut self) {
+        if self.changeover.confirm_spool_mounted() {
+            self.set_changeover_signal(false);
+            if self.changeover.get_rezero_tension_arm() {
+                self.tension_arm.zero();
+            }
+            if self.changeover.get_rehome_traverse() || self.traverse_rehome_due() {
+                self.traverse_controller.goto_home();
+            }
+        }
+        self.emit_state();
+    }
+
+    /// Stop the sequence, the machine stays in its current mode
+    pub fn changeover_abort(&mut self) {
+        if !self.changeover.is_active() {
+            return;
+        }
+        if self.changeover.is_waiting_for_spool() {
+            self.set_changeover_signal(false);
+        }
+        self.changeover.reset();
+        self.puller_speed_controller.set_speed_limit(None);
+        self.emit_state();
+    }
+
+    /// Set the puller speed during the changeover in m/min
+    pub fn changeover_set_puller_speed(&mut self, speed: f64) -> Result<(), anyhow::Error> {
+        if !(speed.is_finite() && speed > 0.0) {
+            return Err(anyhow::anyhow!(
+                "[{}::Winder2::changeover_set_puller_speed] Invalid speed {} m/min",
+                module_path!(),
+                speed
+            ));
+        }
+        self.changeover
+            .set_puller_speed(Velocity::new::<meter_per_minute>(speed));
+        self.emit_state();
+        Ok(())
+    }
+
+    pub fn changeover_set_rezero_tension_arm(&mut self, rezero: bool) {
+        self.changeover.set_rezero_tension_arm(rezero);
+        self.emit_state();
+    }
+
+    pub fn changeover_set_rehome_traverse(&mut self, rehome: bool) {
+        self.changeover.set_rehome_traverse(rehome);
+        self.emit_state();
+    }
+
+    /// Start the diameter estimate over, e.g. after a spool change
+    pub fn spool_reset_diameter_estimate(&mut self) {
+        self.spool_diameter_estimator.reset();
+        self.emit_state();
+    }
 }
 
 // Winder2 Extension
 #[cfg(not(feature = "mock-machine"))]
 impl Winder2 {
+    /// Applies to both diameter regulation modes
     pub fn puller_set_adaptive_max_speed_change_percent(&mut self, value: f64) {
         self.puller_speed_controller
             .adaptive
             .set_speed_delta_max(value);
+        self.puller_speed_controller
+            .predictive
+            .set_speed_delta_max(value);
         self.emit_state();
     }
 
@@ -564,4 +1419,75 @@ impl Winder2 {
         self.emit_state();
         Ok(())
     }
+
+    /// Set the line length from the extruder to the laser in m
+    pub fn puller_set_predictive_extruder_to_laser(
+        &mut self,
+        value: f64,
+    ) -> Result<(), anyhow::Error> {
+        if !(value.is_finite() && value >= 0.0) {
+            return Err(anyhow::anyhow!(
+                "[{}::Winder2::puller_set_predictive_extruder_to_laser] Invalid length {} m",
+                module_path!(),
+                value
+            ));
+        }
+        self.puller_speed_controller
+            .predictive
+            .set_extruder_to_laser(Length::new::<meter>(value));
+        self.emit_state();
+        Ok(())
+    }
+
+    /// Set the line length from the laser to the puller in m
+    pub fn puller_set_predictive_laser_to_puller(
+        &mut self,
+        value: f64,
+    ) -> Result<(), anyhow::Error> {
+        if !(value.is_finite() && value >= 0.0) {
+            return Err(anyhow::anyhow!(
+                "[{}::Winder2::puller_set_predictive_laser_to_puller] Invalid length {} m",
+                module_path!(),
+                value
+            ));
+        }
+        self.puller_speed_controller
+            .predictive
+            .set_laser_to_puller(Length::new::<meter>(value));
+        self.emit_state();
+        Ok(())
+    }
+
+    pub fn puller_set_predictive_proportional_gain(
+        &mut self,
+        value: f64,
+    ) -> Result<(), anyhow::Error> {
+        if !(value.is_finite() && value >= 0.0) {
+            return Err(anyhow::anyhow!(
+                "[{}::Winder2::puller_set_predictive_proportional_gain] Invalid gain {}",
+                module_path!(),
+                value
+            ));
+        }
+        self.puller_speed_controller
+            .predictive
+            .set_proportional_gain(value);
+        self.emit_state();
+        Ok(())
+    }
+
+    pub fn puller_set_predictive_integral_gain(&mut self, value: f64) -> Result<(), anyhow::Error> {
+        if !(value.is_finite() && value >= 0.0) {
+            return Err(anyhow::anyhow!(
+                "[{}::Winder2::puller_set_predictive_integral_gain] Invalid gain {}",
+                module_path!(),
+                value
+            ));
+        }
+        self.puller_speed_controller
+            .predictive
+            .set_integral_gain(value);
+        self.emit_state();
+        Ok(())
+    }
 }
@@ -1,11 +1,14 @@
 use control_core::converters::angle_converter::{AngleConverter, AngleConverterUom};
 use euclid::Point2D;
 use qitech_lib::units::ConstZero;
-use qitech_lib::units::angle::radian;
+use qitech_lib::units::angle::{degree, radian};
 use qitech_lib::units::f64::*;
 use qitech_lib::units::length::centimeter;
 use qitech_lib::units::ratio::ratio;
 
+use super::clamp_revolution::clamp_revolution_uom;
+use super::tension_arm::TensionArm;
+
 // The "tension" of the filament is not linear regarding the angle of the tension arm since it moves in an angular motion.
 // With this calculator we can calculate the filament length and tension based on the angle of the tension arm using geometry.
 #[derive(Debug, Clone)]
@@ -24,6 +27,13 @@ pub struct FilamentTensionCalculator {
     pub angle_converter: AngleConverterUom,
 }
 
+impl Default for FilamentTensionCalculator {
+    /// Travel of the winder tension arm, loosest at 90° and tightest at 20°
+    fn default() -> Self {
+        Self::new(Angle::new::<degree>(90.0), Angle::new::<degree>(20.0))
+    }
+}
+
 impl FilamentTensionCalculator {
     /// [`max_angle_deg`] in Y-Flipped CW roation system
     pub fn new(min_angle: Angle, max_angle: Angle) -> Self {
@@ -90,6 +100,29 @@ impl FilamentTensionCalculator {
             .clamp(0.0, 1.0)
     }
 
+    /// Filament the tension system releases per radian the arm moves toward larger angles
+    /// at `tension_arm_angle`, negative where it takes filament up
+    pub fn calc_released_per_radian(&self, tension_arm_angle: Angle) -> Length {
+        let step = Angle::new::<degree>(0.5);
+        (self.calc_filament_length(tension_arm_angle - step)
+            - self.calc_filament_length(tension_arm_angle + step))
+            / (2.0 * step.get::<radian>())
+    }
+
+    /// Filament tension from the tension arm angle, `None` if the arm can't be read
+    ///
+    /// Angles outside of the arm travel count as the nearest end.
+    pub fn get_filament_tension(&self, tension_arm: &TensionArm) -> Option<f64> {
+        let tension_arm_angle = tension_arm.get_angle().ok()?;
+        let (clamped_angle, _) = clamp_revolution_uom(
+            tension_arm_angle,
+            // inverted because min angle is max tension
+            self.max_angle,
+            self.min_angle,
+        );
+        Some(self.calc_filament_tension(clamped_angle))
+    }
+
     // Get the optimal angle (minimum filament length)
     // Returns the angle in Y-Flipped CW rotation system.
     pub fn get_min_angle(&self) -> Angle {
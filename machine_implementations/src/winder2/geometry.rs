@@ -0,0 +1,242 @@
+use control_core::transmission::{Transmission, multi_stage::MultiStageTransmission};
+use qitech_lib::units::f64::Length;
+use qitech_lib::units::length::millimeter;
+use serde::{Deserialize, Serialize};
+
+use super::Winder2;
+use super::config_store::ConfigStore;
+use crate::MACHINE_WINDER_V1_7031_0030_SPOOL;
+use crate::machine_identification::{MachineIdentification, QiTechMachineIdentificationUnique};
+
+/// Name of the persisted geometry override
+const GEOMETRY_CONFIG: &str = "geometry";
+
+/// Mechanics of a winder build
+///
+/// Lengths are in mm. The profile follows the machine identification and can be overridden
+/// per machine by a persisted configuration, which is read when the machine is created.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Winder2Geometry {
+    /// Full steps per revolution of the traverse stepper
+    pub traverse_steps_per_revolution: i16,
+    /// Microsteps of the traverse position counter
+    pub traverse_microsteps: u8,
+    /// Travel of the traverse per revolution of its drivetrain output
+    pub traverse_circumference: f64,
+    /// Drivetrain from the traverse stepper to the belt pulley or lead screw
+    #[serde(default)]
+    pub traverse_transmission: MultiStageTransmission,
+    /// Full steps per revolution of the puller stepper
+    pub puller_steps_per_revolution: i16,
+    /// Diameter of the puller wheel
+    pub puller_wheel_diameter: f64,
+    /// Drivetrain from the puller stepper to the wheel, can be changed while running
+    #[serde(default)]
+    pub puller_transmission: MultiStageTransmission,
+    /// Full steps per revolution of the spool stepper
+    pub spool_steps_per_revolution: i16,
+    /// Drivetrain from the spool stepper to the spool
+    #[serde(default)]
+    pub spool_transmission: MultiStageTransmission,
+    /// Inner spool flange measured from the traverse home point, the default inner limit
+    pub spool_flange_inner: f64,
+    /// Outer spool flange measured from the traverse home point, the default outer limit
+    pub spool_flange_outer: f64,
+    /// Outer face of the mounted spool's inner flange measured from the traverse home point,
+    /// the reference for the traverse limits of a spool profile
+    #[serde(default = "Winder2Geometry::default_spool_seat")]
+    pub spool_seat: f64,
+    /// Sensor voltage of one full tension arm revolution
+    pub tension_arm_volts_per_revolution: f64,
+}
+
+impl Default for Winder2Geometry {
+    fn default() -> Self {
+        Self::WINDER_V1
+    }
+}
+
+impl Winder2Geometry {
+    /// Winder V1 with the spool driven by an EL7041-0052
+    pub const WINDER_V1: Self = Self {
+        traverse_steps_per_revolution: 200,
+        traverse_microsteps: 64,
+        traverse_circumference: 32.0,
+        traverse_transmission: MultiStageTransmission::direct(),
+        puller_steps_per_revolution: 200,
+        puller_wheel_diameter: 80.0,
+        puller_transmission: MultiStageTransmission::direct(),
+        spool_steps_per_revolution: 200,
+        spool_transmission: MultiStageTransmission::direct(),
+        spool_flange_inner: 22.0,
+        spool_flange_outer: 92.0,
+        spool_seat: 19.0,
+        tension_arm_volts_per_revolution: 5.0,
+    };
+
+    /// Winder V1 with the spool driven by an EL7031-0030, the mechanics are the same
+    pub const WINDER_V1_7031_SPOOL: Self = Self::WINDER_V1;
+
+    /// Factory profile of a winder build
+    pub const fn profile(machine: &MachineIdentification) -> Self {
+        if machine.machine == MACHINE_WINDER_V1_7031_0030_SPOOL {
+            Self::WINDER_V1_7031_SPOOL
+        } else {
+            Self::WINDER_V1
+        }
+    }
+
+    /// Overrides persisted before the spool seat was configurable
+    const fn default_spool_seat() -> f64 {
+        Self::WINDER_V1.spool_seat
+    }
+
+    /// Profile of the machine, replaced by its persisted override if there is one
+    pub fn load(machine: &QiTechMachineIdentificationUnique) -> Self {
+        match ConfigStore::new(GEOMETRY_CONFIG, machine).load::<Self>() {
+            Ok(Some(geometry)) if geometry.is_valid() => geometry,
+            Ok(Some(_)) => {
+                tracing::error!(
+                    "[{}::Winder2Geometry::load] Ignoring invalid geometry override",
+                    module_path!()
+                );
+                Self::profile(&machine.machine_identification)
+            }
+            Ok(None) => Self::profile(&machine.machine_identification),
+            Err(e) => {
+                tracing::error!(
+                    "[{}::Winder2Geometry::load] Loading geometry override failed: {:?}",
+                    module_path!(),
+                    e
+                );
+                Self::profile(&machine.machine_identification)
+            }
+        }
+    }
+
+    /// Persist as override of the machine profile
+    pub fn save(&self, machine: &QiTechMachineIdentificationUnique) -> anyhow::Result<()> {
+        if !self.is_valid() {
+            return Err(anyhow::anyhow!(
+                "[{}::Winder2Geometry::save] Invalid geometry",
+                module_path!()
+            ));
+        }
+        ConfigStore::new(GEOMETRY_CONFIG, machine).save(self)
+    }
+
+    pub fn is_valid(&self) -> bool {
+        let positive = [
+            self.traverse_circumference,
+            self.puller_wheel_diameter,
+            self.tension_arm_volts_per_revolution,
+        ]
+        .iter()
+        .all(|x| x.is_finite() && *x > 0.0);
+
+        positive
+            && self.traverse_transmission.is_valid()
+            && self.puller_transmission.is_valid()
+            && self.spool_transmission.is_valid()
+            && self.traverse_steps_per_revolution > 0
+            && self.puller_steps_per_revolution > 0
+            && self.spool_steps_per_revolution > 0
+            && self.traverse_microsteps > 0
+            // The traverse converter counts microsteps per revolution in an i16
+            && self
+                .traverse_steps_per_revolution
+                .checked_mul(i16::from(self.traverse_microsteps))
+                .is_some()
+            && self.spool_flange_inner >= 0.0
+            && self.spool_seat.is_finite()
+            && self.spool_seat >= 0.0
+            && Winder2::validate_traverse_limits(
+                self.get_spool_flange_inner(),
+                self.get_spool_flange_outer(),
+            )
+    }
+
+    pub fn get_traverse_circumference(&self) -> Length {
+        Length::new::<millimeter>(self.traverse_circumference)
+    }
+
+    /// Travel of the traverse per stepper revolution
+    pub fn get_traverse_travel_per_revolution(&self) -> Length {
+        self.traverse_transmission
+            .calculate_linear_output(self.get_traverse_circumference())
+    }
+
+    pub fn get_puller_wheel_diameter(&self) -> Length {
+        Length::new::<millimeter>(self.puller_wheel_diameter)
+    }
+
+    pub fn get_spool_flange_inner(&self) -> Length {
+        Length::new::<millimeter>(self.spool_flange_inner)
+    }
+
+    pub fn get_spool_flange_outer(&self) -> Length {
+        Length::new::<millimeter>(self.spool_flange_outer)
+    }
+
+    pub fn get_spool_seat(&self) -> Length {
+        Length::new::<millimeter>(self.spool_seat)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use control_core::transmission::fixed::FixedTransmission;
+
+    #[test]
+    fn test_validation() {
+        assert!(Winder2Geometry::default().is_valid());
+
+        let flanges_swapped = Winder2Geometry {
+            spool_flange_inner: 92.0,
+            spool_flange_outer: 22.0,
+            ..Default::default()
+        };
+        assert!(!flanges_swapped.is_valid());
+
+        let no_wheel = Winder2Geometry {
+            puller_wheel_diameter: f64::NAN,
+            ..Default::default()
+        };
+        assert!(!no_wheel.is_valid());
+
+        let stalled_puller = Winder2Geometry {
+            puller_transmission: MultiStageTransmission::reduction(0.0),
+            ..Default::default()
+        };
+        assert!(!stalled_puller.is_valid());
+
+        let too_many_microsteps = Winder2Geometry {
+            traverse_steps_per_revolution: 400,
+            traverse_microsteps: 128,
+            ..Default::default()
+        };
+        assert!(!too_many_microsteps.is_valid());
+    }
+
+    #[test]
+    fn test_transmissions() {
+        // Belt halving the speed in front of a 1:10 gearbox
+        let geometry = Winder2Geometry {
+            traverse_transmission: MultiStageTransmission::new(vec![
+                FixedTransmission::new(0.5),
+                FixedTransmission::new(0.1),
+            ]),
+            ..Default::default()
+        };
+        let travel = geometry.get_traverse_travel_per_revolution();
+        assert!((travel.get::<millimeter>() - 1.6).abs() < 1e-9);
+
+        let json = serde_json::to_string(&geometry).unwrap();
+        assert!(json.contains(r#""traverse_transmission":{"stages":[0.5,0.1]}"#));
+        assert_eq!(
+            serde_json::from_str::<Winder2Geometry>(&json).unwrap(),
+            geometry
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use anyhow::Result;
+use qitech_lib::units::ConstZero;
+use qitech_lib::units::f64::Length;
+use qitech_lib::units::length::meter;
+use serde::{Deserialize, Serialize};
+
+/// Calibrations over shorter lengths in m are too inaccurate
+const MIN_CALIBRATION_METERS: f64 = 1.0;
+
+/// Meters the pulled filament length from the commanded puller speed
+///
+/// The position counter of the EL70x1 only counts the commanded steps and can't reveal
+/// slip. A calibration run against a measured length corrects a steady slip of the puller.
+/// There is no measuring wheel or encoder input on the puller, so varying slip isn't detected.
+#[derive(Debug)]
+pub struct LengthMeter {
+    /// Metered length is multiplied by this, found by calibration
+    correction_factor: f64,
+    /// Uncorrected length since the calibration started
+    calibration_length: Option<Length>,
+}
+
+impl Default for LengthMeter {
+    fn default() -> Self {
+        Self {
+            correction_factor: 1.0,
+            calibration_length: None,
+        }
+    }
+}
+
+impl LengthMeter {
+    /// Length pulled since the last update
+    ///
+    /// - `commanded`: commanded speed integrated since the last update
+    pub fn update(&mut self, commanded: Length) -> Length {
+        let length = commanded.abs();
+        if let Some(calibration_length) = &mut self.calibration_length {
+            *calibration_length += length;
+        }
+        length * self.correction_factor
+    }
+
+    /// Start counting the length of a calibration run
+    pub const fn start_calibration(&mut self) {
+        self.calibration_length = Some(Length::ZERO);
+    }
+
+    pub const fn abort_calibration(&mut self) {
+        self.calibration_length = None;
+    }
+
+    /// Finish the calibration run with the actually pulled length, e.g. measured with a tape
+    ///
+    /// Returns the new correction factor.
+    pub fn finish_calibration(&mut self, actual_length: Length) -> Result<f64> {
+        let Some(metered) = self.calibration_length else {
+            return Err(anyhow::anyhow!(
+                "[{}::LengthMeter::finish_calibration] No calibration running",
+                module_path!()
+            ));
+        };
+        if metered < Length::new::<meter>(MIN_CALIBRATION_METERS) {
+            return Err(anyhow::anyhow!(
+                "[{}::LengthMeter::finish_calibration] Only {:.2} m pulled, at least {} m are needed",
+                module_path!(),
+                metered.get::<meter>(),
+                MIN_CALIBRATION_METERS
+            ));
+        }
+
+        let correction_factor = actual_length.get::<meter>() / metered.get::<meter>();
+        if !correction_factor.is_finite() || correction_factor <= 0.0 {
+            return Err(anyhow::anyhow!(
+                "[{}::LengthMeter::finish_calibration] Invalid length {:.2} m",
+                module_path!(),
+                actual_length.get::<meter>()
+            ));
+        }
+        self.calibration_length = None;
+        self.correction_factor = correction_factor;
+        Ok(correction_factor)
+    }
+
+    pub const fn get_correction_factor(&self) -> f64 {
+        self.correction_factor
+    }
+
+    pub const fn set_correction_factor(&mut self, correction_factor: f64) {
+        self.correction_factor = correction_factor;
+    }
+
+    pub const fn is_calibrating(&self) -> bool {
+        self.calibration_length.is_some()
+    }
+}
+
+/// Persisted length calibration of one winder
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LengthCalibration {
+    pub correction_factor: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn m(value: f64) -> Length {
+        Length::new::<meter>(value)
+    }
+
+    #[test]
+    fn test_calibration() {
+        let mut length_meter = LengthMeter::default();
+        assert!(length_meter.finish_calibration(m(2.0)).is_err());
+
+        length_meter.start_calibration();
+        length_meter.update(m(0.5));
+        assert!(length_meter.finish_calibration(m(0.5)).is_err());
+        length_meter.update(m(1.5));
+        assert!(length_meter.finish_calibration(m(-2.0)).is_err());
+
+        let factor = length_meter.finish_calibration(m(2.1)).unwrap();
+        assert!((factor - 1.05).abs() < 1e-9);
+        assert!(!length_meter.is_calibrating());
+        assert!((length_meter.update(m(1.0)).get::<meter>() - 1.05).abs() < 1e-9);
+    }
+}
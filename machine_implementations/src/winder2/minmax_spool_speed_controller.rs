@@ -16,7 +16,6 @@ use control_core::{
 use std::time::Instant;
 
 use qitech_lib::units::ConstZero;
-use qitech_lib::units::angle::degree;
 use qitech_lib::units::angular_acceleration::{
     radian_per_second_squared, revolution_per_minute_per_second,
 };
@@ -61,10 +60,7 @@ impl MinMaxSpoolSpeedController {
                 AngularAcceleration::ZERO,  // Will be dynamically adjusted
                 AngularVelocity::ZERO,
             ),
-            filament_calc: FilamentTensionCalculator::new(
-                Angle::new::<degree>(90.0),
-                Angle::new::<degree>(20.0),
-            ),
+            filament_calc: FilamentTensionCalculator::default(),
             speed_time_window: MovingTimeWindow::new(
                 std::time::Duration::from_secs(5),
                 10, // max samples
@@ -74,6 +70,9 @@ impl MinMaxSpoolSpeedController {
 }
 
 impl MinMaxSpoolSpeedController {
+    /// Max speed as a multiple of the winding speed when the diameter is known
+    const WINDING_SPEED_MULTIPLIER: f64 = 4.0;
+
     /// Helper method to get min speed without Option type
     fn min_speed(&self) -> AngularVelocity {
         self.acceleration_controller
@@ -88,21 +87,41 @@ impl MinMaxSpoolSpeedController {
             .unwrap_or(AngularVelocity::new::<radian_per_second>(f64::INFINITY))
     }
 
+    pub const fn get_filament_calc(&self) -> &FilamentTensionCalculator {
+        &self.filament_calc
+    }
+
     /// Calculates the desired speed based on the tension arm angle.
     /// If the arm is over it's maximum angle, the speed is set to the minimum speed.
     /// If the arm is under it's minimum angle, the speed is set to the maximum speed.
     /// If the arm is within the range, the speed is interpolated between the minimum and maximum speed based on the tension arm angle.
-    fn speed_raw(&mut self, _t: Instant, tension_arm: &TensionArm) -> AngularVelocity {
+    ///
+    /// With a diameter estimate the max speed is limited to a multiple of the winding speed,
+    /// so a loose arm on a full spool doesn't run it far faster than the line.
+    fn speed_raw(
+        &mut self,
+        _t: Instant,
+        tension_arm: &TensionArm,
+        winding_speed: Option<AngularVelocity>,
+    ) -> AngularVelocity {
         let min_speed = AngularVelocity::ZERO;
 
         // Convert puller speed to angular velocity using a magic factor
         // The factor is adjusted so that the tension arm is reasonably high when a standard spool is at its lowest diameter
 
         // Respect the configured max speed limits by taking the minimum of dynamic calculation and configured max
-        let max_speed = self.max_speed();
+        let max_speed = match winding_speed {
+            Some(winding_speed) => self
+                .max_speed()
+                .min(winding_speed * Self::WINDING_SPEED_MULTIPLIER),
+            None => self.max_speed(),
+        };
 
         // calculate filament tension
-        let tension_arm_angle = tension_arm.get_angle().unwrap();
+        // Unreadable arm, the tension arm monitor stops winding
+        let Ok(tension_arm_angle) = tension_arm.get_angle() else {
+            return min_speed;
+        };
         let tension_arm_revolution = clamp_revolution_uom(
             tension_arm_angle,
             // inverted because min angle is max tension
@@ -191,8 +210,13 @@ impl MinMaxSpoolSpeedController {
 }
 
 impl MinMaxSpoolSpeedController {
-    pub fn update_speed(&mut self, t: Instant, tension_arm: &TensionArm) -> AngularVelocity {
-        let speed = self.speed_raw(t, tension_arm);
+    pub fn update_speed(
+        &mut self,
+        t: Instant,
+        tension_arm: &TensionArm,
+        winding_speed: Option<AngularVelocity>,
+    ) -> AngularVelocity {
+        let speed = self.speed_raw(t, tension_arm, winding_speed);
         let speed = match self.enabled {
             true => speed,
             false => AngularVelocity::ZERO,
@@ -1,15 +1,29 @@
 pub mod act;
 pub mod adaptive_spool_speed_controller;
 pub mod api;
+pub mod changeover;
 pub mod clamp_revolution;
+pub mod config_store;
 pub mod emit;
 pub mod filament_tension;
+pub mod geometry;
+pub mod length_meter;
 pub mod minmax_spool_speed_controller;
 pub mod new;
+pub mod predictive_speed_algorithm;
+pub mod profile_library;
 pub mod puller_speed_controller;
+pub mod ramp_profile;
+pub mod spool_diameter_estimator;
+pub mod spool_job;
+pub mod spool_profile;
+pub mod spool_report;
 pub mod spool_speed_controller;
+pub mod step_loss_detector;
 pub mod tension_arm;
+pub mod tension_arm_monitor;
 pub mod traverse_controller;
+pub mod winding_pattern;
 
 use crate::MACHINE_WINDER_V1_7031_0030_SPOOL;
 use crate::MachineMessage;
@@ -17,22 +31,34 @@ use crate::QiTechMachine;
 use crate::{MACHINE_WINDER_V1, VENDOR_QITECH};
 use api::SpoolAutomaticActionMode;
 use api::Winder2Namespace;
+use changeover::Changeover;
 use control_core::converters::angular_step_converter::AngularStepConverter;
-use new::{PullerSpeedController, SpoolSpeedController, TensionArm, TraverseController};
+use control_core::transmission::Transmission;
+use new::{
+    LengthMeter, PullerSpeedController, RampProfileLibrary, SpoolDiameterEstimator,
+    SpoolProfileLibrary, SpoolSpeedController, TensionArm, TensionArmMonitor, TraverseController,
+    Winder2Geometry,
+};
+use postcard::{from_bytes, to_slice};
 #[cfg(not(feature = "mock-machine"))]
 use qitech_lib::ethercat_hal::io::stepper_velocity_el70x1::StepperVelocityEL70x1Device;
 use qitech_lib::units::ConstZero;
 use qitech_lib::{
-    ethercat_hal::io::digital_output::DigitalOutputDevice, machines::MachineIdentificationUnique,
+    ethercat_hal::io::digital_output::DigitalOutputDevice,
+    machines::{ConvertMachineData, MachineData, MachineIdentificationUnique},
 };
 use qitech_lib::{
     machines::MachineIdentification,
     units::{
-        Length,
+        AngularVelocity, Length,
         length::{meter, millimeter},
         velocity::meter_per_second,
     },
 };
+use serde::{Deserialize, Serialize};
+use spool_job::SpoolJob;
+use spool_report::SpoolReportWriter;
+use std::any::TypeId;
 use std::time::Instant;
 use std::{cell::RefCell, rc::Rc};
 #[cfg(not(feature = "mock-machine"))]
@@ -44,6 +70,42 @@ pub const LASER_PORT: usize = 0;
 pub const PULLER_PORT: usize = 0;
 pub const SPOOL_PORT: usize = 0;
 pub const TRAVERSE_END_STOP_PORT: usize = 0;
+/// Second output of the laser terminal, signals the operator to change the spool
+pub const CHANGEOVER_SIGNAL_PORT: usize = 1;
+
+/// Puller state shared with the other machines on the line
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct Winder2Data {
+    /// Puller speed in m/min
+    pub puller_speed: f64,
+    /// Puller regulates the diameter from the laser
+    pub diameter_regulation: bool,
+    /// Relative deviation of the puller from its target speed
+    pub puller_speed_deviation: f64,
+}
+
+impl ConvertMachineData for Winder2Data {
+    fn to_machine_data(&self, data: &mut MachineData) -> Result<(), &'static str> {
+        let serialized_bytes =
+            to_slice(self, &mut data.data).map_err(|_| "Postcard serialization failed")?;
+        data.type_id = TypeId::of::<Self>();
+        data.length = serialized_bytes.len();
+        Ok(())
+    }
+
+    fn from_machine_data(machine_data: &MachineData, out: &mut Self) -> Result<(), &'static str> {
+        if machine_data.type_id != TypeId::of::<Self>() {
+            return Err("Typeid Mismatch");
+        }
+        if machine_data.length == 0 {
+            return Err("Empty buffer data");
+        }
+        let deserialized: Self =
+            from_bytes(&machine_data.data).map_err(|_| "Postcard deserialization failed")?;
+        *out = deserialized;
+        Ok(())
+    }
+}
 
 #[derive(Debug)]
 pub struct SpoolAutomaticAction {
@@ -74,6 +136,7 @@ pub struct Winder2 {
     pub puller: Rc<RefCell<dyn StepperVelocityEL70x1Device>>,
     pub spool: Rc<RefCell<dyn StepperVelocityEL70x1Device>>,
     pub tension_arm: TensionArm,
+    pub tension_arm_monitor: TensionArmMonitor,
 
     pub laser: Rc<RefCell<dyn DigitalOutputDevice>>,
     pub laser_enabled: bool,
@@ -93,16 +156,36 @@ pub struct Winder2 {
     // control circuit arm/spool
     pub spool_speed_controller: SpoolSpeedController,
     pub spool_step_converter: AngularStepConverter,
+    pub spool_diameter_estimator: SpoolDiameterEstimator,
+    pub spool_profiles: SpoolProfileLibrary,
+    /// name of the selected spool profile, cleared when a value it sets is changed by hand
+    pub spool_profile: Option<String>,
 
     // spool automatic action state
     pub spool_automatic_action: SpoolAutomaticAction,
 
+    // production report of the current spool
+    pub spool_job: Option<SpoolJob>,
+    pub spool_report_writer: SpoolReportWriter,
+    /// id of the last saved report
+    pub last_spool_report: Option<u64>,
+
+    // automatic spool changeover
+    pub changeover: Changeover,
+
     // control circuit puller
     pub puller_speed_controller: PullerSpeedController,
+    /// ramp profiles of the puller start, e.g. one per product
+    pub ramp_profiles: RampProfileLibrary,
+    pub length_meter: LengthMeter,
     /// Will be initialized as false and set to true by emit_state
     /// This way we can signal to the client that the first state emission is a default state
     emitted_default_state: bool,
     laser_ident: Option<MachineIdentificationUnique>,
+    /// mechanics of this winder build
+    pub geometry: Winder2Geometry,
+    /// saved geometry that applies after the machine is restarted
+    pub pending_geometry: Option<Winder2Geometry>,
 }
 
 impl Winder2 {
@@ -116,6 +199,9 @@ impl Winder2 {
         machine: MACHINE_WINDER_V1_7031_0030_SPOOL,
     };
 
+    /// Below this filament tension the spool lags behind the puller
+    const RAMP_HOLD_TENSION: f64 = 0.2;
+
     /// Validates that traverse limits maintain proper constraints:
     /// - Inner limit must be smaller than outer limit
     /// - At least 0.9mm difference between inner and outer limits
@@ -123,16 +209,50 @@ impl Winder2 {
         outer > inner + Length::new::<millimeter>(0.9)
     }
 
+    /// Spool speed to stepper steps per second through the spool drivetrain
+    fn spool_speed_to_steps(&self, speed: AngularVelocity) -> f64 {
+        let motor_speed = self
+            .geometry
+            .spool_transmission
+            .calculate_angular_velocity_input(speed);
+        self.spool_step_converter
+            .angular_velocity_to_steps(motor_speed)
+    }
+
+    /// Stepper steps per second to spool speed through the spool drivetrain
+    fn steps_to_spool_speed(&self, steps_per_second: f64) -> AngularVelocity {
+        let motor_speed = self
+            .spool_step_converter
+            .steps_to_angular_velocity(steps_per_second);
+        self.geometry
+            .spool_transmission
+            .calculate_angular_velocity_output(motor_speed)
+    }
+
     pub fn sync_traverse_speed(&mut self) {
-        let traverse = &mut *self.traverse.borrow_mut();
-        self.traverse_controller
-            .update_speed(traverse, self.spool_speed_controller.get_speed());
+        let was_detected = self.traverse_controller.get_step_loss().is_detected();
+        {
+            let traverse = &mut *self.traverse.borrow_mut();
+            let line_speed = self.puller_speed_controller.get_line_speed();
+            self.traverse_controller.update_speed(
+                traverse,
+                self.spool_speed_controller.get_speed(),
+                line_speed,
+            );
+        }
+
+        // Detected while homed, cleared by homing
+        let detected = self.traverse_controller.get_step_loss().is_detected();
+        if detected != was_detected {
+            self.traverse_step_loss_changed(detected);
+        }
     }
 
     /// Can wind capability check
     pub const fn can_wind(&self) -> bool {
-        // Check if tension arm is zeroed and traverse is homed
+        // Check if tension arm is zeroed without a pending fault and traverse is homed
         self.tension_arm.zeroed
+            && self.tension_arm_monitor.get_fault().is_none()
             && self.traverse_controller.is_homed()
             && !self.traverse_controller.is_going_home()
     }
@@ -283,22 +403,36 @@ impl Winder2 {
     }
 
     pub fn calculate_spool_auto_progress_(&mut self, now: Instant) {
+        let meters_pulled_this_interval = self.meter_length(now);
+
+        self.spool_automatic_action.progress += meters_pulled_this_interval;
+        if let Some(spool_job) = &mut self.spool_job {
+            spool_job.add_length(meters_pulled_this_interval);
+        }
+    }
+
+    /// Filament length pulled since the last check
+    pub fn meter_length(&mut self, now: Instant) -> Length {
         let dt = now
             .duration_since(self.spool_automatic_action.progress_last_check)
             .as_secs_f64();
-
-        let meters_pulled_this_interval = Length::new::<meter>(
-            self.puller_speed_controller
-                .last_speed
-                .get::<meter_per_second>()
-                * dt,
-        );
-
-        self.spool_automatic_action.progress += meters_pulled_this_interval.abs();
         self.spool_automatic_action.progress_last_check = now;
+
+        let line_speed = self.puller_speed_controller.get_line_speed();
+        let commanded = Length::new::<meter>(line_speed.get::<meter_per_second>() * dt);
+        self.length_meter.update(commanded)
     }
 
     pub fn sync_puller_speed(&mut self, t: Instant) {
+        // The traverse follows the spool, so the ramp waits while the spool lags behind.
+        // An unreadable arm doesn't hold, the tension arm monitor faults and stops winding.
+        let spool_lags = self.spool_mode == SpoolMode::Wind
+            && self
+                .spool_speed_controller
+                .get_filament_tension(&self.tension_arm)
+                .is_some_and(|tension| tension < Self::RAMP_HOLD_TENSION);
+        self.puller_speed_controller.set_ramp_hold(spool_lags);
+
         let angular_velocity = self.puller_speed_controller.calc_angular_velocity(t);
         let steps_per_second = self
             .puller_speed_controller
@@ -1,8 +1,15 @@
 mod winder2_imports {
     pub use super::super::api::Winder2Namespace;
+    pub use super::super::geometry::Winder2Geometry;
+    pub use super::super::length_meter::LengthMeter;
+    pub use super::super::ramp_profile::RampProfileLibrary;
+    pub use super::super::spool_profile::SpoolProfileLibrary;
+    pub use super::super::spool_report::{SpoolReportStore, SpoolReportWriter};
     pub use super::super::tension_arm::TensionArm;
+    pub use super::super::tension_arm_monitor::TensionArmMonitor;
     pub use super::super::{Winder2, Winder2Mode};
     pub use crate::winder2::puller_speed_controller::PullerSpeedController;
+    pub use crate::winder2::spool_diameter_estimator::SpoolDiameterEstimator;
     pub use crate::winder2::spool_speed_controller::SpoolSpeedController;
     pub use crate::winder2::traverse_controller::TraverseController;
     pub use anyhow::Error;
@@ -40,7 +47,7 @@ mod winder2_imports {
     };
     pub use qitech_lib::units::ConstZero;
     pub use qitech_lib::units::f64::*;
-    pub use qitech_lib::units::length::{centimeter, meter, millimeter};
+    pub use qitech_lib::units::length::meter;
     pub use qitech_lib::units::velocity::meter_per_minute;
     pub use std::time::Instant;
 }
@@ -138,6 +145,10 @@ impl Winder2 {
         drop(b);
         interface.enable_dc_sync0(el7041.1)?;
 
+        let geometry = Winder2Geometry::load(&hw.identification.into());
+        let mut tension_arm_monitor = TensionArmMonitor::default();
+        tension_arm_monitor.set_sensor_full_scale(geometry.tension_arm_volts_per_revolution);
+
         let mut new = Self {
             api_receiver: receiver,
             api_sender: sender,
@@ -145,11 +156,18 @@ impl Winder2 {
             puller: el7031_0030.0.clone(),
             spool: el7041.0,
             laser: el2002.0,
-            tension_arm: TensionArm::new(el7031_0030.0.clone()),
+            tension_arm: TensionArm::new(
+                el7031_0030.0.clone(),
+                geometry.tension_arm_volts_per_revolution,
+            ),
+            tension_arm_monitor,
             namespace: Winder2Namespace { namespace: None },
             mode: mode.clone(),
-            spool_step_converter: AngularStepConverter::new(200),
+            spool_step_converter: AngularStepConverter::new(geometry.spool_steps_per_revolution),
             spool_speed_controller: SpoolSpeedController::new(),
+            spool_diameter_estimator: SpoolDiameterEstimator::default(),
+            spool_profiles: SpoolProfileLibrary::load(&hw.identification.into()),
+            spool_profile: None,
             last_measurement_emit: Instant::now(),
             spool_mode: mode.clone().into(),
             traverse_mode: mode.clone().into(),
@@ -157,16 +175,27 @@ impl Winder2 {
             puller_speed_controller: PullerSpeedController::new(
                 Velocity::new::<meter_per_minute>(1.0),
                 LinearStepConverter::from_diameter(
-                    200,                            // Assuming 200 steps per revolution for the puller stepper,
-                    Length::new::<centimeter>(8.0), // 8cm diameter of the puller wheel
+                    geometry.puller_steps_per_revolution,
+                    geometry.get_puller_wheel_diameter(),
                 ),
+                geometry.puller_transmission.clone(),
             ),
+            ramp_profiles: RampProfileLibrary::load(&hw.identification.into()),
+            length_meter: LengthMeter::default(),
             traverse_controller: TraverseController::new(
-                Length::new::<millimeter>(22.0), // Default inner limit
-                Length::new::<millimeter>(92.0), // Default outer limit
-                64,                              // Microsteps
+                geometry.get_spool_flange_inner(), // Default inner limit
+                geometry.get_spool_flange_outer(), // Default outer limit
+                geometry.traverse_steps_per_revolution,
+                geometry.traverse_microsteps,
+                geometry.get_traverse_travel_per_revolution(),
             ),
             emitted_default_state: false,
+            spool_job: None,
+            spool_report_writer: SpoolReportWriter::spawn(SpoolReportStore::new(
+                &hw.identification.into(),
+            ))?,
+            last_spool_report: None,
+            changeover: super::changeover::Changeover::default(),
             spool_automatic_action: super::SpoolAutomaticAction {
                 progress: Length::ZERO,
                 progress_last_check: Instant::now(),
@@ -176,8 +205,13 @@ impl Winder2 {
             machine_identification_unique: hw.identification,
             laser_enabled: false,
             laser_ident: None,
+            geometry,
+            pending_geometry: None,
         };
 
+        new.length_meter_load_calibration();
+        new.puller_load_ramp_profile_selection();
+
         // initialize events
         new.emit_state();
         Ok(new)
@@ -263,6 +297,10 @@ impl Winder2 {
         drop(b);
         interface.enable_dc_sync0(el7031_0030_spool.1)?;
 
+        let geometry = Winder2Geometry::load(&hw.identification.into());
+        let mut tension_arm_monitor = TensionArmMonitor::default();
+        tension_arm_monitor.set_sensor_full_scale(geometry.tension_arm_volts_per_revolution);
+
         let mut new = Self {
             api_receiver: receiver,
             api_sender: sender,
@@ -270,11 +308,18 @@ impl Winder2 {
             puller: el7031_0030.0.clone(),
             spool: el7031_0030_spool.0,
             laser: el2002.0,
-            tension_arm: TensionArm::new(el7031_0030.0.clone()),
+            tension_arm: TensionArm::new(
+                el7031_0030.0.clone(),
+                geometry.tension_arm_volts_per_revolution,
+            ),
+            tension_arm_monitor,
             namespace: Winder2Namespace { namespace: None },
             mode: mode.clone(),
-            spool_step_converter: AngularStepConverter::new(200),
+            spool_step_converter: AngularStepConverter::new(geometry.spool_steps_per_revolution),
             spool_speed_controller: SpoolSpeedController::new(),
+            spool_diameter_estimator: SpoolDiameterEstimator::default(),
+            spool_profiles: SpoolProfileLibrary::load(&hw.identification.into()),
+            spool_profile: None,
             last_measurement_emit: Instant::now(),
             spool_mode: mode.clone().into(),
             traverse_mode: mode.clone().into(),
@@ -282,16 +327,27 @@ impl Winder2 {
             puller_speed_controller: PullerSpeedController::new(
                 Velocity::new::<meter_per_minute>(1.0),
                 LinearStepConverter::from_diameter(
-                    200,                            // Assuming 200 steps per revolution for the puller stepper,
-                    Length::new::<centimeter>(8.0), // 8cm diameter of the puller wheel
+                    geometry.puller_steps_per_revolution,
+                    geometry.get_puller_wheel_diameter(),
                 ),
+                geometry.puller_transmission.clone(),
             ),
+            ramp_profiles: RampProfileLibrary::load(&hw.identification.into()),
+            length_meter: LengthMeter::default(),
             traverse_controller: TraverseController::new(
-                Length::new::<millimeter>(22.0), // Default inner limit
-                Length::new::<millimeter>(92.0), // Default outer limit
-                64,                              // Microsteps
+                geometry.get_spool_flange_inner(), // Default inner limit
+                geometry.get_spool_flange_outer(), // Default outer limit
+                geometry.traverse_steps_per_revolution,
+                geometry.traverse_microsteps,
+                geometry.get_traverse_travel_per_revolution(),
             ),
             emitted_default_state: false,
+            spool_job: None,
+            spool_report_writer: SpoolReportWriter::spawn(SpoolReportStore::new(
+                &hw.identification.into(),
+            ))?,
+            last_spool_report: None,
+            changeover: super::changeover::Changeover::default(),
             spool_automatic_action: super::SpoolAutomaticAction {
                 progress: Length::ZERO,
                 progress_last_check: Instant::now(),
@@ -301,8 +357,13 @@ impl Winder2 {
             machine_identification_unique: hw.identification,
             laser_enabled: false,
             laser_ident: None,
+            geometry,
+            pending_geometry: None,
         };
 
+        new.length_meter_load_calibration();
+        new.puller_load_ramp_profile_selection();
+
         // initialize events
         new.emit_state();
         Ok(new)
@@ -0,0 +1,310 @@
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use qitech_lib::units::ConstZero;
+use qitech_lib::units::f64::{Length, Velocity};
+use qitech_lib::units::length::{meter, millimeter};
+use qitech_lib::units::velocity::meter_per_second;
+
+/// Pulled length between two samples of the line history in mm
+const HISTORY_RESOLUTION_MILLIMETERS: f64 = 5.0;
+
+/// Line state when a piece of filament passed the puller
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    /// Pulled length when the sample was taken
+    pulled: Length,
+    /// Material speed of the puller
+    speed: Velocity,
+    /// Diameter the laser measured at that time in mm
+    diameter: Option<f64>,
+}
+
+/// Regulates the puller speed from the laser diameter with a transport-delay model
+///
+/// The extruder delivers a volume flow, so `diameter² × speed` is conserved while the melt
+/// is drawn. The laser sees filament that was drawn `extruder_to_laser` ago, at the puller
+/// speed of back then. The measurement is projected onto the current speed, which predicts
+/// the diameter being drawn right now without waiting for the transport delay:
+///
+/// ```text
+/// d_predicted = d_laser · √(v_drawn / v_now)
+/// ```
+///
+/// A proportional-integral correction acts on the relative cross-section error of the
+/// prediction. The integral grows per meter pulled, like the steps of
+/// [`super::puller_speed_controller::AdaptiveSpeedAlgorithm`]. A thicker filament speeds up
+/// the puller.
+#[derive(Debug, Clone)]
+pub struct PredictiveSpeedAlgorithm {
+    // config
+    /// Line length from the extruder die to the laser
+    extruder_to_laser: Length,
+    /// Line length from the laser to the puller
+    laser_to_puller: Length,
+    /// Speed change per relative cross-section error
+    proportional_gain: f64,
+    /// Speed change per relative cross-section error and meter pulled
+    integral_gain: f64,
+    /// Largest relative deviation from the base speed, `0.33` is 33 %
+    speed_delta_max: f64,
+
+    // internal state
+    /// Length pulled since the reset
+    pulled: Length,
+    /// Material speed of the last update
+    speed: Velocity,
+    last_update: Option<Instant>,
+    /// Oldest first, reaches back `extruder_to_laser + laser_to_puller`
+    history: VecDeque<Sample>,
+    integral: f64,
+    /// Pulled length of the last measurement
+    last_measurement: Option<Length>,
+    predicted_diameter: Option<f64>,
+    factor: f64,
+}
+
+impl Default for PredictiveSpeedAlgorithm {
+    fn default() -> Self {
+        Self {
+            extruder_to_laser: Length::new::<meter>(1.0),
+            laser_to_puller: Length::new::<meter>(2.0),
+            proportional_gain: 0.5,
+            integral_gain: 0.5,
+            speed_delta_max: 0.33,
+            pulled: Length::ZERO,
+            speed: Velocity::ZERO,
+            last_update: None,
+            history: VecDeque::new(),
+            integral: 0.0,
+            last_measurement: None,
+            predicted_diameter: None,
+            factor: 1.0,
+        }
+    }
+}
+
+// public interface
+impl PredictiveSpeedAlgorithm {
+    pub fn compute(&self, base_speed: Velocity) -> Velocity {
+        (base_speed * self.factor).max(Velocity::ZERO)
+    }
+
+    /// Record the material speed of the puller
+    pub fn track(&mut self, speed: Velocity, now: Instant) {
+        let speed = speed.abs();
+        if let Some(last_update) = self.last_update {
+            let dt = now.duration_since(last_update).as_secs_f64();
+            let travelled = self.speed.get::<meter_per_second>() * dt;
+            self.pulled += Length::new::<meter>(travelled);
+        }
+        self.speed = speed;
+        self.last_update = Some(now);
+
+        let resolution = Length::new::<millimeter>(HISTORY_RESOLUTION_MILLIMETERS);
+        let is_due = self
+            .history
+            .back()
+            .is_none_or(|last| self.pulled - last.pulled >= resolution);
+        if is_due {
+            self.history.push_back(Sample {
+                pulled: self.pulled,
+                speed,
+                diameter: None,
+            });
+        }
+
+        // Keep one sample older than the span so lookups at its end still succeed
+        let span = self.extruder_to_laser + self.laser_to_puller;
+        while self
+            .history
+            .get(1)
+            .is_some_and(|second| self.pulled - second.pulled > span)
+        {
+            self.history.pop_front();
+        }
+    }
+
+    /// Correct the speed factor with a laser measurement in mm
+    pub fn update_with_measurement(&mut self, current: f64, target: f64) {
+        if !(current > 0.0 && target > 0.0) {
+            return;
+        }
+        if let Some(last) = self.history.back_mut() {
+            last.diameter = Some(current);
+        }
+
+        // The puller has to move for the transport delay to be known
+        let Some(drawn) = self.sample_at(self.pulled - self.extruder_to_laser) else {
+            self.last_measurement = None;
+            return;
+        };
+        if drawn.speed <= Velocity::ZERO || self.speed <= Velocity::ZERO {
+            self.last_measurement = None;
+            return;
+        }
+
+        let speed_ratio =
+            drawn.speed.get::<meter_per_second>() / self.speed.get::<meter_per_second>();
+        let predicted = current * speed_ratio.sqrt();
+        self.predicted_diameter = Some(predicted);
+        let error = (predicted.powi(2) - target.powi(2)) / target.powi(2);
+
+        let distance = self
+            .last_measurement
+            .map_or(Length::ZERO, |last| self.pulled - last);
+        self.last_measurement = Some(self.pulled);
+        self.integral = self
+            .integral_gain
+            .mul_add(error * distance.get::<meter>(), self.integral)
+            .clamp(-self.speed_delta_max, self.speed_delta_max);
+
+        let delta = self
+            .proportional_gain
+            .mul_add(error, self.integral)
+            .clamp(-self.speed_delta_max, self.speed_delta_max);
+        self.factor = 1.0 + delta;
+    }
+
+    /// Start over from the base speed
+    pub fn reset(&mut self) {
+        self.history.clear();
+        self.pulled = Length::ZERO;
+        self.last_update = None;
+        self.integral = 0.0;
+        self.last_measurement = None;
+        self.predicted_diameter = None;
+        self.factor = 1.0;
+    }
+
+    /// Newest sample taken at or before `pulled`
+    fn sample_at(&self, pulled: Length) -> Option<Sample> {
+        if pulled < Length::ZERO {
+            return None;
+        }
+        self.history
+            .iter()
+            .rev()
+            .find(|sample| sample.pulled <= pulled)
+            .copied()
+    }
+}
+
+// getters + setters
+impl PredictiveSpeedAlgorithm {
+    /// Relative deviation of the output from the base speed
+    pub const fn deviation(&self) -> f64 {
+        self.factor - 1.0
+    }
+
+    /// Diameter being drawn at the extruder right now in mm
+    pub const fn predicted_diameter(&self) -> Option<f64> {
+        self.predicted_diameter
+    }
+
+    /// Diameter of the filament arriving at the puller in mm, measured when it passed the laser
+    pub fn puller_diameter(&self) -> Option<f64> {
+        self.sample_at(self.pulled - self.laser_to_puller)?.diameter
+    }
+
+    pub fn extruder_to_laser(&self) -> Length {
+        self.extruder_to_laser
+    }
+
+    pub fn set_extruder_to_laser(&mut self, value: Length) {
+        self.extruder_to_laser = value.max(Length::ZERO);
+    }
+
+    pub fn laser_to_puller(&self) -> Length {
+        self.laser_to_puller
+    }
+
+    pub fn set_laser_to_puller(&mut self, value: Length) {
+        self.laser_to_puller = value.max(Length::ZERO);
+    }
+
+    pub const fn proportional_gain(&self) -> f64 {
+        self.proportional_gain
+    }
+
+    pub const fn set_proportional_gain(&mut self, value: f64) {
+        self.proportional_gain = value.max(0.0);
+    }
+
+    pub const fn integral_gain(&self) -> f64 {
+        self.integral_gain
+    }
+
+    pub const fn set_integral_gain(&mut self, value: f64) {
+        self.integral_gain = value.max(0.0);
+    }
+
+    pub const fn speed_delta_max(&self) -> f64 {
+        self.speed_delta_max
+    }
+
+    pub const fn set_speed_delta_max(&mut self, value: f64) {
+        self.speed_delta_max = value.max(0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qitech_lib::units::velocity::meter_per_minute;
+    use std::time::Duration;
+
+    /// Extruder throughput of 1.75 mm filament at 10 m/min
+    const THROUGHPUT: f64 = 1.75 * 1.75 * 10.0;
+
+    /// Run the line with the laser 1 m downstream, returns the last laser reading
+    fn run(algorithm: &mut PredictiveSpeedAlgorithm, throughput: f64, seconds: u64) -> f64 {
+        let base_speed = Velocity::new::<meter_per_minute>(10.0);
+        let mut t = Instant::now();
+        let mut drawn: VecDeque<(Length, f64)> = VecDeque::new();
+        let mut pulled = Length::ZERO;
+        let mut laser = 0.0;
+        for _ in 0..seconds * 100 {
+            let speed = algorithm.compute(base_speed);
+            algorithm.track(speed, t);
+            pulled += Length::new::<meter>(speed.get::<meter_per_second>() * 0.01);
+
+            // Mass conservation at the die
+            let speed = speed.get::<meter_per_minute>();
+            drawn.push_back((pulled, (throughput / speed).sqrt()));
+            while drawn.len() > 1 && pulled - drawn[1].0 >= Length::new::<meter>(1.0) {
+                drawn.pop_front();
+            }
+            if pulled - drawn[0].0 >= Length::new::<meter>(1.0) {
+                laser = drawn[0].1;
+                algorithm.update_with_measurement(laser, 1.75);
+            }
+            t += Duration::from_millis(10);
+        }
+        laser
+    }
+
+    #[test]
+    fn test_corrects_throughput_change() {
+        let mut algorithm = PredictiveSpeedAlgorithm::default();
+        let laser = run(&mut algorithm, THROUGHPUT, 30);
+        assert!((laser - 1.75).abs() < 1e-3, "{laser}");
+        assert!(algorithm.deviation().abs() < 1e-3);
+
+        // The extruder delivers 10 % more, the puller has to run 10 % faster
+        let laser = run(&mut algorithm, THROUGHPUT * 1.1, 120);
+        assert!((laser - 1.75).abs() < 0.01, "{laser}");
+        assert!((algorithm.deviation() - 0.1).abs() < 0.01);
+        assert!(algorithm.puller_diameter().is_some());
+    }
+
+    #[test]
+    fn test_waits_for_transport_delay() {
+        let mut algorithm = PredictiveSpeedAlgorithm::default();
+        let t = Instant::now();
+        algorithm.track(Velocity::new::<meter_per_minute>(10.0), t);
+        algorithm.update_with_measurement(2.0, 1.75);
+        assert_eq!(algorithm.deviation(), 0.0);
+        assert_eq!(algorithm.predicted_diameter(), None);
+    }
+}
@@ -0,0 +1,181 @@
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use super::config_store::ConfigStore;
+use crate::machine_identification::QiTechMachineIdentificationUnique;
+
+/// Named settings a winder keeps in a [`ProfileLibrary`]
+pub trait Profile: Serialize + DeserializeOwned + Clone {
+    /// Name of the persisted custom profiles
+    const CONFIG: &'static str;
+    /// Kind of profile in messages, e.g. `spool`
+    const KIND: &'static str;
+
+    fn name(&self) -> &str;
+
+    fn is_valid(&self) -> bool;
+
+    /// Profiles that ship with the winder, they can't be replaced
+    fn builtin() -> Vec<Self>;
+
+    fn is_builtin(&self) -> bool {
+        Self::builtin().iter().any(|x| x.name() == self.name())
+    }
+}
+
+/// Built-in profiles and the custom profiles of one winder
+#[derive(Debug, Clone)]
+pub struct ProfileLibrary<T> {
+    custom: Vec<T>,
+    store: ConfigStore,
+}
+
+impl<T: Profile> ProfileLibrary<T> {
+    /// Library with the persisted custom profiles of the machine
+    pub fn load(machine: &QiTechMachineIdentificationUnique) -> Self {
+        Self::with_store(ConfigStore::new(T::CONFIG, machine))
+    }
+
+    fn with_store(store: ConfigStore) -> Self {
+        let custom = match store.load::<Vec<T>>() {
+            Ok(custom) => custom.unwrap_or_default(),
+            Err(e) => {
+                tracing::error!(
+                    "[{}::ProfileLibrary::load] Loading {} profiles failed: {:?}",
+                    module_path!(),
+                    T::KIND,
+                    e
+                );
+                Vec::new()
+            }
+        };
+        Self {
+            custom: custom.into_iter().filter(T::is_valid).collect(),
+            store,
+        }
+    }
+
+    /// Built-in profiles first
+    pub fn get_profiles(&self) -> Vec<T> {
+        let mut profiles = T::builtin();
+        profiles.extend(self.custom.iter().cloned());
+        profiles
+    }
+
+    pub fn get(&self, name: &str) -> Option<T> {
+        self.get_profiles().into_iter().find(|x| x.name() == name)
+    }
+
+    /// Add a custom profile or replace the one with the same name
+    pub fn save(&mut self, profile: T) -> anyhow::Result<()> {
+        if !profile.is_valid() {
+            return Err(anyhow::anyhow!(
+                "[{}::ProfileLibrary::save] Invalid {} profile {}",
+                module_path!(),
+                T::KIND,
+                profile.name()
+            ));
+        }
+        if profile.is_builtin() {
+            return Err(anyhow::anyhow!(
+                "[{}::ProfileLibrary::save] Built-in {} profile {} can't be replaced",
+                module_path!(),
+                T::KIND,
+                profile.name()
+            ));
+        }
+        let mut custom = self.custom.clone();
+        match custom.iter_mut().find(|x| x.name() == profile.name()) {
+            Some(existing) => *existing = profile,
+            None => custom.push(profile),
+        }
+        self.store.save(&custom)?;
+        self.custom = custom;
+        Ok(())
+    }
+
+    pub fn delete(&mut self, name: &str) -> anyhow::Result<()> {
+        let mut custom = self.custom.clone();
+        custom.retain(|x| x.name() != name);
+        if custom.len() == self.custom.len() {
+            return Err(anyhow::anyhow!(
+                "[{}::ProfileLibrary::delete] No custom {} profile {}",
+                module_path!(),
+                T::KIND,
+                name
+            ));
+        }
+        self.store.save(&custom)?;
+        self.custom = custom;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::machine_identification::MachineIdentification;
+    use crate::{MACHINE_WINDER_V1, VENDOR_QITECH};
+    use serde::Deserialize;
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    struct Named {
+        name: String,
+        value: f64,
+    }
+
+    impl Named {
+        fn new(name: &str, value: f64) -> Self {
+            Self {
+                name: name.to_string(),
+                value,
+            }
+        }
+    }
+
+    impl Profile for Named {
+        const CONFIG: &'static str = "named_profiles";
+        const KIND: &'static str = "named";
+
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn is_valid(&self) -> bool {
+            self.value > 0.0
+        }
+
+        fn builtin() -> Vec<Self> {
+            vec![Self::new("Default", 1.0)]
+        }
+    }
+
+    #[test]
+    fn test_custom_profiles() {
+        let base = std::env::temp_dir().join(format!("profile_library_{}", std::process::id()));
+        let machine = QiTechMachineIdentificationUnique {
+            machine_identification: MachineIdentification {
+                vendor: VENDOR_QITECH,
+                machine: MACHINE_WINDER_V1,
+            },
+            serial: 7,
+        };
+        let store = ConfigStore::with_dir(base.clone(), &machine);
+        let mut library = ProfileLibrary::<Named>::with_store(store.clone());
+
+        assert!(library.save(Named::new("Default", 2.0)).is_err());
+        assert!(library.save(Named::new("Custom", -1.0)).is_err());
+        library.save(Named::new("Custom", 2.0)).unwrap();
+        library.save(Named::new("Custom", 3.0)).unwrap();
+        assert_eq!(library.get_profiles().len(), 2);
+
+        // Custom profiles are persisted
+        let mut library = ProfileLibrary::<Named>::with_store(store);
+        assert_eq!(library.get("Custom"), Some(Named::new("Custom", 3.0)));
+        assert!(library.delete("Default").is_err());
+        library.delete("Custom").unwrap();
+        assert_eq!(library.get("Custom"), None);
+
+        std::fs::remove_dir_all(base).unwrap();
+    }
+}
@@ -3,6 +3,7 @@ use std::time::Instant;
 use control_core::{
     controllers::second_degree_motion::linear_jerk_speed_controller::LinearJerkSpeedController,
     converters::linear_step_converter::LinearStepConverter,
+    transmission::{Transmission, multi_stage::MultiStageTransmission},
 };
 use qitech_lib::units::ConstZero;
 use qitech_lib::units::acceleration::meter_per_minute_per_second;
@@ -13,53 +14,74 @@ use qitech_lib::units::length::{meter, millimeter};
 use qitech_lib::units::velocity::{meter_per_minute, meter_per_second};
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
-pub enum GearRatio {
-    OneToOne,
-    OneToFive,
-    OneToTen,
-}
+use super::predictive_speed_algorithm::PredictiveSpeedAlgorithm;
+use super::ramp_profile::RampProfile;
 
-impl GearRatio {
-    /// Get the speed multiplier for this gear ratio
-    pub fn multiplier(&self) -> f64 {
-        match self {
-            GearRatio::OneToOne => 1.0,
-            GearRatio::OneToFive => 5.0,
-            GearRatio::OneToTen => 10.0,
-        }
-    }
-}
+/// Material acceleration limit outside of a ramp in m/min/s
+const DEFAULT_ACCELERATION: f64 = 5.0;
 
-impl Default for GearRatio {
-    fn default() -> Self {
-        GearRatio::OneToOne
-    }
-}
+/// Material jerk limit outside of a ramp in m/min/s²
+const DEFAULT_JERK: f64 = 10.0;
+
+/// Below this material speed in m/min a held ramp keeps speeding up, a slack arm can't
+/// tighten while the line stands still
+const RAMP_HOLD_MIN_SPEED: f64 = 1.0;
+
+/// Longest hold of a ramp in s, afterwards the ramp continues even if the spool lags behind
+const RAMP_HOLD_MAX_TIME: f64 = 10.0;
 
 #[derive(Debug)]
 pub struct PullerSpeedController {
     enabled: bool,
     pub target_speed: Velocity,
+    /// Caps the material speed below the target, e.g. during a spool changeover
+    speed_limit: Option<Velocity>,
 
     pub adaptive: AdaptiveSpeedAlgorithm,
+    pub predictive: PredictiveSpeedAlgorithm,
 
     pub regulation_mode: PullerRegulationMode,
     /// Forward rotation direction. If false, applies negative sign to speed
     pub forward: bool,
-    /// Gear ratio for winding speed (1:5 or 1:10)
-    pub gear_ratio: GearRatio,
+    /// Drivetrain from the stepper (input) to the puller wheel (output)
+    transmission: MultiStageTransmission,
     /// Linear acceleration controller to dampen speed change
     acceleration_controller: LinearJerkSpeedController,
+    /// Material acceleration limit of the controller, converted with the transmission
+    acceleration: Acceleration,
+    /// Material jerk limit of the controller, converted with the transmission
+    jerk: Jerk,
     /// Converter for linear to angular transformations
     pub converter: LinearStepConverter,
+    /// Stepper speed of the last update
     pub last_speed: Velocity,
+    /// Speed fed into the acceleration controller in the last update
+    commanded_speed: Velocity,
+
+    /// Ramp up when the puller starts, `None` uses the default limits
+    ramp_profile: Option<RampProfile>,
+    /// A ramp of the profile is running
+    ramping: bool,
+    /// Keeps the ramp from speeding up further, e.g. while the spool lags behind
+    ramp_hold: bool,
+    /// Start of the current hold of the ramp
+    ramp_held_since: Option<Instant>,
+    /// The ramp was held for the longest hold time and isn't held again
+    ramp_hold_expired: bool,
+    /// Pull at the threading speed instead of the target speed
+    threading: bool,
+    threading_speed: Velocity,
 }
 
 impl PullerSpeedController {
-    pub fn new(target_speed: Velocity, converter: LinearStepConverter) -> Self {
-        let acceleration = Acceleration::new::<meter_per_minute_per_second>(5.0);
-        let jerk = Jerk::new::<meter_per_minute_per_second_squared>(10.0);
+    pub fn new(
+        target_speed: Velocity,
+        converter: LinearStepConverter,
+        transmission: MultiStageTransmission,
+    ) -> Self {
+        let acceleration = Acceleration::new::<meter_per_minute_per_second>(DEFAULT_ACCELERATION);
+        let jerk = Jerk::new::<meter_per_minute_per_second_squared>(DEFAULT_JERK);
+        let ratio = transmission.get_ratio();
         let speed = Velocity::new::<meter_per_minute>(50.0);
 
         let mut adaptive = AdaptiveSpeedAlgorithm::default();
@@ -71,34 +93,209 @@ impl PullerSpeedController {
         Self {
             enabled: false,
             target_speed,
+            speed_limit: None,
             adaptive,
+            predictive: PredictiveSpeedAlgorithm::default(),
             regulation_mode: PullerRegulationMode::Speed,
             forward: true,
-            gear_ratio: GearRatio::default(),
+            transmission,
             acceleration_controller: LinearJerkSpeedController::new_simple(
                 Some(speed),
-                acceleration,
-                jerk,
+                acceleration / ratio,
+                jerk / ratio,
             ),
+            acceleration,
+            jerk,
             converter,
             last_speed: Velocity::ZERO,
+            commanded_speed: Velocity::ZERO,
+            ramp_profile: None,
+            ramping: false,
+            ramp_hold: false,
+            ramp_held_since: None,
+            ramp_hold_expired: false,
+            threading: false,
+            threading_speed: Velocity::new::<meter_per_minute>(2.0),
         }
     }
 
-    pub const fn set_enabled(&mut self, enabled: bool) {
+    /// Starting to pull runs the ramp of the profile
+    pub fn set_enabled(&mut self, enabled: bool) {
+        let was_enabled = self.enabled;
         self.enabled = enabled;
+        if enabled && !was_enabled {
+            self.start_ramp();
+        }
+    }
+
+    pub const fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Applies to the next start, a running ramp keeps its limits
+    pub fn set_ramp_profile(&mut self, profile: Option<RampProfile>) {
+        self.ramp_profile = profile;
+    }
+
+    pub const fn get_ramp_profile(&self) -> Option<&RampProfile> {
+        self.ramp_profile.as_ref()
+    }
+
+    pub const fn is_ramping(&self) -> bool {
+        self.ramping
+    }
+
+    /// Request to hold the ramp, see [`Self::update_ramp_hold`] for when it applies
+    pub const fn set_ramp_hold(&mut self, hold: bool) {
+        self.ramp_hold = hold;
+    }
+
+    pub const fn is_ramp_held(&self) -> bool {
+        self.ramp_held_since.is_some() && !self.ramp_hold_expired
+    }
+
+    /// Leaving the threading speed ramps up to the target speed
+    pub fn set_threading(&mut self, threading: bool) {
+        let was_threading = self.threading;
+        self.threading = threading;
+        if was_threading && !threading && self.enabled {
+            self.start_ramp();
+        }
+    }
+
+    pub const fn is_threading(&self) -> bool {
+        self.threading
+    }
+
+    pub fn set_threading_speed(&mut self, speed: Velocity) {
+        self.threading_speed = speed.abs();
+    }
+
+    pub fn get_threading_speed(&self) -> Velocity {
+        self.threading_speed
+    }
+
+    /// Jump to the start speed and limit acceleration and jerk so the target speed is
+    /// reached in the ramp time of the profile
+    fn start_ramp(&mut self) {
+        self.ramp_held_since = None;
+        self.ramp_hold_expired = false;
+        let Some(profile) = self.ramp_profile.clone() else {
+            return;
+        };
+
+        let target_speed = self.ramp_target_speed();
+        let start_speed = profile.get_start_speed().min(target_speed);
+        if self.get_line_speed().abs() < start_speed {
+            let speed = self
+                .transmission
+                .calculate_linear_velocity_input(start_speed);
+            let speed = if self.forward { speed } else { -speed };
+            match self.acceleration_controller.reset(speed) {
+                Ok(()) => self.last_speed = speed,
+                Err(e) => tracing::error!(
+                    "[{}::PullerSpeedController::start_ramp] Can't start at {:.2} m/min: {:?}",
+                    module_path!(),
+                    start_speed.get::<meter_per_minute>(),
+                    e
+                ),
+            }
+        }
+
+        let from = self.get_line_speed().abs();
+        match profile.limits(from, target_speed) {
+            Some((acceleration, jerk)) => {
+                self.set_limits(acceleration, jerk);
+                self.ramping = true;
+            }
+            None => self.finish_ramp(),
+        }
+    }
+
+    /// Material speed the ramp ends at, before diameter regulation
+    fn ramp_target_speed(&self) -> Velocity {
+        let speed = match self.threading {
+            true => self.threading_speed,
+            false => self.target_speed.abs(),
+        };
+        match self.speed_limit {
+            Some(limit) => speed.min(limit),
+            None => speed,
+        }
+    }
+
+    /// A requested hold applies from the minimum hold speed on and for at most the longest
+    /// hold time of a ramp, so a slack arm at standstill can't stall the start
+    fn update_ramp_hold(&mut self, t: Instant) {
+        let min_speed = Velocity::new::<meter_per_minute>(RAMP_HOLD_MIN_SPEED);
+        if !(self.ramping && self.ramp_hold && self.get_line_speed().abs() >= min_speed) {
+            self.ramp_held_since = None;
+            return;
+        }
+
+        let since = *self.ramp_held_since.get_or_insert(t);
+        let held_time = t.duration_since(since).as_secs_f64();
+        if !self.ramp_hold_expired && held_time > RAMP_HOLD_MAX_TIME {
+            tracing::warn!(
+                "[{}::PullerSpeedController::update_ramp_hold] Ramp held for {} s, continuing",
+                module_path!(),
+                RAMP_HOLD_MAX_TIME
+            );
+            self.ramp_hold_expired = true;
+        }
+    }
+
+    fn finish_ramp(&mut self) {
+        self.ramping = false;
+        self.set_limits(
+            Acceleration::new::<meter_per_minute_per_second>(DEFAULT_ACCELERATION),
+            Jerk::new::<meter_per_minute_per_second_squared>(DEFAULT_JERK),
+        );
+    }
+
+    /// Material acceleration and jerk limits, converted to the stepper side
+    fn set_limits(&mut self, acceleration: Acceleration, jerk: Jerk) {
+        self.acceleration = acceleration.abs();
+        self.jerk = jerk.abs();
+        let ratio = self.transmission.get_ratio();
+        let acceleration = self.acceleration / ratio;
+        let jerk = self.jerk / ratio;
+        let controller = &mut self.acceleration_controller;
+        let result = controller
+            .set_max_acceleration(acceleration)
+            .and_then(|()| controller.set_min_acceleration(-acceleration))
+            .and_then(|()| controller.set_max_jerk(jerk))
+            .and_then(|()| controller.set_min_jerk(-jerk));
+        if let Err(e) = result {
+            tracing::error!(
+                "[{}::PullerSpeedController::set_limits] Invalid limits: {:?}",
+                module_path!(),
+                e
+            );
+        }
     }
 
     pub fn set_target_speed(&mut self, target: Velocity) {
         self.target_speed = target;
     }
 
+    pub fn set_speed_limit(&mut self, limit: Option<Velocity>) {
+        self.speed_limit = limit.map(|x| x.abs());
+    }
+
+    pub fn get_speed_limit(&self) -> Option<Velocity> {
+        self.speed_limit
+    }
+
     pub fn set_regulation_mode(&mut self, regulation: PullerRegulationMode) {
         // Reset adaptive modulation when switching to Diameter mode
         // so it starts from the current target_speed without jumps
         if matches!(regulation, PullerRegulationMode::Diameter) {
             self.adaptive.reset_modulation();
         }
+        if matches!(regulation, PullerRegulationMode::Predictive) {
+            self.predictive.reset();
+        }
         self.regulation_mode = regulation;
     }
 
@@ -106,31 +303,63 @@ impl PullerSpeedController {
         self.forward = forward;
     }
 
-    pub const fn set_gear_ratio(&mut self, gear_ratio: GearRatio) {
-        self.gear_ratio = gear_ratio;
+    /// The material limits are kept, the stepper limits follow the new ratio
+    pub fn set_transmission(&mut self, transmission: MultiStageTransmission) {
+        self.transmission = transmission;
+        self.set_limits(self.acceleration, self.jerk);
     }
 
-    pub const fn get_gear_ratio(&self) -> GearRatio {
-        self.gear_ratio
+    pub const fn get_transmission(&self) -> &MultiStageTransmission {
+        &self.transmission
+    }
+
+    /// Material speed of the last update, [`Self::last_speed`] is the stepper speed
+    pub fn get_line_speed(&self) -> Velocity {
+        self.transmission
+            .calculate_linear_velocity_output(self.last_speed)
     }
 
     fn update_speed(&mut self, t: Instant) -> Velocity {
         let base_speed = match self.enabled {
+            true if self.threading => self.threading_speed,
             true => match self.regulation_mode {
                 PullerRegulationMode::Speed => self.target_speed,
                 PullerRegulationMode::Diameter => self.adaptive.compute(self.target_speed),
+                PullerRegulationMode::Predictive => self.predictive.compute(self.target_speed),
             },
             false => Velocity::ZERO,
         };
+        let base_speed = match self.speed_limit {
+            Some(limit) => base_speed.min(limit),
+            None => base_speed,
+        };
 
-        // Apply gear ratio multiplier
-        let speed = base_speed * self.gear_ratio.multiplier();
+        // Material speed at the puller wheel to stepper speed
+        let speed = self
+            .transmission
+            .calculate_linear_velocity_input(base_speed);
 
         let speed = if self.forward { speed } else { -speed };
+        self.commanded_speed = speed;
+
+        // A held ramp keeps its speed but may still slow down
+        self.update_ramp_hold(t);
+        let speed = if self.is_ramp_held() && speed.abs() > self.last_speed.abs() {
+            self.last_speed
+        } else {
+            speed
+        };
 
         let speed = self.acceleration_controller.update(speed, t);
 
+        // The line history of the transport-delay model is kept in material speed
+        self.predictive
+            .track(self.transmission.calculate_linear_velocity_output(speed), t);
+
         self.last_speed = speed;
+        if self.ramping && !self.is_ramp_held() && self.is_at_speed() {
+            self.finish_ramp();
+        }
         speed
     }
 
@@ -152,6 +381,14 @@ impl PullerSpeedController {
     pub fn get_target_speed(&self) -> Velocity {
         self.target_speed
     }
+
+    /// Acceleration ramp has reached the commanded speed
+    pub fn is_at_speed(&self) -> bool {
+        let tolerance = self
+            .transmission
+            .calculate_linear_velocity_input(Velocity::new::<meter_per_minute>(0.05));
+        (self.last_speed - self.commanded_speed).abs() <= tolerance
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -159,6 +396,8 @@ pub enum PullerRegulationMode {
     #[default]
     Speed,
     Diameter,
+    /// Diameter regulation with a model of the transport delay from the extruder to the laser
+    Predictive,
 }
 
 /// Controls adaptive puller speed based on laser diameter feedback.
@@ -300,3 +539,54 @@ impl AdaptiveSpeedAlgorithm {
         self.distance_since_last_adjustment = Length::ZERO;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::winder2::profile_library::Profile;
+    use std::time::Duration;
+
+    fn line_speed(controller: &PullerSpeedController) -> f64 {
+        controller.get_line_speed().get::<meter_per_minute>()
+    }
+
+    #[test]
+    fn test_ramp_from_standstill_with_slack_arm() {
+        let mut controller = PullerSpeedController::new(
+            Velocity::new::<meter_per_minute>(10.0),
+            LinearStepConverter::from_diameter(200, Length::new::<millimeter>(80.0)),
+            MultiStageTransmission::direct(),
+        );
+        // Standard profile, starting at standstill
+        controller.set_ramp_profile(RampProfile::builtin().into_iter().next());
+        controller.set_enabled(true);
+
+        // The arm stays slack the whole time, the spool never catches up
+        let mut t = Instant::now();
+        let mut run = |controller: &mut PullerSpeedController, seconds: u64| {
+            for _ in 0..seconds * 1000 {
+                t += Duration::from_millis(1);
+                controller.set_ramp_hold(true);
+                controller.update_speed(t);
+            }
+        };
+
+        // Speeds up to the minimum hold speed and holds there
+        run(&mut controller, 5);
+        assert!(controller.is_ramp_held());
+        let held_speed = line_speed(&controller);
+        assert!(
+            (RAMP_HOLD_MIN_SPEED..3.0).contains(&held_speed),
+            "{held_speed}"
+        );
+        run(&mut controller, 4);
+        assert!(controller.is_ramp_held());
+        assert!((line_speed(&controller) - held_speed).abs() < 0.1);
+
+        // The hold expires and the ramp continues to the target speed
+        run(&mut controller, 30);
+        assert!(!controller.is_ramp_held());
+        assert!(!controller.is_ramping());
+        assert!((line_speed(&controller) - 10.0).abs() < 0.1);
+    }
+}
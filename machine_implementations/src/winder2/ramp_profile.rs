@@ -0,0 +1,131 @@
+use qitech_lib::units::acceleration::meter_per_minute_per_second;
+use qitech_lib::units::f64::{Acceleration, Jerk, Velocity};
+use qitech_lib::units::jerk::meter_per_minute_per_second_squared;
+use qitech_lib::units::velocity::meter_per_minute;
+use serde::{Deserialize, Serialize};
+
+use super::profile_library::{Profile, ProfileLibrary};
+
+/// Shortest change of the acceleration in s, used for ramps without S-curve
+const MIN_JERK_TIME: f64 = 0.05;
+
+/// How the line speeds up when the puller starts pulling
+///
+/// Speeds are material speeds in m/min, the ramp time is in s.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RampProfile {
+    pub name: String,
+    /// The puller jumps to this speed and ramps up from there
+    pub start_speed: f64,
+    /// Time from the start speed to the target speed
+    pub ramp_time: f64,
+    /// Share of the ramp time in which the acceleration changes, `0.0` is a linear ramp
+    /// and `1.0` a full S-curve
+    pub s_curve: f64,
+}
+
+impl Profile for RampProfile {
+    const CONFIG: &'static str = "ramp_profiles";
+    const KIND: &'static str = "ramp";
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn is_valid(&self) -> bool {
+        !self.name.trim().is_empty()
+            && self.start_speed.is_finite()
+            && self.start_speed >= 0.0
+            && self.ramp_time.is_finite()
+            && self.ramp_time > 0.0
+            && (0.0..=1.0).contains(&self.s_curve)
+    }
+
+    fn builtin() -> Vec<Self> {
+        vec![
+            Self::new("Standard", 0.0, 10.0, 0.5),
+            Self::new("Gentle", 1.0, 30.0, 1.0),
+        ]
+    }
+}
+
+impl RampProfile {
+    fn new(name: &str, start_speed: f64, ramp_time: f64, s_curve: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            start_speed,
+            ramp_time,
+            s_curve,
+        }
+    }
+
+    pub fn get_start_speed(&self) -> Velocity {
+        Velocity::new::<meter_per_minute>(self.start_speed)
+    }
+
+    /// Acceleration and jerk limits that ramp from `from` to `to` in the ramp time
+    ///
+    /// The acceleration changes at the start and the end of the ramp, each for half of the
+    /// S-curve share. `None` if there is nothing to ramp.
+    pub fn limits(&self, from: Velocity, to: Velocity) -> Option<(Acceleration, Jerk)> {
+        let delta = (to - from).abs().get::<meter_per_minute>();
+        if !self.is_valid() || !delta.is_normal() {
+            return None;
+        }
+
+        let acceleration = delta / (self.ramp_time * (1.0 - self.s_curve / 2.0));
+        let jerk_time = (self.s_curve * self.ramp_time / 2.0).max(MIN_JERK_TIME);
+        Some((
+            Acceleration::new::<meter_per_minute_per_second>(acceleration),
+            Jerk::new::<meter_per_minute_per_second_squared>(acceleration / jerk_time),
+        ))
+    }
+}
+
+/// Built-in ramp profiles and the custom profiles of one winder, e.g. one per product
+pub type RampProfileLibrary = ProfileLibrary<RampProfile>;
+
+/// Persisted ramp profile selection of one winder
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RampProfileSelection {
+    pub name: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mpm(value: f64) -> Velocity {
+        Velocity::new::<meter_per_minute>(value)
+    }
+
+    #[test]
+    fn test_limits() {
+        // Linear ramp, the acceleration changes as fast as allowed
+        let profile = RampProfile::new("Linear", 0.0, 10.0, 0.0);
+        let (acceleration, jerk) = profile.limits(mpm(0.0), mpm(20.0)).unwrap();
+        let acceleration = acceleration.get::<meter_per_minute_per_second>();
+        assert!((acceleration - 2.0).abs() < 1e-9);
+        let jerk = jerk.get::<meter_per_minute_per_second_squared>();
+        assert!((jerk - 2.0 / MIN_JERK_TIME).abs() < 1e-9);
+
+        // Full S-curve, the peak acceleration is twice the one of a linear ramp
+        let profile = RampProfile::new("S", 0.0, 10.0, 1.0);
+        let (acceleration, jerk) = profile.limits(mpm(20.0), mpm(0.0)).unwrap();
+        let acceleration = acceleration.get::<meter_per_minute_per_second>();
+        assert!((acceleration - 4.0).abs() < 1e-9);
+        let jerk = jerk.get::<meter_per_minute_per_second_squared>();
+        assert!((jerk - 0.8).abs() < 1e-9);
+
+        assert!(profile.limits(mpm(5.0), mpm(5.0)).is_none());
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(RampProfile::builtin().iter().all(RampProfile::is_valid));
+        assert!(!RampProfile::new("Instant", 0.0, 0.0, 0.5).is_valid());
+        assert!(!RampProfile::new("Reverse", -1.0, 10.0, 0.5).is_valid());
+        assert!(!RampProfile::new("Overshoot", 0.0, 10.0, 1.5).is_valid());
+        assert!(!RampProfile::new(" ", 0.0, 10.0, 0.5).is_valid());
+    }
+}
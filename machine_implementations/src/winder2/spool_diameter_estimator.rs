@@ -0,0 +1,385 @@
+use std::f64::consts::PI;
+use std::time::{Duration, Instant};
+
+use qitech_lib::units::ConstZero;
+use qitech_lib::units::angle::radian;
+use qitech_lib::units::angular_velocity::radian_per_second;
+use qitech_lib::units::f64::{Angle, AngularVelocity, Length, Velocity};
+use qitech_lib::units::length::{centimeter, meter, millimeter};
+use qitech_lib::units::velocity::meter_per_second;
+
+use super::filament_tension::FilamentTensionCalculator;
+
+/// Below this spool speed a radius measurement is too noisy to be used
+const MIN_SPOOL_SPEED_RAD_S: f64 = 0.5;
+
+/// Line speed measurement noise in m/s
+const LINE_SPEED_NOISE: f64 = 0.005;
+
+/// Process noise of the radial growth rate in m²/s³
+const GROWTH_NOISE: f64 = 1e-9;
+
+/// Estimates the wound spool radius with a Kalman filter.
+///
+/// # State
+/// - radius `r` in m
+/// - radial growth rate `ṙ` in m/s, modelled as a random walk
+///
+/// # Measurement
+/// The spool surface takes up the line and whatever the tension arm releases:
+///
+/// ```text
+/// r = (v_line + k · dθ/dt) / ω
+/// ```
+///
+/// with `k` the filament the arm releases per radian at its current angle, from the arm
+/// geometry of the [`FilamentTensionCalculator`]. The noise of the line
+/// speed maps to `σ_v / ω`, so slow revolutions barely move the estimate.
+///
+/// Wound length, mass and capacity follow from the integrated line speed. The wound
+/// length is proportional to the wound cross section `r² - r_core²`, which extrapolates
+/// the capacity up to the full diameter.
+#[derive(Debug)]
+pub struct SpoolDiameterEstimator {
+    // configuration
+    core_diameter: Length,
+    full_diameter: Length,
+    filament_diameter: Length,
+    /// Filament density in g/cm³
+    filament_density: f64,
+    /// Arm geometry, gives the filament released per radian of arm movement
+    filament_calc: FilamentTensionCalculator,
+    /// Largest radius standard deviation at which the estimate is used for control
+    confidence_limit: Length,
+
+    // filter state
+    radius: f64,
+    growth_rate: f64,
+    covariance: [[f64; 2]; 2],
+    wound_length: Length,
+    last_arm_angle: Option<Angle>,
+    last_update: Option<Instant>,
+}
+
+impl Default for SpoolDiameterEstimator {
+    fn default() -> Self {
+        Self::new(&FilamentTensionCalculator::default())
+    }
+}
+
+impl SpoolDiameterEstimator {
+    pub fn new(filament_calc: &FilamentTensionCalculator) -> Self {
+        let mut estimator = Self {
+            core_diameter: Length::new::<millimeter>(85.0),
+            full_diameter: Length::new::<millimeter>(200.0),
+            filament_diameter: Length::new::<millimeter>(1.75),
+            filament_density: 1.24,
+            filament_calc: filament_calc.clone(),
+            confidence_limit: Length::new::<millimeter>(2.0),
+            radius: 0.0,
+            growth_rate: 0.0,
+            covariance: [[0.0; 2]; 2],
+            wound_length: Length::ZERO,
+            last_arm_angle: None,
+            last_update: None,
+        };
+        estimator.reset();
+        estimator
+    }
+
+    /// Start over on an empty spool
+    pub fn reset(&mut self) {
+        self.radius = self.core_radius();
+        self.growth_rate = 0.0;
+        // The core may be wound already, allow the first measurements to pull the estimate
+        let initial_deviation = (self.full_radius() - self.core_radius()) / 2.0;
+        self.covariance = [[initial_deviation.powi(2), 0.0], [0.0, 1e-8]];
+        self.wound_length = Length::ZERO;
+        self.last_arm_angle = None;
+        self.last_update = None;
+    }
+
+    /// Advance the filter
+    ///
+    /// - `line_speed`: filament speed at the puller
+    /// - `spool_speed`: measured spool speed
+    /// - `arm_angle`: tension arm angle, `None` if it can't be read
+    pub fn update(
+        &mut self,
+        line_speed: Velocity,
+        spool_speed: AngularVelocity,
+        arm_angle: Option<Angle>,
+        t: Instant,
+    ) {
+        let dt = match self.last_update {
+            Some(last) => t.duration_since(last).as_secs_f64(),
+            None => 0.0,
+        };
+        self.last_update = Some(t);
+
+        let arm_rate = match (self.last_arm_angle, arm_angle) {
+            (Some(last), Some(angle)) if dt > 0.0 => {
+                // Shortest way around, the angle wraps at one revolution
+                let delta = (angle - last).get::<radian>();
+                let delta = (delta + PI).rem_euclid(2.0 * PI) - PI;
+                Some(delta / dt)
+            }
+            _ => None,
+        };
+        self.last_arm_angle = arm_angle;
+
+        self.predict(dt);
+
+        let line_speed = line_speed.get::<meter_per_second>().abs();
+        let spool_speed = spool_speed.get::<radian_per_second>().abs();
+        if spool_speed < MIN_SPOOL_SPEED_RAD_S {
+            return;
+        }
+        self.wound_length += Length::new::<meter>(line_speed * dt);
+
+        // Without the arm movement the slack it takes up shows up as radius error
+        let (Some(arm_rate), Some(arm_angle)) = (arm_rate, arm_angle) else {
+            return;
+        };
+        let released = self
+            .filament_calc
+            .calc_released_per_radian(arm_angle)
+            .get::<meter>()
+            * arm_rate;
+        let measurement = (line_speed + released) / spool_speed;
+        let noise = (LINE_SPEED_NOISE / spool_speed).powi(2);
+        self.correct(measurement, noise);
+    }
+
+    fn predict(&mut self, dt: f64) {
+        if dt <= 0.0 {
+            return;
+        }
+        let [[p00, p01], [p10, p11]] = self.covariance;
+
+        self.radius += self.growth_rate * dt;
+
+        // P = F P Fᵀ + Q with F = [[1, dt], [0, 1]]
+        let q = GROWTH_NOISE;
+        self.covariance = [
+            [
+                p00 + dt * (p01 + p10) + dt * dt * p11 + q * dt.powi(3) / 3.0,
+                p01 + dt * p11 + q * dt.powi(2) / 2.0,
+            ],
+            [p10 + dt * p11 + q * dt.powi(2) / 2.0, p11 + q * dt],
+        ];
+    }
+
+    fn correct(&mut self, measurement: f64, noise: f64) {
+        let [[p00, p01], [p10, p11]] = self.covariance;
+
+        // H = [1, 0]
+        let innovation_covariance = p00 + noise;
+        let gain = [p00 / innovation_covariance, p10 / innovation_covariance];
+        let innovation = measurement - self.radius;
+
+        self.radius += gain[0] * innovation;
+        self.growth_rate += gain[1] * innovation;
+        self.covariance = [
+            [(1.0 - gain[0]) * p00, (1.0 - gain[0]) * p01],
+            [p10 - gain[1] * p00, p11 - gain[1] * p01],
+        ];
+
+        // The spool can't be smaller than its core
+        self.radius = self.radius.max(self.core_radius());
+    }
+
+    fn core_radius(&self) -> f64 {
+        self.core_diameter.get::<meter>() / 2.0
+    }
+
+    fn full_radius(&self) -> f64 {
+        self.full_diameter.get::<meter>() / 2.0
+    }
+}
+
+// estimates
+impl SpoolDiameterEstimator {
+    pub fn get_diameter(&self) -> Length {
+        Length::new::<meter>(2.0 * self.radius)
+    }
+
+    /// Standard deviation of the diameter
+    pub fn get_diameter_uncertainty(&self) -> Length {
+        Length::new::<meter>(2.0 * self.covariance[0][0].max(0.0).sqrt())
+    }
+
+    /// Radius once the estimate is certain enough to control with
+    pub fn get_confident_radius(&self) -> Option<Length> {
+        let deviation = Length::new::<meter>(self.covariance[0][0].max(0.0).sqrt());
+        (deviation <= self.confidence_limit).then(|| Length::new::<meter>(self.radius))
+    }
+
+    pub fn get_wound_length(&self) -> Length {
+        self.wound_length
+    }
+
+    /// Wound filament mass in g
+    pub fn get_filament_mass(&self) -> f64 {
+        self.filament_mass(self.wound_length)
+    }
+
+    /// Mass of `length` filament in g
+    pub fn filament_mass(&self, length: Length) -> f64 {
+        let radius_cm = self.filament_diameter.get::<centimeter>() / 2.0;
+        let length_cm = length.get::<centimeter>();
+        PI * radius_cm.powi(2) * length_cm * self.filament_density
+    }
+
+    /// Filament length that still fits until the full diameter is reached
+    ///
+    /// `None` until enough is wound to extrapolate.
+    pub fn get_remaining_capacity(&self) -> Option<Length> {
+        let core = self.core_radius().powi(2);
+        let wound_area = self.radius.powi(2) - core;
+        // Less than a millimeter of windings is not enough to extrapolate
+        if wound_area <= (self.core_radius() + 0.001).powi(2) - core {
+            return None;
+        }
+        let full_area = self.full_radius().powi(2) - core;
+        let full_length = self.wound_length * (full_area / wound_area);
+        Some((full_length - self.wound_length).max(Length::ZERO))
+    }
+
+    /// Time until the full diameter is reached at `line_speed`
+    pub fn get_time_to_full(&self, line_speed: Velocity) -> Option<Duration> {
+        let line_speed = line_speed.get::<meter_per_second>().abs();
+        if line_speed <= f64::EPSILON {
+            return None;
+        }
+        let remaining = self.get_remaining_capacity()?.get::<meter>();
+        Duration::try_from_secs_f64(remaining / line_speed).ok()
+    }
+}
+
+// getters + setters
+impl SpoolDiameterEstimator {
+    pub fn get_core_diameter(&self) -> Length {
+        self.core_diameter
+    }
+
+    /// Applies on the next reset
+    pub fn set_core_diameter(&mut self, diameter: Length) {
+        self.core_diameter = diameter;
+    }
+
+    pub fn get_full_diameter(&self) -> Length {
+        self.full_diameter
+    }
+
+    pub fn set_full_diameter(&mut self, diameter: Length) {
+        self.full_diameter = diameter;
+    }
+
+    pub fn get_filament_diameter(&self) -> Length {
+        self.filament_diameter
+    }
+
+    pub fn set_filament_diameter(&mut self, diameter: Length) {
+        self.filament_diameter = diameter;
+    }
+
+    pub const fn get_filament_density(&self) -> f64 {
+        self.filament_density
+    }
+
+    pub const fn set_filament_density(&mut self, density: f64) {
+        self.filament_density = density;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qitech_lib::units::angle::degree;
+
+    const DT: Duration = Duration::from_millis(10);
+
+    fn winding(
+        estimator: &mut SpoolDiameterEstimator,
+        radius: f64,
+        seconds: u32,
+        start: Instant,
+    ) -> Instant {
+        let line_speed = 0.5;
+        let mut t = start;
+        for _ in 0..seconds * 100 {
+            t += DT;
+            estimator.update(
+                Velocity::new::<meter_per_second>(line_speed),
+                AngularVelocity::new::<radian_per_second>(line_speed / radius),
+                Some(Angle::new::<degree>(45.0)),
+                t,
+            );
+        }
+        t
+    }
+
+    #[test]
+    fn test_converges_to_wound_radius() {
+        let mut estimator = SpoolDiameterEstimator::default();
+        assert!(estimator.get_confident_radius().is_none());
+
+        let t = winding(&mut estimator, 0.06, 5, Instant::now());
+        let radius = estimator.get_confident_radius().unwrap().get::<meter>();
+        assert!((radius - 0.06).abs() < 0.001);
+        assert!((estimator.get_diameter().get::<millimeter>() - 120.0).abs() < 2.0);
+
+        // Follows the growing spool
+        winding(&mut estimator, 0.07, 20, t);
+        assert!((estimator.get_diameter().get::<millimeter>() - 140.0).abs() < 2.0);
+    }
+
+    #[test]
+    fn test_arm_movement_is_not_taken_as_radius() {
+        let mut estimator = SpoolDiameterEstimator::default();
+        let calc = FilamentTensionCalculator::default();
+        let radius = 0.06;
+        let line_speed = 0.5;
+        // 10°/s toward the loose end, the spool takes up the released filament as well
+        let arm_rate = Angle::new::<degree>(10.0).get::<radian>();
+
+        let mut t = Instant::now();
+        for i in 0..500 {
+            t += DT;
+            let arm_angle = Angle::new::<degree>(30.0 + 0.1 * f64::from(i));
+            let released = calc.calc_released_per_radian(arm_angle).get::<meter>() * arm_rate;
+            estimator.update(
+                Velocity::new::<meter_per_second>(line_speed),
+                AngularVelocity::new::<radian_per_second>((line_speed + released) / radius),
+                Some(arm_angle),
+                t,
+            );
+        }
+        let estimate = estimator.get_confident_radius().unwrap().get::<meter>();
+        assert!((estimate - radius).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_capacity_extrapolates_wound_length() {
+        let mut estimator = SpoolDiameterEstimator::default();
+        estimator.set_core_diameter(Length::new::<millimeter>(100.0));
+        estimator.set_full_diameter(Length::new::<millimeter>(200.0));
+        estimator.reset();
+        assert!(estimator.get_remaining_capacity().is_none());
+
+        estimator.radius = 0.00625_f64.sqrt();
+        estimator.wound_length = Length::new::<meter>(100.0);
+        // Half of the cross section is wound
+        let remaining = estimator.get_remaining_capacity().unwrap().get::<meter>();
+        assert!((remaining - 100.0).abs() < 0.5);
+
+        let time = estimator
+            .get_time_to_full(Velocity::new::<meter_per_second>(1.0))
+            .unwrap();
+        assert!((time.as_secs_f64() - remaining).abs() < 1e-6);
+
+        // 100 m of 1.75 mm filament at 1.24 g/cm³
+        assert!((estimator.get_filament_mass() - 298.2).abs() < 0.1);
+    }
+}
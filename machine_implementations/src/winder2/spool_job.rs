@@ -0,0 +1,213 @@
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use qitech_lib::units::ConstZero;
+use qitech_lib::units::f64::Length;
+use qitech_lib::units::length::meter;
+
+use super::spool_report::{DiameterStatistics, SpoolReport};
+use crate::machine_identification::QiTechMachineIdentificationUnique;
+
+/// Below this the laser sees no filament
+const DIAMETER_EPSILON: f64 = 0.0001;
+
+/// Tolerance band of the laser, all in mm
+#[derive(Debug, Clone, Copy)]
+struct ToleranceBand {
+    target: f64,
+    lower_tolerance: f64,
+    higher_tolerance: f64,
+}
+
+impl ToleranceBand {
+    fn contains(&self, diameter: f64) -> bool {
+        let top = self.target + self.higher_tolerance;
+        let bottom = self.target - self.lower_tolerance;
+        (bottom..=top).contains(&diameter)
+    }
+}
+
+/// Production data of the spool that is currently wound
+///
+/// Diameter statistics are weighted by length, every wound meter is attributed to the
+/// laser measurement that was current while it passed.
+#[derive(Debug)]
+pub struct SpoolJob {
+    /// Start time in ms since the unix epoch, becomes the report id
+    started_at: u64,
+    start: Instant,
+    length: Length,
+
+    /// Last laser measurement in mm
+    diameter: Option<f64>,
+    tolerance_band: Option<ToleranceBand>,
+    /// Length wound while a diameter measurement was available
+    measured_length: Length,
+    /// Integral of the diameter over the measured length in mm·m
+    diameter_integral: f64,
+    diameter_min: f64,
+    diameter_max: f64,
+    out_of_tolerance_length: Length,
+}
+
+impl SpoolJob {
+    pub fn start(now: Instant) -> Self {
+        Self {
+            started_at: unix_millis(),
+            start: now,
+            length: Length::ZERO,
+            diameter: None,
+            tolerance_band: None,
+            measured_length: Length::ZERO,
+            diameter_integral: 0.0,
+            diameter_min: f64::INFINITY,
+            diameter_max: f64::NEG_INFINITY,
+            out_of_tolerance_length: Length::ZERO,
+        }
+    }
+
+    /// Latest laser measurement in mm
+    pub fn record_diameter(
+        &mut self,
+        diameter: f64,
+        target: f64,
+        lower_tolerance: f64,
+        higher_tolerance: f64,
+    ) {
+        self.diameter = (diameter >= DIAMETER_EPSILON).then_some(diameter);
+        self.tolerance_band = Some(ToleranceBand {
+            target,
+            lower_tolerance,
+            higher_tolerance,
+        });
+    }
+
+    /// Laser is no longer available
+    pub const fn clear_diameter(&mut self) {
+        self.diameter = None;
+    }
+
+    /// Count wound filament
+    pub fn add_length(&mut self, length: Length) {
+        let length = length.abs();
+        if length <= Length::ZERO {
+            return;
+        }
+        self.length += length;
+
+        let Some(diameter) = self.diameter else {
+            return;
+        };
+        self.measured_length += length;
+        self.diameter_integral += diameter * length.get::<meter>();
+        self.diameter_min = self.diameter_min.min(diameter);
+        self.diameter_max = self.diameter_max.max(diameter);
+        let in_tolerance = self
+            .tolerance_band
+            .is_none_or(|band| band.contains(diameter));
+        if !in_tolerance {
+            self.out_of_tolerance_length += length;
+        }
+    }
+
+    pub const fn get_started_at(&self) -> u64 {
+        self.started_at
+    }
+
+    pub fn get_length(&self) -> Length {
+        self.length
+    }
+
+    pub fn get_out_of_tolerance_length(&self) -> Length {
+        self.out_of_tolerance_length
+    }
+
+    /// Close the job
+    ///
+    /// - `filament_mass`: wound mass in g
+    pub fn finish(
+        self,
+        now: Instant,
+        machine: QiTechMachineIdentificationUnique,
+        filament_mass: f64,
+    ) -> SpoolReport {
+        SpoolReport {
+            id: self.started_at,
+            machine,
+            started_at: self.started_at,
+            finished_at: unix_millis().max(self.started_at),
+            duration: now.duration_since(self.start).as_secs_f64(),
+            length: self.length.get::<meter>(),
+            filament_mass,
+            diameter: self.diameter_statistics(),
+            out_of_tolerance_length: self.out_of_tolerance_length.get::<meter>(),
+        }
+    }
+
+    fn diameter_statistics(&self) -> Option<DiameterStatistics> {
+        let measured_length = self.measured_length.get::<meter>();
+        if measured_length <= 0.0 {
+            return None;
+        }
+        // Last tolerance band, it rarely changes during a spool
+        let band = self.tolerance_band;
+        Some(DiameterStatistics {
+            average: self.diameter_integral / measured_length,
+            min: self.diameter_min,
+            max: self.diameter_max,
+            target: band.map(|x| x.target),
+            lower_tolerance: band.map(|x| x.lower_tolerance),
+            higher_tolerance: band.map(|x| x.higher_tolerance),
+        })
+    }
+}
+
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|x| x.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::machine_identification::MachineIdentification;
+
+    fn m(value: f64) -> Length {
+        Length::new::<meter>(value)
+    }
+
+    #[test]
+    fn test_statistics_are_length_weighted() {
+        let mut job = SpoolJob::start(Instant::now());
+
+        // Not measured yet
+        job.add_length(m(5.0));
+
+        job.record_diameter(1.75, 1.75, 0.05, 0.05);
+        job.add_length(m(30.0));
+        job.record_diameter(1.85, 1.75, 0.05, 0.05);
+        job.add_length(m(10.0));
+
+        // No filament in the laser
+        job.record_diameter(0.0, 1.75, 0.05, 0.05);
+        job.add_length(m(5.0));
+
+        assert!((job.get_length().get::<meter>() - 50.0).abs() < 1e-9);
+        assert!((job.get_out_of_tolerance_length().get::<meter>() - 10.0).abs() < 1e-9);
+
+        let machine = QiTechMachineIdentificationUnique {
+            machine_identification: MachineIdentification {
+                vendor: 1,
+                machine: 2,
+            },
+            serial: 3,
+        };
+        let report = job.finish(Instant::now(), machine, 100.0);
+        let diameter = report.diameter.unwrap();
+        assert!((diameter.average - 1.775).abs() < 1e-9);
+        assert_eq!(diameter.min, 1.75);
+        assert_eq!(diameter.max, 1.85);
+        assert_eq!(diameter.target, Some(1.75));
+    }
+}
@@ -0,0 +1,214 @@
+use std::f64::consts::PI;
+
+use qitech_lib::units::f64::Length;
+use qitech_lib::units::length::{centimeter, millimeter};
+use serde::{Deserialize, Serialize};
+
+use super::Winder2;
+use super::profile_library::{Profile, ProfileLibrary};
+
+/// Windings stop this far in mm below the flange rim
+const FLANGE_RIM: f64 = 5.0;
+
+/// Dimensions of a spool type
+///
+/// Lengths are in mm, the mass in g.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SpoolProfile {
+    pub name: String,
+    /// Diameter of the empty core
+    pub core_diameter: f64,
+    /// Outer diameter of the flanges
+    pub flange_diameter: f64,
+    /// Winding width between the flanges
+    pub inner_width: f64,
+    /// Width over both flanges
+    pub outer_width: f64,
+    /// Filament mass the spool is sold with
+    pub max_filament_mass: f64,
+}
+
+/// Traverse limits, padding and capacity of a profile for a filament
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpoolProfileFit {
+    pub limit_inner: Length,
+    pub limit_outer: Length,
+    pub padding: Length,
+    /// Filament length until either the flange rim or the maximum mass is reached
+    pub capacity: Length,
+}
+
+impl Profile for SpoolProfile {
+    const CONFIG: &'static str = "spool_profiles";
+    const KIND: &'static str = "spool";
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn is_valid(&self) -> bool {
+        let positive = [
+            self.core_diameter,
+            self.flange_diameter,
+            self.inner_width,
+            self.outer_width,
+            self.max_filament_mass,
+        ]
+        .iter()
+        .all(|x| x.is_finite() && *x > 0.0);
+
+        positive
+            && !self.name.trim().is_empty()
+            && self.get_full_diameter() > self.get_core_diameter()
+            && self.outer_width >= self.inner_width
+    }
+
+    fn builtin() -> Vec<Self> {
+        vec![
+            Self::new("1 kg (200 mm)", 85.0, 200.0, 70.0, 76.0, 1000.0),
+            Self::new("250 g (140 mm)", 52.0, 140.0, 40.0, 45.0, 250.0),
+            Self::new("500 g (160 mm)", 60.0, 160.0, 55.0, 61.0, 500.0),
+            Self::new("2 kg (250 mm)", 100.0, 250.0, 85.0, 93.0, 2000.0),
+        ]
+    }
+}
+
+impl SpoolProfile {
+    fn new(
+        name: &str,
+        core_diameter: f64,
+        flange_diameter: f64,
+        inner_width: f64,
+        outer_width: f64,
+        max_filament_mass: f64,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            core_diameter,
+            flange_diameter,
+            inner_width,
+            outer_width,
+            max_filament_mass,
+        }
+    }
+
+    pub fn get_core_diameter(&self) -> Length {
+        Length::new::<millimeter>(self.core_diameter)
+    }
+
+    /// Diameter the windings may reach
+    pub fn get_full_diameter(&self) -> Length {
+        Length::new::<millimeter>(2.0f64.mul_add(-FLANGE_RIM, self.flange_diameter))
+    }
+
+    /// Fit the profile to the winder and the filament
+    ///
+    /// `seat` is where the outer face of the inner flange sits, see
+    /// [`super::geometry::Winder2Geometry::spool_seat`]. `None` if the limits are invalid.
+    pub fn fit(
+        &self,
+        seat: Length,
+        filament_diameter: Length,
+        filament_density: f64,
+    ) -> Option<SpoolProfileFit> {
+        let flange = Length::new::<millimeter>((self.outer_width - self.inner_width) / 2.0);
+        let limit_inner = seat + flange;
+        let limit_outer = limit_inner + Length::new::<millimeter>(self.inner_width);
+        if !self.is_valid() || !Winder2::validate_traverse_limits(limit_inner, limit_outer) {
+            return None;
+        }
+
+        Some(SpoolProfileFit {
+            limit_inner,
+            limit_outer,
+            // The filament center turns half a diameter from the flange
+            padding: filament_diameter / 2.0,
+            capacity: self.capacity(filament_diameter, filament_density)?,
+        })
+    }
+
+    /// Filament length until either the flange rim or the maximum mass is reached
+    ///
+    /// Every winding takes up a square of the filament diameter in the cross section.
+    fn capacity(&self, filament_diameter: Length, filament_density: f64) -> Option<Length> {
+        let filament = filament_diameter.get::<centimeter>();
+        if !(filament > 0.0 && filament_density > 0.0) {
+            return None;
+        }
+        let core = self.get_core_diameter().get::<centimeter>();
+        let full = self.get_full_diameter().get::<centimeter>();
+        let width = Length::new::<millimeter>(self.inner_width).get::<centimeter>();
+
+        let wound_volume = PI / 4.0 * core.mul_add(-core, full.powi(2)) * width;
+        let volume_length = wound_volume / filament.powi(2);
+
+        let filament_area = PI / 4.0 * filament.powi(2);
+        let mass_length = self.max_filament_mass / (filament_density * filament_area);
+
+        Some(Length::new::<centimeter>(volume_length.min(mass_length)))
+    }
+}
+
+/// Built-in spool profiles and the custom profiles of one winder
+pub type SpoolProfileLibrary = ProfileLibrary<SpoolProfile>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qitech_lib::units::length::meter;
+
+    #[test]
+    fn test_fit() {
+        let profile = SpoolProfile::builtin().remove(0);
+        let fit = profile
+            .fit(
+                Length::new::<millimeter>(19.0),
+                Length::new::<millimeter>(1.75),
+                1.24,
+            )
+            .unwrap();
+        assert!((fit.limit_inner.get::<millimeter>() - 22.0).abs() < 1e-9);
+        assert!((fit.limit_outer.get::<millimeter>() - 92.0).abs() < 1e-9);
+        assert!((fit.padding.get::<millimeter>() - 0.875).abs() < 1e-9);
+
+        // 1 kg of 1.75 mm PLA is about 335 m and fits below the rim
+        let capacity = fit.capacity.get::<meter>();
+        assert!((330.0..340.0).contains(&capacity), "{capacity}");
+
+        // Thick filament fills the spool before the mass is reached
+        let fit = profile
+            .fit(
+                Length::new::<millimeter>(19.0),
+                Length::new::<millimeter>(3.0),
+                0.5,
+            )
+            .unwrap();
+        let capacity = fit.capacity.get::<meter>();
+        assert!(
+            capacity < 1000.0 / (0.5 * PI / 4.0 * 0.09) / 100.0,
+            "{capacity}"
+        );
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(SpoolProfile::builtin().iter().all(SpoolProfile::is_valid));
+
+        let mut profile = SpoolProfile::builtin().remove(0);
+        profile.outer_width = 60.0;
+        assert!(!profile.is_valid());
+
+        let mut profile = SpoolProfile::builtin().remove(0);
+        profile.flange_diameter = profile.core_diameter + 5.0;
+        assert!(!profile.is_valid());
+        assert!(
+            profile
+                .fit(
+                    Length::new::<millimeter>(19.0),
+                    Length::new::<millimeter>(1.75),
+                    1.24
+                )
+                .is_none()
+        );
+    }
+}
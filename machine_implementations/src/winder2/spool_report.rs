@@ -0,0 +1,337 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::JoinHandle;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::machine_identification::QiTechMachineIdentificationUnique;
+use crate::persist::data_dir;
+
+/// Laser diameter over the wound length, all in mm
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DiameterStatistics {
+    /// length weighted average
+    pub average: f64,
+    pub min: f64,
+    pub max: f64,
+    /// target diameter at the end of the spool
+    pub target: Option<f64>,
+    pub lower_tolerance: Option<f64>,
+    pub higher_tolerance: Option<f64>,
+}
+
+/// Production report of a finished spool
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SpoolReport {
+    /// start time in ms since the unix epoch
+    pub id: u64,
+    pub machine: QiTechMachineIdentificationUnique,
+    /// ms since the unix epoch
+    pub started_at: u64,
+    /// ms since the unix epoch
+    pub finished_at: u64,
+    /// duration in s
+    pub duration: f64,
+    /// wound length in m
+    pub length: f64,
+    /// wound filament mass in g
+    pub filament_mass: f64,
+    /// `None` if no laser was connected
+    pub diameter: Option<DiameterStatistics>,
+    /// length wound outside of the laser tolerance in m
+    pub out_of_tolerance_length: f64,
+}
+
+/// Text of a printable spool label
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct SpoolLabel {
+    pub title: String,
+    pub lines: Vec<String>,
+}
+
+impl SpoolReport {
+    pub fn label(&self) -> SpoolLabel {
+        let machine = self.machine.machine_identification.slug();
+        let minutes = (self.duration / 60.0).round() as u64;
+        let mut lines = vec![
+            format!("Machine: {} #{}", machine, self.machine.serial),
+            format!("Finished: {}", format_utc(self.finished_at)),
+            format!("Duration: {} h {:02} min", minutes / 60, minutes % 60),
+            format!("Length: {:.1} m", self.length),
+            format!("Mass: {:.0} g", self.filament_mass),
+        ];
+        if let Some(diameter) = &self.diameter {
+            lines.push(format!(
+                "Diameter: {:.3} mm ({:.3} - {:.3})",
+                diameter.average, diameter.min, diameter.max
+            ));
+            if let (Some(target), Some(lower), Some(higher)) = (
+                diameter.target,
+                diameter.lower_tolerance,
+                diameter.higher_tolerance,
+            ) {
+                lines.push(format!("Target: {target:.3} mm -{lower:.3}/+{higher:.3}"));
+            }
+            lines.push(format!(
+                "Out of tolerance: {:.1} m",
+                self.out_of_tolerance_length
+            ));
+        }
+
+        SpoolLabel {
+            title: format!("Spool {}", self.id),
+            lines,
+        }
+    }
+}
+
+/// Finished spool reports of one machine, stored as one JSON file per spool
+#[derive(Debug, Clone)]
+pub struct SpoolReportStore {
+    dir: PathBuf,
+}
+
+impl SpoolReportStore {
+    /// Reports are kept next to the machine device info
+    pub fn new(machine: &QiTechMachineIdentificationUnique) -> Self {
+        Self::with_dir(data_dir().join("spool_reports"), machine)
+    }
+
+    pub fn with_dir(base: PathBuf, machine: &QiTechMachineIdentificationUnique) -> Self {
+        let name = format!(
+            "{}_{}",
+            machine.machine_identification.slug(),
+            machine.serial
+        );
+        Self {
+            dir: base.join(name),
+        }
+    }
+
+    fn report_path(&self, id: u64) -> PathBuf {
+        self.dir.join(format!("{id}.json"))
+    }
+
+    pub fn save(&self, report: &SpoolReport) -> Result<()> {
+        fs::create_dir_all(&self.dir).with_context(|| {
+            format!(
+                "[{}::SpoolReportStore::save] Can't create {}",
+                module_path!(),
+                self.dir.display()
+            )
+        })?;
+        let json = serde_json::to_string_pretty(report)?;
+        fs::write(self.report_path(report.id), json)?;
+        Ok(())
+    }
+
+    pub fn load(&self, id: u64) -> Result<Option<SpoolReport>> {
+        let path = self.report_path(id);
+        if !fs::exists(&path)? {
+            return Ok(None);
+        }
+        let json = fs::read_to_string(path)?;
+        Ok(Some(serde_json::from_str(&json)?))
+    }
+
+    /// All reports, newest first
+    pub fn list(&self) -> Result<Vec<SpoolReport>> {
+        if !fs::exists(&self.dir)? {
+            return Ok(vec![]);
+        }
+
+        let mut reports = vec![];
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|x| x != "json") {
+                continue;
+            }
+            let json = fs::read_to_string(&path)?;
+            match serde_json::from_str::<SpoolReport>(&json) {
+                Ok(report) => reports.push(report),
+                Err(e) => tracing::warn!(
+                    "[{}::SpoolReportStore::list] Skipping {}: {}",
+                    module_path!(),
+                    path.display(),
+                    e
+                ),
+            }
+        }
+        reports.sort_by(|a, b| b.id.cmp(&a.id));
+        Ok(reports)
+    }
+}
+
+/// Saves finished spool reports on a separate thread
+///
+/// The machine loop never waits on the file system. The worker stops when this handle
+/// is dropped.
+#[derive(Debug)]
+pub struct SpoolReportWriter {
+    report_sender: Sender<SpoolReport>,
+    result_receiver: Receiver<(u64, Result<()>)>,
+    _worker: JoinHandle<()>,
+}
+
+impl SpoolReportWriter {
+    pub fn spawn(store: SpoolReportStore) -> Result<Self> {
+        let (report_sender, report_receiver) = mpsc::channel::<SpoolReport>();
+        let (result_sender, result_receiver) = mpsc::channel();
+
+        let worker = std::thread::Builder::new()
+            .name("spool-report-writer".to_owned())
+            .spawn(move || {
+                for report in report_receiver {
+                    let result = store.save(&report);
+                    if result_sender.send((report.id, result)).is_err() {
+                        break;
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            report_sender,
+            result_receiver,
+            _worker: worker,
+        })
+    }
+
+    /// Queue a report, the outcome is returned by [`Self::try_recv`]
+    pub fn save(&self, report: SpoolReport) -> Result<()> {
+        self.report_sender.send(report).map_err(|_| {
+            anyhow::anyhow!(
+                "[{}::SpoolReportWriter::save] Worker has stopped",
+                module_path!()
+            )
+        })
+    }
+
+    /// Id and outcome of the next saved report without blocking
+    pub fn try_recv(&self) -> Option<(u64, Result<()>)> {
+        self.result_receiver.try_recv().ok()
+    }
+}
+
+/// `YYYY-MM-DD HH:MM UTC` from ms since the unix epoch
+fn format_utc(millis: u64) -> String {
+    let seconds = millis / 1000;
+    let (days, seconds_of_day) = (seconds / 86400, seconds % 86400);
+
+    // Civil date from days since 1970-01-01, proleptic gregorian calendar
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        seconds_of_day % 3600 / 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::machine_identification::MachineIdentification;
+    use crate::{MACHINE_WINDER_V1, VENDOR_QITECH};
+
+    fn report(id: u64) -> SpoolReport {
+        SpoolReport {
+            id,
+            machine: QiTechMachineIdentificationUnique {
+                machine_identification: MachineIdentification {
+                    vendor: VENDOR_QITECH,
+                    machine: MACHINE_WINDER_V1,
+                },
+                serial: 7,
+            },
+            started_at: id,
+            finished_at: 1_709_210_096_000,
+            duration: 5400.0,
+            length: 250.0,
+            filament_mass: 745.6,
+            diameter: Some(DiameterStatistics {
+                average: 1.752,
+                min: 1.70,
+                max: 1.81,
+                target: Some(1.75),
+                lower_tolerance: Some(0.05),
+                higher_tolerance: Some(0.05),
+            }),
+            out_of_tolerance_length: 1.26,
+        }
+    }
+
+    #[test]
+    fn test_label() {
+        let label = report(1).label();
+        assert_eq!(label.title, "Spool 1");
+        assert_eq!(
+            label.lines,
+            vec![
+                "Machine: winder_v1 #7",
+                "Finished: 2024-02-29 12:34 UTC",
+                "Duration: 1 h 30 min",
+                "Length: 250.0 m",
+                "Mass: 746 g",
+                "Diameter: 1.752 mm (1.700 - 1.810)",
+                "Target: 1.750 mm -0.050/+0.050",
+                "Out of tolerance: 1.3 m",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_store_roundtrip() {
+        let base = std::env::temp_dir().join(format!("spool_reports_{}", std::process::id()));
+        let store = SpoolReportStore::with_dir(base.clone(), &report(0).machine);
+        assert!(store.list().unwrap().is_empty());
+
+        store.save(&report(1)).unwrap();
+        store.save(&report(2)).unwrap();
+        assert_eq!(store.load(1).unwrap(), Some(report(1)));
+        assert_eq!(store.load(3).unwrap(), None);
+        let ids: Vec<u64> = store.list().unwrap().iter().map(|x| x.id).collect();
+        assert_eq!(ids, vec![2, 1]);
+
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn test_writer_saves_in_background() {
+        let base = std::env::temp_dir().join(format!("spool_writer_{}", std::process::id()));
+        let store = SpoolReportStore::with_dir(base.clone(), &report(0).machine);
+        let writer = SpoolReportWriter::spawn(store.clone()).unwrap();
+
+        writer.save(report(1)).unwrap();
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        let (id, result) = loop {
+            if let Some(saved) = writer.try_recv() {
+                break saved;
+            }
+            assert!(std::time::Instant::now() < deadline);
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        };
+        assert_eq!(id, 1);
+        assert!(result.is_ok());
+        assert_eq!(store.load(1).unwrap(), Some(report(1)));
+
+        fs::remove_dir_all(base).unwrap();
+    }
+}
@@ -6,7 +6,10 @@ use crate::winder2::{
 use control_core::controllers::second_degree_motion::acceleration_position_controller::MotionControllerError;
 
 use super::tension_arm::TensionArm;
+use qitech_lib::units::angular_velocity::radian_per_second;
 use qitech_lib::units::f64::*;
+use qitech_lib::units::length::meter;
+use qitech_lib::units::velocity::meter_per_second;
 use serde::{Deserialize, Serialize};
 use std::time::Instant;
 
@@ -48,6 +51,17 @@ impl SpoolSpeedController {
         }
     }
 
+    /// Filament tension of the active controller, `None` if the tension arm can't be read
+    ///
+    /// `0.0` is a slack arm with the most filament in the tensioning system.
+    pub fn get_filament_tension(&self, tension_arm: &TensionArm) -> Option<f64> {
+        let filament_calc = match self.r#type {
+            SpoolSpeedControllerType::Adaptive => self.adaptive_controller.get_filament_calc(),
+            SpoolSpeedControllerType::MinMax => self.minmax_controller.get_filament_calc(),
+        };
+        filament_calc.get_filament_tension(tension_arm)
+    }
+
     pub fn set_speed(&mut self, speed: AngularVelocity) {
         match self.r#type {
             SpoolSpeedControllerType::Adaptive => self.adaptive_controller.set_speed(speed),
@@ -120,18 +134,33 @@ impl SpoolSpeedController {
         self.minmax_controller.get_max_speed()
     }
 
+    /// `spool_radius` is the estimated wound radius, if it is certain enough
     pub fn update_speed(
         &mut self,
         t: Instant,
         tension_arm: &TensionArm,
         puller_speed_controller: &PullerSpeedController,
+        spool_radius: Option<Length>,
     ) -> AngularVelocity {
+        // Spool speed at which the spool surface matches the line speed
+        let winding_speed = spool_radius.map(|radius| {
+            let line_speed = puller_speed_controller.get_line_speed();
+            AngularVelocity::new::<radian_per_second>(
+                line_speed.get::<meter_per_second>().abs() / radius.get::<meter>(),
+            )
+        });
+
         match self.r#type {
-            SpoolSpeedControllerType::Adaptive => {
-                self.adaptive_controller
-                    .update_speed(t, tension_arm, puller_speed_controller)
+            SpoolSpeedControllerType::Adaptive => self.adaptive_controller.update_speed(
+                t,
+                tension_arm,
+                puller_speed_controller,
+                winding_speed,
+            ),
+            SpoolSpeedControllerType::MinMax => {
+                self.minmax_controller
+                    .update_speed(t, tension_arm, winding_speed)
             }
-            SpoolSpeedControllerType::MinMax => self.minmax_controller.update_speed(t, tension_arm),
         }
     }
 
@@ -0,0 +1,130 @@
+use qitech_lib::units::f64::Length;
+use qitech_lib::units::length::millimeter;
+use serde::{Deserialize, Serialize};
+
+/// What the winder does once the traverse lost steps
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StepLossReaction {
+    /// Only report it
+    #[default]
+    Warn,
+    /// Keep winding and re-home when the spool is changed
+    RehomeAtSpoolChange,
+    /// Stop winding, which re-homes the traverse
+    Stop,
+}
+
+/// Detects lost traverse steps while the traverse is homed
+///
+/// The EL70x1 counter only counts the steps it sends, so it can't see lost steps on its own.
+/// The endstop is the only measurement of the actual position: whenever it switches
+/// the counter has to be at home. Steps lost while the traverse stays away from home
+/// are found once the traverse reaches home again.
+#[derive(Debug)]
+pub struct StepLossDetector {
+    reaction: StepLossReaction,
+    /// Allowed distance of the counter from home when the endstop switches
+    tolerance: Length,
+
+    /// Counter position when step loss was detected, stays until the traverse is homed
+    detected: Option<Length>,
+}
+
+impl Default for StepLossDetector {
+    fn default() -> Self {
+        Self {
+            reaction: StepLossReaction::Warn,
+            tolerance: Length::new::<millimeter>(0.5),
+            detected: None,
+        }
+    }
+}
+
+impl StepLossDetector {
+    /// Check the counter `position` against the endstop
+    ///
+    /// Returns the deviation from home if step loss was newly detected.
+    pub fn update(&mut self, position: Length, end_stop: bool) -> Option<Length> {
+        if self.detected.is_some() {
+            // The reaction is pending until the traverse is homed
+            return None;
+        }
+
+        // Traverse is at home but the counter says otherwise, or the other way round
+        if (end_stop && position > self.tolerance) || (!end_stop && position < -self.tolerance) {
+            self.detected = Some(position);
+            return self.detected;
+        }
+        None
+    }
+
+    /// Forget a detected step loss, e.g. while homing
+    pub const fn reset(&mut self) {
+        self.detected = None;
+    }
+
+    pub const fn is_detected(&self) -> bool {
+        self.detected.is_some()
+    }
+
+    pub fn get_detected_deviation(&self) -> Option<Length> {
+        self.detected
+    }
+
+    pub const fn get_reaction(&self) -> StepLossReaction {
+        self.reaction
+    }
+
+    pub const fn set_reaction(&mut self, reaction: StepLossReaction) {
+        self.reaction = reaction;
+    }
+
+    pub fn get_tolerance(&self) -> Length {
+        self.tolerance
+    }
+
+    pub fn set_tolerance(&mut self, tolerance: Length) {
+        self.tolerance = tolerance.abs();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mm(value: f64) -> Length {
+        Length::new::<millimeter>(value)
+    }
+
+    #[test]
+    fn test_counter_away_from_home() {
+        let mut detector = StepLossDetector::default();
+
+        // Winding between the limits, the endstop stays free
+        for i in 0..1000 {
+            let position = mm(10.0 + f64::from(i) * 0.05);
+            assert_eq!(detector.update(position, false), None);
+        }
+
+        // Traverse hits the endstop while the counter is still 3 mm out
+        assert_eq!(detector.update(mm(3.0), true), Some(mm(3.0)));
+        assert!(detector.is_detected());
+
+        detector.reset();
+        assert!(!detector.is_detected());
+    }
+
+    #[test]
+    fn test_end_stop_disagrees() {
+        let mut detector = StepLossDetector::default();
+
+        assert_eq!(detector.update(mm(0.2), true), None);
+        assert_eq!(detector.update(mm(-0.2), false), None);
+        assert_eq!(detector.update(mm(-1.0), false), Some(mm(-1.0)));
+
+        let mut detector = StepLossDetector::default();
+        assert_eq!(detector.update(mm(30.0), true), Some(mm(30.0)));
+        // Reported once
+        assert_eq!(detector.update(mm(30.0), true), None);
+    }
+}
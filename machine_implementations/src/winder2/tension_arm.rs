@@ -11,24 +11,30 @@ pub struct TensionArm {
     pub zero: Angle,
     /// was zeroed at least once
     pub zeroed: bool,
+    /// sensor voltage of one revolution
+    volts_per_revolution: f64,
 }
 
 impl TensionArm {
-    pub fn new(analog_input: Rc<RefCell<dyn StepperVelocityEL70x1Device>>) -> Self {
+    pub fn new(
+        analog_input: Rc<RefCell<dyn StepperVelocityEL70x1Device>>,
+        volts_per_revolution: f64,
+    ) -> Self {
         Self {
             analog_input,
             zero: Angle::new::<revolution>(0.0),
             zeroed: false,
+            volts_per_revolution,
         }
     }
 
     fn volts_to_angle(&self, volts: f64) -> Angle {
-        // 0V = 0deg 5V = 3600deg
+        // 0V = 0deg, volts per revolution = 360deg
         // always wrap into 0..1 revolution
-        Angle::new::<revolution>(volts / 5.0) % Angle::new::<revolution>(1.0)
+        Angle::new::<revolution>(volts / self.volts_per_revolution) % Angle::new::<revolution>(1.0)
     }
 
-    fn get_volts(&self) -> Result<f64, anyhow::Error> {
+    pub fn get_volts(&self) -> Result<f64, anyhow::Error> {
         // get the normalized value from the analog input
         let analog_input = &*self.analog_input.borrow();
 
@@ -40,7 +46,10 @@ impl TensionArm {
         let value = analog_input.get_analog_input(0)?.get_physical(&range);
         match value {
             AnalogInputValue::Potential(v) => Ok(v.get::<volt>()),
-            _ => panic!("Expected a potential value"),
+            _ => Err(anyhow::anyhow!(
+                "[{}::TensionArm::get_volts] Expected a potential value",
+                module_path!()
+            )),
         }
     }
 
@@ -48,7 +57,6 @@ impl TensionArm {
         // get volts
         let volts = self.get_volts()?;
 
-        // 0V = 0deg 5V = 3600deg
         Ok(self.volts_to_angle(volts))
     }
 
@@ -0,0 +1,305 @@
+use std::time::{Duration, Instant};
+
+use qitech_lib::units::angle::degree;
+use qitech_lib::units::angular_velocity::{degree_per_second, revolution_per_minute};
+use qitech_lib::units::f64::{Angle, AngularVelocity};
+use serde::{Deserialize, Serialize};
+
+use super::filament_tension::FilamentTensionCalculator;
+
+/// Sensor voltages this far outside of one revolution are still plausible
+const SENSOR_VOLTS_MARGIN: f64 = 0.2;
+
+/// Time constant of the arm rate filter in s
+const RATE_FILTER_TIME_CONSTANT: f64 = 0.02;
+
+/// Above this spool speed the arm has to move
+const SPOOL_TURNING_RPM: f64 = 5.0;
+
+/// Arm angles this close to the loose end count as slack
+const SLACK_MARGIN_DEG: f64 = 10.0;
+
+/// Why winding was stopped by the tension arm supervision
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TensionArmFault {
+    /// Arm is pulled beyond the tight end of its travel
+    EndStop,
+    /// Arm dropped to the loose end, the filament snapped
+    FilamentBreak,
+    /// Angle doesn't change while the spool turns, the arm or sensor is jammed
+    Stuck,
+    /// Sensor can't be read or reports an impossible voltage
+    SensorError,
+}
+
+/// Supervises the tension arm while winding
+///
+/// A detected fault is latched until it is cleared.
+#[derive(Debug)]
+pub struct TensionArmMonitor {
+    // configuration
+    /// Arm angle of the tightest filament, beyond it the arm leaves its travel
+    tight_angle: Angle,
+    /// Arm angle of the loosest filament
+    loose_angle: Angle,
+    /// Moving this fast toward the loose end is a filament break
+    break_rate: AngularVelocity,
+    /// How long the arm may stay beyond the tight end
+    end_stop_time: Duration,
+    /// How long the angle may stay within `stuck_tolerance` while the spool turns
+    stuck_time: Duration,
+    stuck_tolerance: Angle,
+    /// Sensor voltage of one arm revolution
+    sensor_full_scale: f64,
+
+    // tracking
+    last_angle: Option<(Instant, f64)>,
+    /// Filtered arm rate in °/s
+    rate: f64,
+    end_stop_since: Option<Instant>,
+    stuck_reference: Option<(Instant, f64)>,
+    fault: Option<TensionArmFault>,
+}
+
+impl Default for TensionArmMonitor {
+    fn default() -> Self {
+        Self::new(&FilamentTensionCalculator::default())
+    }
+}
+
+impl TensionArmMonitor {
+    /// Supervise the arm travel of `filament_calc`, from its minimum tension angle (loose)
+    /// to its maximum tension angle (tight)
+    pub fn new(filament_calc: &FilamentTensionCalculator) -> Self {
+        Self {
+            tight_angle: filament_calc.get_max_angle(),
+            loose_angle: filament_calc.get_min_angle(),
+            break_rate: AngularVelocity::new::<revolution_per_minute>(30.0),
+            end_stop_time: Duration::from_millis(200),
+            stuck_time: Duration::from_secs(10),
+            stuck_tolerance: Angle::new::<degree>(0.05),
+            sensor_full_scale: 5.0,
+            last_angle: None,
+            rate: 0.0,
+            end_stop_since: None,
+            stuck_reference: None,
+            fault: None,
+        }
+    }
+
+    /// Check the arm while winding
+    ///
+    /// - `volts`: raw sensor voltage, `None` if it can't be read
+    /// - `angle`: zeroed arm angle
+    /// - `spool_speed`: commanded spool speed
+    ///
+    /// Returns a newly detected fault.
+    pub fn update(
+        &mut self,
+        volts: Option<f64>,
+        angle: Option<Angle>,
+        spool_speed: AngularVelocity,
+        now: Instant,
+    ) -> Option<TensionArmFault> {
+        if self.fault.is_some() {
+            return None;
+        }
+        let fault = self.detect(volts, angle, spool_speed, now);
+        self.fault = fault;
+        fault
+    }
+
+    fn detect(
+        &mut self,
+        volts: Option<f64>,
+        angle: Option<Angle>,
+        spool_speed: AngularVelocity,
+        now: Instant,
+    ) -> Option<TensionArmFault> {
+        let plausible_volts = -SENSOR_VOLTS_MARGIN..=self.sensor_full_scale + SENSOR_VOLTS_MARGIN;
+        let plausible = volts.is_some_and(|v| plausible_volts.contains(&v));
+        let (true, Some(angle)) = (plausible, angle) else {
+            return Some(TensionArmFault::SensorError);
+        };
+
+        // Slightly below zero reads as almost one revolution
+        let angle = angle.get::<degree>();
+        let angle = if angle > 180.0 { angle - 360.0 } else { angle };
+
+        if let Some((last_t, last_angle)) = self.last_angle {
+            let dt = now.duration_since(last_t).as_secs_f64();
+            if dt > 0.0 {
+                let alpha = dt / (RATE_FILTER_TIME_CONSTANT + dt);
+                self.rate += alpha * ((angle - last_angle) / dt - self.rate);
+            }
+        }
+        self.last_angle = Some((now, angle));
+
+        // Angles and rate measured toward the loose end
+        let tight = self.tight_angle.get::<degree>();
+        let loose = self.loose_angle.get::<degree>();
+        let direction = (loose - tight).signum();
+        let slack = (angle - loose) * direction > -SLACK_MARGIN_DEG;
+        if slack && self.rate * direction > self.break_rate.get::<degree_per_second>() {
+            return Some(TensionArmFault::FilamentBreak);
+        }
+
+        if (tight - angle) * direction > 0.0 {
+            let since = *self.end_stop_since.get_or_insert(now);
+            if now.duration_since(since) >= self.end_stop_time {
+                return Some(TensionArmFault::EndStop);
+            }
+        } else {
+            self.end_stop_since = None;
+        }
+
+        let spool_turning = spool_speed.abs().get::<revolution_per_minute>() > SPOOL_TURNING_RPM;
+        match self.stuck_reference {
+            Some((since, reference))
+                if spool_turning
+                    && (angle - reference).abs() <= self.stuck_tolerance.get::<degree>() =>
+            {
+                if now.duration_since(since) >= self.stuck_time {
+                    return Some(TensionArmFault::Stuck);
+                }
+            }
+            _ => self.stuck_reference = spool_turning.then_some((now, angle)),
+        }
+
+        None
+    }
+
+    /// Forget the arm history, e.g. when winding stops
+    pub const fn reset(&mut self) {
+        self.last_angle = None;
+        self.rate = 0.0;
+        self.end_stop_since = None;
+        self.stuck_reference = None;
+    }
+
+    pub const fn set_sensor_full_scale(&mut self, volts: f64) {
+        self.sensor_full_scale = volts;
+    }
+
+    pub const fn get_fault(&self) -> Option<TensionArmFault> {
+        self.fault
+    }
+
+    /// Acknowledge the fault, returns the cleared one
+    pub const fn clear_fault(&mut self) -> Option<TensionArmFault> {
+        self.reset();
+        self.fault.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: Duration = Duration::from_millis(1);
+
+    fn deg(value: f64) -> Option<Angle> {
+        Some(Angle::new::<degree>(value))
+    }
+
+    fn rpm(value: f64) -> AngularVelocity {
+        AngularVelocity::new::<revolution_per_minute>(value)
+    }
+
+    /// Feed `angle(i)` for `n` cycles, returns the first fault
+    fn run(
+        monitor: &mut TensionArmMonitor,
+        t: &mut Instant,
+        n: u32,
+        spool_speed: f64,
+        angle: impl Fn(u32) -> f64,
+    ) -> Option<TensionArmFault> {
+        let mut fault = None;
+        for i in 0..n {
+            *t += DT;
+            let angle = angle(i);
+            let volts = angle.rem_euclid(360.0) / 360.0 * 5.0;
+            let update = monitor.update(Some(volts), deg(angle), rpm(spool_speed), *t);
+            fault = fault.or(update);
+        }
+        fault
+    }
+
+    #[test]
+    fn test_filament_break() {
+        let mut monitor = TensionArmMonitor::default();
+        let mut t = Instant::now();
+
+        // Regulated arm with some dither
+        let dither = |i: u32| 50.0 + (f64::from(i) * 0.01).sin();
+        assert_eq!(run(&mut monitor, &mut t, 2000, 100.0, dither), None);
+
+        // Arm drops to the loose end within 100 ms
+        let drop = |i: u32| (50.0 + f64::from(i) * 0.5).min(92.0);
+        assert_eq!(
+            run(&mut monitor, &mut t, 200, 100.0, drop),
+            Some(TensionArmFault::FilamentBreak)
+        );
+
+        // Latched
+        assert_eq!(monitor.get_fault(), Some(TensionArmFault::FilamentBreak));
+        assert_eq!(run(&mut monitor, &mut t, 10, 100.0, dither), None);
+        assert_eq!(monitor.clear_fault(), Some(TensionArmFault::FilamentBreak));
+    }
+
+    #[test]
+    fn test_slow_slack_is_no_break() {
+        let mut monitor = TensionArmMonitor::default();
+        let mut t = Instant::now();
+
+        // Arm settles at the loose end when the spool is stopped
+        let settle = |i: u32| (50.0 + f64::from(i) * 0.01).min(90.0);
+        assert_eq!(run(&mut monitor, &mut t, 6000, 0.0, settle), None);
+    }
+
+    #[test]
+    fn test_fast_tightening_is_no_break() {
+        let mut monitor = TensionArmMonitor::default();
+        let mut t = Instant::now();
+
+        // Spool jerks the arm toward the tight end
+        let tighten = |i: u32| (50.0 - f64::from(i) * 0.5).max(25.0);
+        assert_eq!(run(&mut monitor, &mut t, 200, 100.0, tighten), None);
+    }
+
+    #[test]
+    fn test_end_stop_and_stuck() {
+        let mut monitor = TensionArmMonitor::default();
+        let mut t = Instant::now();
+        // Briefly beyond the tight end is tolerated
+        assert_eq!(run(&mut monitor, &mut t, 100, 100.0, |_| 10.0), None);
+        assert_eq!(
+            run(&mut monitor, &mut t, 300, 100.0, |_| 10.0),
+            Some(TensionArmFault::EndStop)
+        );
+
+        let mut monitor = TensionArmMonitor::default();
+        // Standing still is fine while the spool doesn't turn
+        assert_eq!(run(&mut monitor, &mut t, 11_000, 0.0, |_| 45.0), None);
+        assert_eq!(
+            run(&mut monitor, &mut t, 11_000, 100.0, |_| 45.0),
+            Some(TensionArmFault::Stuck)
+        );
+    }
+
+    #[test]
+    fn test_sensor_error() {
+        let mut monitor = TensionArmMonitor::default();
+        let t = Instant::now();
+        assert_eq!(
+            monitor.update(None, None, rpm(0.0), t),
+            Some(TensionArmFault::SensorError)
+        );
+
+        let mut monitor = TensionArmMonitor::default();
+        assert_eq!(
+            monitor.update(Some(9.8), deg(45.0), rpm(0.0), t),
+            Some(TensionArmFault::SensorError)
+        );
+    }
+}
@@ -1,12 +1,20 @@
+use super::step_loss_detector::StepLossDetector;
+use super::winding_pattern::WindingPatternController;
 use super::{TRAVERSE_END_STOP_PORT, TRAVERSE_PORT};
+use control_core::controllers::second_degree_motion::linear_jerk_position_controller::LinearJerkPositionController;
 use control_core::converters::linear_step_converter::LinearStepConverter;
 use qitech_lib::ethercat_hal::io::stepper_velocity_el70x1::StepperVelocityEL70x1Device;
 use qitech_lib::units::ConstZero;
+use qitech_lib::units::acceleration::meter_per_second_squared;
 use qitech_lib::units::angular_velocity::revolution_per_second;
-use qitech_lib::units::f64::{AngularVelocity, Length, Velocity};
+use qitech_lib::units::f64::{Acceleration, AngularVelocity, Jerk, Length, Velocity};
+use qitech_lib::units::jerk::meter_per_second_cubed;
 use qitech_lib::units::length::millimeter;
 use qitech_lib::units::velocity::millimeter_per_second;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+
+/// Proportional correction of the stepper position towards the planned position in 1/s
+const POSITION_CORRECTION_GAIN: f64 = 5.0;
 
 #[derive(Debug)]
 pub struct TraverseController {
@@ -19,6 +27,19 @@ pub struct TraverseController {
     state: State,
     fullstep_converter: LinearStepConverter,
     microstep_converter: LinearStepConverter,
+    /// S-curve planner for the moves while traversing. A single axis is enough: the spool
+    /// runs speed controlled and the traverse follows it through the pitch, so there is no
+    /// second positioned axis to plan together with it
+    trajectory: LinearJerkPositionController,
+    /// Standstill at each reversal point
+    dwell_time: Duration,
+    /// Moves the reversal points past `limit - padding` towards the flanks,
+    /// negative values move them inwards
+    overshoot_compensation: Length,
+    /// Pitch per spool revolution while traversing
+    winding_pattern: WindingPatternController,
+    /// Compares the counter with the endstop while homed
+    step_loss: StepLossDetector,
     // A sticky flag if the [`State`] changed (not the sub states)
     // Needed to send state updates to the UI
     did_change_state: bool,
@@ -67,6 +88,14 @@ pub enum TraversingState {
     /// - will go into [`State::GoingIn`] after reaching the outer limit
     /// - speed is synced to spool speed
     TraversingOut,
+
+    /// Standing at the inner reversal point for the dwell time
+    /// - will go into [`TraversingState::TraversingOut`] afterwards
+    DwellInner(Instant),
+
+    /// Standing at the outer reversal point for the dwell time
+    /// - will go into [`TraversingState::TraversingIn`] afterwards
+    DwellOuter(Instant),
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -95,7 +124,14 @@ pub enum HomingState {
 }
 
 impl TraverseController {
-    pub fn new(limit_inner: Length, limit_outer: Length, microsteps: u8) -> Self {
+    /// `circumference` is the travel per revolution of the stepper
+    pub fn new(
+        limit_inner: Length,
+        limit_outer: Length,
+        steps_per_revolution: i16,
+        microsteps: u8,
+        circumference: Length,
+    ) -> Self {
         Self {
             enabled: false,
             position: Length::ZERO,
@@ -106,13 +142,26 @@ impl TraverseController {
             state: State::NotHomed,
             did_change_state: false,
             fullstep_converter: LinearStepConverter::from_circumference(
-                200,
-                Length::new::<millimeter>(32.0),
+                steps_per_revolution,
+                circumference,
             ),
             microstep_converter: LinearStepConverter::from_circumference(
-                200 * microsteps as i16,
-                Length::new::<millimeter>(32.0),
+                steps_per_revolution
+                    .checked_mul(i16::from(microsteps))
+                    .expect("Traverse microsteps per revolution fit into i16"),
+                circumference,
             ),
+            trajectory: LinearJerkPositionController::new(
+                Velocity::new::<millimeter_per_second>(100.0),
+                Acceleration::new::<meter_per_second_squared>(0.2),
+                Jerk::new::<meter_per_second_cubed>(4.0),
+                Length::new::<millimeter>(0.01),
+            )
+            .expect("Traverse trajectory limits are valid"),
+            dwell_time: Duration::ZERO,
+            overshoot_compensation: Length::ZERO,
+            winding_pattern: WindingPatternController::default(),
+            step_loss: StepLossDetector::default(),
         }
     }
 }
@@ -155,6 +204,38 @@ impl TraverseController {
         self.padding
     }
 
+    pub const fn set_dwell_time(&mut self, dwell_time: Duration) {
+        self.dwell_time = dwell_time;
+    }
+
+    pub const fn get_dwell_time(&self) -> Duration {
+        self.dwell_time
+    }
+
+    pub fn set_overshoot_compensation(&mut self, overshoot_compensation: Length) {
+        self.overshoot_compensation = overshoot_compensation;
+    }
+
+    pub fn get_overshoot_compensation(&self) -> Length {
+        self.overshoot_compensation
+    }
+
+    pub const fn get_winding_pattern(&self) -> &WindingPatternController {
+        &self.winding_pattern
+    }
+
+    pub const fn get_winding_pattern_mut(&mut self) -> &mut WindingPatternController {
+        &mut self.winding_pattern
+    }
+
+    pub const fn get_step_loss(&self) -> &StepLossDetector {
+        &self.step_loss
+    }
+
+    pub const fn get_step_loss_mut(&mut self) -> &mut StepLossDetector {
+        &mut self.step_loss
+    }
+
     pub fn get_current_position(&self) -> Option<Length> {
         match self.is_homed() {
             true => Some(self.position),
@@ -188,7 +269,9 @@ impl TraverseController {
         self.state = State::Homing(HomingState::Initialize);
     }
 
-    pub const fn start_traversing(&mut self) {
+    pub fn start_traversing(&mut self) {
+        self.trajectory.reset(self.position);
+        self.winding_pattern.reset();
         self.state = State::Traversing(TraversingState::GoingOut);
     }
 
@@ -226,6 +309,39 @@ impl TraverseController {
         self.position >= lower_tolerance && self.position <= upper_tolerance
     }
 
+    /// Outer point where the traverse turns around while traversing
+    fn reversal_outer(&self) -> Length {
+        (self.limit_outer - self.padding + self.overshoot_compensation)
+            .min(self.limit_outer)
+            .max(self.limit_inner)
+    }
+
+    /// Inner point where the traverse turns around while traversing
+    fn reversal_inner(&self) -> Length {
+        (self.limit_inner + self.padding - self.overshoot_compensation)
+            .max(self.limit_inner)
+            .min(self.limit_outer)
+    }
+
+    /// The planned move to `target_position` ended
+    fn reached_reversal(&self, target_position: Length) -> bool {
+        self.trajectory.is_at_target() && self.trajectory.get_target_position() == target_position
+    }
+
+    /// Velocity command following the S-curve move to `target_position`
+    ///
+    /// Open loop the stepper drifts from the planned position, the difference is corrected proportionally.
+    fn speed_along_trajectory(&mut self, target_position: Length, max_speed: Velocity) -> Velocity {
+        self.trajectory.set_max_speed(max_speed);
+        self.trajectory.update(target_position, Instant::now());
+
+        let position_error = self.trajectory.get_position() - self.position;
+        self.trajectory.get_speed()
+            + Velocity::new::<millimeter_per_second>(
+                position_error.get::<millimeter>() * POSITION_CORRECTION_GAIN,
+            )
+    }
+
     /// Calculate distance to position
     fn distance_to_position(&self, target_position: Length) -> Length {
         if self.position > target_position {
@@ -271,13 +387,16 @@ impl TraverseController {
     /// Calculates a desired speed based on the current state and the end stop status.
     ///
     /// Positive speed moved out, negative speed moves in.
+    /// The line speed is needed by winding patterns that depend on the spool diameter.
     fn get_speed(
         &mut self,
         traverse: &mut dyn StepperVelocityEL70x1Device,
         spool_speed: AngularVelocity,
+        line_speed: Velocity,
     ) -> Velocity {
         // Don't move if not enabled or in a state that doesn't result in movement
         if !self.enabled {
+            self.trajectory.reset(self.position);
             return Velocity::ZERO;
         }
 
@@ -374,23 +493,30 @@ impl TraverseController {
 
             // If state changed we
             State::Traversing(traversing_state) => match traversing_state {
-                TraversingState::GoingOut => {
-                    // If outer limit is reached
-                    if self.position >= self.limit_outer - self.padding {
-                        // Turn around
-                        self.state = State::Traversing(TraversingState::TraversingIn);
+                TraversingState::GoingOut | TraversingState::TraversingOut => {
+                    // If the move to the outer reversal point ended
+                    if self.reached_reversal(self.reversal_outer()) {
+                        // Dwell before turning around
+                        self.winding_pattern.on_reversal();
+                        self.state = State::Traversing(TraversingState::DwellOuter(Instant::now()));
                     }
                 }
                 TraversingState::TraversingIn => {
-                    // If inner limit is reached
-                    if self.position <= self.limit_inner + self.padding {
+                    // If the move to the inner reversal point ended
+                    if self.reached_reversal(self.reversal_inner()) {
+                        // Dwell before turning around
+                        self.winding_pattern.on_reversal();
+                        self.state = State::Traversing(TraversingState::DwellInner(Instant::now()));
+                    }
+                }
+                TraversingState::DwellInner(instant) => {
+                    if instant.elapsed() >= self.dwell_time {
                         // Turn around
                         self.state = State::Traversing(TraversingState::TraversingOut);
                     }
                 }
-                TraversingState::TraversingOut => {
-                    // If outer limit is reached
-                    if self.position >= self.limit_outer - self.padding {
+                TraversingState::DwellOuter(instant) => {
+                    if instant.elapsed() >= self.dwell_time {
                         // Turn around
                         self.state = State::Traversing(TraversingState::TraversingIn);
                     }
@@ -405,7 +531,7 @@ impl TraverseController {
 
         // Speed
 
-        match &self.state {
+        let speed = match &self.state {
             State::NotHomed => Velocity::ZERO, // Not homed, no movement
             State::Idle => Velocity::ZERO,     // No movement in idle state
             State::GoingIn => {
@@ -455,23 +581,53 @@ impl TraverseController {
                     Velocity::ZERO
                 }
             }, // Homing speed
-            State::Traversing(traversing_state) => match traversing_state {
-                TraversingState::GoingOut => {
+            State::Traversing(traversing_state) => {
+                let stroke = self.reversal_outer() - self.reversal_inner();
+                let pitch = self.winding_pattern.update_pitch(
+                    spool_speed,
+                    line_speed,
+                    self.step_size,
+                    stroke,
+                );
+                let traverse_speed = Self::calculate_traverse_speed(spool_speed, pitch);
+                let (target_position, max_speed) = match traversing_state {
                     // Move out at a speed of 100 mm/s
-                    self.speed_to_position(
-                        self.limit_outer - self.padding + Length::new::<millimeter>(0.01),
+                    TraversingState::GoingOut => (
+                        self.reversal_outer(),
                         Velocity::new::<millimeter_per_second>(100.0),
-                    )
-                }
-                TraversingState::TraversingIn => self.speed_to_position(
-                    self.limit_inner + self.padding - Length::new::<millimeter>(0.01),
-                    Self::calculate_traverse_speed(spool_speed, self.step_size),
-                ),
-                TraversingState::TraversingOut => self.speed_to_position(
-                    self.limit_outer - self.padding + Length::new::<millimeter>(0.01),
-                    Self::calculate_traverse_speed(spool_speed, self.step_size),
-                ),
-            },
+                    ),
+                    // Dwelling holds the reversal point
+                    TraversingState::TraversingIn | TraversingState::DwellInner(_) => {
+                        (self.reversal_inner(), traverse_speed)
+                    }
+                    TraversingState::TraversingOut | TraversingState::DwellOuter(_) => {
+                        (self.reversal_outer(), traverse_speed)
+                    }
+                };
+                self.speed_along_trajectory(target_position, max_speed)
+            }
+        };
+
+        self.supervise_steps(traverse);
+        speed
+    }
+
+    /// Step loss is only detected against a homed position
+    fn supervise_steps(&mut self, traverse: &mut dyn StepperVelocityEL70x1Device) {
+        if matches!(self.state, State::NotHomed | State::Homing(_)) {
+            self.step_loss.reset();
+            return;
+        }
+
+        let end_stop = traverse
+            .get_digital_input(TRAVERSE_END_STOP_PORT)
+            .unwrap_or(false);
+        if let Some(deviation) = self.step_loss.update(self.position, end_stop) {
+            tracing::warn!(
+                "[{}::TraverseController::supervise_steps] Traverse lost steps, deviation {:.2} mm",
+                module_path!(),
+                deviation.get::<millimeter>()
+            );
         }
     }
 
@@ -480,7 +636,7 @@ impl TraverseController {
     /// The traverse speed is the linear speed at which the winding mechanism moves along the spool.
     /// It's directly proportional to how fast the spool rotates and how far the traverse moves per rotation.
     ///
-    /// - Traverse Distance per Revolution [mm] = Step Size [mm]
+    /// - Traverse Distance per Revolution [mm] = Step Size [mm] (pitch of the winding pattern)
     /// - Traverse Speed [mm/s] = Spool Speed [rev/s or rad/s] * Step Size [mm]
     ///
     /// Note: While the traverse range (from outer limit minus padding to inner limit plus padding)
@@ -499,8 +655,9 @@ impl TraverseController {
         &mut self,
         traverse: &mut dyn StepperVelocityEL70x1Device,
         spool_speed: AngularVelocity,
+        line_speed: Velocity,
     ) {
-        let speed = self.get_speed(traverse, spool_speed);
+        let speed = self.get_speed(traverse, spool_speed, line_speed);
         let steps_per_second = self.fullstep_converter.velocity_to_steps(speed);
         // ignore if we can't set speed
         let _ = traverse.set_speed(TRAVERSE_PORT, steps_per_second);
@@ -0,0 +1,276 @@
+use std::f64::consts::PI;
+
+use qitech_lib::units::ConstZero;
+use qitech_lib::units::angle::{degree, radian};
+use qitech_lib::units::angular_velocity::revolution_per_second;
+use qitech_lib::units::f64::{Angle, AngularVelocity, Length, Velocity};
+use qitech_lib::units::length::millimeter;
+use qitech_lib::units::velocity::millimeter_per_second;
+use serde::{Deserialize, Serialize};
+
+/// Below this spool speed the spool diameter can't be derived from the line speed
+const MIN_SPOOL_SPEED_RPS: f64 = 0.01;
+
+/// Lay pattern of the traverse
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum WindingPattern {
+    /// Constant pitch per spool revolution, the wind ratio stays constant while the crossing
+    /// angle shrinks with the diameter
+    #[default]
+    Precision,
+    /// Constant crossing angle, the wind ratio drifts with the diameter
+    Random,
+    /// Precision winding with the pitch modulated stroke by stroke, so turns of
+    /// consecutive layers don't stack up into ribbons
+    Wave,
+    /// Precision winding, the wind ratio is stepped down whenever the crossing angle
+    /// fell out of its band
+    SteppedPrecision,
+}
+
+/// Calculates the traverse pitch per spool revolution for the selected [`WindingPattern`]
+///
+/// The spool diameter follows from the line speed and the spool speed, `D = v / (π · n)`.
+#[derive(Debug)]
+pub struct WindingPatternController {
+    pattern: WindingPattern,
+    /// Crossing angle of random winding and nominal angle of stepped precision winding
+    crossing_angle: Angle,
+    /// Stepped precision winding steps once the crossing angle is this far below nominal
+    crossing_angle_tolerance: Angle,
+    /// Relative pitch modulation of wave winding, `0.1` is ±10 %
+    wave_amplitude: f64,
+    /// Strokes per period of wave winding
+    wave_period: u32,
+
+    /// Strokes since traversing started
+    strokes: u32,
+    /// Pitch of stepped precision winding after the first step
+    stepped_pitch: Option<Length>,
+    /// Pitch of the last update
+    pitch: Length,
+}
+
+impl Default for WindingPatternController {
+    fn default() -> Self {
+        Self {
+            pattern: WindingPattern::Precision,
+            crossing_angle: Angle::new::<degree>(15.0),
+            crossing_angle_tolerance: Angle::new::<degree>(3.0),
+            wave_amplitude: 0.1,
+            wave_period: 10,
+            strokes: 0,
+            stepped_pitch: None,
+            pitch: Length::ZERO,
+        }
+    }
+}
+
+impl WindingPatternController {
+    /// Pitch per spool revolution
+    ///
+    /// - `step_size`: configured pitch of precision winding
+    /// - `stroke`: distance between the reversal points
+    pub fn update_pitch(
+        &mut self,
+        spool_speed: AngularVelocity,
+        line_speed: Velocity,
+        step_size: Length,
+        stroke: Length,
+    ) -> Length {
+        let diameter = Self::spool_diameter(spool_speed, line_speed);
+
+        self.pitch = match self.pattern {
+            WindingPattern::Precision => step_size,
+            WindingPattern::Random => match diameter {
+                Some(diameter) => diameter * PI * self.crossing_angle.get::<radian>().tan(),
+                // Spool not turning, the traverse doesn't move anyway
+                None => step_size,
+            },
+            WindingPattern::Wave => {
+                let phase = 2.0 * PI * f64::from(self.strokes) / f64::from(self.wave_period.max(1));
+                step_size * (1.0 + self.wave_amplitude * phase.sin())
+            }
+            WindingPattern::SteppedPrecision => self.stepped_pitch(diameter, step_size, stroke),
+        };
+        self.pitch
+    }
+
+    fn stepped_pitch(
+        &mut self,
+        diameter: Option<Length>,
+        step_size: Length,
+        stroke: Length,
+    ) -> Length {
+        let pitch = self.stepped_pitch.unwrap_or(step_size);
+        let Some(diameter) = diameter else {
+            return pitch;
+        };
+
+        let circumference = diameter * PI;
+        let crossing_angle = (pitch / circumference).value.atan();
+        let nominal = self.crossing_angle.get::<radian>();
+        if crossing_angle >= nominal - self.crossing_angle_tolerance.get::<radian>() {
+            return pitch;
+        }
+
+        // Wind ratio is spool revolutions per double stroke. Its fraction sets how far
+        // the turns shift per double stroke, keep the one of the configured step size.
+        let double_stroke = stroke * 2.0;
+        let ratio = (double_stroke / pitch).value;
+        let fraction = (double_stroke / step_size).value.fract();
+        let nominal_ratio = (double_stroke / (circumference * nominal.tan())).value;
+        let stepped_ratio = (nominal_ratio.floor() + fraction).min(ratio);
+        if stepped_ratio <= 0.0 {
+            return pitch;
+        }
+
+        let stepped = double_stroke / stepped_ratio;
+        self.stepped_pitch = Some(stepped);
+        stepped
+    }
+
+    fn spool_diameter(spool_speed: AngularVelocity, line_speed: Velocity) -> Option<Length> {
+        let revolutions = spool_speed.get::<revolution_per_second>().abs();
+        if revolutions < MIN_SPOOL_SPEED_RPS {
+            return None;
+        }
+        let circumference = line_speed.get::<millimeter_per_second>().abs() / revolutions;
+        Some(Length::new::<millimeter>(circumference / PI))
+    }
+
+    /// Count a reversal of the traverse
+    pub const fn on_reversal(&mut self) {
+        self.strokes = self.strokes.wrapping_add(1);
+    }
+
+    /// Start over with a new spool
+    pub const fn reset(&mut self) {
+        self.strokes = 0;
+        self.stepped_pitch = None;
+    }
+}
+
+// getters + setters
+impl WindingPatternController {
+    pub const fn get_pattern(&self) -> WindingPattern {
+        self.pattern
+    }
+
+    pub const fn set_pattern(&mut self, pattern: WindingPattern) {
+        self.pattern = pattern;
+        self.stepped_pitch = None;
+    }
+
+    pub fn get_pitch(&self) -> Length {
+        self.pitch
+    }
+
+    pub fn get_crossing_angle(&self) -> Angle {
+        self.crossing_angle
+    }
+
+    pub fn set_crossing_angle(&mut self, angle: Angle) {
+        self.crossing_angle = angle;
+    }
+
+    pub fn get_crossing_angle_tolerance(&self) -> Angle {
+        self.crossing_angle_tolerance
+    }
+
+    pub fn set_crossing_angle_tolerance(&mut self, tolerance: Angle) {
+        self.crossing_angle_tolerance = tolerance;
+    }
+
+    pub const fn get_wave_amplitude(&self) -> f64 {
+        self.wave_amplitude
+    }
+
+    pub fn set_wave_amplitude(&mut self, amplitude: f64) {
+        self.wave_amplitude = amplitude.clamp(0.0, 0.5);
+    }
+
+    pub const fn get_wave_period(&self) -> u32 {
+        self.wave_period
+    }
+
+    pub fn set_wave_period(&mut self, period: u32) {
+        self.wave_period = period.max(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qitech_lib::units::velocity::meter_per_minute;
+
+    fn mm(value: f64) -> Length {
+        Length::new::<millimeter>(value)
+    }
+
+    fn rps(value: f64) -> AngularVelocity {
+        AngularVelocity::new::<revolution_per_second>(value)
+    }
+
+    #[test]
+    fn test_random_keeps_crossing_angle() {
+        let mut controller = WindingPatternController::default();
+        controller.set_pattern(WindingPattern::Random);
+        controller.set_crossing_angle(Angle::new::<degree>(45.0));
+
+        // 100 mm diameter
+        let line_speed = Velocity::new::<millimeter_per_second>(100.0 * PI);
+        let pitch = controller.update_pitch(rps(1.0), line_speed, mm(1.75), mm(80.0));
+        assert!((pitch.get::<millimeter>() - 100.0 * PI).abs() < 1e-9);
+
+        // Spool stopped, the diameter is unknown
+        let pitch = controller.update_pitch(rps(0.0), line_speed, mm(1.75), mm(80.0));
+        assert_eq!(pitch, mm(1.75));
+    }
+
+    #[test]
+    fn test_wave_modulates_per_stroke() {
+        let mut controller = WindingPatternController::default();
+        controller.set_pattern(WindingPattern::Wave);
+        controller.set_wave_period(4);
+        let line_speed = Velocity::new::<meter_per_minute>(10.0);
+
+        let pitch = controller.update_pitch(rps(1.0), line_speed, mm(2.0), mm(80.0));
+        assert!((pitch.get::<millimeter>() - 2.0).abs() < 1e-9);
+
+        controller.on_reversal();
+        let pitch = controller.update_pitch(rps(1.0), line_speed, mm(2.0), mm(80.0));
+        assert!((pitch.get::<millimeter>() - 2.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_stepped_precision_keeps_ratio_fraction() {
+        let mut controller = WindingPatternController::default();
+        controller.set_pattern(WindingPattern::SteppedPrecision);
+        controller.set_crossing_angle(Angle::new::<degree>(1.0));
+        controller.set_crossing_angle_tolerance(Angle::new::<degree>(0.2));
+        let (step_size, stroke) = (mm(3.0), mm(100.0));
+
+        // Small core, the crossing angle is above nominal
+        let core = Velocity::new::<millimeter_per_second>(50.0 * PI);
+        assert_eq!(
+            controller.update_pitch(rps(1.0), core, step_size, stroke),
+            step_size
+        );
+
+        // Spool grew, the angle fell out of the band and the ratio steps down
+        let full = Velocity::new::<millimeter_per_second>(200.0 * PI);
+        let pitch = controller.update_pitch(rps(1.0), full, step_size, stroke);
+        assert!(pitch > step_size);
+
+        let ratio = 200.0 / pitch.get::<millimeter>();
+        let fraction = (200.0_f64 / 3.0).fract();
+        assert!((ratio.fract() - fraction).abs() < 1e-9);
+
+        // Stays on the stepped pitch
+        assert_eq!(
+            controller.update_pitch(rps(1.0), full, step_size, stroke),
+            pitch
+        );
+    }
+}
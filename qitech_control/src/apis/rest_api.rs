@@ -5,12 +5,30 @@ use axum::routing::{get, post};
 use axum::{Extension, Json, Router, debug_handler};
 use machine_implementations::MachineMessage;
 use machine_implementations::aquapath1::AquaPathV1;
+use machine_implementations::bottle_cap_sorter::Sorter1;
 use machine_implementations::extruder1::ExtruderV2;
 use machine_implementations::laser::LaserMachine;
 use machine_implementations::machine_identification::{
     MachineIdentification, QiTechMachineIdentificationUnique,
 };
+#[cfg(feature = "mock-machine")]
+use machine_implementations::minimal_machines::mock::MockMachine;
+use machine_implementations::minimal_machines::{
+    analog_input_test_machine::AnalogInputTestMachine,
+    bottlecaps_test_machine::BottlecapsTestMachine,
+    digital_input_test_machine::DigitalInputTestMachine, ip20_test_machine::IP20TestMachine,
+    motor_test_machine::MotorTestMachine, test_machine::TestMachine,
+    test_machine_stepper::TestMachineStepper,
+    wago_8ch_dio_test_machine::Wago8chDigitalIOTestMachine,
+    wago_750_430_di_machine::Wago750_430DiMachine, wago_750_460_machine::Wago750_460Machine,
+    wago_750_501_test_machine::Wago750_501TestMachine, wago_750_531_machine::Wago750_531Machine,
+    wago_750_553_machine::Wago750_553Machine, wago_ai_test_machine::WagoAiTestMachine,
+    wago_do_test_machine::WagoDOTestMachine,
+};
+use machine_implementations::wago_power::WagoPower;
+use machine_implementations::wago_serial_machine::WagoSerialMachine;
 use machine_implementations::winder2::Winder2;
+use machine_implementations::winder2::spool_report::{SpoolLabel, SpoolReport, SpoolReportStore};
 use serde::Serialize;
 use std::sync::Arc;
 
@@ -135,6 +153,62 @@ async fn post_machine_handler(
     json(())
 }
 
+#[debug_handler]
+async fn get_spool_reports_handler(
+    Extension(id): Extension<MachineIdentification>,
+    Path(serial): Path<u16>,
+) -> Result<Vec<SpoolReport>> {
+    let id = QiTechMachineIdentificationUnique {
+        serial,
+        machine_identification: id,
+    };
+
+    // Reading the report files blocks
+    let reports = tokio::task::spawn_blocking(move || SpoolReportStore::new(&id).list())
+        .await
+        .map_err(internal_error)?
+        .map_err(internal_error)?;
+    json(reports)
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+struct GetSpoolReportResponce {
+    report: SpoolReport,
+    label: SpoolLabel,
+}
+
+#[debug_handler]
+async fn get_spool_report_handler(
+    Extension(id): Extension<MachineIdentification>,
+    Path((serial, report_id)): Path<(u16, u64)>,
+) -> Result<GetSpoolReportResponce> {
+    let id = QiTechMachineIdentificationUnique {
+        serial,
+        machine_identification: id,
+    };
+
+    let report = tokio::task::spawn_blocking(move || SpoolReportStore::new(&id).load(report_id))
+        .await
+        .map_err(internal_error)?
+        .map_err(internal_error)?
+        .ok_or_else(|| not_found(format!("No spool report {report_id} for {id}")))?;
+    let label = report.label();
+    json(GetSpoolReportResponce { report, label })
+}
+
+/// Finished spool reports of a winder
+fn make_spool_report_router(id: MachineIdentification) -> Router<Arc<SharedAppState>> {
+    let slug = id.slug();
+    let path = format!("/machine/{slug}/{{serial}}/spool_reports");
+    Router::new()
+        .route(&path, get(get_spool_reports_handler))
+        .route(
+            &format!("{path}/{{report_id}}"),
+            get(get_spool_report_handler),
+        )
+        .layer(Extension(id))
+}
+
 fn make_machine_router(id: MachineIdentification) -> Router<Arc<SharedAppState>> {
     let slug = id.slug();
     let path = format!("/machine/{slug}/{{serial}}");
@@ -154,16 +228,80 @@ pub fn rest_api_router() -> Router<Arc<SharedAppState>> {
         .merge(make_machine_router(
             Winder2::MACHINE_IDENTIFICATION_7031_SPOOL.into(),
         ))
+        .merge(make_spool_report_router(
+            Winder2::MACHINE_IDENTIFICATION.into(),
+        ))
+        .merge(make_spool_report_router(
+            Winder2::MACHINE_IDENTIFICATION_7031_SPOOL.into(),
+        ))
         .merge(make_machine_router(
             ExtruderV2::MACHINE_IDENTIFICATION.into(),
         ))
         .merge(make_machine_router(
             AquaPathV1::MACHINE_IDENTIFICATION.into(),
         ))
-    /*.merge(make_machine_router(TestMachine::MACHINE_IDENTIFICATION.into()))
-    .merge(make_machine_router(WagoPower::MACHINE_IDENTIFICATION.into()))
-    .merge(make_machine_router(IP20TestMachine::MACHINE_IDENTIFICATION.into()))
-    .merge(make_machine_router(
-        AnalogInputTestMachine::MACHINE_IDENTIFICATION,
-    ))*/
+        .merge(make_machine_router(Sorter1::MACHINE_IDENTIFICATION.into()))
+        .merge(make_machine_router(
+            WagoPower::MACHINE_IDENTIFICATION.into(),
+        ))
+        .merge(make_machine_router(
+            TestMachine::MACHINE_IDENTIFICATION.into(),
+        ))
+        .merge(make_machine_router(
+            IP20TestMachine::MACHINE_IDENTIFICATION.into(),
+        ))
+        .merge(make_machine_router(
+            AnalogInputTestMachine::MACHINE_IDENTIFICATION.into(),
+        ))
+        .merge(make_machine_router(
+            WagoAiTestMachine::MACHINE_IDENTIFICATION.into(),
+        ))
+        .merge(make_machine_router(
+            MotorTestMachine::MACHINE_IDENTIFICATION.into(),
+        ))
+        .merge(make_machine_router(
+            DigitalInputTestMachine::MACHINE_IDENTIFICATION.into(),
+        ))
+        .merge(make_machine_router(
+            WagoDOTestMachine::MACHINE_IDENTIFICATION.into(),
+        ))
+        .merge(make_machine_router(
+            Wago750_531Machine::MACHINE_IDENTIFICATION.into(),
+        ))
+        .merge(make_machine_router(
+            Wago750_501TestMachine::MACHINE_IDENTIFICATION.into(),
+        ))
+        .merge(make_machine_router(
+            Wago8chDigitalIOTestMachine::MACHINE_IDENTIFICATION.into(),
+        ))
+        .merge(make_machine_router(
+            TestMachineStepper::MACHINE_IDENTIFICATION.into(),
+        ))
+        .merge(make_machine_router(
+            Wago750_430DiMachine::MACHINE_IDENTIFICATION.into(),
+        ))
+        .merge(make_machine_router(
+            Wago750_460Machine::MACHINE_IDENTIFICATION.into(),
+        ))
+        .merge(make_machine_router(
+            Wago750_553Machine::MACHINE_IDENTIFICATION.into(),
+        ))
+        .merge(make_machine_router(
+            BottlecapsTestMachine::MACHINE_IDENTIFICATION.into(),
+        ))
+        .merge(make_machine_router(
+            WagoSerialMachine::MACHINE_IDENTIFICATION.into(),
+        ))
+        .merge(mock_machine_router())
+}
+
+/// The mock machine is only registered when it is built, see the machine registry
+#[cfg(feature = "mock-machine")]
+fn mock_machine_router() -> Router<Arc<SharedAppState>> {
+    make_machine_router(MockMachine::MACHINE_IDENTIFICATION.into())
+}
+
+#[cfg(not(feature = "mock-machine"))]
+fn mock_machine_router() -> Router<Arc<SharedAppState>> {
+    Router::new()
 }
@@ -9,18 +9,23 @@ use crate::apis::socketio::{
     },
     namespaces::Namespaces,
 };
+use crate::serial_hotplug::SerialProbe;
 use anyhow::bail;
-use control_core::socketio::{
-    event::{Event, GenericEvent},
-    namespace::NamespaceCacheingLogic,
+use control_core::{
+    ethernet::modbus_tcp_discovery::ModbusTcpProbe,
+    socketio::{
+        event::{Event, GenericEvent},
+        namespace::NamespaceCacheingLogic,
+    },
 };
 use machine_implementations::{
-    Hardware, IdentifiedEthercat, IdentifiedModbus, MachineHardware, MachineMessage, QiTechMachine,
-    laser::LaserMachine,
+    Hardware, IdentifiedEthercat, IdentifiedModbus, IdentifiedModbusTcp, MACHINE_LASER_V1,
+    MachineHardware, MachineMessage, QiTechMachine,
     machine_identification::{
         DeviceHardwareIdentificationEthercat, DeviceIdentification, DeviceMachineIdentification,
         QiTechMachineIdentificationUnique,
     },
+    wago_power::WagoPower,
 };
 use qitech_lib::{
     ethercat_hal::{
@@ -255,6 +260,10 @@ pub struct MainState {
     pub machines: Vec<Box<dyn QiTechMachine>>,
     pub machine_errors: HashMap<MachineIdentificationUnique, String>,
     pub machine_data_reg: MachineDataRegistry,
+    /// Serial port name of every machine built from a serial device
+    pub serial_ports: HashMap<String, MachineIdentificationUnique>,
+    /// Consecutive Modbus TCP scans that missed the device of a machine
+    pub modbus_tcp_misses: HashMap<MachineIdentificationUnique, u32>,
 }
 
 impl MainState {
@@ -269,30 +278,83 @@ impl MainState {
             subdevices: vec![],
             hardware: HashMap::new(),
             machine_errors: HashMap::new(),
+            serial_ports: HashMap::new(),
+            modbus_tcp_misses: HashMap::new(),
         }
     }
 
+    /// Opens the device of an identified serial port, the machine type decides the driver
     pub fn generate_machine_hardware_from_serial(
         &mut self,
-        path: &str,
+        probe: &SerialProbe,
     ) -> Result<(), anyhow::Error> {
-        let laser_device: Rc<RefCell<LaserDevice>> =
-            Rc::new(RefCell::new(LaserDevice::new(path.to_owned(), 1, None)?));
-        let id_modbus: IdentifiedModbus = IdentifiedModbus { hw: laser_device };
-        let ident = MachineIdentificationUnique {
-            machine_ident: LaserMachine::MACHINE_IDENTIFICATION,
-            serial: 1,
+        let ident = probe.identification;
+        let device: Rc<RefCell<dyn ModbusDevice>> = match ident.machine_ident.machine {
+            MACHINE_LASER_V1 => Rc::new(RefCell::new(LaserDevice::new(
+                probe.port_name.clone(),
+                probe.slave_id,
+                None,
+            )?)),
+            machine => bail!(
+                "[{}::MainState::generate_machine_hardware_from_serial] No serial device known for machine 0x{:04x}",
+                module_path!(),
+                machine
+            ),
         };
-        let mut hw = MachineHardware {
-            hw: vec![],
+        let hw = MachineHardware {
+            hw: vec![Hardware::Modbus(IdentifiedModbus { hw: device })],
             identification: ident,
             ethercat_interface: None,
         };
-        hw.hw.push(Hardware::Modbus(id_modbus));
         self.hardware.insert(ident, hw);
+        self.serial_ports.insert(probe.port_name.clone(), ident);
         Ok(())
     }
 
+    /// Machine of a Modbus TCP device from the module number it reports
+    pub fn identify_modbus_tcp(probe: &ModbusTcpProbe) -> Option<MachineIdentification> {
+        match (probe.module_number, probe.module_variant) {
+            (WagoPower::MODULE_NUMBER, 0) => Some(WagoPower::MACHINE_IDENTIFICATION),
+            _ => None,
+        }
+    }
+
+    /// Adds the hardware of an identified Modbus TCP device, `None` for unknown devices
+    pub fn generate_machine_hardware_from_modbus_tcp(
+        &mut self,
+        probe: &ModbusTcpProbe,
+    ) -> Option<MachineIdentificationUnique> {
+        let ident = MachineIdentificationUnique {
+            machine_ident: Self::identify_modbus_tcp(probe)?,
+            serial: probe.serial as u32,
+        };
+        let id_modbus_tcp = IdentifiedModbusTcp {
+            addr: probe.addr,
+            serial: probe.serial,
+        };
+        let hw = MachineHardware {
+            hw: vec![Hardware::ModbusTcp(id_modbus_tcp)],
+            identification: ident,
+            ethercat_interface: None,
+        };
+        self.hardware.insert(ident, hw);
+        Some(ident)
+    }
+
+    /// Returns the machines whose hardware is a Modbus TCP device matching `filter`
+    pub fn find_modbus_tcp_hardware(
+        &self,
+        filter: impl Fn(&IdentifiedModbusTcp) -> bool,
+    ) -> Vec<MachineIdentificationUnique> {
+        self.hardware
+            .iter()
+            .filter_map(|(ident, hw)| match hw.hw.first() {
+                Some(Hardware::ModbusTcp(modbus_tcp)) if filter(modbus_tcp) => Some(*ident),
+                _ => None,
+            })
+            .collect()
+    }
+
     pub fn generate_machine_hardware_from_ethercat(
         &mut self,
         device_infos: &Vec<MachineDeviceInfo>,
@@ -1,7 +1,7 @@
+use control_core::ethernet::modbus_tcp_discovery::{ModbusTcpProbe, probe_modbus_tcp};
 use qitech_lib::common::get_async_runtime;
 use std::{fs, os::unix::io::AsRawFd};
 use tokio::sync::mpsc::{Receiver, Sender};
-use tokio_serial::{SerialPortInfo, available_ports};
 
 const SIOCSIFFLAGS: libc::c_ulong = 0x8914;
 const IFF_UP: libc::c_short = 0x1;
@@ -56,7 +56,9 @@ pub fn set_all_ethernet_up() -> bool {
     true
 }
 
-pub fn detect_serial(rx: Receiver<()>, tx_ports: Sender<Vec<SerialPortInfo>>) {
+/// Scans all ethernet networks for Modbus TCP devices whenever `rx` is triggered.
+/// Triggers arriving while a scan is still running are dropped by the bounded channel.
+pub fn detect_modbus_tcp(rx: Receiver<()>, tx_probes: Sender<Vec<ModbusTcpProbe>>) {
     get_async_runtime().spawn(async move {
         let mut rx = rx;
         loop {
@@ -65,12 +67,8 @@ pub fn detect_serial(rx: Receiver<()>, tx_ports: Sender<Vec<SerialPortInfo>>) {
                 Some(_) => (),
                 None => break, // In this case channel is closed, so stop
             }
-            let ports = available_ports();
-            let ports = match ports {
-                Ok(p) => p,
-                Err(_e) => vec![],
-            };
-            let _res = tx_ports.send(ports).await;
+            let probes = probe_modbus_tcp().await;
+            let _res = tx_probes.send(probes).await;
         }
     });
 }
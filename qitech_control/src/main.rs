@@ -1,7 +1,7 @@
 use anyhow::bail;
 use apis::socketio::queue::start_socketio_queue;
 use app_state::SharedAppState;
-use machine_implementations::MACHINE_LASER_V1;
+use control_core::ethernet::modbus_tcp_discovery::ModbusTcpProbe;
 use machine_implementations::registry::MACHINE_REGISTRY;
 #[cfg(not(feature = "mock"))]
 use machine_loop::{run_machines, write_ecat_inputs, write_ecat_outputs};
@@ -18,7 +18,6 @@ use qitech_lib::{
 #[cfg(not(feature = "mock"))]
 use std::{sync::Arc, time::Duration};
 use tokio::sync::mpsc::Receiver;
-use tokio_serial::SerialPortInfo;
 
 use crate::{
     apis::socketio::main_namespace::{
@@ -30,7 +29,8 @@ use crate::{
 #[cfg(not(feature = "mock"))]
 use crate::{
     app_state::MainState,
-    interfaces::{detect_serial, set_all_ethernet_up},
+    interfaces::{detect_modbus_tcp, set_all_ethernet_up},
+    serial_hotplug::{SerialProbe, detect_serial},
 };
 
 pub mod apis;
@@ -40,6 +40,10 @@ mod machine_loop;
 #[cfg(feature = "mock")]
 mod mock;
 pub mod persist;
+mod serial_hotplug;
+
+/// Scans in a row that have to miss a Modbus TCP device before its machine is removed
+const MODBUS_TCP_MAX_MISSES: u32 = 3;
 
 fn setup_ethercat(
     state: Arc<SharedAppState>,
@@ -120,65 +124,107 @@ fn setup_ethercat(
     Ok(())
 }
 
-fn add_laser(
+/// Syncs the hardware with the identified serial devices: unplugged devices are removed
+/// together with their machine, new devices and devices whose machine was dropped are built
+fn serial_hotplug(
     main_state: &mut MainState,
     shared_state: Arc<SharedAppState>,
-    rx_ports: &mut Receiver<Vec<SerialPortInfo>>,
+    rx_probes: &mut Receiver<Vec<SerialProbe>>,
 ) -> Result<(), anyhow::Error> {
-    let ports = rx_ports.try_recv()?;
-    let machine_index_to_remove = main_state
-        .machines
-        .iter()
-        .position(|m| m.get_identification().machine_ident.machine == MACHINE_LASER_V1);
-    let machine_obj_index = shared_state.machines.try_read()?.iter().position(|m| {
-        m.machine_identification_unique
-            .machine_identification
-            .machine
-            == MACHINE_LASER_V1
-    });
+    let probes = rx_probes.try_recv()?;
 
-    match machine_index_to_remove {
-        Some(index) => {
-            main_state.machines.remove(index);
+    let unplugged: Vec<String> = main_state
+        .serial_ports
+        .keys()
+        .filter(|port_name| !probes.iter().any(|probe| &probe.port_name == *port_name))
+        .cloned()
+        .collect();
+    for port_name in &unplugged {
+        if let Some(ident) = main_state.serial_ports.remove(port_name) {
+            remove_machine_by_identification(main_state, shared_state.clone(), ident)?;
         }
-        None => (),
     }
 
-    match machine_obj_index {
-        Some(index) => {
-            shared_state.machines.try_write()?.remove(index);
+    let mut added = false;
+    for probe in probes {
+        if main_state.hardware.contains_key(&probe.identification) {
+            continue;
+        }
+        match main_state.generate_machine_hardware_from_serial(&probe) {
+            Ok(()) => added = true,
+            Err(e) => tracing::warn!(
+                "[{}::serial_hotplug] Could not open serial device {}: {:?}",
+                module_path!(),
+                probe.port_name,
+                e
+            ),
         }
-        None => (),
     }
 
-    // Port is not used right now, so check if port exists
-    for port in ports {
-        if port.port_name == "/dev/ttyUSB0" || port.port_name == "/dev/ttyUSB1" {
-            main_state.generate_machine_hardware_from_serial(&port.port_name)?;
-            detect_and_build_machines(shared_state.clone(), main_state);
-            send_machines_event(shared_state);
-            break;
-        }
+    if added {
+        detect_and_build_machines(shared_state.clone(), main_state);
+    }
+    if added || !unplugged.is_empty() {
+        send_machines_event(shared_state);
     }
     Ok(())
 }
 
-fn laser_hotplug(
+/// Syncs the hardware with the latest Modbus TCP scan: devices that no longer answer
+/// are removed together with their machine, newly found devices are built as machines.
+/// A single dropped probe doesn't remove a device, it has to be missed several times.
+fn modbus_tcp_hotplug(
     main_state: &mut MainState,
     shared_state: Arc<SharedAppState>,
-    rx_ports: &mut Receiver<Vec<SerialPortInfo>>,
+    rx_probes: &mut Receiver<Vec<ModbusTcpProbe>>,
 ) -> Result<(), anyhow::Error> {
-    match main_state
-        .machines
-        .iter()
-        .any(|x| x.get_identification().machine_ident.machine == MACHINE_LASER_V1)
-    {
-        true => Ok(()),
-        false => {
-            add_laser(main_state, shared_state.clone(), rx_ports)?;
-            Ok(())
+    let probes = rx_probes.try_recv()?;
+
+    let missed = main_state.find_modbus_tcp_hardware(|modbus_tcp| {
+        !probes
+            .iter()
+            .any(|probe| probe.addr == modbus_tcp.addr && probe.serial == modbus_tcp.serial)
+    });
+    main_state
+        .modbus_tcp_misses
+        .retain(|ident, _| missed.contains(ident));
+    let mut lost = Vec::new();
+    for ident in missed {
+        let misses = main_state.modbus_tcp_misses.entry(ident).or_insert(0);
+        *misses += 1;
+        if *misses >= MODBUS_TCP_MAX_MISSES {
+            main_state.modbus_tcp_misses.remove(&ident);
+            remove_machine_by_identification(main_state, shared_state.clone(), ident)?;
+            lost.push(ident);
+        }
+    }
+
+    let mut found = false;
+    for probe in &probes {
+        let known = !main_state
+            .find_modbus_tcp_hardware(|modbus_tcp| modbus_tcp.addr == probe.addr)
+            .is_empty();
+        if known {
+            continue;
+        }
+        match main_state.generate_machine_hardware_from_modbus_tcp(probe) {
+            Some(_) => found = true,
+            None => tracing::debug!(
+                "[{}::modbus_tcp_hotplug] Ignoring unknown Modbus TCP device {} with module number {:#010x}",
+                module_path!(),
+                probe.addr,
+                probe.module_number
+            ),
         }
     }
+
+    if found {
+        detect_and_build_machines(shared_state.clone(), main_state);
+    }
+    if found || !lost.is_empty() {
+        send_machines_event(shared_state);
+    }
+    Ok(())
 }
 
 fn send_machines_event(state: Arc<SharedAppState>) {
@@ -345,6 +391,31 @@ pub fn remove_machines(
     }
 }
 
+/// Removes the machine and its hardware, also if building the machine had failed
+fn remove_machine_by_identification(
+    main_state: &mut MainState,
+    shared_state: Arc<SharedAppState>,
+    ident: MachineIdentificationUnique,
+) -> Result<(), anyhow::Error> {
+    let machine_index = main_state
+        .machines
+        .iter()
+        .position(|m| m.get_identification() == ident);
+
+    match machine_index {
+        Some(_) => remove_machines(main_state, shared_state, machine_index),
+        None => {
+            main_state.hardware.remove(&ident);
+            main_state.machine_errors.remove(&ident);
+            shared_state
+                .machines
+                .try_write()?
+                .retain(|x| x.machine_identification_unique != ident.into());
+        }
+    }
+    Ok(())
+}
+
 fn find_ethercat_interface(state: &SharedAppState) -> String {
     loop {
         let _ = state
@@ -418,8 +489,12 @@ fn main_logic() {
     setup_api_and_websock(state.clone());
 
     let (tx, rx) = tokio::sync::mpsc::channel(2);
-    let (tx_ports, mut rx_ports) = tokio::sync::mpsc::channel(2);
-    detect_serial(rx, tx_ports);
+    let (tx_serial_probes, mut rx_serial_probes) = tokio::sync::mpsc::channel(2);
+    detect_serial(rx, tx_serial_probes);
+
+    let (tx_modbus_tcp, rx_modbus_tcp) = tokio::sync::mpsc::channel(1);
+    let (tx_probes, mut rx_probes) = tokio::sync::mpsc::channel(1);
+    detect_modbus_tcp(rx_modbus_tcp, tx_probes);
 
     match &eth_control {
         Some(control) => {
@@ -463,6 +538,10 @@ fn main_logic() {
 
     let mut last_check = std::time::Instant::now();
     let hotplug_duration = Duration::from_secs(1);
+    // A scan probes every address of each /24, so run it less often than the serial hotplug
+    let mut last_modbus_tcp_check = std::time::Instant::now();
+    let modbus_tcp_discovery_duration = Duration::from_secs(5);
+    let _ = tx_modbus_tcp.try_send(());
 
     loop {
         let now = std::time::Instant::now();
@@ -489,10 +568,16 @@ fn main_logic() {
 
         if now.duration_since(last_check) >= hotplug_duration {
             let _ = tx.try_send(());
-            let _ = laser_hotplug(&mut main_state, state.clone(), &mut rx_ports);
+            let _ = serial_hotplug(&mut main_state, state.clone(), &mut rx_serial_probes);
             last_check = now;
         }
 
+        if now.duration_since(last_modbus_tcp_check) >= modbus_tcp_discovery_duration {
+            let _ = tx_modbus_tcp.try_send(());
+            last_modbus_tcp_check = now;
+        }
+        let _ = modbus_tcp_hotplug(&mut main_state, state.clone(), &mut rx_probes);
+
         match &mut eth_control {
             Some(control) => {
                 write_ecat_outputs(&mut control.app_handle, main_state.subdevices.clone());
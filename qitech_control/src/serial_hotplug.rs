@@ -0,0 +1,359 @@
+use anyhow::{Result, anyhow, bail};
+use control_core::modbus::{
+    ModbusFunctionCode, ModbusRequest, ModbusResponse, calculate_modbus_rtu_timeout,
+    receive_data_modbus,
+};
+use machine_implementations::{
+    MACHINE_LASER_V1, VENDOR_QITECH, machine_identification::MachineIdentificationAddresses,
+};
+use qitech_lib::{
+    common::get_async_runtime,
+    machines::{MachineIdentification, MachineIdentificationUnique},
+    modbus::{ModbusDevice, devices::qitech_laser::LaserDevice},
+};
+use std::{
+    collections::HashMap,
+    io::Write,
+    time::{Duration, Instant},
+};
+use tokio::sync::mpsc::{Receiver, Sender};
+use tokio_serial::{SerialPortInfo, SerialPortType, available_ports};
+
+/// USB serial adapter a QiTech Modbus RTU device can be connected with
+struct SerialAdapter {
+    vid: u16,
+    pid: u16,
+    baudrate: u32,
+    slave_id: u8,
+}
+
+const SERIAL_ADAPTERS: &[SerialAdapter] = &[
+    // CH340 based USB-RS485 adapter
+    SerialAdapter {
+        vid: 0x1a86,
+        pid: 0x7523,
+        baudrate: 38400,
+        slave_id: 1,
+    },
+    // FTDI FT232R based USB-RS485 adapter
+    SerialAdapter {
+        vid: 0x0403,
+        pid: 0x6001,
+        baudrate: 38400,
+        slave_id: 1,
+    },
+];
+
+/// Identification block of the QiTech RS485 firmware, read as holding registers:
+///
+/// | Register | Word    |
+/// |----------|---------|
+/// | 0x0028   | vendor  |
+/// | 0x0029   | machine |
+/// | 0x002a   | serial  |
+///
+/// Same addresses as the EtherCAT EEPROM identification, see [`MachineIdentificationAddresses`].
+/// Laser firmware released before this block existed answers with an exception or not at
+/// all, such ports are probed with the [`LaserDevice`] protocol instead, see [`probe_laser`].
+const IDENTIFICATION_REGISTER_COUNT: u16 = 3;
+
+/// Machines with a serial driver, any other answer is treated as a misidentification
+const SERIAL_MACHINES: &[u16] = &[MACHINE_LASER_V1];
+const IDENTIFICATION_TIMEOUT: Duration = Duration::from_millis(100);
+/// Time the device needs to process the request
+const IDENTIFICATION_PROCESSING_DELAY: Duration = Duration::from_millis(20);
+/// Serial of lasers without the identification block, the fixed serial they always had
+const LEGACY_LASER_SERIAL: u32 = 1;
+/// Time a laser without the identification block has to answer a measurement request
+const LASER_PROBE_TIMEOUT: Duration = Duration::from_millis(200);
+/// Same request interval as the laser machine uses
+const LASER_PROBE_REQUEST_INTERVAL: Duration = Duration::from_millis(6);
+
+/// Serial device that answered the identification query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SerialProbe {
+    pub port_name: String,
+    /// Modbus address the device answered the identification query on
+    pub slave_id: u8,
+    pub identification: MachineIdentificationUnique,
+}
+
+/// Probes all serial ports whenever `rx` is triggered and sends the identified devices.
+/// Identified ports are not queried again until they are unplugged, since the machine
+/// owns the port from then on. A port that answers with the identification of another
+/// port is logged as a conflict, only the first one gets a machine.
+pub fn detect_serial(rx: Receiver<()>, tx_probes: Sender<Vec<SerialProbe>>) {
+    get_async_runtime().spawn(async move {
+        let mut rx = rx;
+        let mut identified: HashMap<String, SerialProbe> = HashMap::new();
+        loop {
+            let res = rx.recv().await;
+            match res {
+                Some(_) => (),
+                None => break, // In this case channel is closed, so stop
+            }
+            let ports = available_ports().unwrap_or_default();
+            identified.retain(|port_name, _| ports.iter().any(|p| &p.port_name == port_name));
+
+            for port in ports {
+                if identified.contains_key(&port.port_name) {
+                    continue;
+                }
+                let probe = tokio::task::spawn_blocking(move || probe_serial_port(&port)).await;
+                if let Ok(Some(probe)) = probe {
+                    if let Some(other) = identified
+                        .values()
+                        .find(|other| other.identification == probe.identification)
+                    {
+                        tracing::error!(
+                            "[{}::detect_serial] {} and {} both identify as {:?}, {} is ignored until one is unplugged",
+                            module_path!(),
+                            other.port_name,
+                            probe.port_name,
+                            probe.identification,
+                            probe.port_name
+                        );
+                    }
+                    identified.insert(probe.port_name.clone(), probe);
+                }
+            }
+
+            let probes = identified.values().cloned().collect();
+            let _res = tx_probes.send(probes).await;
+        }
+    });
+}
+
+/// Blocking, only ports of known USB adapters are queried
+fn probe_serial_port(port: &SerialPortInfo) -> Option<SerialProbe> {
+    let SerialPortType::UsbPort(usb) = &port.port_type else {
+        return None;
+    };
+    let adapter = SERIAL_ADAPTERS
+        .iter()
+        .find(|adapter| adapter.vid == usb.vid && adapter.pid == usb.pid)?;
+
+    // Devices which are still booting or don't speak Modbus are retried on the next tick
+    let identification = match read_identification(&port.port_name, adapter) {
+        Ok(identification) => identification,
+        Err(e) => probe_laser(&port.port_name, adapter)
+            .map_err(|laser_err| {
+                tracing::debug!(
+                    "[{}::probe_serial_port] {} not identified: {:?}, laser probe: {:?}",
+                    module_path!(),
+                    port.port_name,
+                    e,
+                    laser_err
+                )
+            })
+            .ok()?,
+    };
+    Some(SerialProbe {
+        port_name: port.port_name.clone(),
+        slave_id: adapter.slave_id,
+        identification,
+    })
+}
+
+fn read_identification(
+    port_name: &str,
+    adapter: &SerialAdapter,
+) -> Result<MachineIdentificationUnique> {
+    let addresses = MachineIdentificationAddresses::default();
+    let request: Vec<u8> = ModbusRequest {
+        slave_id: adapter.slave_id,
+        function_code: ModbusFunctionCode::ReadHoldingRegister,
+        data: [
+            addresses.vendor_word.to_be_bytes(),
+            IDENTIFICATION_REGISTER_COUNT.to_be_bytes(),
+        ]
+        .concat(),
+    }
+    .into();
+
+    let mut port = tokio_serial::new(port_name, adapter.baudrate)
+        .timeout(IDENTIFICATION_TIMEOUT)
+        .open()?;
+    port.write_all(&request)?;
+
+    // slave id, function code, byte count, registers, crc
+    let response_size = 5 + 2 * IDENTIFICATION_REGISTER_COUNT as usize;
+    std::thread::sleep(calculate_modbus_rtu_timeout(
+        10,
+        IDENTIFICATION_PROCESSING_DELAY,
+        adapter.baudrate,
+        request.len() + response_size,
+    ));
+
+    let raw_response = receive_data_modbus(port.as_mut())?.ok_or_else(|| {
+        anyhow!(
+            "[{}::read_identification] No response from {}",
+            module_path!(),
+            port_name
+        )
+    })?;
+    parse_identification(
+        &ModbusResponse::try_from(raw_response)?,
+        adapter.slave_id,
+        &addresses,
+    )
+}
+
+/// Fallback for lasers without the identification block: the port is a laser if it answers
+/// a measurement request of the laser driver. These lasers all had the same serial, so only
+/// one of them can be connected at a time.
+fn probe_laser(port_name: &str, adapter: &SerialAdapter) -> Result<MachineIdentificationUnique> {
+    let mut laser = LaserDevice::new(port_name.to_owned(), adapter.slave_id, None)?;
+    let start = Instant::now();
+    while start.elapsed() < LASER_PROBE_TIMEOUT {
+        laser.send_next_request()?;
+        std::thread::sleep(LASER_PROBE_REQUEST_INTERVAL);
+        // Errors are timeouts or partial frames until the laser answers
+        let _ = laser.handle_response();
+        if laser.measurement.is_some() {
+            return Ok(MachineIdentificationUnique {
+                machine_ident: MachineIdentification {
+                    vendor: VENDOR_QITECH,
+                    machine: MACHINE_LASER_V1,
+                },
+                serial: LEGACY_LASER_SERIAL,
+            });
+        }
+    }
+    bail!(
+        "[{}::probe_laser] No laser measurement from {}",
+        module_path!(),
+        port_name
+    )
+}
+
+fn parse_identification(
+    response: &ModbusResponse,
+    slave_id: u8,
+    addresses: &MachineIdentificationAddresses,
+) -> Result<MachineIdentificationUnique> {
+    if response.slave_id != slave_id
+        || response.function_code != ModbusFunctionCode::ReadHoldingRegister
+    {
+        bail!(
+            "[{}::parse_identification] Unexpected response {:?} from slave {}",
+            module_path!(),
+            response.function_code,
+            response.slave_id
+        );
+    }
+
+    // first data byte is the byte count
+    let byte_count = 2 * IDENTIFICATION_REGISTER_COUNT as usize;
+    if response.data.first().map(|&count| count as usize) != Some(byte_count)
+        || response.data.len() != byte_count + 1
+    {
+        bail!(
+            "[{}::parse_identification] Expected {} bytes, got {:?}",
+            module_path!(),
+            byte_count,
+            response.data
+        );
+    }
+
+    let registers: Vec<u16> = response
+        .data
+        .get(1..)
+        .unwrap_or_default()
+        .chunks_exact(2)
+        .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+        .collect();
+
+    let register = |address: u16| {
+        registers
+            .get(address.wrapping_sub(addresses.vendor_word) as usize)
+            .copied()
+            .ok_or_else(|| {
+                anyhow!(
+                    "[{}::parse_identification] Register 0x{:04x} missing in response",
+                    module_path!(),
+                    address
+                )
+            })
+    };
+
+    let vendor = register(addresses.vendor_word)?;
+    if vendor != VENDOR_QITECH {
+        bail!(
+            "[{}::parse_identification] Unknown vendor 0x{:04x}",
+            module_path!(),
+            vendor
+        );
+    }
+
+    let machine = register(addresses.machine_word)?;
+    if !SERIAL_MACHINES.contains(&machine) {
+        bail!(
+            "[{}::parse_identification] Machine 0x{:04x} has no serial driver",
+            module_path!(),
+            machine
+        );
+    }
+
+    Ok(MachineIdentificationUnique {
+        machine_ident: MachineIdentification { vendor, machine },
+        serial: register(addresses.serial_word)? as u32,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(data: Vec<u8>) -> ModbusResponse {
+        ModbusResponse {
+            slave_id: 1,
+            function_code: ModbusFunctionCode::ReadHoldingRegister,
+            data,
+            crc: 0,
+        }
+    }
+
+    #[test]
+    fn test_parse_identification() {
+        let response = response(vec![0x06, 0x00, 0x01, 0x00, 0x06, 0x00, 0x2a]);
+        let ident =
+            parse_identification(&response, 1, &MachineIdentificationAddresses::default()).unwrap();
+
+        assert_eq!(ident.machine_ident.vendor, VENDOR_QITECH);
+        assert_eq!(ident.machine_ident.machine, MACHINE_LASER_V1);
+        assert_eq!(ident.serial, 42);
+    }
+
+    #[test]
+    fn test_parse_identification_unknown_vendor() {
+        let response = response(vec![0x06, 0x12, 0x34, 0x00, 0x06, 0x00, 0x2a]);
+        assert!(
+            parse_identification(&response, 1, &MachineIdentificationAddresses::default()).is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_identification_short_response() {
+        let response = response(vec![0x02, 0x00, 0x01]);
+        assert!(
+            parse_identification(&response, 1, &MachineIdentificationAddresses::default()).is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_identification_wrong_slave() {
+        let response = response(vec![0x06, 0x00, 0x01, 0x00, 0x06, 0x00, 0x2a]);
+        assert!(
+            parse_identification(&response, 2, &MachineIdentificationAddresses::default()).is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_identification_unknown_machine() {
+        let response = response(vec![0x06, 0x00, 0x01, 0x00, 0x02, 0x00, 0x2a]);
+        assert!(
+            parse_identification(&response, 1, &MachineIdentificationAddresses::default()).is_err()
+        );
+    }
+}